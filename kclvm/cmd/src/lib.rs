@@ -52,6 +52,7 @@ pub fn app() -> Command {
             .arg(arg!(sort_keys: -k --sort_keys "Sort result keys"))
             .arg(arg!(show_hidden: -H --show_hidden "Display hidden attributes"))
             .arg(arg!(fast_eval: -K --fast_eval "Use the fast evaluation mode"))
+            .arg(arg!(warnings_as_errors: -W --warnings_as_errors "Report the warnings as errors"))
             .arg(arg!(arguments: -D --argument <arguments> ... "Specify the top-level argument").num_args(1..))
             .arg(arg!(path_selector: -S --path_selector <path_selector> ... "Specify the path selector").num_args(1..))
             .arg(arg!(overrides: -O --overrides <overrides> ... "Specify the configuration override path and value").num_args(1..))
@@ -54,6 +54,7 @@ pub(crate) fn build_settings(matches: &ArgMatches) -> Result<SettingsPathBuf> {
                 sort_keys: bool_from_matches(matches, "sort_keys"),
                 show_hidden: bool_from_matches(matches, "show_hidden"),
                 fast_eval: bool_from_matches(matches, "fast_eval"),
+                warnings_as_errors: bool_from_matches(matches, "warnings_as_errors"),
                 package_maps,
                 ..Default::default()
             }),
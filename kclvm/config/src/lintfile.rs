@@ -0,0 +1,185 @@
+//! Copyright The KCL Authors. All rights reserved.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    io::Read,
+    path::{Path, PathBuf},
+};
+use toml;
+
+pub const KCL_LINT_FILE: &str = ".kcllint.toml";
+
+/// LintFile is the lint configuration file '.kcllint.toml' of a directory, e.g.
+///
+/// ```toml
+/// [lints]
+/// UnusedImportWarning = false
+///
+/// [severity]
+/// ReimportWarning = "error"
+/// ```
+///
+/// The lints are named by their warning kinds. A lint file applies to all the files in the
+/// directory and its subdirectories, and it is merged with the lint files of the parent
+/// directories, see [lookup_lint_file].
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct LintFile {
+    /// Whether each lint is enabled, all the lints are enabled by default.
+    pub lints: Option<HashMap<String, bool>>,
+    /// The severity overrides of the lints.
+    pub severity: Option<HashMap<String, LintSeverity>>,
+}
+
+/// The severity which a lint is reported with.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl LintFile {
+    /// Whether the lint is enabled, returns `true` if the lint is not configured.
+    pub fn is_enabled(&self, lint: &str) -> bool {
+        self.lints
+            .as_ref()
+            .and_then(|lints| lints.get(lint).cloned())
+            .unwrap_or(true)
+    }
+
+    /// Returns the severity override of the lint.
+    pub fn get_severity(&self, lint: &str) -> Option<LintSeverity> {
+        self.severity
+            .as_ref()
+            .and_then(|severity| severity.get(lint).cloned())
+    }
+
+    /// Returns the names of the lints configured by the lint file which are not in
+    /// `known_lints`, e.g. the misspelled ones, sorted by the names.
+    pub fn unknown_lints(&self, known_lints: &[String]) -> Vec<String> {
+        let mut lints: Vec<String> = self
+            .lints
+            .iter()
+            .flat_map(|lints| lints.keys())
+            .chain(self.severity.iter().flat_map(|severity| severity.keys()))
+            .filter(|lint| !known_lints.contains(lint))
+            .cloned()
+            .collect();
+        lints.sort();
+        lints.dedup();
+        lints
+    }
+
+    /// Merges the lint file of a subdirectory into the current one, the settings of `other` win
+    /// on conflicts.
+    pub fn merge(&mut self, other: LintFile) {
+        if let Some(lints) = other.lints {
+            self.lints.get_or_insert_with(HashMap::new).extend(lints);
+        }
+        if let Some(severity) = other.severity {
+            self.severity
+                .get_or_insert_with(HashMap::new)
+                .extend(severity);
+        }
+    }
+}
+
+/// The error of a lint file, which is reported at the lint file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LintFileError {
+    pub kind: LintFileErrorKind,
+    /// The path of the lint file.
+    pub path: PathBuf,
+    /// The 0-based line and column of the error in the lint file.
+    pub line_col: Option<(usize, usize)>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LintFileErrorKind {
+    /// The lint file is not a valid lint file, so it is skipped.
+    Invalid,
+    /// The lint file configures a lint which does not exist, e.g. a misspelled one.
+    UnknownLint,
+}
+
+/// Load kcl lint file from path
+pub fn load_lint_file<P: AsRef<Path>>(path: P) -> Result<LintFile> {
+    let file_path = path.as_ref().join(KCL_LINT_FILE);
+    let mut file = std::fs::File::open(file_path)?;
+    let mut buffer: Vec<u8> = vec![];
+    file.read_to_end(&mut buffer)?;
+    toml::from_slice(buffer.as_slice()).map_err(|e| anyhow::anyhow!(e))
+}
+
+/// Parse the content of a lint file, the error holds the position of the malformed content.
+pub fn parse_lint_file(content: &str) -> Result<LintFile, toml::de::Error> {
+    toml::from_str(content)
+}
+
+/// Returns the lint configuration of the directory `dir`, which merges all the lint files from
+/// the root directory down to `dir`, so the nearest lint file wins on conflicts. The lint files
+/// which are missing or invalid are skipped.
+pub fn lookup_lint_file<P: AsRef<Path>>(dir: P) -> LintFile {
+    lookup_lint_file_with_errors(dir, None).0
+}
+
+/// Same as [lookup_lint_file], the lint files which are invalid or configure the lints not in
+/// `known_lints` are also returned as the errors.
+pub fn lookup_lint_file_with_errors<P: AsRef<Path>>(
+    dir: P,
+    known_lints: Option<&[String]>,
+) -> (LintFile, Vec<LintFileError>) {
+    let dir = dir
+        .as_ref()
+        .canonicalize()
+        .unwrap_or_else(|_| dir.as_ref().to_path_buf());
+    let mut lint_files = vec![];
+    let mut errors = vec![];
+    for dir in dir.ancestors() {
+        let path = dir.join(KCL_LINT_FILE);
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(_) => continue,
+        };
+        match parse_lint_file(&content) {
+            Ok(lint_file) => {
+                if let Some(known_lints) = known_lints {
+                    for lint in lint_file.unknown_lints(known_lints) {
+                        errors.push(LintFileError {
+                            kind: LintFileErrorKind::UnknownLint,
+                            path: path.clone(),
+                            line_col: key_line_col(&content, &lint),
+                            message: format!("unknown lint '{}'", lint),
+                        });
+                    }
+                }
+                lint_files.push(lint_file);
+            }
+            Err(err) => errors.push(LintFileError {
+                kind: LintFileErrorKind::Invalid,
+                line_col: err.line_col(),
+                message: format!("invalid lint file: {}", err),
+                path,
+            }),
+        }
+    }
+    let mut lint_file = LintFile::default();
+    while let Some(other) = lint_files.pop() {
+        lint_file.merge(other);
+    }
+    (lint_file, errors)
+}
+
+/// Returns the position of the first declaration of the key in the content, e.g. `Lint = false`.
+fn key_line_col(content: &str, key: &str) -> Option<(usize, usize)> {
+    content.lines().enumerate().find_map(|(line, text)| {
+        let (name, _) = text.split_once('=')?;
+        let name = name.trim();
+        (name.trim_matches('"').trim_matches('\'') == key)
+            .then(|| (line, text.len() - text.trim_start().len()))
+    })
+}
@@ -190,6 +190,24 @@ pub fn load_mod_file<P: AsRef<Path>>(path: P) -> Result<ModFile> {
     toml::from_slice(buffer.as_slice()).map_err(|e| anyhow::anyhow!(e))
 }
 
+/// Parse the content of a kcl mod file, the error holds the position of the malformed content.
+pub fn parse_mod_file(content: &str) -> Result<ModFile, toml::de::Error> {
+    toml::from_str(content)
+}
+
+/// Returns whether all the keys of the content of a kcl mod file are the fields of [ModFile], so
+/// the parsed [ModFile] holds all of its data, e.g. there is no misspelled or unknown key.
+pub fn has_only_mod_file_fields(content: &str) -> bool {
+    let value: toml::Value = match toml::from_str(content) {
+        Ok(value) => value,
+        Err(_) => return false,
+    };
+    match parse_mod_file(content).map(toml::Value::try_from) {
+        Ok(Ok(parsed)) => parsed == value,
+        _ => false,
+    }
+}
+
 /// Load kcl mod lock file from path
 pub fn load_mod_lock_file<P: AsRef<Path>>(path: P) -> Result<ModLockFile> {
     let file_path = path.as_ref().join(KCL_MOD_LOCK_FILE);
@@ -386,4 +404,18 @@ mod modfile_test {
             }))
         );
     }
+
+    #[test]
+    fn test_has_only_mod_file_fields() {
+        assert!(has_only_mod_file_fields(
+            "[package]\nname = \"app\"\n\n[dependencies]\nk8s = \"1.28\"\n"
+        ));
+        assert!(!has_only_mod_file_fields(
+            "[package]\nname = \"app\"\nauthors = [\"kcl\"]\n"
+        ));
+        assert!(!has_only_mod_file_fields(
+            "[dependencies]\nhelper = { path = \"../helper\", version = \"0.1.0\" }\n"
+        ));
+        assert!(!has_only_mod_file_fields("[package\n"));
+    }
 }
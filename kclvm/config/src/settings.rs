@@ -68,6 +68,8 @@ pub struct Config {
     pub package_maps: Option<HashMap<String, String>>,
     /// Use the evaluator to execute the AST program instead of AOT.
     pub fast_eval: Option<bool>,
+    /// Report the compile warnings as errors.
+    pub warnings_as_errors: Option<bool>,
 }
 
 impl SettingsFile {
@@ -86,6 +88,7 @@ impl SettingsFile {
                 sort_keys: Some(false),
                 show_hidden: Some(false),
                 fast_eval: Some(false),
+                warnings_as_errors: Some(false),
                 include_schema_type_path: Some(false),
                 package_maps: Some(HashMap::default()),
             }),
@@ -388,6 +391,7 @@ pub fn merge_settings(settings: &[SettingsFile]) -> SettingsFile {
                 set_if!(result_kcl_cli_configs, sort_keys, kcl_cli_configs);
                 set_if!(result_kcl_cli_configs, show_hidden, kcl_cli_configs);
                 set_if!(result_kcl_cli_configs, fast_eval, kcl_cli_configs);
+                set_if!(result_kcl_cli_configs, warnings_as_errors, kcl_cli_configs);
                 set_if!(
                     result_kcl_cli_configs,
                     include_schema_type_path,
@@ -486,6 +490,7 @@ mod settings_test {
             assert!(kcl_cli_configs.include_schema_type_path.is_none());
             assert!(kcl_cli_configs.show_hidden.is_none());
             assert!(kcl_cli_configs.fast_eval.is_none());
+            assert!(kcl_cli_configs.warnings_as_errors.is_none());
             assert_eq!(kcl_cli_configs.sort_keys, Some(true));
             if let Some(config_files) = kcl_cli_configs.files {
                 assert!(config_files == files);
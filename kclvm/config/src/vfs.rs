@@ -1,6 +1,7 @@
 use std::path::Path;
 
 use kclvm_utils::path::PathPrefix;
+use serde::{Deserialize, Serialize};
 
 extern crate pathdiff;
 
@@ -29,6 +30,60 @@ pub fn is_rel_pkgpath(pkgpath: &str) -> bool {
     pkgpath.starts_with('.')
 }
 
+/// The base which the relative import paths, e.g. `import .sub`, are resolved against.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RelativeImportBase {
+    /// The directory of the importing file, e.g. `.sub` in `app/main.k` is `app.sub`, which is
+    /// the default and the same as `kcl run`.
+    #[default]
+    FileDir,
+    /// The package root, e.g. `.sub` in `app/main.k` is `sub`.
+    PkgRoot,
+    /// The directory of the importing file if the package exists there, otherwise the package
+    /// root, which is the order of the paths searched for the package.
+    SearchPath,
+}
+
+/// Same as [fix_import_path] with the relative import paths resolved against the `base`.
+pub fn fix_import_path_with_base(
+    root: &str,
+    filepath: &str,
+    import_path: &str,
+    base: RelativeImportBase,
+) -> String {
+    match base {
+        RelativeImportBase::FileDir => fix_import_path(root, filepath, import_path),
+        // A file right under the package root has the root package path.
+        RelativeImportBase::PkgRoot => fix_import_path(
+            root,
+            &Path::new(root).join("main.k").to_string_lossy(),
+            import_path,
+        ),
+        RelativeImportBase::SearchPath => {
+            let path = fix_import_path(root, filepath, import_path);
+            if !path.is_empty() && pkg_exists(root, &path) {
+                return path;
+            }
+            match fix_import_path_with_base(
+                root,
+                filepath,
+                import_path,
+                RelativeImportBase::PkgRoot,
+            ) {
+                root_path if !root_path.is_empty() && pkg_exists(root, &root_path) => root_path,
+                _ => path,
+            }
+        }
+    }
+}
+
+/// Whether the package path is a directory or a KCL file under the root.
+fn pkg_exists(root: &str, pkgpath: &str) -> bool {
+    let path = Path::new(root).join(pkgpath.replace('.', "/"));
+    path.is_dir() || path.with_extension("k").is_file()
+}
+
 pub fn fix_import_path(root: &str, filepath: &str, import_path: &str) -> String {
     // relpath: import .sub
     // fix_import_path(root, "path/to/app/file.k", ".sub")        => path.to.app.sub
@@ -124,3 +179,29 @@ fn test_fix_import_path() {
     let s = fix_import_path(root, "path/to/app/file.k", ".....sub");
     assert_eq!(s, "");
 }
+
+#[test]
+fn test_fix_import_path_with_base() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("testdata")
+        .join("relative_import");
+    let file = root.join("app").join("main.k");
+    let (root, file) = (root.to_str().unwrap(), file.to_str().unwrap());
+    let fix = |import_path: &str, base: RelativeImportBase| {
+        fix_import_path_with_base(root, file, import_path, base)
+    };
+
+    assert_eq!(fix(".sub", RelativeImportBase::FileDir), "app.sub");
+    assert_eq!(fix(".sub", RelativeImportBase::PkgRoot), "sub");
+    assert_eq!(fix("..sub", RelativeImportBase::PkgRoot), "");
+    // `app/sub` exists next to the file, while `common` exists only under the package root.
+    assert_eq!(fix(".sub", RelativeImportBase::SearchPath), "app.sub");
+    assert_eq!(fix(".common", RelativeImportBase::SearchPath), "common");
+    assert_eq!(
+        fix(".missing", RelativeImportBase::SearchPath),
+        "app.missing"
+    );
+    // The absolute import paths are not changed by any of the bases.
+    assert_eq!(fix("sub", RelativeImportBase::PkgRoot), "sub");
+}
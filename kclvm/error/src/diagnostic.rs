@@ -3,7 +3,7 @@ use kclvm_span::Loc;
 use std::fmt;
 use std::hash::Hash;
 
-use crate::{ErrorKind, WarningKind};
+use crate::{DiagnosticCategory, ErrorKind, WarningKind};
 
 pub type Errors = IndexSet<Diagnostic>;
 
@@ -158,6 +158,18 @@ pub enum DiagnosticId {
     Suggestions,
 }
 
+impl DiagnosticId {
+    /// Returns the category of the diagnostic, the suggestions go with the errors they help to
+    /// fix and are categorized as correctness.
+    pub fn category(&self) -> DiagnosticCategory {
+        match self {
+            DiagnosticId::Error(kind) => kind.category(),
+            DiagnosticId::Warning(kind) => kind.category(),
+            DiagnosticId::Suggestions => DiagnosticCategory::Correctness,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Level {
     Error,
@@ -50,6 +50,7 @@ register_errors! {
     E2A31: ErrorKind::IllegalAttributeError, include_str!("./error_codes/E2A31.md"),
     E2L28: ErrorKind::UniqueKeyError, include_str!("./error_codes/E2L28.md"),
     E2D34: ErrorKind::IllegalInheritError, include_str!("./error_codes/E2D34.md"),
+    E2N35: ErrorKind::AmbiguousReferenceError, include_str!("./error_codes/E2N35.md"),
     // E3XXX Runtime Errors
     E3M38: ErrorKind::EvaluationError, include_str!("./error_codes/E2D34.md"),
 }
@@ -89,6 +90,7 @@ pub enum ErrorKind {
     IndexSignatureError,
     TypeError,
     NameError,
+    AmbiguousReferenceError,
     ValueError,
     KeyError,
     AttributeError,
@@ -126,6 +128,13 @@ impl ErrorKind {
             None => E1001.code.to_string(),
         }
     }
+    /// Returns the error category.
+    pub fn category(&self) -> DiagnosticCategory {
+        match self {
+            ErrorKind::Deprecated => DiagnosticCategory::Deprecation,
+            _ => DiagnosticCategory::Correctness,
+        }
+    }
 }
 
 /// Warning information of KCL. Usually something that does not conform to the specification but does not cause an error.
@@ -144,6 +153,23 @@ pub enum WarningKind {
     UnusedImportWarning,
     ReimportWarning,
     ImportPositionWarning,
+    DuplicateAssignmentWarning,
+    PrecisionLossWarning,
+    UnusedSchemaWarning,
+    ConstantAssertWarning,
+    ImplicitCoercionWarning,
+    UnusedPrivateSymbolWarning,
+    LineEndingWarning,
+    DefaultViolatesCheckWarning,
+    TodoCommentWarning,
+    IncompatibleComparisonWarning,
+    UnsatisfiableCheckWarning,
+    MissingTypeAnnotationWarning,
+    MissingCheckMessageWarning,
+    InconsistentIndentationWarning,
+    BuiltinShadowingWarning,
+    DeprecatedBuiltinWarning,
+    MagicNumberWarning,
 }
 
 /// Test warning `fmt`
@@ -198,6 +224,30 @@ impl std::fmt::Display for WarningKind {
 /// }
 /// ```
 impl WarningKind {
+    /// All the warning kinds, e.g. to check the lint names of the lint files.
+    pub const ALL: &'static [WarningKind] = &[
+        WarningKind::CompilerWarning,
+        WarningKind::UnusedImportWarning,
+        WarningKind::ReimportWarning,
+        WarningKind::ImportPositionWarning,
+        WarningKind::DuplicateAssignmentWarning,
+        WarningKind::PrecisionLossWarning,
+        WarningKind::UnusedSchemaWarning,
+        WarningKind::ConstantAssertWarning,
+        WarningKind::ImplicitCoercionWarning,
+        WarningKind::UnusedPrivateSymbolWarning,
+        WarningKind::LineEndingWarning,
+        WarningKind::DefaultViolatesCheckWarning,
+        WarningKind::TodoCommentWarning,
+        WarningKind::IncompatibleComparisonWarning,
+        WarningKind::UnsatisfiableCheckWarning,
+        WarningKind::MissingTypeAnnotationWarning,
+        WarningKind::MissingCheckMessageWarning,
+        WarningKind::InconsistentIndentationWarning,
+        WarningKind::BuiltinShadowingWarning,
+        WarningKind::DeprecatedBuiltinWarning,
+        WarningKind::MagicNumberWarning,
+    ];
     /// Returns the warning name.
     pub fn name(&self) -> String {
         format!("{self:?}")
@@ -212,4 +262,61 @@ impl WarningKind {
             None => W1001.code.to_string(),
         }
     }
+    /// Returns the warning category.
+    pub fn category(&self) -> DiagnosticCategory {
+        match self {
+            WarningKind::UnusedImportWarning
+            | WarningKind::ReimportWarning
+            | WarningKind::ImportPositionWarning
+            | WarningKind::UnusedSchemaWarning
+            | WarningKind::UnusedPrivateSymbolWarning
+            | WarningKind::LineEndingWarning
+            | WarningKind::TodoCommentWarning
+            | WarningKind::MissingTypeAnnotationWarning
+            | WarningKind::MissingCheckMessageWarning
+            | WarningKind::InconsistentIndentationWarning
+            | WarningKind::MagicNumberWarning => DiagnosticCategory::Style,
+            WarningKind::CompilerWarning
+            | WarningKind::DuplicateAssignmentWarning
+            | WarningKind::PrecisionLossWarning
+            | WarningKind::ConstantAssertWarning
+            | WarningKind::ImplicitCoercionWarning
+            | WarningKind::DefaultViolatesCheckWarning
+            | WarningKind::IncompatibleComparisonWarning
+            | WarningKind::UnsatisfiableCheckWarning
+            | WarningKind::BuiltinShadowingWarning => DiagnosticCategory::Correctness,
+            WarningKind::DeprecatedBuiltinWarning => DiagnosticCategory::Deprecation,
+        }
+    }
+}
+
+/// The category of the errors and warnings, which is used to group the diagnostics, e.g.
+///
+/// ```
+/// use kclvm_error::*;
+/// assert_eq!(ErrorKind::TypeError.category().name(), "correctness");
+/// assert_eq!(WarningKind::UnusedImportWarning.category().name(), "style");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiagnosticCategory {
+    /// The code is wrong or very likely to be wrong.
+    Correctness,
+    /// The code is correct but not idiomatic.
+    Style,
+    /// The code uses a deprecated feature.
+    Deprecation,
+    /// The code is correct but can be slow.
+    Performance,
+}
+
+impl DiagnosticCategory {
+    /// Returns the category name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            DiagnosticCategory::Correctness => "correctness",
+            DiagnosticCategory::Style => "style",
+            DiagnosticCategory::Deprecation => "deprecation",
+            DiagnosticCategory::Performance => "performance",
+        }
+    }
 }
@@ -0,0 +1,55 @@
+//! The GitHub Actions workflow commands of the diagnostics, e.g.
+//! `::error file=main.k,line=1,col=5,title=TypeError::expected int, got str`, which are shown as
+//! the inline annotations of the pull requests when printed in a workflow step.
+
+use crate::diagnostic::{Diagnostic, DiagnosticId, Level};
+
+/// Returns the workflow command of the diagnostic on one line. The position is the 1-based line
+/// and column of the first message, whose note is appended to the message on a new line.
+pub(crate) fn workflow_command(diag: &Diagnostic) -> Option<String> {
+    let msg = diag.messages.first()?;
+    let command = match diag.level {
+        Level::Error => "error",
+        Level::Warning => "warning",
+        Level::Note | Level::Suggestions => "notice",
+    };
+    let mut properties = vec![];
+    let start = &msg.range.0;
+    if !start.filename.is_empty() {
+        properties.push(format!("file={}", escape_property(&start.filename)));
+        if start.is_valid() {
+            properties.push(format!("line={}", start.line));
+            if let Some(column) = start.column {
+                properties.push(format!("col={}", column + 1));
+            }
+        }
+    }
+    let title = match &diag.code {
+        Some(DiagnosticId::Error(kind)) => kind.name(),
+        Some(DiagnosticId::Warning(kind)) => kind.name(),
+        Some(DiagnosticId::Suggestions) | None => diag.level.to_str().to_string(),
+    };
+    properties.push(format!("title={}", escape_property(&title)));
+    let message = match &msg.note {
+        Some(note) => format!("{}\n{}", msg.message, note),
+        None => msg.message.clone(),
+    };
+    Some(format!(
+        "::{} {}::{}",
+        command,
+        properties.join(","),
+        escape_data(&message)
+    ))
+}
+
+/// Escapes the message of a command, whose line breaks would end the command.
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escapes the value of a command property, where `:` and `,` separate the properties.
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
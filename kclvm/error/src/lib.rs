@@ -5,6 +5,7 @@
 
 pub mod diagnostic;
 mod error;
+mod github;
 
 use annotate_snippets::{
     display_list::DisplayList,
@@ -51,6 +52,38 @@ impl Handler {
         compiler_base_macros::bug!("{}", msg)
     }
 
+    /// Promotes all the warnings to errors, which is the `--warnings-as-errors` mode, so that
+    /// [Handler::has_errors] also reports the warnings. Notes and suggestions are unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kclvm_error::*;
+    /// let mut handler = Handler::default();
+    /// handler.add_warning(WarningKind::UnusedImportWarning, &[Message {
+    ///     range: (Position::dummy_pos(), Position::dummy_pos()),
+    ///     style: Style::LineAndColumn,
+    ///     message: "Module 'a' imported but unused.".to_string(),
+    ///     note: None,
+    ///     suggested_replacement: None,
+    /// }]);
+    /// assert!(!handler.has_errors());
+    /// handler.promote_warnings_to_errors();
+    /// assert!(handler.has_errors());
+    /// ```
+    pub fn promote_warnings_to_errors(&mut self) -> &mut Self {
+        self.diagnostics = std::mem::take(&mut self.diagnostics)
+            .into_iter()
+            .map(|mut diag| {
+                if diag.level == Level::Warning {
+                    diag.level = Level::Error;
+                }
+                diag
+            })
+            .collect();
+        self
+    }
+
     #[inline]
     pub fn has_errors(&self) -> bool {
         self.diagnostics
@@ -82,6 +115,47 @@ impl Handler {
         Ok(error_strings.join("\n"))
     }
 
+    /// Emit the diagnostics as the GitHub Actions workflow commands, one command per line, which
+    /// are shown as the annotations of the files when printed in a workflow.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kclvm_error::*;
+    /// let pos = |line, column| Position {
+    ///     filename: "main.k".to_string(),
+    ///     line,
+    ///     column: Some(column),
+    /// };
+    /// let mut handler = Handler::default();
+    /// handler.add_error(ErrorKind::TypeError, &[Message {
+    ///     range: (pos(3, 4), pos(3, 9)),
+    ///     style: Style::LineAndColumn,
+    ///     message: "expected int, got str(\"80\")".to_string(),
+    ///     note: None,
+    ///     suggested_replacement: None,
+    /// }]);
+    /// handler.add_warning(WarningKind::UnusedImportWarning, &[Message {
+    ///     range: (pos(1, 0), pos(1, 11)),
+    ///     style: Style::LineAndColumn,
+    ///     message: "Module 'math' imported but unused".to_string(),
+    ///     note: Some("Consider removing this statement".to_string()),
+    ///     suggested_replacement: None,
+    /// }]);
+    /// assert_eq!(
+    ///     handler.emit_to_github_workflow_commands(),
+    ///     "::error file=main.k,line=3,col=5,title=TypeError::expected int, got str(\"80\")\n\
+    ///      ::warning file=main.k,line=1,col=1,title=UnusedImportWarning::Module 'math' imported but unused%0AConsider removing this statement"
+    /// );
+    /// ```
+    pub fn emit_to_github_workflow_commands(&self) -> String {
+        self.diagnostics
+            .iter()
+            .filter_map(github::workflow_command)
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
     /// Emit all diagnostics and abort if has any errors.
     pub fn abort_if_any_errors(&mut self) {
         match self.emit() {
@@ -21,6 +21,7 @@ use indexmap::IndexMap;
 use kclvm_ast::ast::Module;
 use kclvm_ast::{ast, MAIN_PKG};
 use kclvm_config::modfile::{get_vendor_home, KCL_FILE_EXTENSION, KCL_FILE_SUFFIX, KCL_MOD_FILE};
+use kclvm_config::vfs::RelativeImportBase;
 use kclvm_error::diagnostic::{Errors, Range};
 use kclvm_error::{ErrorKind, Message, Position, Style};
 use kclvm_sema::plugin::PLUGIN_MODULE_PREFIX;
@@ -262,6 +263,9 @@ pub struct LoadProgramOptions {
     pub load_packages: bool,
     /// Whether to load plugins
     pub load_plugins: bool,
+    /// The base which the relative import paths are resolved against, the directory of the
+    /// importing file by default.
+    pub relative_import_base: RelativeImportBase,
 }
 
 impl Default for LoadProgramOptions {
@@ -274,6 +278,7 @@ impl Default for LoadProgramOptions {
             mode: ParseMode::ParseComments,
             load_packages: true,
             load_plugins: false,
+            relative_import_base: RelativeImportBase::default(),
         }
     }
 }
@@ -396,10 +401,11 @@ fn fix_rel_import_path_with_file(
     for stmt in &mut m.body {
         let pos = stmt.pos().clone();
         if let ast::Stmt::Import(ref mut import_spec) = &mut stmt.node {
-            let fix_path = kclvm_config::vfs::fix_import_path(
+            let fix_path = kclvm_config::vfs::fix_import_path_with_base(
                 pkgroot,
                 &m.filename,
                 import_spec.path.node.as_str(),
+                opts.relative_import_base,
             );
             import_spec.path.node = fix_path.clone();
 
@@ -747,10 +753,11 @@ pub fn get_deps(
         let pos = stmt.pos().clone();
         let pkg = pkgmap.get(file).expect("file not in pkgmap").clone();
         if let ast::Stmt::Import(import_spec) = &stmt.node {
-            let fix_path = kclvm_config::vfs::fix_import_path(
+            let fix_path = kclvm_config::vfs::fix_import_path_with_base(
                 &pkg.pkg_root,
                 &m.filename,
                 import_spec.path.node.as_str(),
+                opts.relative_import_base,
             );
             let pkg_info = find_packages(
                 pos.into(),
@@ -7,19 +7,22 @@ use std::{
 
 use anyhow::{anyhow, bail, Result};
 use assembler::KclvmLibAssembler;
+use indexmap::IndexMap;
 use kclvm_ast::{
-    ast::{Module, Program},
+    ast::{Identifier, Module, NodeRef, Program, SchemaAttr, Stmt},
+    walker::MutSelfWalker,
     MAIN_PKG,
 };
+use kclvm_ast_pretty::{print_ast_node, ASTNode};
 use kclvm_config::cache::KCL_CACHE_PATH_ENV_VAR;
-use kclvm_parser::{load_program, KCLModuleCache, ParseSessionRef};
+use kclvm_parser::{load_program, parse_file_force_errors, KCLModuleCache, ParseSessionRef};
 use kclvm_query::apply_overrides;
 use kclvm_sema::resolver::{
     resolve_program, resolve_program_with_opts, scope::ProgramScope, Options,
 };
 use kclvm_utils::fslock::open_lock_file;
 use linker::Command;
-pub use runner::{Artifact, ExecProgramArgs, ExecProgramResult, MapErrorResult};
+pub use runner::{Artifact, ExecProgramArgs, ExecProgramResult, MapErrorResult, SandboxOptions};
 use runner::{FastRunner, RunnerOptions};
 #[cfg(feature = "llvm")]
 use runner::{LibRunner, ProgramRunner};
@@ -104,6 +107,165 @@ pub fn exec_program(sess: ParseSessionRef, args: &ExecProgramArgs) -> Result<Exe
     execute(sess, program, args)
 }
 
+/// The schema and the variable synthesized by [`eval_attr_default`].
+const ATTR_DEFAULT_SCHEMA: &str = "KclAttrDefault";
+const ATTR_DEFAULT_VAR: &str = "_kcl_attr_default";
+
+/// Evaluates the default value of a schema attribute without constructing an instance of the
+/// schema, so the required attributes of the schema do not need to be set, e.g.
+///
+/// ```kcl
+/// schema Server:
+///     name: str
+///     port: int = 8080
+///     url: str = "http://localhost:${port}"
+/// ```
+///
+/// The default of `url` is `"http://localhost:8080"`, the attributes referenced by the default
+/// expression are evaluated with their own defaults. Returns an error when the attribute has no
+/// default or its default depends on an attribute without default, e.g. `name`.
+///
+/// The result is the value in the JSON format. The attributes inherited from the base schemas in
+/// the main package are supported.
+pub fn eval_attr_default(
+    sess: ParseSessionRef,
+    args: &ExecProgramArgs,
+    schema_name: &str,
+    attr_name: &str,
+) -> Result<String> {
+    let opts = args.get_load_program_options();
+    let kcl_paths_str = args
+        .k_filename_list
+        .iter()
+        .map(|s| s.as_str())
+        .collect::<Vec<&str>>();
+    let program = load_program(sess.clone(), kcl_paths_str.as_slice(), Some(opts), None)?.program;
+    let (filename, attrs) = schema_attrs(&program, schema_name)?;
+    let mut defaults = vec![];
+    collect_attr_defaults(&attrs, attr_name, &mut vec![], &mut defaults)?;
+    // Synthesize a schema with only the attribute and its dependencies in the module declaring
+    // the schema, so the defaults are evaluated with the imports and the globals of the module.
+    let mut code = format!("schema {}:\n", ATTR_DEFAULT_SCHEMA);
+    for stmt in defaults {
+        for line in print_ast_node(ASTNode::Stmt(stmt)).lines() {
+            code.push_str(&format!("    {}\n", line));
+        }
+    }
+    code.push_str(&format!(
+        "{} = {} {{}}.{}\n",
+        ATTR_DEFAULT_VAR, ATTR_DEFAULT_SCHEMA, attr_name
+    ));
+    let synthesized = parse_file_force_errors(&filename, Some(code))?;
+    match program.get_module_mut(&filename)? {
+        Some(mut module) => module.body.extend(synthesized.body),
+        None => bail!("module {} not found in program", filename),
+    }
+    let mut args = args.clone();
+    args.show_hidden = true;
+    let result = execute(sess, program, &args)?;
+    if !result.err_message.is_empty() {
+        bail!(result.err_message);
+    }
+    let value: serde_json::Value = serde_json::from_str(&result.json_result)?;
+    match value.get(ATTR_DEFAULT_VAR) {
+        Some(value) => Ok(value.to_string()),
+        None => bail!(
+            "failed to evaluate the default of {}.{}",
+            schema_name,
+            attr_name
+        ),
+    }
+}
+
+/// Returns the file declaring the schema in the main package and the attribute statements of the
+/// schema and its base schemas, an attribute of the schema overrides the one of the base schema.
+fn schema_attrs(
+    program: &Program,
+    schema_name: &str,
+) -> Result<(String, IndexMap<String, NodeRef<Stmt>>)> {
+    for module in program.get_modules_for_pkg(MAIN_PKG) {
+        let module = match module.read() {
+            Ok(module) => module,
+            Err(_) => bail!("Failed to acquire module lock"),
+        };
+        for stmt in &module.body {
+            if let Stmt::Schema(schema_stmt) = &stmt.node {
+                if schema_stmt.name.node != schema_name {
+                    continue;
+                }
+                let mut attrs = match &schema_stmt.parent_name {
+                    Some(parent_name) => schema_attrs(program, &parent_name.node.get_name())?.1,
+                    None => IndexMap::new(),
+                };
+                for stmt in &schema_stmt.body {
+                    if let Stmt::SchemaAttr(attr) = &stmt.node {
+                        attrs.insert(attr.name.node.clone(), stmt.clone());
+                    }
+                }
+                return Ok((module.filename.clone(), attrs));
+            }
+        }
+    }
+    bail!("schema {} not found in the main package", schema_name)
+}
+
+/// Collects the attribute and the attributes its default depends on, the dependencies come first.
+fn collect_attr_defaults<'a>(
+    attrs: &'a IndexMap<String, NodeRef<Stmt>>,
+    attr_name: &str,
+    visiting: &mut Vec<String>,
+    defaults: &mut Vec<&'a NodeRef<Stmt>>,
+) -> Result<()> {
+    let stmt = match attrs.get(attr_name) {
+        Some(stmt) => stmt,
+        None => bail!("attribute {} not found in the schema", attr_name),
+    };
+    if visiting.iter().any(|name| name == attr_name)
+        || defaults.iter().any(|default| std::ptr::eq(*default, stmt))
+    {
+        return Ok(());
+    }
+    let value = match &stmt.node {
+        Stmt::SchemaAttr(SchemaAttr {
+            value: Some(value), ..
+        }) => value,
+        _ => bail!("attribute {} has no default value", attr_name),
+    };
+    let mut collector = NameCollector::default();
+    collector.walk_expr(&value.node);
+    visiting.push(attr_name.to_string());
+    for name in collector.names {
+        if name == attr_name || !attrs.contains_key(&name) {
+            continue;
+        }
+        if let Err(err) = collect_attr_defaults(attrs, &name, visiting, defaults) {
+            bail!(
+                "the default of {} depends on the attribute {}: {}",
+                attr_name,
+                name,
+                err
+            );
+        }
+    }
+    visiting.pop();
+    defaults.push(stmt);
+    Ok(())
+}
+
+/// Collects the first names of the identifiers in an expression.
+#[derive(Default)]
+struct NameCollector {
+    names: Vec<String>,
+}
+
+impl MutSelfWalker for NameCollector {
+    fn walk_identifier(&mut self, identifier: &Identifier) {
+        if let Some(name) = identifier.names.first() {
+            self.names.push(name.node.clone());
+        }
+    }
+}
+
 /// Execute the KCL artifact with args.
 pub fn exec_artifact<P: AsRef<OsStr>>(
     path: P,
@@ -181,17 +343,21 @@ pub fn execute(
         let mut resolve_opts = Options::default();
         resolve_opts.merge_program = false;
         // Resolve ast
-        let scope = resolve_program_with_opts(&mut program, resolve_opts, None);
+        let mut scope = resolve_program_with_opts(&mut program, resolve_opts, None);
+        promote_warnings(&mut scope, args);
         emit_compile_diag_to_string(sess, &scope, args.compile_only)?;
         return Ok(ExecProgramResult::default());
     }
     // Resolve ast
-    let scope = resolve_program(&mut program);
+    let mut scope = resolve_program(&mut program);
+    promote_warnings(&mut scope, args);
     // Emit parse and resolve errors if exists.
     emit_compile_diag_to_string(sess, &scope, false)?;
     Ok(
-        // Use the fast evaluator to run the kcl program.
-        if args.fast_eval || std::env::var(KCL_FAST_EVAL_ENV_VAR).is_ok() {
+        // Use the fast evaluator to run the kcl program, the sandbox mode is only
+        // supported by the evaluator.
+        if args.fast_eval || args.sandbox.is_some() || std::env::var(KCL_FAST_EVAL_ENV_VAR).is_ok()
+        {
             FastRunner::new(Some(RunnerOptions {
                 plugin_agent_ptr: args.plugin_agent,
             }))
@@ -288,7 +454,8 @@ pub fn build_program<P: AsRef<Path>>(
     let mut program =
         load_program(sess.clone(), kcl_paths_str.as_slice(), Some(opts), None)?.program;
     // Resolve program.
-    let scope = resolve_program(&mut program);
+    let mut scope = resolve_program(&mut program);
+    promote_warnings(&mut scope, args);
     // Emit parse and resolve errors if exists.
     emit_compile_diag_to_string(sess, &scope, false)?;
     // When set the common package cache path, lock the package to prevent the
@@ -392,6 +559,14 @@ fn temp_file(dir: &str) -> Result<String> {
         .to_string())
 }
 
+/// Promotes the resolve warnings to errors in the [`ExecProgramArgs::warnings_as_errors`] mode, so
+/// that the warnings fail the compilation like the errors.
+fn promote_warnings(scope: &mut ProgramScope, args: &ExecProgramArgs) {
+    if args.warnings_as_errors {
+        scope.handler.promote_warnings_to_errors();
+    }
+}
+
 // [`emit_compile_diag_to_string`] will emit compile diagnostics to string, including parsing and resolving diagnostics.
 fn emit_compile_diag_to_string(
     sess: ParseSessionRef,
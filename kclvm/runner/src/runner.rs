@@ -13,12 +13,17 @@ use kclvm_error::{Diagnostic, Handler};
 use kclvm_runtime::kclvm_plugin_init;
 #[cfg(feature = "llvm")]
 use kclvm_runtime::FFIRunOptions;
-use kclvm_runtime::{Context, PanicInfo, RuntimePanicRecord};
+use kclvm_runtime::{Context, PanicInfo, RuntimePanicRecord, Sandbox, ValueRef};
 #[cfg(target_arch = "wasm32")]
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::ffi::OsStr;
 use std::os::raw::c_char;
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    cell::Cell,
+    sync::{Arc, Mutex},
+};
 
 const RESULT_SIZE: usize = 2048 * 2048;
 const KCL_DEBUG_ERROR_ENV_VAR: &str = "KCL_DEBUG_ERROR";
@@ -65,6 +70,9 @@ pub struct ExecProgramArgs {
     pub include_schema_type_path: bool,
     /// Whether to compile only.
     pub compile_only: bool,
+    /// -W --warnings_as_errors: report the compile warnings as errors.
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub warnings_as_errors: bool,
     /// plugin_agent is the address of plugin.
     #[serde(skip)]
     pub plugin_agent: u64,
@@ -72,6 +80,28 @@ pub struct ExecProgramArgs {
     /// the result without any form of compilation.
     #[serde(skip)]
     pub fast_eval: bool,
+    /// Evaluate in the sandbox mode, see [`SandboxOptions`].
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub sandbox: Option<SandboxOptions>,
+}
+
+/// SandboxOptions denotes a deterministic evaluation environment, the options are fixed by
+/// [`ExecProgramArgs::args`] and the builtin functions depending on the environment e.g.,
+/// `datetime.now`, `crypto.uuid`, `file.read_env`, `file.read` and the plugin functions return
+/// the stubbed values. Calling such a function which is not stubbed, or a function changing
+/// the files e.g., `file.write`, is an error, so the result is reproducible across runs and
+/// machines.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct SandboxOptions {
+    /// The JSON results of the calls, the key is the function name e.g., `crypto.uuid` or
+    /// `hello.say_hello` of the plugin `kcl_plugin.hello`, or the function name with the
+    /// argument for the functions taking a path, a pattern or a variable name e.g.,
+    /// `file.read_env(HOME)`.
+    pub stubs: HashMap<String, String>,
+    /// The current time in the RFC 3339 format e.g., `1998-06-06T16:26:11+08:00`. The
+    /// `datetime` functions format the time as usual, e.g. `datetime.now("%Y")` is `1998`.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub now: Option<String>,
 }
 
 impl ExecProgramArgs {
@@ -187,6 +217,7 @@ impl TryFrom<SettingsFile> for ExecProgramArgs {
             args.sort_keys = cli_configs.sort_keys.unwrap_or_default();
             args.show_hidden = cli_configs.show_hidden.unwrap_or_default();
             args.fast_eval = cli_configs.fast_eval.unwrap_or_default();
+            args.warnings_as_errors = cli_configs.warnings_as_errors.unwrap_or_default();
             args.include_schema_type_path =
                 cli_configs.include_schema_type_path.unwrap_or_default();
             for override_str in cli_configs.overrides.unwrap_or_default() {
@@ -489,6 +520,98 @@ static ONCE_PANIC_HOOK: Lazy<()> = Lazy::new(|| {
     }));
 });
 
+#[cfg(not(target_arch = "wasm32"))]
+type PanicHook = Box<dyn Fn(&std::panic::PanicHookInfo) + Sync + Send + 'static>;
+
+#[cfg(not(target_arch = "wasm32"))]
+thread_local! {
+    /// Whether the thread is running a KCL program, whose panics are recorded by the panic hook.
+    static KCL_RUNTIME_RUNNING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// The number of the runs in progress on all the threads and the panic hook installed before
+/// the first of them, which is restored after the last of them.
+#[cfg(not(target_arch = "wasm32"))]
+static PANIC_HOOK_STATE: Mutex<(usize, Option<Arc<PanicHook>>)> = Mutex::new((0, None));
+
+/// Installs the panic hook recording the runtime panics for a run. The process-wide hook is
+/// shared by the concurrent runs instead of being swapped by each of them, so a run can not
+/// restore the hook while another run is still in progress. The panics of the threads not
+/// running a KCL program are passed to the previous hook.
+#[cfg(not(target_arch = "wasm32"))]
+struct PanicHookGuard {
+    was_running: bool,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl PanicHookGuard {
+    fn enter() -> Self {
+        let mut state = PANIC_HOOK_STATE
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        if state.0 == 0 {
+            let prev_hook = Arc::new(std::panic::take_hook());
+            let fallback_hook = prev_hook.clone();
+            std::panic::set_hook(Box::new(move |info: &std::panic::PanicHookInfo| {
+                if KCL_RUNTIME_RUNNING.with(|running| running.get()) {
+                    record_runtime_panic(info);
+                } else {
+                    fallback_hook(info);
+                }
+            }));
+            state.1 = Some(prev_hook);
+        }
+        state.0 += 1;
+        Self {
+            was_running: KCL_RUNTIME_RUNNING.with(|running| running.replace(true)),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for PanicHookGuard {
+    fn drop(&mut self) {
+        KCL_RUNTIME_RUNNING.with(|running| running.set(self.was_running));
+        let mut state = PANIC_HOOK_STATE
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        state.0 -= 1;
+        if state.0 == 0 {
+            // Drop the shared hook first, which holds the other reference of the previous hook.
+            drop(std::panic::take_hook());
+            if let Some(prev_hook) = state.1.take() {
+                match Arc::try_unwrap(prev_hook) {
+                    Ok(prev_hook) => std::panic::set_hook(prev_hook),
+                    Err(prev_hook) => std::panic::set_hook(Box::new(move |info| prev_hook(info))),
+                }
+            }
+        }
+    }
+}
+
+/// Records the panic of the current thread in its runtime panic record.
+#[cfg(not(target_arch = "wasm32"))]
+fn record_runtime_panic(info: &std::panic::PanicHookInfo) {
+    KCL_RUNTIME_PANIC_RECORD.with(|record| {
+        let mut record = record.borrow_mut();
+        record.kcl_panic_info = true;
+        record.message = if let Some(s) = info.payload().downcast_ref::<&str>() {
+            s.to_string()
+        } else if let Some(s) = info.payload().downcast_ref::<&String>() {
+            (*s).clone()
+        } else if let Some(s) = info.payload().downcast_ref::<String>() {
+            (*s).clone()
+        } else {
+            "unknown runtime error".to_string()
+        };
+        if let Some(location) = info.location() {
+            record.rust_file = location.file().to_string();
+            record.rust_line = location.line() as i32;
+            record.rust_col = location.column() as i32;
+        }
+    })
+}
+
 pub struct FastRunner {
     opts: RunnerOptions,
 }
@@ -510,28 +633,7 @@ impl FastRunner {
         // because it is single threaded.
         Lazy::force(&ONCE_PANIC_HOOK);
         #[cfg(not(target_arch = "wasm32"))]
-        let prev_hook = std::panic::take_hook();
-        #[cfg(not(target_arch = "wasm32"))]
-        std::panic::set_hook(Box::new(|info: &std::panic::PanicHookInfo| {
-            KCL_RUNTIME_PANIC_RECORD.with(|record| {
-                let mut record = record.borrow_mut();
-                record.kcl_panic_info = true;
-                record.message = if let Some(s) = info.payload().downcast_ref::<&str>() {
-                    s.to_string()
-                } else if let Some(s) = info.payload().downcast_ref::<&String>() {
-                    (*s).clone()
-                } else if let Some(s) = info.payload().downcast_ref::<String>() {
-                    (*s).clone()
-                } else {
-                    "unknown runtime error".to_string()
-                };
-                if let Some(location) = info.location() {
-                    record.rust_file = location.file().to_string();
-                    record.rust_line = location.line() as i32;
-                    record.rust_col = location.column() as i32;
-                }
-            })
-        }));
+        let panic_hook = PanicHookGuard::enter();
         let evaluator_result = std::panic::catch_unwind(|| {
             if self.opts.plugin_agent_ptr > 0 {
                 #[cfg(not(target_arch = "wasm32"))]
@@ -547,7 +649,7 @@ impl FastRunner {
             evaluator.run()
         });
         #[cfg(not(target_arch = "wasm32"))]
-        std::panic::set_hook(prev_hook);
+        drop(panic_hook);
         KCL_RUNTIME_PANIC_RECORD.with(|record| {
             let record = record.borrow();
             ctx.borrow_mut().set_panic_info(&record);
@@ -608,6 +710,20 @@ pub(crate) fn args_to_ctx(program: &ast::Program, args: &ExecProgramArgs) -> Con
     for arg in &args.args {
         ctx.builtin_option_init(&arg.name, &arg.value);
     }
+    if let Some(sandbox) = &args.sandbox {
+        let mut stubs = HashMap::new();
+        for (name, value) in &sandbox.stubs {
+            let value = match ValueRef::from_json(&mut ctx, value) {
+                Ok(value) => value,
+                Err(_) => ValueRef::str(value),
+            };
+            stubs.insert(name.clone(), value);
+        }
+        ctx.sandbox = Some(Sandbox {
+            stubs,
+            now: sandbox.now.clone(),
+        });
+    }
     ctx.set_kcl_workdir(&args.work_dir.clone().unwrap_or_default());
     ctx.set_kcl_module_path(&program.root);
     ctx
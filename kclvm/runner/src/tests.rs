@@ -6,14 +6,18 @@ use crate::assembler::KclvmAssembler;
 use crate::assembler::KclvmLibAssembler;
 #[cfg(feature = "llvm")]
 use crate::assembler::LibAssembler;
+use crate::eval_attr_default;
 use crate::exec_program;
 #[cfg(feature = "llvm")]
 use crate::temp_file;
-use crate::{execute, runner::ExecProgramArgs};
+use crate::{
+    execute,
+    runner::{ExecProgramArgs, SandboxOptions},
+};
 #[cfg(feature = "llvm")]
 use anyhow::Context;
 use anyhow::Result;
-use kclvm_ast::ast::{Module, Program};
+use kclvm_ast::ast::{Argument, Module, Program};
 #[cfg(feature = "llvm")]
 use kclvm_compiler::codegen::OBJECT_FILE_SUFFIX;
 use kclvm_config::settings::load_file;
@@ -735,3 +739,144 @@ fn test_kcl_issue_1799() {
         )
     );
 }
+
+#[test]
+fn test_exec_in_sandbox() {
+    let mut args = ExecProgramArgs::default();
+    args.k_filename_list
+        .push("./src/test_sandbox/main.k".to_string());
+    args.args.push(Argument {
+        name: "env".to_string(),
+        value: "prod".to_string(),
+    });
+    let mut sandbox = SandboxOptions {
+        now: Some("1998-06-06T16:26:11+08:00".to_string()),
+        ..Default::default()
+    };
+    for (call, value) in [
+        ("crypto.uuid", "\"00000000-0000-0000-0000-000000000000\""),
+        ("file.read_env(HOME)", "\"/home/kcl\""),
+        ("file.read_env(USER)", "\"kcl\""),
+    ] {
+        sandbox.stubs.insert(call.to_string(), value.to_string());
+    }
+    args.sandbox = Some(sandbox);
+    let first = exec_program(Arc::new(ParseSession::default()), &args).unwrap();
+    let second = exec_program(Arc::new(ParseSession::default()), &args).unwrap();
+    assert!(first.err_message.is_empty(), "{}", first.err_message);
+    assert_eq!(first.json_result, second.json_result);
+    assert_eq!(first.yaml_result, second.yaml_result);
+    assert_eq!(
+        first.json_result,
+        "{\"env\": \"prod\", \"now\": \"Sat Jun 06 16:26:11 1998\", \"day\": \"1998-06-06\", \"id\": \"00000000-0000-0000-0000-000000000000\", \"home\": \"/home/kcl\", \"user\": \"kcl\"}"
+    );
+
+    // The environment variable `USER` is not stubbed.
+    let mut unstubbed = args.clone();
+    let stubs = &mut unstubbed.sandbox.as_mut().unwrap().stubs;
+    stubs.remove("file.read_env(USER)");
+    let res = exec_program(Arc::new(ParseSession::default()), &unstubbed).unwrap();
+    assert!(
+        res.err_message
+            .contains("nondeterministic call 'file.read_env(USER)' is not stubbed"),
+        "{}",
+        res.err_message
+    );
+
+    // The current time is not fixed.
+    let mut unstubbed = args.clone();
+    unstubbed.sandbox.as_mut().unwrap().now = None;
+    let res = exec_program(Arc::new(ParseSession::default()), &unstubbed).unwrap();
+    assert!(
+        res.err_message
+            .contains("the current time is not fixed in the sandbox mode"),
+        "{}",
+        res.err_message
+    );
+
+    // The files are hashed by the stubs.
+    let mut hash = args.clone();
+    hash.k_filename_list = vec!["./src/test_sandbox/hash.k".to_string()];
+    for (call, value) in [
+        ("crypto.filesha256(main.k)", "\"sha256\""),
+        ("crypto.filesha512(main.k)", "\"sha512\""),
+        ("crypto.fileblake3(main.k)", "\"blake3\""),
+    ] {
+        let stubs = &mut hash.sandbox.as_mut().unwrap().stubs;
+        stubs.insert(call.to_string(), value.to_string());
+    }
+    let res = exec_program(Arc::new(ParseSession::default()), &hash).unwrap();
+    assert!(res.err_message.is_empty(), "{}", res.err_message);
+    assert_eq!(
+        res.json_result,
+        "{\"sha256\": \"sha256\", \"sha512\": \"sha512\", \"blake3\": \"blake3\"}"
+    );
+    let stubs = &mut hash.sandbox.as_mut().unwrap().stubs;
+    stubs.remove("crypto.fileblake3(main.k)");
+    let res = exec_program(Arc::new(ParseSession::default()), &hash).unwrap();
+    assert!(
+        res.err_message
+            .contains("nondeterministic call 'crypto.fileblake3(main.k)' is not stubbed"),
+        "{}",
+        res.err_message
+    );
+
+    // The files can not be changed.
+    for (file, call) in [
+        ("write.k", "file.write"),
+        ("write_json.k", "json.dump_to_file"),
+        ("write_yaml.k", "yaml.dump_to_file"),
+        ("write_yaml_all.k", "yaml.dump_all_to_file"),
+    ] {
+        args.k_filename_list = vec![format!("./src/test_sandbox/{file}")];
+        let res = exec_program(Arc::new(ParseSession::default()), &args).unwrap();
+        assert!(
+            res.err_message
+                .contains(&format!("'{call}' is not allowed in the sandbox mode")),
+            "{}",
+            res.err_message
+        );
+        assert!(!Path::new("sandbox.txt").exists());
+    }
+}
+
+#[test]
+fn test_eval_attr_default() {
+    let mut args = ExecProgramArgs::default();
+    args.k_filename_list
+        .push("./src/test_attr_default/main.k".to_string());
+    let eval = |schema: &str, attr: &str| {
+        eval_attr_default(Arc::new(ParseSession::default()), &args, schema, attr)
+    };
+    // A constant default.
+    assert_eq!(eval("Server", "port").unwrap(), "8080");
+    // The default depends on other defaulted attributes including an inherited one.
+    assert_eq!(eval("Server", "url").unwrap(), "\"http://localhost:8080\"");
+    // The default depends on the required attribute `name`.
+    let err = eval("Server", "metrics").unwrap_err().to_string();
+    assert!(err.contains("name"), "{err}");
+    assert!(eval("Server", "name").is_err());
+    assert!(eval("NotExist", "name").is_err());
+}
+
+#[test]
+fn test_exec_warnings_as_errors() {
+    let mut args = ExecProgramArgs::default();
+    args.k_filename_list
+        .push("./src/test_warnings_as_errors/main.k".to_string());
+    // The unused import is only a warning by default.
+    let res = exec_program(Arc::new(ParseSession::default()), &args).unwrap();
+    assert!(res.err_message.is_empty(), "{}", res.err_message);
+
+    args.warnings_as_errors = true;
+    let sess = Arc::new(ParseSession::default());
+    let err = exec_program(sess.clone(), &args).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Module 'math' imported but unused"),
+        "{}",
+        err
+    );
+    // The command line aborts with a non-zero status on the errors of the session.
+    assert!(sess.0.diag_handler.has_errors().unwrap());
+}
@@ -1,6 +1,7 @@
 //! Copyright The KCL Authors. All rights reserved.
 
 use crate::{new_mut_ptr, val_plan::PlanOptions, IndexMap};
+use chrono::{DateTime, FixedOffset};
 use generational_arena::Index;
 use indexmap::IndexSet;
 use serde::{Deserialize, Serialize};
@@ -371,6 +372,23 @@ pub struct Context {
     pub plan_opts: PlanOptions,
     /// Builtin plugin functions, the key of the map is the form <module_name>.<module_func> e.g., `hello.say_hello`
     pub plugin_functions: IndexMap<String, PluginFunction>,
+    /// The environment of the sandbox mode, `None` means the sandbox mode is off.
+    pub sandbox: Option<Sandbox>,
+}
+
+/// The fixed environment of a deterministic evaluation. Every builtin function depending on the
+/// environment, e.g. `datetime.now`, `crypto.uuid`, `file.read` or a plugin function, returns
+/// the fixed value, and it is an error if the value is not fixed.
+#[derive(Clone, Default)]
+pub struct Sandbox {
+    /// The results of the calls, the key is the function name e.g., `crypto.uuid`,
+    /// `file.workdir` or `hello.say_hello` of the plugin `kcl_plugin.hello`, or the function
+    /// name with the argument for the functions taking a path, a pattern or a variable name
+    /// e.g., `file.read_env(HOME)` or `file.read(data.json)`.
+    pub stubs: HashMap<String, ValueRef>,
+    /// The current time in the RFC 3339 format e.g., `1998-06-06T16:26:11+08:00`, which the
+    /// `datetime` functions return in their formats.
+    pub now: Option<String>,
 }
 
 impl UnwindSafe for Context {}
@@ -416,6 +434,38 @@ impl Context {
             ..Default::default()
         }
     }
+
+    /// Returns the stubbed result of the call in the sandbox mode, see [`Sandbox::stubs`] for
+    /// the keys of the calls, or `None` if the sandbox mode is off. A call which is not stubbed
+    /// is an error in the sandbox mode, so that the evaluation result is always reproducible.
+    pub fn sandbox_stub(&self, call: &str) -> Option<ValueRef> {
+        let sandbox = self.sandbox.as_ref()?;
+        match sandbox.stubs.get(call) {
+            Some(value) => Some(value.clone()),
+            None => panic!("nondeterministic call '{call}' is not stubbed in the sandbox mode"),
+        }
+    }
+
+    /// Returns the fixed current time in the sandbox mode, or `None` if the sandbox mode is off.
+    pub fn sandbox_now(&self) -> Option<DateTime<FixedOffset>> {
+        let sandbox = self.sandbox.as_ref()?;
+        let now = match &sandbox.now {
+            Some(now) => now,
+            None => panic!("the current time is not fixed in the sandbox mode"),
+        };
+        match DateTime::parse_from_rfc3339(now) {
+            Ok(now) => Some(now),
+            Err(err) => panic!("invalid current time '{now}' of the sandbox mode: {err}"),
+        }
+    }
+
+    /// Reports an error if the function `name` changing the environment, e.g. `file.write`, is
+    /// called in the sandbox mode, because the later reads could depend on the change.
+    pub fn sandbox_deny(&self, name: &str) {
+        if self.sandbox.is_some() {
+            panic!("'{name}' is not allowed in the sandbox mode");
+        }
+    }
 }
 
 #[repr(C)]
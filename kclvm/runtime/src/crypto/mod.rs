@@ -227,6 +227,9 @@ pub extern "C-unwind" fn kclvm_crypto_uuid(
     _kwargs: *const kclvm_value_ref_t,
 ) -> *const kclvm_value_ref_t {
     let ctx = mut_ptr_as_ref(ctx);
+    if let Some(value) = ctx.sandbox_stub("crypto.uuid") {
+        return value.into_raw(ctx);
+    }
     return ValueRef::str(Uuid::new_v4().to_string().as_ref()).into_raw(ctx);
 }
 
@@ -242,6 +245,9 @@ pub extern "C-unwind" fn kclvm_crypto_filesha256(
     let ctx = mut_ptr_as_ref(ctx);
 
     if let Some(filepath) = get_call_arg_str(args, kwargs, 0, Some("filepath")) {
+        if let Some(value) = ctx.sandbox_stub(&format!("crypto.filesha256({filepath})")) {
+            return value.into_raw(ctx);
+        }
         // Open the file
         let mut file = File::open(&filepath)
             .unwrap_or_else(|e| panic!("failed to access file '{}': {}", filepath, e));
@@ -282,6 +288,9 @@ pub extern "C-unwind" fn kclvm_crypto_filesha512(
     let ctx = mut_ptr_as_ref(ctx);
 
     if let Some(filepath) = get_call_arg_str(args, kwargs, 0, Some("filepath")) {
+        if let Some(value) = ctx.sandbox_stub(&format!("crypto.filesha512({filepath})")) {
+            return value.into_raw(ctx);
+        }
         let mut file = File::open(&filepath)
             .unwrap_or_else(|e| panic!("failed to access file '{}': {}", filepath, e));
 
@@ -320,6 +329,9 @@ pub extern "C-unwind" fn kclvm_crypto_fileblake3(
     let ctx = mut_ptr_as_ref(ctx);
 
     if let Some(filepath) = get_call_arg_str(args, kwargs, 0, Some("filepath")) {
+        if let Some(value) = ctx.sandbox_stub(&format!("crypto.fileblake3({filepath})")) {
+            return value.into_raw(ctx);
+        }
         let mut file = File::open(&filepath)
             .unwrap_or_else(|e| panic!("failed to access file '{}': {}", filepath, e));
 
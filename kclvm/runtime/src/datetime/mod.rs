@@ -2,10 +2,18 @@
 
 extern crate chrono;
 
-use chrono::{prelude::Local, NaiveDate, NaiveDateTime, NaiveTime};
+use chrono::{prelude::Local, DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
 
 use crate::*;
 
+/// Return the current time, which is fixed in the sandbox mode.
+fn current_time(ctx: &Context) -> DateTime<FixedOffset> {
+    ctx.sandbox_now().unwrap_or_else(|| {
+        let now = Local::now();
+        now.with_timezone(now.offset())
+    })
+}
+
 /// Return the "%Y-%m-%d %H:%M:%S.%{ticks}" format date.
 /// `today() -> str`
 #[no_mangle]
@@ -15,8 +23,8 @@ pub extern "C-unwind" fn kclvm_datetime_today(
     _args: *const kclvm_value_ref_t,
     _kwargs: *const kclvm_value_ref_t,
 ) -> *const kclvm_value_ref_t {
-    let s = Local::now();
     let ctx = mut_ptr_as_ref(ctx);
+    let s = current_time(ctx);
     ValueRef::str(&(s.format("%Y-%m-%d %H:%M:%S").to_string() + "." + &s.timestamp().to_string()))
         .into_raw(ctx)
 }
@@ -31,8 +39,8 @@ pub extern "C-unwind" fn kclvm_datetime_now(
     args: *const kclvm_value_ref_t,
     kwargs: *const kclvm_value_ref_t,
 ) -> *const kclvm_value_ref_t {
-    let s = Local::now();
     let ctx = mut_ptr_as_ref(ctx);
+    let s = current_time(ctx);
     let args = ptr_as_ref(args);
     let kwargs = ptr_as_ref(kwargs);
     let format = get_call_arg_str(args, kwargs, 0, Some("format"))
@@ -50,7 +58,7 @@ pub extern "C-unwind" fn kclvm_datetime_ticks(
     _kwargs: *const kclvm_value_ref_t,
 ) -> *const kclvm_value_ref_t {
     let ctx = mut_ptr_as_ref(ctx);
-    let x = Local::now().timestamp();
+    let x = current_time(ctx).timestamp();
     ValueRef::float(x as f64).into_raw(ctx)
 }
 
@@ -63,8 +71,8 @@ pub extern "C-unwind" fn kclvm_datetime_date(
     _args: *const kclvm_value_ref_t,
     _kwargs: *const kclvm_value_ref_t,
 ) -> *const kclvm_value_ref_t {
-    let s = Local::now();
     let ctx = mut_ptr_as_ref(ctx);
+    let s = current_time(ctx);
     ValueRef::str(&s.format("%Y-%m-%d %H:%M:%S").to_string()).into_raw(ctx)
 }
 
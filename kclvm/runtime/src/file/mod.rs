@@ -19,6 +19,9 @@ pub extern "C-unwind" fn kclvm_file_read(
     let ctx = mut_ptr_as_ref(ctx);
 
     if let Some(x) = get_call_arg_str(args, kwargs, 0, Some("filepath")) {
+        if let Some(value) = ctx.sandbox_stub(&format!("file.read({x})")) {
+            return value.into_raw(ctx);
+        }
         let contents = fs::read_to_string(&x)
             .unwrap_or_else(|e| panic!("failed to access the file '{}': {}", x, e));
 
@@ -42,6 +45,9 @@ pub extern "C-unwind" fn kclvm_file_glob(
 
     let pattern = get_call_arg_str(args, kwargs, 0, Some("pattern"))
         .expect("glob() takes exactly one argument (0 given)");
+    if let Some(value) = ctx.sandbox_stub(&format!("file.glob({pattern})")) {
+        return value.into_raw(ctx);
+    }
 
     let mut matched_paths = vec![];
     for entry in glob(&pattern).unwrap_or_else(|e| panic!("Failed to read glob pattern: {}", e)) {
@@ -62,6 +68,9 @@ pub extern "C-unwind" fn kclvm_file_modpath(
     _kwargs: *const kclvm_value_ref_t,
 ) -> *const kclvm_value_ref_t {
     let ctx = mut_ptr_as_ref(ctx);
+    if let Some(value) = ctx.sandbox_stub("file.modpath") {
+        return value.into_raw(ctx);
+    }
     let s = ValueRef::str(ctx.module_path.as_ref());
     s.into_raw(ctx)
 }
@@ -74,6 +83,9 @@ pub extern "C-unwind" fn kclvm_file_workdir(
     _kwargs: *const kclvm_value_ref_t,
 ) -> *const kclvm_value_ref_t {
     let ctx = mut_ptr_as_ref(ctx);
+    if let Some(value) = ctx.sandbox_stub("file.workdir") {
+        return value.into_raw(ctx);
+    }
     let s = ValueRef::str(ctx.workdir.as_ref());
     s.into_raw(ctx)
 }
@@ -87,6 +99,9 @@ pub extern "C-unwind" fn kclvm_file_current(
     _kwargs: *const kclvm_value_ref_t,
 ) -> *const kclvm_value_ref_t {
     let ctx = mut_ptr_as_ref(ctx);
+    if let Some(value) = ctx.sandbox_stub("file.current") {
+        return value.into_raw(ctx);
+    }
     let s = ValueRef::str(ctx.panic_info.kcl_file.as_ref());
     s.into_raw(ctx)
 }
@@ -106,6 +121,9 @@ pub extern "C-unwind" fn kclvm_file_exists(
     let ctx = mut_ptr_as_ref(ctx);
 
     if let Some(path) = get_call_arg_str(args, kwargs, 0, Some("filepath")) {
+        if let Some(value) = ctx.sandbox_stub(&format!("file.exists({path})")) {
+            return value.into_raw(ctx);
+        }
         let exist = Path::new(&path).exists();
         return ValueRef::bool(exist).into_raw(ctx);
     }
@@ -127,6 +145,9 @@ pub extern "C-unwind" fn kclvm_file_abs(
     let ctx = mut_ptr_as_ref(ctx);
 
     if let Some(path) = get_call_arg_str(args, kwargs, 0, Some("filepath")) {
+        if let Some(value) = ctx.sandbox_stub(&format!("file.abs({path})")) {
+            return value.into_raw(ctx);
+        }
         if let Ok(abs_path) = Path::new(&path).canonicalize() {
             return ValueRef::str(abs_path.to_str().unwrap()).into_raw(ctx);
         } else {
@@ -147,6 +168,7 @@ pub extern "C-unwind" fn kclvm_file_mkdir(
     let args = ptr_as_ref(args);
     let kwargs = ptr_as_ref(kwargs);
     let ctx = mut_ptr_as_ref(ctx);
+    ctx.sandbox_deny("file.mkdir");
 
     if let Some(path) = get_call_arg_str(args, kwargs, 0, Some("directory")) {
         let exists = get_call_arg_bool(args, kwargs, 1, Some("exists")).unwrap_or_default();
@@ -173,6 +195,7 @@ pub extern "C-unwind" fn kclvm_file_delete(
     let args = ptr_as_ref(args);
     let kwargs = ptr_as_ref(kwargs);
     let ctx = mut_ptr_as_ref(ctx);
+    ctx.sandbox_deny("file.delete");
 
     if let Some(path) = get_call_arg_str(args, kwargs, 0, Some("filepath")) {
         if let Err(e) = fs::remove_file(&path) {
@@ -204,6 +227,7 @@ pub extern "C-unwind" fn kclvm_file_cp(
     let args = ptr_as_ref(args);
     let kwargs = ptr_as_ref(kwargs);
     let ctx = mut_ptr_as_ref(ctx);
+    ctx.sandbox_deny("file.cp");
 
     if let Some(src_path) = get_call_arg_str(args, kwargs, 0, Some("src")) {
         if let Some(dest_path) = get_call_arg_str(args, kwargs, 1, Some("dest")) {
@@ -241,6 +265,7 @@ pub extern "C-unwind" fn kclvm_file_mv(
     let args = ptr_as_ref(args);
     let kwargs = ptr_as_ref(kwargs);
     let ctx = mut_ptr_as_ref(ctx);
+    ctx.sandbox_deny("file.mv");
 
     if let Some(src_path) = get_call_arg_str(args, kwargs, 0, Some("src")) {
         if let Some(dest_path) = get_call_arg_str(args, kwargs, 1, Some("dest")) {
@@ -268,6 +293,9 @@ pub extern "C-unwind" fn kclvm_file_size(
     let ctx = mut_ptr_as_ref(ctx);
 
     if let Some(path) = get_call_arg_str(args, kwargs, 0, Some("filepath")) {
+        if let Some(value) = ctx.sandbox_stub(&format!("file.size({path})")) {
+            return value.into_raw(ctx);
+        }
         let metadata = fs::metadata(&path);
         match metadata {
             Ok(metadata) => {
@@ -294,6 +322,7 @@ pub extern "C-unwind" fn kclvm_file_write(
     let args = ptr_as_ref(args);
     let kwargs = ptr_as_ref(kwargs);
     let ctx = mut_ptr_as_ref(ctx);
+    ctx.sandbox_deny("file.write");
 
     if let Some(path) = get_call_arg_str(args, kwargs, 0, Some("filepath")) {
         if let Some(content) = get_call_arg_str(args, kwargs, 1, Some("content")) {
@@ -324,6 +353,7 @@ pub extern "C-unwind" fn kclvm_file_append(
     let args = ptr_as_ref(args);
     let kwargs = ptr_as_ref(kwargs);
     let ctx = mut_ptr_as_ref(ctx);
+    ctx.sandbox_deny("file.append");
 
     if let Some(path) = get_call_arg_str(args, kwargs, 0, Some("filepath")) {
         if let Some(content) = get_call_arg_str(args, kwargs, 1, Some("content")) {
@@ -359,6 +389,9 @@ pub extern "C-unwind" fn kclvm_file_read_env(
     let ctx = mut_ptr_as_ref(ctx);
 
     if let Some(key) = get_call_arg_str(args, kwargs, 0, Some("key")) {
+        if let Some(value) = ctx.sandbox_stub(&format!("file.read_env({key})")) {
+            return value.into_raw(ctx);
+        }
         match std::env::var(key) {
             Ok(v) => ValueRef::str(&v).into_raw(ctx),
             Err(_) => ValueRef::undefined().into_raw(ctx),
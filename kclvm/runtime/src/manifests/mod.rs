@@ -30,6 +30,7 @@ type kclvm_value_ref_t = ValueRef;
 ///     ignore_private: bool = True
 ///     ignore_none: bool = False
 ///     separator: str = "---\n"
+///     flow_threshold: int = None
 ///
 /// manifests.yaml_stream(values: [any], * , opts: ManifestsYamlStreamOptions = ManifestsYamlStreamOptions {})
 /// ```
@@ -82,6 +83,10 @@ pub extern "C-unwind" fn kclvm_manifests_yaml_stream(
                         .get_by_key("sep")
                         .unwrap_or_else(|| ValueRef::str("---"))
                         .as_str(),
+                    flow_threshold: opts
+                        .get_by_key("flow_threshold")
+                        .filter(|threshold| !threshold.is_none_or_undefined())
+                        .map(|threshold| threshold.as_int() as usize),
                 }
             } else {
                 panic!(
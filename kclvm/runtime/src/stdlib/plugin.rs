@@ -55,6 +55,9 @@ pub unsafe extern "C-unwind" fn kclvm_plugin_invoke(
         Some(s) => s,
         None => method_ref,
     };
+    if let Some(value) = ctx_ref.sandbox_stub(plugin_short_method) {
+        return value.into_raw(ctx_ref);
+    }
     if let Some(func) = ctx_ref.plugin_functions.get(plugin_short_method) {
         let args = ptr_as_ref(args);
         let kwargs = ptr_as_ref(kwargs);
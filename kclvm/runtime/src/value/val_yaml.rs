@@ -13,6 +13,9 @@ use serde::{Deserialize, Serialize};
 ///     a character `_` (defaults to false).
 /// - ignore_none: Whether to ignore the attribute whose value is `None` (defaults to false).
 /// - sep: Which separator to use between YAML documents (defaults to "---").
+/// - flow_threshold: Emit the lists and dicts nested deeper than the threshold
+///     in the flow style e.g., `[1, 2]` (defaults to None, which means always
+///     the block style). Empty lists and dicts are always emitted as `[]` and `{}`.
 ///
 /// TODO: We have not yet supported the following options because serde_yaml
 /// does not support these capabilities yet.
@@ -29,6 +32,7 @@ pub struct YamlEncodeOptions {
     pub ignore_private: bool,
     pub ignore_none: bool,
     pub sep: String,
+    pub flow_threshold: Option<usize>,
 }
 
 impl Default for YamlEncodeOptions {
@@ -38,10 +42,26 @@ impl Default for YamlEncodeOptions {
             ignore_private: false,
             ignore_none: false,
             sep: "---".to_string(),
+            flow_threshold: None,
         }
     }
 }
 
+/// Serialize the YAML value to a string, the same value is always serialized
+/// to the same string with the same flow threshold.
+fn yaml_value_to_string(
+    value: &serde_yaml::Value,
+    flow_threshold: Option<usize>,
+) -> Result<String, serde_yaml::Error> {
+    let mut buffer = Vec::with_capacity(128);
+    let mut serializer =
+        serde_yaml::Serializer::new(&mut buffer).with_flow_threshold(flow_threshold);
+    value.serialize(&mut serializer)?;
+    serializer.flush()?;
+    drop(serializer);
+    Ok(String::from_utf8_lossy(&buffer).into_owned())
+}
+
 impl ValueRef {
     /// Decode a yaml single document string to a ValueRef.
     /// Returns [serde_yaml::Error] when decoding fails.
@@ -85,7 +105,7 @@ impl ValueRef {
     pub fn to_yaml(&self) -> Vec<u8> {
         let json = self.to_json_string();
         let yaml_value: serde_yaml::Value = serde_json::from_str(json.as_ref()).unwrap();
-        match serde_yaml::to_string(&yaml_value) {
+        match yaml_value_to_string(&yaml_value, None) {
             Ok(s) => s.into_bytes(),
             _ => Vec::new(),
         }
@@ -94,7 +114,7 @@ impl ValueRef {
     pub fn to_yaml_string(&self) -> String {
         let json = self.to_json_string();
         let yaml_value: serde_yaml::Value = serde_json::from_str(json.as_ref()).unwrap();
-        match serde_yaml::to_string(&yaml_value) {
+        match yaml_value_to_string(&yaml_value, None) {
             Ok(s) => {
                 let s = s.strip_prefix("---\n").unwrap_or_else(|| s.as_ref());
                 s.to_string()
@@ -114,7 +134,7 @@ impl ValueRef {
         };
         let json = self.to_json_string_with_options(&json_opts);
         let yaml_value: serde_yaml::Value = serde_json::from_str(json.as_ref()).unwrap();
-        match serde_yaml::to_string(&yaml_value) {
+        match yaml_value_to_string(&yaml_value, opts.flow_threshold) {
             Ok(s) => {
                 let s = s.strip_prefix("---\n").unwrap_or_else(|| s.as_ref());
                 s.to_string()
@@ -256,6 +276,7 @@ mod test_value_yaml {
                     ignore_private: false,
                     ignore_none: false,
                     sep: "---".to_string(),
+                    flow_threshold: None,
                 },
             ),
             (
@@ -266,6 +287,7 @@ mod test_value_yaml {
                     ignore_private: false,
                     ignore_none: false,
                     sep: "---".to_string(),
+                    flow_threshold: None,
                 },
             ),
             (
@@ -276,6 +298,7 @@ mod test_value_yaml {
                     ignore_private: true,
                     ignore_none: false,
                     sep: "---".to_string(),
+                    flow_threshold: None,
                 },
             ),
             (
@@ -286,6 +309,7 @@ mod test_value_yaml {
                     ignore_private: true,
                     ignore_none: true,
                     sep: "---".to_string(),
+                    flow_threshold: None,
                 },
             ),
             (
@@ -299,6 +323,7 @@ mod test_value_yaml {
                     ignore_private: false,
                     ignore_none: false,
                     sep: "---".to_string(),
+                    flow_threshold: None,
                 },
             ),
         ];
@@ -307,4 +332,38 @@ mod test_value_yaml {
             assert_eq!(result, expected);
         }
     }
+
+    #[test]
+    fn test_value_to_yaml_string_nested_golden() {
+        let mut ctx = Context::new();
+        let value = ValueRef::from_json(
+            &mut ctx,
+            r#"{"a": {"b": {"c": [1, 2, {"d": []}]}, "e": {}}, "f": [[1], []]}"#,
+        )
+        .unwrap();
+        let first = ValueRef::to_yaml_string(&value);
+        let second = ValueRef::to_yaml_string(&value);
+        assert_eq!(first.as_bytes(), second.as_bytes());
+        assert_eq!(first, include_str!("test_data/nested.yaml"));
+        let opts = YamlEncodeOptions::default();
+        assert_eq!(ValueRef::to_yaml_string_with_options(&value, &opts), first);
+    }
+
+    #[test]
+    fn test_value_to_yaml_string_with_flow_threshold() {
+        let mut ctx = Context::new();
+        let value = ValueRef::from_json(
+            &mut ctx,
+            r#"{"a": {"b": {"c": [1, 2, {"d": []}]}, "e": {}}, "f": [[1], []]}"#,
+        )
+        .unwrap();
+        let opts = YamlEncodeOptions {
+            flow_threshold: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            ValueRef::to_yaml_string_with_options(&value, &opts),
+            "a:\n  b: {c: [1, 2, {d: []}]}\n  e: {}\nf:\n- [1]\n- []\n"
+        );
+    }
 }
@@ -102,6 +102,7 @@ pub extern "C-unwind" fn kclvm_yaml_dump_to_file(
 ) -> *const kclvm_value_ref_t {
     let args = ptr_as_ref(args);
     let kwargs = ptr_as_ref(kwargs);
+    mut_ptr_as_ref(ctx).sandbox_deny("yaml.dump_to_file");
     let data = args.arg_i(0).or(kwargs.get_by_key("data"));
     let filename = args.arg_i(1).or(kwargs.get_by_key("filename"));
     match (data, filename) {
@@ -129,6 +130,7 @@ pub extern "C-unwind" fn kclvm_yaml_dump_all_to_file(
 ) -> *const kclvm_value_ref_t {
     let args = ptr_as_ref(args);
     let kwargs = ptr_as_ref(kwargs);
+    mut_ptr_as_ref(ctx).sandbox_deny("yaml.dump_all_to_file");
 
     let data = args.arg_i(0).or(kwargs.get_by_key("data"));
     let filename = args.arg_i(1).or(kwargs.get_by_key("filename"));
@@ -83,6 +83,8 @@ pub struct Context<'ctx> {
     maybe_def: bool,
     // whether in schema config right value, affect lookup def
     in_config_r_value: bool,
+    // whether the config being visited is the config of a schema expr, e.g. `{...}` of `Service {...}`
+    in_schema_expr_config: bool,
 
     is_type_expr: bool,
 }
@@ -116,6 +118,7 @@ impl<'ctx> AdvancedResolver<'ctx> {
                 cur_node: AstIndex::default(),
                 maybe_def: false,
                 in_config_r_value: false,
+                in_schema_expr_config: false,
                 is_type_expr: false,
             },
         };
@@ -883,8 +883,15 @@ impl<'ctx> MutSelfTypedResultWalker<'ctx> for AdvancedResolver<'_> {
                 schema_expr
             ))?
             .clone();
+        // The config is not resolved by the resolver if the schema nesting is too deep.
+        let config_resolved = self
+            .ctx
+            .node_ty_map
+            .borrow()
+            .contains_key(&self.ctx.get_node_key(&schema_expr.config.id));
         match schema_ty.kind {
-            TypeKind::Schema(_) => {
+            TypeKind::Schema(_) if config_resolved => {
+                self.ctx.in_schema_expr_config = true;
                 self.expr(&schema_expr.config)?;
                 self.do_arguments_symbol_resolve(&schema_expr.args, &schema_expr.kwargs)?;
             }
@@ -1036,6 +1043,22 @@ impl<'ctx> AdvancedResolver<'_> {
             self.ctx.end_pos = end;
         }
         self.ctx.cur_node = expr.id.clone();
+        // The configs and the lists nested too deep are not resolved by the resolver.
+        if matches!(
+            &expr.node,
+            ast::Expr::Config(_)
+                | ast::Expr::List(_)
+                | ast::Expr::ListComp(_)
+                | ast::Expr::DictComp(_)
+        ) && !self
+            .ctx
+            .node_ty_map
+            .borrow()
+            .contains_key(&self.ctx.get_node_key(&expr.id))
+        {
+            return Ok(None);
+        }
+        let in_schema_expr_config = std::mem::take(&mut self.ctx.in_schema_expr_config);
 
         if let Some(expr_ty) = self
             .ctx
@@ -1051,6 +1074,16 @@ impl<'ctx> AdvancedResolver<'_> {
                         .get_type_symbol(&expr_ty, self.get_current_module_info())
                         .ok_or(anyhow!("schema_symbol not found"))?;
                     self.ctx.schema_symbol_stack.push(Some(schema_symbol));
+                    // The config literal is typed by the expected schema type.
+                    if matches!(expr.node, ast::Expr::Config(_)) && !in_schema_expr_config {
+                        self.gs.get_symbols_mut().alloc_hint(
+                            SymbolHint {
+                                kind: SymbolHintKind::SchemaTypeHint(expr_ty.ty_hint()),
+                                pos: expr.get_pos(),
+                            },
+                            self.ctx.current_pkgpath.clone().unwrap(),
+                        );
+                    }
                 }
                 _ => {
                     self.ctx.schema_symbol_stack.push(None);
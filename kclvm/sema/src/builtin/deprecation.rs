@@ -0,0 +1,47 @@
+use kclvm_error::diagnostic::Range;
+use kclvm_error::{Message, Style};
+
+/// The deprecation of a builtin function, e.g. `len`, or a system module function, e.g.
+/// `datetime.date`, which is warned on use until the builtin is removed. The deprecation is set
+/// on the type of the builtin function with [`crate::ty::Type::deprecated`].
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Deprecation {
+    /// The version deprecating the builtin, e.g. `0.11.0`.
+    pub since: &'static str,
+    /// The qualified name of the builtin to use instead with the same arguments, if any.
+    pub replacement: Option<&'static str>,
+    /// How to replace the builtin if there is no builtin with the same arguments.
+    pub note: Option<&'static str>,
+}
+
+/// Returns the warning message of the use of the deprecated builtin named `name` at `range`,
+/// where the module of the builtin is referred as `prefix`, e.g. `dt` of `import datetime as dt`,
+/// which is kept by the replacement in the same module.
+pub fn deprecation_message(
+    name: &str,
+    deprecation: &Deprecation,
+    prefix: &[String],
+    range: Range,
+) -> Message {
+    let replacement = deprecation.replacement.map(|replacement| {
+        match (replacement.rsplit_once('.'), name.rsplit_once('.')) {
+            (Some((module, member)), Some((deprecated_module, _)))
+                if module == deprecated_module && !prefix.is_empty() =>
+            {
+                format!("{}.{}", prefix.join("."), member)
+            }
+            _ => replacement.to_string(),
+        }
+    });
+    let note = match &replacement {
+        Some(replacement) => Some(format!("Use '{}' instead", replacement)),
+        None => deprecation.note.map(|note| note.to_string()),
+    };
+    Message {
+        range,
+        style: Style::LineAndColumn,
+        message: format!("'{}' is deprecated since {}", name, deprecation.since),
+        note,
+        suggested_replacement: replacement.map(|replacement| vec![replacement]),
+    }
+}
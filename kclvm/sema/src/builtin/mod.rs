@@ -1,6 +1,7 @@
 //! This package mainly contains the type definitions of built-in system libraries,
 //! functions, decorators and member methods.
 pub mod decorator;
+pub mod deprecation;
 pub mod option;
 pub mod string;
 pub mod system_module;
@@ -13,6 +14,7 @@ use once_cell::sync::Lazy;
 
 use crate::ty::{Parameter, Type};
 pub use decorator::BUILTIN_DECORATORS;
+pub use deprecation::{deprecation_message, Deprecation};
 pub use string::STRING_MEMBER_FUNCTIONS;
 pub use system_module::*;
 
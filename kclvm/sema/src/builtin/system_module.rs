@@ -2,6 +2,7 @@
 
 use std::sync::Arc;
 
+use crate::builtin::Deprecation;
 use crate::ty::{Parameter, Type, TypeRef};
 use indexmap::IndexMap;
 use kclvm_error::diagnostic::dummy_range;
@@ -565,6 +566,7 @@ opts - The YAML serialization options
  + ignore_private: Whether to ignore the attribute output whose name starts with the character _ (the default value is True).
  + ignore_none: Whether to ignore the attribute with the value of' None '(the default value is False).
  + sep: Set the separator between multiple YAML documents (the default value is "---").
+ + flow_threshold: Emit the lists and dicts nested deeper than the threshold in the flow style (the default value is None, which means always the block style).
 "#,
         false,
         None,
@@ -898,10 +900,15 @@ register_datetime_member! {
         None,
         Type::str_ref(),
         &[],
-        r#"Return the `%Y-%m-%d %H:%M:%S` format date."#,
+        r#"Return the `%Y-%m-%d %H:%M:%S` format date. Deprecated, use `now("%Y-%m-%d %H:%M:%S")` instead."#,
         false,
         None,
     )
+    .deprecated(Deprecation {
+        since: "0.11.2",
+        replacement: None,
+        note: Some(r#"Use 'datetime.now("%Y-%m-%d %H:%M:%S")' instead"#),
+    })
     now => Type::function(
         None,
         Type::str_ref(),
@@ -83,16 +83,27 @@ impl PackageInfo {
 pub struct ImportInfo {
     pub(crate) unqualified_name: String,
     pub(crate) fully_qualified_name: String,
+    /// Whether the package is imported with an alias e.g., `import a.b as c`.
+    pub(crate) is_alias: bool,
 }
 
 impl ImportInfo {
-    pub fn new(unqualified_name: String, fully_qualified_name: String) -> Self {
+    pub fn new(unqualified_name: String, fully_qualified_name: String, is_alias: bool) -> Self {
         Self {
             unqualified_name,
             fully_qualified_name,
+            is_alias,
         }
     }
 
+    pub fn get_unqualified_name(&self) -> String {
+        self.unqualified_name.clone()
+    }
+
+    pub fn is_alias(&self) -> bool {
+        self.is_alias
+    }
+
     pub fn get_fully_qualified_name(&self) -> String {
         self.fully_qualified_name.clone()
     }
@@ -129,4 +140,22 @@ impl ModuleInfo {
     pub fn get_imports(&self) -> IndexMap<String, ImportInfo> {
         self.imports.clone()
     }
+
+    /// Returns the alias bound to the imported package in the module e.g.,
+    /// `c` of `import a.b as c`, or `None` if the package is not aliased.
+    pub fn get_import_alias(&self, fully_qualified_name: &str) -> Option<String> {
+        self.imports
+            .values()
+            .find(|info| info.is_alias && info.fully_qualified_name == fully_qualified_name)
+            .map(|info| info.unqualified_name.clone())
+    }
+
+    /// Whether the name is an alias of the imported package in the module. The same package may
+    /// be imported both with and without an alias, e.g. `c` of `import a.b as c` is an alias and
+    /// `b` of `import a.b` is not.
+    pub fn is_import_alias(&self, name: &str, fully_qualified_name: &str) -> bool {
+        self.imports.get(name).map_or(false, |info| {
+            info.is_alias && info.fully_qualified_name == fully_qualified_name
+        })
+    }
 }
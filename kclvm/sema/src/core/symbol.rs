@@ -103,6 +103,8 @@ pub enum SymbolHintKind {
     TypeHint(String),
     VarHint(String),
     KeyTypeHint(String),
+    /// The schema type of a config literal without the schema name, e.g. `s: Service = {...}`.
+    SchemaTypeHint(String),
 }
 
 impl SymbolData {
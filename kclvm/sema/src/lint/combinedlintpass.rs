@@ -1,7 +1,11 @@
 use crate::lint::lint::{LintArray, LintContext};
 use crate::lint::lintpass::LintPass;
+use crate::lint::lints_def::ConstantAssert;
+use crate::lint::lints_def::DefaultViolatesCheck;
+use crate::lint::lints_def::DuplicateAssignment;
 use crate::lint::lints_def::ImportPosition;
 use crate::lint::lints_def::ReImport;
+use crate::lint::lints_def::UnsatisfiableCheck;
 use crate::lint::lints_def::UnusedImport;
 use crate::lint_methods;
 use crate::resolver::scope::Scope;
@@ -112,6 +116,10 @@ macro_rules! default_lint_passes {
                 ImportPosition: ImportPosition,
                 UnusedImport: UnusedImport,
                 ReImport: ReImport,
+                DuplicateAssignment: DuplicateAssignment,
+                ConstantAssert: ConstantAssert,
+                DefaultViolatesCheck: DefaultViolatesCheck,
+                UnsatisfiableCheck: UnsatisfiableCheck,
             ]
         );
     };
@@ -25,10 +25,10 @@ macro_rules! lint_methods {
             // fn check_type_alias_stmt(type_alias_stmt: &ast::TypeAliasStmt);
             // fn check_assign_stmt(assign_stmt: &ast::AssignStmt);
             // fn check_aug_assign_stmt(aug_assign_stmt: &ast::AugAssignStmt);
-            // fn check_assert_stmt(assert_stmt: &ast::AssertStmt);
+            fn check_assert_stmt(_assert_stmt: &ast::AssertStmt);
             // fn check_if_stmt(if_stmt: &ast::IfStmt);
             // fn check_import_stmt(import_stmt: &ast::ImportStmt);
-            // fn check_schema_stmt(schema_stmt: &ast::SchemaStmt);
+            fn check_schema_stmt(_schema_stmt: &ast::SchemaStmt);
             // fn check_rule_stmt(rule_stmt: &ast::RuleStmt);
 
             /*
@@ -54,7 +54,7 @@ macro_rules! lint_methods {
             // );
             // fn check_comp_clause(comp_clause: &ast::CompClause);
             // fn check_schema_expr(schema_expr: &ast::SchemaExpr);
-            // fn check_config_expr(config_expr: &ast::ConfigExpr);
+            fn check_config_expr(_config_expr: &ast::ConfigExpr);
             // fn check_check_expr(check_expr: &ast::CheckExpr);
             // fn check_lambda_expr(lambda_expr: &ast::LambdaExpr);
             // fn check_keyword(keyword: &ast::Keyword);
@@ -2,10 +2,12 @@ use crate::lint::lint::{Lint, LintArray, LintContext};
 use crate::lint::lintpass::LintPass;
 use crate::resolver::scope::Scope;
 use crate::{declare_lint_pass, resolver::scope::ScopeObjectKind};
-use indexmap::IndexSet;
+use indexmap::{IndexMap, IndexSet};
 use kclvm_ast::ast;
 use kclvm_ast::pos::GetPos;
-use kclvm_error::{Handler, Level, Message, Style, WarningKind};
+use kclvm_ast::walker::MutSelfWalker;
+use kclvm_ast_pretty::{print_ast_node, ASTNode};
+use kclvm_error::{Diagnostic, DiagnosticId, Handler, Level, Message, Style, WarningKind};
 
 /// The 'import_position' lint detects import statements that are not declared at the top of file.
 /// ### Example
@@ -175,3 +177,633 @@ impl LintPass for ReImport {
         }
     }
 }
+
+/// The 'duplicate_assignment' lint detects the same attribute assigned more than once in one config.
+/// ### Example
+///
+/// ```kcl
+/// config = {
+///     a = 1
+///     a = 2
+/// }
+/// ```
+/// ### Explanation
+///
+/// The later assignment overrides the earlier one in the same config, which is almost always a bug.
+/// Overriding the attribute of another config e.g., `base | {a = 2}` is intentional and not reported.
+pub static DUPLICATE_ASSIGNMENT: &Lint = &Lint {
+    name: stringify!("DUPLICATE_ASSIGNMENT"),
+    level: Level::Warning,
+    desc: "Check for duplicate attribute assignment in one config",
+    code: "W0109",
+    note: Some("Consider removing the earlier assignment"),
+};
+
+declare_lint_pass!(DuplicateAssignment => [DUPLICATE_ASSIGNMENT]);
+
+impl LintPass for DuplicateAssignment {
+    fn check_config_expr(
+        &mut self,
+        handler: &mut Handler,
+        _ctx: &mut LintContext,
+        config_expr: &ast::ConfigExpr,
+    ) {
+        let mut assigned_keys = IndexMap::<String, &ast::NodeRef<ast::Expr>>::new();
+        for entry in &config_expr.items {
+            let key = match &entry.node.key {
+                Some(key) => key,
+                None => continue,
+            };
+            let name = match &key.node {
+                ast::Expr::Identifier(identifier) => identifier.get_name(),
+                ast::Expr::StringLit(string_lit) => string_lit.value.clone(),
+                _ => continue,
+            };
+            if let ast::ConfigEntryOperation::Override = entry.node.operation {
+                if let Some(earlier_key) = assigned_keys.get(&name) {
+                    handler.add_warning(
+                        WarningKind::DuplicateAssignmentWarning,
+                        &[
+                            Message {
+                                range: key.get_span_pos(),
+                                style: Style::LineAndColumn,
+                                message: format!("duplicate assignment of '{}'", name),
+                                note: Some("Consider removing the earlier assignment".to_string()),
+                                suggested_replacement: None,
+                            },
+                            Message {
+                                range: earlier_key.get_span_pos(),
+                                style: Style::LineAndColumn,
+                                message: format!(
+                                    "'{}' is overridden by the later assignment",
+                                    name
+                                ),
+                                note: None,
+                                suggested_replacement: None,
+                            },
+                        ],
+                    );
+                }
+            }
+            assigned_keys.insert(name, key);
+        }
+    }
+}
+
+/// The 'constant_assert' lint detects assert statements whose conditions are statically constant.
+///
+/// ### Example
+///
+/// ```kcl
+/// assert True
+/// assert 1 > 2, "never holds"
+/// ```
+///
+/// ### Explanation
+///
+/// An assertion which always holds is dead code, and an assertion which never holds always fails
+/// the evaluation, both are usually left over from debugging. The always true conditions are
+/// reported as hints and the always false ones as warnings. The conditional assertions, e.g.
+/// `assert False if debug`, are not reported.
+pub static CONSTANT_ASSERT: &Lint = &Lint {
+    name: stringify!("CONSTANT_ASSERT"),
+    level: Level::Warning,
+    desc: "Check for assert statements with constant conditions",
+    code: "W0110",
+    note: Some("Consider removing this assert statement"),
+};
+
+declare_lint_pass!(ConstantAssert => [CONSTANT_ASSERT]);
+
+impl LintPass for ConstantAssert {
+    fn check_assert_stmt(
+        &mut self,
+        handler: &mut Handler,
+        _ctx: &mut LintContext,
+        assert_stmt: &ast::AssertStmt,
+    ) {
+        if assert_stmt.if_cond.is_some() {
+            return;
+        }
+        match const_truth(&assert_stmt.test.node, &ConstEnv::new()) {
+            Some(true) => {
+                handler.add_diagnostic(Diagnostic {
+                    level: Level::Note,
+                    messages: vec![Message {
+                        range: assert_stmt.test.get_span_pos(),
+                        style: Style::LineAndColumn,
+                        message: "The assert condition is always true".to_string(),
+                        note: Some("Consider removing this assert statement".to_string()),
+                        suggested_replacement: None,
+                    }],
+                    code: Some(DiagnosticId::Warning(WarningKind::ConstantAssertWarning)),
+                });
+            }
+            Some(false) => {
+                handler.add_warning(
+                    WarningKind::ConstantAssertWarning,
+                    &[Message {
+                        range: assert_stmt.test.get_span_pos(),
+                        style: Style::LineAndColumn,
+                        message:
+                            "The assert condition is always false and the assertion always fails"
+                                .to_string(),
+                        note: None,
+                        suggested_replacement: None,
+                    }],
+                );
+            }
+            None => {}
+        }
+    }
+}
+
+/// The 'default_violates_check' lint detects schema attributes whose default values violate the
+/// check block of the schema.
+///
+/// ### Example
+///
+/// ```kcl
+/// schema Server:
+///     port: int = 0
+///
+///     check:
+///         1 <= port <= 65535, "port must be between 1 and 65535"
+/// ```
+///
+/// ### Explanation
+///
+/// The violation is only caught when an instance keeps the default, e.g. `Server {}`. The checks
+/// are evaluated with the constant defaults of the attributes they refer to, and the checks
+/// referring to other values, e.g. the attributes without defaults or the attributes assigned in
+/// the schema body, can not be evaluated standalone and are skipped. The defaults violating the
+/// attribute types are reported by the type checker.
+pub static DEFAULT_VIOLATES_CHECK: &Lint = &Lint {
+    name: stringify!("DEFAULT_VIOLATES_CHECK"),
+    level: Level::Warning,
+    desc: "Check for schema attribute defaults which violate the schema checks",
+    code: "W0111",
+    note: Some("Consider changing the default value"),
+};
+
+declare_lint_pass!(DefaultViolatesCheck => [DEFAULT_VIOLATES_CHECK]);
+
+impl LintPass for DefaultViolatesCheck {
+    fn check_schema_stmt(
+        &mut self,
+        handler: &mut Handler,
+        _ctx: &mut LintContext,
+        schema_stmt: &ast::SchemaStmt,
+    ) {
+        let mut assigned = IndexSet::new();
+        collect_assigned_names(&schema_stmt.body, &mut assigned);
+        let mut defaults: IndexMap<String, &ast::NodeRef<ast::Expr>> = IndexMap::new();
+        let mut env = ConstEnv::new();
+        for stmt in &schema_stmt.body {
+            if let ast::Stmt::SchemaAttr(schema_attr) = &stmt.node {
+                let name = &schema_attr.name.node;
+                if assigned.contains(name) || schema_attr.op.is_some() {
+                    continue;
+                }
+                if let Some(value) = &schema_attr.value {
+                    if let Some(const_value) = const_value(&value.node, &ConstEnv::new()) {
+                        defaults.insert(name.clone(), value);
+                        env.insert(name.clone(), const_value);
+                    }
+                }
+            }
+        }
+        if defaults.is_empty() {
+            return;
+        }
+        for check in &schema_stmt.checks {
+            if let Some(if_cond) = &check.node.if_cond {
+                if const_truth(&if_cond.node, &env) != Some(true) {
+                    continue;
+                }
+            }
+            if const_truth(&check.node.test.node, &env) != Some(false) {
+                continue;
+            }
+            let mut collector = NameCollector::default();
+            collector.walk_expr(&check.node.test.node);
+            let constraint = match &check.node.msg {
+                Some(msg) => match &msg.node {
+                    ast::Expr::StringLit(string_lit) => string_lit.value.clone(),
+                    _ => print_ast_node(ASTNode::Expr(msg)),
+                },
+                None => print_ast_node(ASTNode::Expr(&check.node.test)),
+            };
+            let messages: Vec<Message> = collector
+                .names
+                .iter()
+                .filter_map(|name| defaults.get(name).map(|value| (name, value)))
+                .map(|(name, value)| Message {
+                    range: value.get_span_pos(),
+                    style: Style::LineAndColumn,
+                    message: format!(
+                        "The default value of '{}' violates the check: {}",
+                        name, constraint
+                    ),
+                    note: Some("Consider changing the default value".to_string()),
+                    suggested_replacement: None,
+                })
+                .collect();
+            if !messages.is_empty() {
+                handler.add_warning(WarningKind::DefaultViolatesCheckWarning, &messages);
+            }
+        }
+    }
+}
+
+/// The 'unsatisfiable_check' lint detects schema checks which no value of the attribute type
+/// satisfies, so no valid instance of the schema exists.
+///
+/// ### Example
+///
+/// ```kcl
+/// schema Server:
+///     port: int
+///
+///     check:
+///         port == "80"
+/// ```
+///
+/// ### Explanation
+///
+/// Only the equality and the membership of an attribute against the literals are decided, e.g.
+/// `port == "80"` and `mode in ["a", "b"]`, either standalone or as an operand of `and`. The
+/// optional attributes, the conditional checks and the named types are skipped, because the
+/// attributes may be `None` or the types may be unknown to the lint.
+pub static UNSATISFIABLE_CHECK: &Lint = &Lint {
+    name: stringify!("UNSATISFIABLE_CHECK"),
+    level: Level::Warning,
+    desc: "Check for schema checks which no value of the attribute type satisfies",
+    code: "W0112",
+    note: Some("Consider changing the attribute type or the check"),
+};
+
+declare_lint_pass!(UnsatisfiableCheck => [UNSATISFIABLE_CHECK]);
+
+impl LintPass for UnsatisfiableCheck {
+    fn check_schema_stmt(
+        &mut self,
+        handler: &mut Handler,
+        _ctx: &mut LintContext,
+        schema_stmt: &ast::SchemaStmt,
+    ) {
+        let attrs: IndexMap<String, &ast::SchemaAttr> = schema_stmt
+            .body
+            .iter()
+            .filter_map(|stmt| match &stmt.node {
+                ast::Stmt::SchemaAttr(schema_attr) if !schema_attr.is_optional => {
+                    Some((schema_attr.name.node.clone(), schema_attr))
+                }
+                _ => None,
+            })
+            .collect();
+        if attrs.is_empty() {
+            return;
+        }
+        for check in &schema_stmt.checks {
+            if check.node.if_cond.is_some() {
+                continue;
+            }
+            if let Some(attr) = unsatisfiable_attr(&check.node.test.node, &attrs) {
+                let ty = attr.ty.node.to_string();
+                handler.add_warning(
+                    WarningKind::UnsatisfiableCheckWarning,
+                    &[
+                        Message {
+                            range: check.node.test.get_span_pos(),
+                            style: Style::LineAndColumn,
+                            message: format!(
+                                "attribute '{}' can never be satisfied",
+                                attr.name.node
+                            ),
+                            note: Some(format!(
+                                "no value of the type '{}' satisfies the check: {}",
+                                ty,
+                                print_ast_node(ASTNode::Expr(&check.node.test))
+                            )),
+                            suggested_replacement: None,
+                        },
+                        Message {
+                            range: attr.name.get_span_pos(),
+                            style: Style::LineAndColumn,
+                            message: format!(
+                                "attribute '{}' is declared with the type '{}' here",
+                                attr.name.node, ty
+                            ),
+                            note: None,
+                            suggested_replacement: None,
+                        },
+                    ],
+                );
+            }
+        }
+    }
+}
+
+/// Returns the attribute which makes the check unsatisfiable, e.g. `port` of `port == "80"` with
+/// `port: int`, or `None` if the check may be satisfied or is not decidable.
+fn unsatisfiable_attr<'a>(
+    expr: &ast::Expr,
+    attrs: &IndexMap<String, &'a ast::SchemaAttr>,
+) -> Option<&'a ast::SchemaAttr> {
+    let attr = |expr: &ast::Expr| match expr {
+        ast::Expr::Identifier(identifier) if identifier.names.len() == 1 => {
+            attrs.get(&identifier.names[0].node).copied()
+        }
+        _ => None,
+    };
+    let env = ConstEnv::new();
+    match expr {
+        ast::Expr::Paren(paren_expr) => unsatisfiable_attr(&paren_expr.expr.node, attrs),
+        ast::Expr::Binary(binary_expr) if binary_expr.op == ast::BinOp::And => {
+            unsatisfiable_attr(&binary_expr.left.node, attrs)
+                .or_else(|| unsatisfiable_attr(&binary_expr.right.node, attrs))
+        }
+        ast::Expr::Compare(compare) if compare.ops.len() == 1 => {
+            let (left, right) = (&compare.left.node, &compare.comparators[0].node);
+            match compare.ops[0] {
+                ast::CmpOp::Eq => {
+                    let (attr, value) = match (attr(left), attr(right)) {
+                        (Some(attr), None) => (attr, const_value(right, &env)?),
+                        (None, Some(attr)) => (attr, const_value(left, &env)?),
+                        _ => return None,
+                    };
+                    (!type_contains(&attr.ty.node, &value)?).then_some(attr)
+                }
+                ast::CmpOp::In => {
+                    let attr = attr(left)?;
+                    let elts = match right {
+                        ast::Expr::List(list_expr) if !list_expr.elts.is_empty() => &list_expr.elts,
+                        _ => return None,
+                    };
+                    for elt in elts {
+                        let value = const_value(&elt.node, &env)?;
+                        if type_contains(&attr.ty.node, &value)? {
+                            return None;
+                        }
+                    }
+                    Some(attr)
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Returns whether a value of the type equals the constant value, or `None` if it is unknown,
+/// e.g. the named types, and `None` which is a value of all the types.
+fn type_contains(ty: &ast::Type, value: &ConstValue) -> Option<bool> {
+    match (ty, value) {
+        (_, ConstValue::None) => None,
+        (ast::Type::Any | ast::Type::Named(_) | ast::Type::Function(_), _) => None,
+        (ast::Type::Union(union_type), value) => {
+            let mut contains = Some(false);
+            for elem in &union_type.type_elements {
+                match type_contains(&elem.node, value) {
+                    Some(true) => return Some(true),
+                    Some(false) => {}
+                    None => contains = None,
+                }
+            }
+            contains
+        }
+        (ast::Type::List(_) | ast::Type::Dict(_), _) => Some(false),
+        // The equality of the booleans and the numbers is not decided.
+        (ast::Type::Basic(ast::BasicType::Bool), ConstValue::Int(_) | ConstValue::Float(_))
+        | (ast::Type::Basic(ast::BasicType::Int | ast::BasicType::Float), ConstValue::Bool(_)) => {
+            None
+        }
+        (ast::Type::Basic(ast::BasicType::Bool), value) => {
+            Some(matches!(value, ConstValue::Bool(_)))
+        }
+        (ast::Type::Basic(ast::BasicType::Int), ConstValue::Int(_)) => Some(true),
+        (ast::Type::Basic(ast::BasicType::Int), ConstValue::Float(value)) => {
+            Some(value.fract() == 0.0)
+        }
+        (ast::Type::Basic(ast::BasicType::Float), value) => Some(value.as_float().is_some()),
+        (ast::Type::Basic(ast::BasicType::Str), value) => Some(matches!(value, ConstValue::Str(_))),
+        (ast::Type::Basic(_), _) => Some(false),
+        (ast::Type::Literal(literal_type), value) => match (literal_type, value) {
+            (ast::LiteralType::Bool(literal), ConstValue::Bool(value)) => Some(literal == value),
+            (ast::LiteralType::Str(literal), ConstValue::Str(value)) => Some(literal == value),
+            // The literals with the unit suffixes, e.g. `1Ki`, are not folded.
+            (ast::LiteralType::Int(literal), _) if literal.suffix.is_some() => None,
+            (ast::LiteralType::Bool(_), ConstValue::Int(_) | ConstValue::Float(_))
+            | (ast::LiteralType::Int(_) | ast::LiteralType::Float(_), ConstValue::Bool(_)) => None,
+            (ast::LiteralType::Int(literal), value) => {
+                Some(value.as_float() == Some(literal.value as f64))
+            }
+            (ast::LiteralType::Float(literal), value) => Some(value.as_float() == Some(*literal)),
+            _ => Some(false),
+        },
+    }
+}
+
+/// Collects the names assigned in the schema body, e.g. `port = 80`, which override the defaults.
+fn collect_assigned_names(body: &[ast::NodeRef<ast::Stmt>], names: &mut IndexSet<String>) {
+    for stmt in body {
+        match &stmt.node {
+            ast::Stmt::Assign(assign_stmt) => {
+                for target in &assign_stmt.targets {
+                    names.insert(target.node.name.node.clone());
+                }
+            }
+            ast::Stmt::AugAssign(aug_assign_stmt) => {
+                names.insert(aug_assign_stmt.target.node.name.node.clone());
+            }
+            ast::Stmt::If(if_stmt) => {
+                collect_assigned_names(&if_stmt.body, names);
+                collect_assigned_names(&if_stmt.orelse, names);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Collects the root names of the identifiers in an expression in order without duplicates.
+#[derive(Default)]
+struct NameCollector {
+    names: IndexSet<String>,
+}
+
+impl MutSelfWalker for NameCollector {
+    fn walk_identifier(&mut self, identifier: &ast::Identifier) {
+        if let Some(name) = identifier.names.first() {
+            self.names.insert(name.node.clone());
+        }
+    }
+}
+
+/// A value of an expression which is known statically.
+#[derive(Debug, Clone, PartialEq)]
+enum ConstValue {
+    None,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+}
+
+impl ConstValue {
+    fn truthy(&self) -> bool {
+        match self {
+            ConstValue::None => false,
+            ConstValue::Bool(value) => *value,
+            ConstValue::Int(value) => *value != 0,
+            ConstValue::Float(value) => *value != 0.0,
+            ConstValue::Str(value) => !value.is_empty(),
+        }
+    }
+
+    fn as_float(&self) -> Option<f64> {
+        match self {
+            ConstValue::Int(value) => Some(*value as f64),
+            ConstValue::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+/// The values of the names known statically, e.g. the defaults of schema attributes.
+type ConstEnv = IndexMap<String, ConstValue>;
+
+/// Returns the truthiness of the expression if it is statically known. The logical operators
+/// fold with one constant operand, e.g. `x or True` is always true.
+fn const_truth(expr: &ast::Expr, env: &ConstEnv) -> Option<bool> {
+    match expr {
+        ast::Expr::Paren(paren_expr) => const_truth(&paren_expr.expr.node, env),
+        ast::Expr::Unary(unary_expr) if unary_expr.op == ast::UnaryOp::Not => {
+            const_truth(&unary_expr.operand.node, env).map(|value| !value)
+        }
+        ast::Expr::Binary(binary_expr) if binary_expr.op == ast::BinOp::And => {
+            let left = const_truth(&binary_expr.left.node, env);
+            let right = const_truth(&binary_expr.right.node, env);
+            match (left, right) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (Some(true), Some(true)) => Some(true),
+                _ => None,
+            }
+        }
+        ast::Expr::Binary(binary_expr) if binary_expr.op == ast::BinOp::Or => {
+            let left = const_truth(&binary_expr.left.node, env);
+            let right = const_truth(&binary_expr.right.node, env);
+            match (left, right) {
+                (Some(true), _) | (_, Some(true)) => Some(true),
+                (Some(false), Some(false)) => Some(false),
+                _ => None,
+            }
+        }
+        _ => const_value(expr, env).map(|value| value.truthy()),
+    }
+}
+
+/// Folds the expression consisting of literals, the names in `env` and the arithmetic, logical
+/// and comparison operators to its value, or returns `None` if it is not statically constant.
+fn const_value(expr: &ast::Expr, env: &ConstEnv) -> Option<ConstValue> {
+    match expr {
+        ast::Expr::NameConstantLit(name_constant_lit) => match name_constant_lit.value {
+            ast::NameConstant::True => Some(ConstValue::Bool(true)),
+            ast::NameConstant::False => Some(ConstValue::Bool(false)),
+            ast::NameConstant::None | ast::NameConstant::Undefined => Some(ConstValue::None),
+        },
+        // The number literals with the unit suffixes, e.g. `1Ki`, are not folded.
+        ast::Expr::NumberLit(number_lit) if number_lit.binary_suffix.is_none() => {
+            match number_lit.value {
+                ast::NumberLitValue::Int(value) => Some(ConstValue::Int(value)),
+                ast::NumberLitValue::Float(value) => Some(ConstValue::Float(value)),
+            }
+        }
+        ast::Expr::StringLit(string_lit) => Some(ConstValue::Str(string_lit.value.clone())),
+        ast::Expr::Identifier(identifier) if identifier.names.len() == 1 => {
+            env.get(&identifier.names[0].node).cloned()
+        }
+        ast::Expr::Paren(paren_expr) => const_value(&paren_expr.expr.node, env),
+        ast::Expr::Unary(unary_expr) => {
+            let operand = const_value(&unary_expr.operand.node, env)?;
+            match (&unary_expr.op, operand) {
+                (ast::UnaryOp::Not, operand) => Some(ConstValue::Bool(!operand.truthy())),
+                (ast::UnaryOp::UAdd, ConstValue::Int(value)) => Some(ConstValue::Int(value)),
+                (ast::UnaryOp::UAdd, ConstValue::Float(value)) => Some(ConstValue::Float(value)),
+                (ast::UnaryOp::USub, ConstValue::Int(value)) => {
+                    value.checked_neg().map(ConstValue::Int)
+                }
+                (ast::UnaryOp::USub, ConstValue::Float(value)) => Some(ConstValue::Float(-value)),
+                _ => None,
+            }
+        }
+        ast::Expr::Binary(binary_expr) => {
+            let left = const_value(&binary_expr.left.node, env)?;
+            let right = const_value(&binary_expr.right.node, env)?;
+            match binary_expr.op {
+                ast::BinOp::And => Some(if left.truthy() { right } else { left }),
+                ast::BinOp::Or => Some(if left.truthy() { left } else { right }),
+                ast::BinOp::Add | ast::BinOp::Sub | ast::BinOp::Mul => {
+                    const_arith(&binary_expr.op, left, right)
+                }
+                _ => None,
+            }
+        }
+        ast::Expr::Compare(compare) => {
+            let mut left = const_value(&compare.left.node, env)?;
+            for (op, comparator) in compare.ops.iter().zip(&compare.comparators) {
+                let right = const_value(&comparator.node, env)?;
+                if !const_compare(op, &left, &right)? {
+                    return Some(ConstValue::Bool(false));
+                }
+                left = right;
+            }
+            Some(ConstValue::Bool(true))
+        }
+        _ => None,
+    }
+}
+
+fn const_arith(op: &ast::BinOp, left: ConstValue, right: ConstValue) -> Option<ConstValue> {
+    match (op, left, right) {
+        (ast::BinOp::Add, ConstValue::Str(left), ConstValue::Str(right)) => {
+            Some(ConstValue::Str(left + &right))
+        }
+        (ast::BinOp::Add, ConstValue::Int(left), ConstValue::Int(right)) => {
+            left.checked_add(right).map(ConstValue::Int)
+        }
+        (ast::BinOp::Sub, ConstValue::Int(left), ConstValue::Int(right)) => {
+            left.checked_sub(right).map(ConstValue::Int)
+        }
+        (ast::BinOp::Mul, ConstValue::Int(left), ConstValue::Int(right)) => {
+            left.checked_mul(right).map(ConstValue::Int)
+        }
+        (op, left, right) => {
+            let (left, right) = (left.as_float()?, right.as_float()?);
+            match op {
+                ast::BinOp::Add => Some(ConstValue::Float(left + right)),
+                ast::BinOp::Sub => Some(ConstValue::Float(left - right)),
+                ast::BinOp::Mul => Some(ConstValue::Float(left * right)),
+                _ => None,
+            }
+        }
+    }
+}
+
+fn const_compare(op: &ast::CmpOp, left: &ConstValue, right: &ConstValue) -> Option<bool> {
+    let ordering = match (left, right) {
+        (ConstValue::Str(left), ConstValue::Str(right)) => left.partial_cmp(right),
+        (ConstValue::Bool(left), ConstValue::Bool(right)) => left.partial_cmp(right),
+        (ConstValue::None, ConstValue::None) => Some(std::cmp::Ordering::Equal),
+        _ => left.as_float()?.partial_cmp(&right.as_float()?),
+    }?;
+    match op {
+        ast::CmpOp::Eq => Some(ordering.is_eq()),
+        ast::CmpOp::NotEq => Some(ordering.is_ne()),
+        ast::CmpOp::Lt => Some(ordering.is_lt()),
+        ast::CmpOp::LtE => Some(ordering.is_le()),
+        ast::CmpOp::Gt => Some(ordering.is_gt()),
+        ast::CmpOp::GtE => Some(ordering.is_ge()),
+        _ => None,
+    }
+}
@@ -213,6 +213,8 @@ impl MutSelfWalker for Linter<CombinedLintPass> {
         self.walk_expr(&aug_assign_stmt.value.node);
     }
     fn walk_assert_stmt(&mut self, assert_stmt: &ast::AssertStmt) {
+        self.pass
+            .check_assert_stmt(&mut self.handler, &mut self.ctx, assert_stmt);
         set_pos!(self, &assert_stmt.test);
         self.walk_expr(&assert_stmt.test.node);
         walk_set_if!(self, walk_expr, assert_stmt.if_cond);
@@ -233,6 +235,8 @@ impl MutSelfWalker for Linter<CombinedLintPass> {
         walk_set_if!(self, walk_expr, schema_attr.value);
     }
     fn walk_schema_stmt(&mut self, schema_stmt: &ast::SchemaStmt) {
+        self.pass
+            .check_schema_stmt(&mut self.handler, &mut self.ctx, schema_stmt);
         walk_set_if!(self, walk_identifier, schema_stmt.parent_name);
         walk_set_if!(self, walk_identifier, schema_stmt.for_host_name);
         walk_set_if!(self, walk_arguments, schema_stmt.args);
@@ -354,6 +358,8 @@ impl MutSelfWalker for Linter<CombinedLintPass> {
         self.walk_expr(&schema_expr.config.node);
     }
     fn walk_config_expr(&mut self, config_expr: &ast::ConfigExpr) {
+        self.pass
+            .check_config_expr(&mut self.handler, &mut self.ctx, config_expr);
         for config_entry in &config_expr.items {
             walk_set_if!(self, walk_expr, config_entry.node.key);
             set_pos!(self, &config_entry.node.value);
@@ -197,6 +197,7 @@ impl<'ctx> MutSelfTypedResultWalker<'ctx> for Namer<'_> {
             .add_import_info(ImportInfo::new(
                 import_stmt.name.clone(),
                 import_stmt.path.node.clone(),
+                import_stmt.asname.is_some(),
             ));
 
         None
@@ -94,6 +94,13 @@ impl<'ctx> Resolver<'_> {
             }
             TypeKind::Module(module_ty) => {
                 match &module_ty.kind {
+                    // The failed import is reported, and the members of it are unknown.
+                    crate::ty::ModuleKind::User
+                        if self.options.minimal_diagnostics
+                            && self.ctx.invalid_pkg_scope.contains(&module_ty.pkgpath) =>
+                    {
+                        (true, self.any_ty())
+                    }
                     crate::ty::ModuleKind::User => match self.scope_map.get(&module_ty.pkgpath) {
                         Some(scope) => match scope.borrow().elems.get(attr) {
                             Some(v) => {
@@ -2,15 +2,37 @@ use std::sync::Arc;
 
 use crate::resolver::Resolver;
 use crate::ty::{
-    has_any_type, is_upper_bound, sup, Type, TypeInferMethods, TypeKind, TypeRef, ZERO_LIT_TYPES,
+    has_any_type, is_upper_bound, subsume, sup, Type, TypeInferMethods, TypeKind, TypeRef,
 };
 use indexmap::IndexMap;
 use kclvm_ast::ast;
 use kclvm_error::diagnostic::Range;
+use kclvm_error::{Message, Style, WarningKind};
 
-const DIV_OR_MOD_ZERO_MSG: &str = "integer division or modulo by zero";
+const DIV_OR_MOD_ZERO_MSG: &str = "division by zero";
 
 impl<'ctx> Resolver<'ctx> {
+    /// Reports the division or the modulo of a number by a constant zero, e.g. `1 / 0`,
+    /// `1.0 % 0.0` and `1 // (2 - 2)`. The divisors which are not constants are not checked.
+    pub(crate) fn check_div_by_zero(
+        &mut self,
+        left: &TypeRef,
+        op: &ast::BinOp,
+        divisor: &ast::NodeRef<ast::Expr>,
+        range: Range,
+    ) {
+        if matches!(op, ast::BinOp::Div | ast::BinOp::FloorDiv | ast::BinOp::Mod)
+            && self
+                .ctx
+                .ty_ctx
+                .literal_union_type_to_variable_type(left.clone())
+                .is_number()
+            && const_number(&divisor.node) == Some(0.0)
+        {
+            self.handler.add_type_error(DIV_OR_MOD_ZERO_MSG, range);
+        }
+    }
+
     /// Binary operator calculation table.
     ///
     /// Arithmetic (int or float; result has type float unless both operands have type int)
@@ -124,10 +146,6 @@ impl<'ctx> Resolver<'ctx> {
             }
             ast::BinOp::Div | ast::BinOp::FloorDiv => {
                 if t1.is_number() && t2.is_number() {
-                    if ZERO_LIT_TYPES.contains(&t2) {
-                        self.handler
-                            .add_type_error(DIV_OR_MOD_ZERO_MSG, range.clone());
-                    }
                     (true, number_binary(&t1, &t2))
                 } else {
                     (false, self.any_ty())
@@ -135,10 +153,6 @@ impl<'ctx> Resolver<'ctx> {
             }
             ast::BinOp::Mod => {
                 if t1.is_number() && t2.is_number() {
-                    if ZERO_LIT_TYPES.contains(&t2) {
-                        self.handler
-                            .add_type_error(DIV_OR_MOD_ZERO_MSG, range.clone());
-                    }
                     (true, self.int_ty())
                 } else {
                     (false, self.any_ty())
@@ -262,6 +276,10 @@ impl<'ctx> Resolver<'ctx> {
     /// list/config/schema  # lexicographical         [1] == [2]
     /// iterable            # 1 in [1, 2, 3], "s" in "ss", "key" in Schema
     /// relation            # a is True, b is Undefined
+    ///
+    /// The `range` is the operator between the operands. An ordered comparison of the
+    /// incompatible types is a type error, and an equality of the disjoint types, e.g.
+    /// `[1] == 1`, is a warning because it is always False.
     pub fn compare(
         &mut self,
         left: TypeRef,
@@ -298,10 +316,11 @@ impl<'ctx> Resolver<'ctx> {
         {
             return self.bool_ty();
         }
-        if matches!(op, ast::CmpOp::Eq) && t1.is_list() && t2.is_list() {
+        let is_equality = matches!(op, ast::CmpOp::Eq | ast::CmpOp::NotEq);
+        if is_equality && t1.is_list() && t2.is_list() {
             return self.bool_ty();
         }
-        if matches!(op, ast::CmpOp::Eq) && t1.is_dict_or_schema() && t2.is_dict_or_schema() {
+        if is_equality && t1.is_dict_or_schema() && t2.is_dict_or_schema() {
             return self.bool_ty();
         }
         if matches!(op, ast::CmpOp::In | ast::CmpOp::NotIn) && t2.is_iterable() {
@@ -319,15 +338,96 @@ impl<'ctx> Resolver<'ctx> {
         {
             return self.bool_ty();
         }
+        if is_equality {
+            if !is_overlapping(&t1, &t2) {
+                self.handler.add_warning(
+                    WarningKind::IncompatibleComparisonWarning,
+                    &[Message {
+                        range,
+                        style: Style::LineAndColumn,
+                        message: format!(
+                            "comparison of '{}' and '{}' with '{}' is always {}",
+                            t1.ty_str(),
+                            t2.ty_str(),
+                            op.symbol(),
+                            if matches!(op, ast::CmpOp::Eq) {
+                                "False"
+                            } else {
+                                "True"
+                            },
+                        ),
+                        note: None,
+                        suggested_replacement: None,
+                    }],
+                );
+            }
+            return self.bool_ty();
+        }
         self.handler.add_type_error(
             &format!(
-                "unsupported operand type(s) for {}: '{}' and '{}'",
-                op.symbol(),
+                "cannot compare '{}' and '{}' with '{}'",
                 t1.ty_str(),
                 t2.ty_str(),
+                op.symbol(),
             ),
             range,
         );
         self.any_ty()
     }
 }
+
+/// Whether a value can be of both types, e.g. `int | [int]` and `[int]`.
+fn is_overlapping(t1: &TypeRef, t2: &TypeRef) -> bool {
+    let members = |ty: &TypeRef| {
+        if ty.is_union() {
+            ty.union_types()
+        } else {
+            vec![ty.clone()]
+        }
+    };
+    let members2 = members(t2);
+    members(t1).iter().any(|ty1| {
+        members2.iter().any(|ty2| {
+            subsume(ty1.clone(), ty2.clone(), false) || subsume(ty2.clone(), ty1.clone(), false)
+        })
+    })
+}
+
+/// Returns the value of the numeric constant expression folded from the number literals, or
+/// `None` if the expression is not a constant, e.g. `-(1 - 1.0)` is `0.0`. The literals with the
+/// unit suffixes, e.g. `1Ki`, are not folded.
+fn const_number(expr: &ast::Expr) -> Option<f64> {
+    match expr {
+        ast::Expr::NumberLit(number_lit) if number_lit.binary_suffix.is_none() => {
+            match number_lit.value {
+                ast::NumberLitValue::Int(value) => Some(value as f64),
+                ast::NumberLitValue::Float(value) => Some(value),
+            }
+        }
+        ast::Expr::NameConstantLit(name_constant) => match name_constant.value {
+            ast::NameConstant::True => Some(1.0),
+            ast::NameConstant::False => Some(0.0),
+            _ => None,
+        },
+        ast::Expr::Paren(paren_expr) => const_number(&paren_expr.expr.node),
+        ast::Expr::Unary(unary_expr) => {
+            let value = const_number(&unary_expr.operand.node)?;
+            match unary_expr.op {
+                ast::UnaryOp::UAdd => Some(value),
+                ast::UnaryOp::USub => Some(-value),
+                _ => None,
+            }
+        }
+        ast::Expr::Binary(binary_expr) => {
+            let left = const_number(&binary_expr.left.node)?;
+            let right = const_number(&binary_expr.right.node)?;
+            match binary_expr.op {
+                ast::BinOp::Add => Some(left + right),
+                ast::BinOp::Sub => Some(left - right),
+                ast::BinOp::Mul => Some(left * right),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
@@ -10,6 +10,7 @@ use crate::ty::{
 use indexmap::IndexMap;
 use kclvm_ast::ast;
 use kclvm_ast_pretty::{print_ast_node, print_schema_expr, ASTNode};
+use kclvm_error::diagnostic::Range;
 use kclvm_error::*;
 
 use super::doc::parse_schema_doc_string;
@@ -110,6 +111,7 @@ impl<'ctx> Resolver<'_> {
                                 return_ty: Arc::new(Type::VOID),
                                 is_variadic: false,
                                 kw_only_index: None,
+                                deprecation: None,
                             }),
                             index_signature: None,
                             decorators: vec![],
@@ -327,6 +329,7 @@ impl<'ctx> Resolver<'_> {
             } else {
                 self.any_ty()
             };
+            self.record_ambiguous_import(name, (start.clone(), end.clone()));
             self.insert_object(
                 name,
                 ScopeObject {
@@ -349,6 +352,7 @@ impl<'ctx> Resolver<'_> {
         let name = &target.node.names[0].node;
         let (start, end) = target.get_span_pos();
         let ty = self.walk_identifier_expr(&unification_stmt.value.node.name);
+        self.record_ambiguous_import(name, (start.clone(), end.clone()));
         self.insert_object(
             name,
             ScopeObject {
@@ -362,6 +366,26 @@ impl<'ctx> Resolver<'_> {
         );
     }
 
+    /// Records the name if it is imported, because the definition replaces the imported module
+    /// in the package scope, e.g. `import math as _m` and `_m = 1`, so the references to the name
+    /// in the importing files are ambiguous.
+    fn record_ambiguous_import(&mut self, name: &str, range: Range) {
+        let imports: Vec<Range> = match self.scope.borrow().elems.get(name) {
+            Some(obj) => match &obj.borrow().kind {
+                ScopeObjectKind::Module(module) => module
+                    .import_stmts
+                    .iter()
+                    .map(|(stmt, _)| stmt.get_span_pos())
+                    .collect(),
+                _ => return,
+            },
+            None => return,
+        };
+        self.ctx
+            .ambiguous_names
+            .insert(format!("{}.{}", self.ctx.pkgpath, name), (imports, range));
+    }
+
     pub(crate) fn build_rule_protocol_type(
         &mut self,
         rule_stmt: &'ctx ast::RuleStmt,
@@ -846,6 +870,7 @@ impl<'ctx> Resolver<'_> {
                 return_ty: Arc::new(Type::ANY),
                 is_variadic: false,
                 kw_only_index: None,
+                deprecation: None,
             }),
             index_signature,
             decorators,
@@ -998,6 +1023,7 @@ impl<'ctx> Resolver<'_> {
                 return_ty: Arc::new(Type::ANY),
                 is_variadic: false,
                 kw_only_index: None,
+                deprecation: None,
             }),
             index_signature,
             decorators,
@@ -164,19 +164,46 @@ pub struct Context {
     pub type_alias_mapping: IndexMap<String, IndexMap<String, String>>,
     /// invalid pkg scope, remove when after resolve
     pub invalid_pkg_scope: IndexSet<String>,
+    /// The nesting depth of the configs and the lists being resolved.
+    pub nesting_depth: usize,
+    /// The undefined names already reported in the minimal diagnostics mode, which are the
+    /// package paths joined with the names, e.g. `__main__.Nam`.
+    pub poisoned_names: IndexSet<String>,
+    /// The imported names which are also defined in the package, keyed by the package paths
+    /// joined with the names, with the ranges of the import statements and the definition.
+    pub ambiguous_names: IndexMap<String, (Vec<Range>, Range)>,
 }
 
 /// Resolve options.
 /// - lint_check: whether to run lint passes
 /// - resolve_val: whether to resolve and print their AST to value for some nodes.
+/// - implicit_coercion_check: whether to warn the implicit coercions, e.g. `"1" == 1`.
+/// - explicit_type_check: whether to warn the top level variables and the schema attributes
+///   without the type annotations, e.g. `port = 80`.
+/// - ambiguous_reference_check: whether to report the references to the names both imported and
+///   defined in the package, e.g. `_m` of `import math as _m` and `_m = 1`.
+/// - max_schema_depth: the maximum nesting depth of the configs, the lists and the schema
+///   expressions, e.g. the configs of a recursive schema type. The deeper expressions are not
+///   resolved and reported instead of overflowing the stack.
+/// - minimal_diagnostics: whether to report only the root causes, e.g. the uses of a failed import
+///   or an undefined name are not reported again.
 #[derive(Clone, Debug)]
 pub struct Options {
     pub lint_check: bool,
     pub resolve_val: bool,
     pub merge_program: bool,
     pub type_erasure: bool,
+    pub implicit_coercion_check: bool,
+    pub explicit_type_check: bool,
+    pub ambiguous_reference_check: bool,
+    pub max_schema_depth: usize,
+    pub minimal_diagnostics: bool,
 }
 
+/// The default maximum nesting depth of the configs and the lists, which is far beyond the depth
+/// of the handwritten configs.
+pub const DEFAULT_MAX_SCHEMA_DEPTH: usize = 100;
+
 impl Default for Options {
     fn default() -> Self {
         Self {
@@ -184,6 +211,11 @@ impl Default for Options {
             resolve_val: false,
             merge_program: true,
             type_erasure: true,
+            implicit_coercion_check: false,
+            explicit_type_check: false,
+            ambiguous_reference_check: false,
+            max_schema_depth: DEFAULT_MAX_SCHEMA_DEPTH,
+            minimal_diagnostics: false,
         }
     }
 }
@@ -185,6 +185,13 @@ impl<'ctx> MutSelfTypedResultWalker<'ctx> for Resolver<'_> {
                     target.get_span_pos(),
                     None,
                 );
+                self.check_implicit_coercion_assign(&assign_stmt.value, &value_ty, &expected_ty);
+                if assign_stmt.ty.is_none()
+                    && assign_stmt.targets.len() == 1
+                    && expected_ty.is_any()
+                {
+                    self.check_missing_type_annotation(target, &value_ty);
+                }
                 let upgrade_schema_type =
                     self.upgrade_dict_to_schema(value_ty.clone(), expected_ty.clone());
                 self.node_ty_map.borrow_mut().insert(
@@ -268,6 +275,12 @@ impl<'ctx> MutSelfTypedResultWalker<'ctx> for Resolver<'_> {
             Ok(op) => op,
             Err(msg) => bug!("{}", msg),
         };
+        self.check_div_by_zero(
+            &left_ty,
+            &op,
+            &aug_assign_stmt.value,
+            aug_assign_stmt.target.get_span_pos(),
+        );
         let new_target_ty = self.binary(
             left_ty,
             right_ty,
@@ -514,7 +527,15 @@ impl<'ctx> MutSelfTypedResultWalker<'ctx> for Resolver<'_> {
                 }
                 self.binary(left_ty, right_ty, &binary_expr.op, range)
             }
-            _ => self.binary(left_ty, right_ty, &binary_expr.op, range),
+            _ => {
+                self.check_div_by_zero(
+                    &left_ty,
+                    &binary_expr.op,
+                    &binary_expr.right,
+                    range.clone(),
+                );
+                self.binary(left_ty, right_ty, &binary_expr.op, range)
+            }
         }
     }
 
@@ -1105,21 +1126,34 @@ impl<'ctx> MutSelfTypedResultWalker<'ctx> for Resolver<'_> {
     fn walk_compare(&mut self, compare: &'ctx ast::Compare) -> Self::Result {
         let t1 = self.expr(&compare.left);
         let t2 = self.expr(&compare.comparators[0]);
+        self.check_implicit_coercion_compare(
+            &compare.left,
+            &t1,
+            &compare.comparators[0],
+            &t2,
+            &compare.ops[0],
+        );
+        // The operator is highlighted, which is between the operands.
         self.compare(
-            t1.clone(),
+            t1,
             t2.clone(),
             &compare.ops[0],
-            (compare.left.get_pos(), compare.comparators[0].get_end_pos()),
+            (compare.left.get_end_pos(), compare.comparators[0].get_pos()),
         );
-        for i in 1..compare.comparators.len() - 1 {
-            let op = &compare.ops[i + 1];
-            let t2 = self.expr(&compare.comparators[i]);
+        // The chained comparison `a < b < c` is `a < b and b < c`.
+        let mut left_ty = t2;
+        for i in 1..compare.comparators.len() {
+            let right_ty = self.expr(&compare.comparators[i]);
             self.compare(
-                t1.clone(),
-                t2.clone(),
-                op,
-                compare.comparators[i].get_span_pos(),
+                left_ty,
+                right_ty.clone(),
+                &compare.ops[i],
+                (
+                    compare.comparators[i - 1].get_end_pos(),
+                    compare.comparators[i].get_pos(),
+                ),
             );
+            left_ty = right_ty;
         }
         self.bool_ty()
     }
@@ -1257,7 +1291,31 @@ impl<'ctx> Resolver<'_> {
             None => None,
         };
 
+        // The configs and the lists are counted, including the configs of the schema expressions,
+        // so the resolving of any deep nesting is bounded.
+        let nested = matches!(
+            &expr.node,
+            ast::Expr::Config(_)
+                | ast::Expr::List(_)
+                | ast::Expr::ListComp(_)
+                | ast::Expr::DictComp(_)
+        );
+        if nested {
+            if self.ctx.nesting_depth >= self.options.max_schema_depth {
+                self.handler.add_compile_error(
+                    "maximum config nesting depth exceeded",
+                    expr.get_span_pos(),
+                );
+                // The type of the too deep expression is not recorded, so the advanced resolver
+                // skips it as well.
+                return self.any_ty();
+            }
+            self.ctx.nesting_depth += 1;
+        }
         let ty = self.walk_expr(&expr.node);
+        if nested {
+            self.ctx.nesting_depth -= 1;
+        }
 
         if let Some(expected_ty) = expected_ty {
             let upgrade_ty = self.upgrade_dict_to_schema(ty.clone(), expected_ty);
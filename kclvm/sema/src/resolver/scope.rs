@@ -6,7 +6,7 @@ use kclvm_ast::ast::Stmt;
 use kclvm_ast::ast::Stmt::Import;
 use kclvm_ast::{ast, MAIN_PKG};
 use kclvm_error::diagnostic::Range;
-use kclvm_error::{Handler, Level};
+use kclvm_error::{ErrorKind, Handler, Level, Message, Style, WarningKind};
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::collections::VecDeque;
@@ -16,9 +16,10 @@ use std::{
     rc::{Rc, Weak},
 };
 
+use crate::builtin::deprecation_message;
 use crate::resolver::Resolver;
 use crate::ty::SchemaType;
-use crate::ty::TypeRef;
+use crate::ty::{TypeKind, TypeRef};
 use crate::{builtin::BUILTIN_FUNCTIONS, ty::TypeInferMethods};
 use kclvm_ast::ast::AstIndex;
 use kclvm_ast::pos::ContainsPos;
@@ -424,8 +425,21 @@ impl<'ctx> Resolver<'ctx> {
     /// return the any type.
     pub fn lookup_type_from_scope(&mut self, name: &str, range: Range) -> TypeRef {
         match self.find_type_in_scope(name) {
-            Some(ty) => ty,
+            Some(ty) => {
+                if !self.ctx.l_value {
+                    self.check_ambiguous_reference(name, &range);
+                }
+                ty
+            }
             None => {
+                if self.options.minimal_diagnostics
+                    && !self
+                        .ctx
+                        .poisoned_names
+                        .insert(format!("{}.{}", self.ctx.pkgpath, name))
+                {
+                    return self.any_ty();
+                }
                 let mut suggestion = String::new();
                 let names = self
                     .scope
@@ -452,6 +466,104 @@ impl<'ctx> Resolver<'ctx> {
         }
     }
 
+    /// Reports the reference to a name which is both imported by the file and defined in the
+    /// package, with the import statements and the definition as the candidates. The names
+    /// shadowed by the local variables are not ambiguous.
+    fn check_ambiguous_reference(&mut self, name: &str, range: &Range) {
+        if !self.options.ambiguous_reference_check {
+            return;
+        }
+        let (imports, definition) = match self
+            .ctx
+            .ambiguous_names
+            .get(&format!("{}.{}", self.ctx.pkgpath, name))
+        {
+            Some((imports, definition)) => (
+                imports
+                    .iter()
+                    .filter(|import| import.0.filename == range.0.filename)
+                    .cloned()
+                    .collect::<Vec<Range>>(),
+                definition.clone(),
+            ),
+            None => return,
+        };
+        let resolves_to_definition = match self.scope.borrow().lookup(name) {
+            Some(obj) => obj.borrow().start == definition.0,
+            None => false,
+        };
+        if imports.is_empty() || !resolves_to_definition {
+            return;
+        }
+        let mut msgs = vec![Message {
+            range: range.clone(),
+            style: Style::LineAndColumn,
+            message: format!("ambiguous reference to '{}'", name),
+            note: Some(format!(
+                "'{}' is both imported and defined, rename the import or the definition",
+                name
+            )),
+            suggested_replacement: None,
+        }];
+        for import in imports {
+            msgs.push(Message {
+                range: import,
+                style: Style::LineAndColumn,
+                message: format!("'{}' is imported here", name),
+                note: None,
+                suggested_replacement: None,
+            });
+        }
+        msgs.push(Message {
+            range: definition,
+            style: Style::LineAndColumn,
+            message: format!("'{}' is defined here", name),
+            note: None,
+            suggested_replacement: None,
+        });
+        self.handler
+            .add_error(ErrorKind::AmbiguousReferenceError, &msgs);
+    }
+
+    /// Warn the load of the deprecated builtin function `name` at `range` unless it is shadowed
+    /// by a user definition.
+    pub(crate) fn check_deprecated_builtin(&mut self, name: &str, range: &Range) {
+        let builtin = match self.builtin_scope.borrow().elems.get(name) {
+            Some(obj) => obj.clone(),
+            None => return,
+        };
+        let resolves_to_builtin = match self.scope.borrow().lookup(name) {
+            Some(obj) => Rc::ptr_eq(&obj, &builtin),
+            None => false,
+        };
+        if resolves_to_builtin {
+            let ty = builtin.borrow().ty.clone();
+            self.report_deprecated_builtin(name, &ty, &[], range);
+        }
+    }
+
+    /// Warn the use of the function type `ty` named `name` if it is deprecated, where the module
+    /// of the function is referred as `prefix`. Only the uses in the main package are warned,
+    /// the external packages are not changed by the users.
+    pub(crate) fn report_deprecated_builtin(
+        &mut self,
+        name: &str,
+        ty: &TypeRef,
+        prefix: &[String],
+        range: &Range,
+    ) {
+        if self.ctx.pkgpath != MAIN_PKG {
+            return;
+        }
+        if let TypeKind::Function(func_ty) = &ty.kind {
+            if let Some(deprecation) = &func_ty.deprecation {
+                let msg = deprecation_message(name, deprecation, prefix, range.clone());
+                self.handler
+                    .add_warning(WarningKind::DeprecatedBuiltinWarning, &[msg]);
+            }
+        }
+    }
+
     /// Set type to the scope exited object, if not found, emit a compile error.
     pub fn set_infer_type_to_scope<T>(&mut self, name: &str, ty: TypeRef, node: &ast::Node<T>) {
         let mut scope = self.scope.borrow_mut();
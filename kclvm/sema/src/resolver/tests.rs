@@ -1,6 +1,6 @@
 use super::Options;
 use super::Resolver;
-use crate::builtin::BUILTIN_FUNCTION_NAMES;
+use crate::builtin::{deprecation_message, Deprecation, BUILTIN_FUNCTION_NAMES};
 use crate::pre_process::pre_process_program;
 use crate::resolver::resolve_program;
 use crate::resolver::resolve_program_with_opts;
@@ -481,6 +481,417 @@ fn test_lint() {
     }
 }
 
+#[test]
+fn test_lint_duplicate_assignment() {
+    let mut program =
+        parse_program("./src/resolver/test_data/lint_duplicate_assignment.k").unwrap();
+    let scope = resolve_program(&mut program);
+    let diags: Vec<&Diagnostic> = scope
+        .handler
+        .diagnostics
+        .iter()
+        .filter(|diag| {
+            diag.code
+                == Some(DiagnosticId::Warning(
+                    WarningKind::DuplicateAssignmentWarning,
+                ))
+        })
+        .collect();
+    // Overriding `a` of another config and the union of `c` are not reported.
+    assert_eq!(diags.len(), 1);
+    let diag = diags[0];
+    assert_eq!(diag.level, Level::Warning);
+    assert_eq!(diag.messages.len(), 2);
+    assert_eq!(diag.messages[0].message, "duplicate assignment of 'a'");
+    assert_eq!(diag.messages[0].range.0.line, 4);
+    assert_eq!(diag.messages[0].range.0.column, Some(4));
+    assert_eq!(diag.messages[1].range.0.line, 2);
+    assert_eq!(diag.messages[1].range.0.column, Some(4));
+}
+
+#[test]
+fn test_lint_constant_assert() {
+    let mut program = parse_program("./src/resolver/test_data/lint_constant_assert.k").unwrap();
+    let scope = resolve_program(&mut program);
+    let diags: Vec<(Level, u64)> = scope
+        .handler
+        .diagnostics
+        .iter()
+        .filter(|diag| diag.code == Some(DiagnosticId::Warning(WarningKind::ConstantAssertWarning)))
+        .map(|diag| (diag.level, diag.messages[0].range.0.line))
+        .collect();
+    // `assert replicas > 0` and the conditional assertion are not reported.
+    assert_eq!(
+        diags,
+        vec![
+            (Level::Note, 2),
+            (Level::Warning, 3),
+            (Level::Note, 5),
+            (Level::Note, 6),
+        ]
+    );
+}
+
+#[test]
+fn test_lint_default_violates_check() {
+    let mut program =
+        parse_program("./src/resolver/test_data/lint_default_violates_check.k").unwrap();
+    let scope = resolve_program(&mut program);
+    let diags: Vec<(&str, u64, Option<u64>)> = scope
+        .handler
+        .diagnostics
+        .iter()
+        .filter(|diag| {
+            diag.code
+                == Some(DiagnosticId::Warning(
+                    WarningKind::DefaultViolatesCheckWarning,
+                ))
+        })
+        .map(|diag| {
+            let (start, _) = &diag.messages[0].range;
+            (diag.messages[0].message.as_str(), start.line, start.column)
+        })
+        .collect();
+    // The checks of `name` and `replicas` can not be evaluated, and the default of `port` in
+    // `Service` is overridden in the schema body.
+    assert_eq!(
+        diags,
+        vec![(
+            "The default value of 'port' violates the check: port must be between 1 and 65535",
+            2,
+            Some(16)
+        )]
+    );
+}
+
+#[test]
+fn test_lint_unsatisfiable_check() {
+    let mut program = parse_program("./src/resolver/test_data/lint_unsatisfiable_check.k").unwrap();
+    let scope = resolve_program(&mut program);
+    let diags: Vec<(&str, u64, Option<u64>, u64)> = scope
+        .handler
+        .diagnostics
+        .iter()
+        .filter(|diag| {
+            diag.code
+                == Some(DiagnosticId::Warning(
+                    WarningKind::UnsatisfiableCheckWarning,
+                ))
+        })
+        .map(|diag| {
+            let (start, _) = &diag.messages[0].range;
+            let (declaration, _) = &diag.messages[1].range;
+            (
+                diag.messages[0].message.as_str(),
+                start.line,
+                start.column,
+                declaration.line,
+            )
+        })
+        .collect();
+    // The checks of `config` with the named type and of the optional `alias` are not decided.
+    assert_eq!(
+        diags,
+        vec![
+            ("attribute 'port' can never be satisfied", 12, Some(8), 5),
+            ("attribute 'mode' can never be satisfied", 13, Some(8), 6),
+        ]
+    );
+}
+
+#[test]
+fn test_implicit_coercion() {
+    let file = "./src/resolver/test_data/implicit_coercion.k";
+    let implicit_coercions = |opts: Options| -> Vec<(u64, Option<Vec<String>>)> {
+        let mut program = parse_program(file).unwrap();
+        let scope = resolve_program_with_opts(&mut program, opts, None);
+        scope
+            .handler
+            .diagnostics
+            .iter()
+            .filter(|diag| {
+                diag.code == Some(DiagnosticId::Warning(WarningKind::ImplicitCoercionWarning))
+            })
+            .map(|diag| {
+                (
+                    diag.messages[0].range.0.line,
+                    diag.messages[0].suggested_replacement.clone(),
+                )
+            })
+            .collect()
+    };
+    // The lint is opt-in.
+    assert!(implicit_coercions(Options::default()).is_empty());
+    // The explicit conversions and the comparison of two strings are not reported.
+    assert_eq!(
+        implicit_coercions(Options {
+            implicit_coercion_check: true,
+            ..Default::default()
+        }),
+        vec![
+            (2, Some(vec!["int(\"1\")".to_string()])),
+            (4, Some(vec!["float(port)".to_string()])),
+            (6, Some(vec!["float(1)".to_string()])),
+        ]
+    );
+}
+
+#[test]
+fn test_missing_type_annotation() {
+    let file = "./src/resolver/test_data/missing_type_annotation.k";
+    let missing_annotations = |opts: Options| -> Vec<(u64, String, Option<Vec<String>>)> {
+        let mut program = parse_program(file).unwrap();
+        let scope = resolve_program_with_opts(&mut program, opts, None);
+        scope
+            .handler
+            .diagnostics
+            .iter()
+            .filter(|diag| {
+                diag.code
+                    == Some(DiagnosticId::Warning(
+                        WarningKind::MissingTypeAnnotationWarning,
+                    ))
+            })
+            .map(|diag| {
+                (
+                    diag.messages[0].range.0.line,
+                    diag.messages[0].message.clone(),
+                    diag.messages[0].suggested_replacement.clone(),
+                )
+            })
+            .collect()
+    };
+    // The lint is opt-in.
+    assert!(missing_annotations(Options::default()).is_empty());
+    // The annotated declarations, the assignments of the declared attributes and the local
+    // variables of the lambdas are not reported.
+    assert_eq!(
+        missing_annotations(Options {
+            explicit_type_check: true,
+            ..Default::default()
+        }),
+        vec![
+            (
+                3,
+                "'port' has no type annotation".to_string(),
+                Some(vec!["port: int".to_string()])
+            ),
+            (
+                7,
+                "'items' has no type annotation".to_string(),
+                Some(vec!["items: [int]".to_string()])
+            ),
+            (
+                8,
+                "'server' has no type annotation".to_string(),
+                Some(vec!["server: Server".to_string()])
+            ),
+            (
+                9,
+                "'nothing' has no type annotation and its type can not be inferred".to_string(),
+                None
+            ),
+            (
+                10,
+                "'double' has no type annotation".to_string(),
+                Some(vec!["double: (int) -> int".to_string()])
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_max_schema_depth() {
+    let file = "./src/resolver/test_data/schema_nesting_depth.k";
+    let depth_errors = |opts: Options| -> Vec<(u64, String)> {
+        let mut program = parse_program(file).unwrap();
+        let scope = resolve_program_with_opts(&mut program, opts, None);
+        scope
+            .handler
+            .diagnostics
+            .iter()
+            .map(|diag| {
+                (
+                    diag.messages[0].range.0.line,
+                    diag.messages[0].message.clone(),
+                )
+            })
+            .collect()
+    };
+    assert!(depth_errors(Options::default()).is_empty());
+    // The schema expressions deeper than the limit are reported once and not resolved.
+    assert_eq!(
+        depth_errors(Options {
+            max_schema_depth: 4,
+            ..Default::default()
+        }),
+        vec![(8, "maximum config nesting depth exceeded".to_string())]
+    );
+}
+
+#[test]
+fn test_max_schema_depth_pathological() {
+    let depth = 10000;
+    let code = format!(
+        "schema Node:\n    child?: Node\n\nlist = {}{}\ndict = {}1{}\nnode = Node {{{}{}}}\n",
+        "[".repeat(depth),
+        "]".repeat(depth),
+        "{a = ".repeat(depth),
+        "}".repeat(depth),
+        "child = {".repeat(depth),
+        "}".repeat(depth),
+    );
+    let file = std::env::temp_dir().join("kcl_resolver_nesting_depth_test.k");
+    std::fs::write(&file, code).unwrap();
+    // The parser is recursive as well, so the input is parsed on a large stack. The resolver
+    // stops at the limit, so each nesting is reported once.
+    let diags = std::thread::Builder::new()
+        .stack_size(512 * 1024 * 1024)
+        .spawn(move || {
+            let mut program = parse_program(file.to_str().unwrap()).unwrap();
+            let scope = resolve_program(&mut program);
+            scope
+                .handler
+                .diagnostics
+                .iter()
+                .map(|diag| {
+                    (
+                        diag.messages[0].range.0.line,
+                        diag.messages[0].message.clone(),
+                    )
+                })
+                .collect::<Vec<(u64, String)>>()
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+    let message = "maximum config nesting depth exceeded".to_string();
+    assert_eq!(
+        diags,
+        vec![(4, message.clone()), (5, message.clone()), (6, message)]
+    );
+}
+
+#[test]
+fn test_minimal_diagnostics() {
+    let file = "./src/resolver/test_data/minimal_diagnostics.k";
+    let error_lines = |opts: Options| -> Vec<u64> {
+        let mut program = parse_program(file).unwrap();
+        let scope = resolve_program_with_opts(&mut program, opts, None);
+        scope
+            .handler
+            .diagnostics
+            .iter()
+            .filter(|diag| diag.level == Level::Error)
+            .map(|diag| diag.messages[0].range.0.line)
+            .collect()
+    };
+    // Each use of the failed import is reported by default.
+    assert!(error_lines(Options::default()).len() > 1);
+    // Only the import failure is reported in the minimal diagnostics mode.
+    assert_eq!(
+        error_lines(Options {
+            minimal_diagnostics: true,
+            ..Default::default()
+        }),
+        vec![1]
+    );
+}
+
+#[test]
+fn test_incompatible_compare() {
+    let mut program = parse_program("./src/resolver/test_data/incompatible_compare.k").unwrap();
+    let scope = resolve_program(&mut program);
+    let diags: Vec<(Level, u64, Option<u64>, &str)> = scope
+        .handler
+        .diagnostics
+        .iter()
+        .map(|diag| {
+            let (start, _) = &diag.messages[0].range;
+            (
+                diag.level,
+                start.line,
+                start.column,
+                diag.messages[0].message.as_str(),
+            )
+        })
+        .collect();
+    // The comparisons of the overlapping types are not reported, and the operator is highlighted.
+    assert_eq!(
+        diags,
+        vec![
+            (
+                Level::Error,
+                2,
+                Some(9),
+                "cannot compare '[int]' and 'int(80)' with '<'"
+            ),
+            (
+                Level::Warning,
+                3,
+                Some(9),
+                "comparison of '[int]' and 'int(80)' with '==' is always False"
+            ),
+            (
+                Level::Warning,
+                4,
+                Some(9),
+                "comparison of '[int]' and 'str(80)' with '!=' is always True"
+            ),
+        ]
+    );
+    assert_eq!(
+        scope.handler.diagnostics[1].code,
+        Some(DiagnosticId::Warning(
+            WarningKind::IncompatibleComparisonWarning
+        ))
+    );
+}
+
+#[test]
+fn test_precision_loss() {
+    let mut program = parse_program("./src/resolver/test_data/precision_loss.k").unwrap();
+    let scope = resolve_program(&mut program);
+    let diags: Vec<(Level, Option<DiagnosticId>, u64, &str)> = scope
+        .handler
+        .diagnostics
+        .iter()
+        .map(|diag| {
+            (
+                diag.level,
+                diag.code.clone(),
+                diag.messages[0].range.0.line,
+                diag.messages[0].message.as_str(),
+            )
+        })
+        .collect();
+    // The floats assigned to int, integral or not, are type errors instead of the precision loss
+    // warnings, since they are never truncated implicitly.
+    assert_eq!(
+        diags,
+        vec![
+            (
+                Level::Error,
+                Some(DiagnosticId::Error(ErrorKind::TypeError)),
+                1,
+                "expected int, got float(3.5)"
+            ),
+            (
+                Level::Error,
+                Some(DiagnosticId::Error(ErrorKind::TypeError)),
+                2,
+                "expected int, got float(3)"
+            ),
+            (
+                Level::Warning,
+                Some(DiagnosticId::Warning(WarningKind::PrecisionLossWarning)),
+                3,
+                "possible precision loss assigning int to float"
+            ),
+        ]
+    );
+}
+
 #[test]
 fn test_resolve_schema_doc() {
     let mut program = parse_program("./src/resolver/test_data/doc.k").unwrap();
@@ -1164,3 +1575,137 @@ fn clear_cache_test() {
         second_scope.schema_mapping.len()
     );
 }
+
+#[test]
+fn test_div_by_zero() {
+    let mut program = parse_program("./src/resolver/test_data/div_by_zero.k").unwrap();
+    let scope = resolve_program(&mut program);
+    let diags: Vec<(u64, Option<u64>, &str)> = scope
+        .handler
+        .diagnostics
+        .iter()
+        .map(|diag| {
+            let (start, _) = &diag.messages[0].range;
+            (start.line, start.column, diag.messages[0].message.as_str())
+        })
+        .collect();
+    // The divisors which are not constants, e.g. `x - x`, are not reported.
+    assert_eq!(
+        diags,
+        vec![
+            (2, Some(4), "division by zero"),
+            (3, Some(4), "division by zero"),
+            (5, Some(4), "division by zero"),
+        ]
+    );
+    assert!(scope
+        .handler
+        .diagnostics
+        .iter()
+        .all(|diag| diag.level == Level::Error));
+}
+
+#[test]
+fn test_ambiguous_reference() {
+    let ambiguous_references = |opts: Options| -> Vec<Diagnostic> {
+        let mut program = parse_program("./src/resolver/test_data/ambiguous_reference.k").unwrap();
+        let scope = resolve_program_with_opts(&mut program, opts, None);
+        scope
+            .handler
+            .diagnostics
+            .iter()
+            .filter(|diag| {
+                diag.code == Some(DiagnosticId::Error(ErrorKind::AmbiguousReferenceError))
+            })
+            .cloned()
+            .collect()
+    };
+    // The check is opt-in.
+    assert!(ambiguous_references(Options::default()).is_empty());
+    let diags = ambiguous_references(Options {
+        ambiguous_reference_check: true,
+        ..Default::default()
+    });
+    assert_eq!(diags.len(), 1);
+    assert_eq!(ErrorKind::AmbiguousReferenceError.code(), "E2N35");
+    // The reference, the import and the definition.
+    let msgs: Vec<(u64, &str)> = diags[0]
+        .messages
+        .iter()
+        .map(|msg| (msg.range.0.line, msg.message.as_str()))
+        .collect();
+    assert_eq!(
+        msgs,
+        vec![
+            (4, "ambiguous reference to '_m'"),
+            (1, "'_m' is imported here"),
+            (3, "'_m' is defined here"),
+        ]
+    );
+}
+
+#[test]
+fn test_deprecated_builtin() {
+    let sess = Arc::new(ParseSession::default());
+    let mut program = load_program(
+        sess.clone(),
+        &["./src/resolver/test_data/deprecated_builtin/main.k"],
+        None,
+        None,
+    )
+    .unwrap()
+    .program;
+    let scope = resolve_program(&mut program);
+    // The uses in the imported package are not reported.
+    let deprecated: Vec<(String, u64)> = scope
+        .handler
+        .diagnostics
+        .iter()
+        .filter(|diag| {
+            diag.code == Some(DiagnosticId::Warning(WarningKind::DeprecatedBuiltinWarning))
+        })
+        .map(|diag| {
+            let msg = &diag.messages[0];
+            (
+                Path::new(&msg.range.0.filename)
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .to_string(),
+                msg.range.0.line,
+            )
+        })
+        .collect();
+    assert_eq!(
+        deprecated,
+        vec![("main.k".to_string(), 5), ("main.k".to_string(), 7)]
+    );
+}
+
+#[test]
+fn test_deprecation_message() {
+    let range = (Position::dummy_pos(), Position::dummy_pos());
+    let deprecation = Deprecation {
+        since: "0.11.2",
+        replacement: Some("datetime.now"),
+        note: None,
+    };
+    // The replacement in the same module keeps the module alias.
+    let msg = deprecation_message(
+        "datetime.date",
+        &deprecation,
+        &["dt".to_string()],
+        range.clone(),
+    );
+    assert_eq!(msg.message, "'datetime.date' is deprecated since 0.11.2");
+    assert_eq!(msg.note.as_deref(), Some("Use 'dt.now' instead"));
+    assert_eq!(msg.suggested_replacement, Some(vec!["dt.now".to_string()]));
+    let deprecation = Deprecation {
+        since: "0.11.2",
+        replacement: None,
+        note: Some("Use 'datetime.now' instead"),
+    };
+    let msg = deprecation_message("datetime.date", &deprecation, &[], range);
+    assert_eq!(msg.note.as_deref(), Some("Use 'datetime.now' instead"));
+    assert_eq!(msg.suggested_replacement, None);
+}
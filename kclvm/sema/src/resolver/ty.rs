@@ -4,14 +4,32 @@ use super::node::ResolvedResult;
 use crate::resolver::Resolver;
 use crate::ty::parser::parse_type_str;
 use crate::ty::{
-    assignable_to, is_upper_bound, Attr, DictType, Parameter, SchemaType, Type, TypeKind, TypeRef,
+    assignable_to, is_upper_bound, Attr, DictType, Parameter, SchemaType, Type, TypeInferMethods,
+    TypeKind, TypeRef,
 };
 use indexmap::IndexMap;
 use kclvm_ast::ast;
 use kclvm_ast::pos::GetPos;
+use kclvm_ast_pretty::{print_ast_node, ASTNode};
 use kclvm_error::diagnostic::Range;
 use kclvm_error::*;
 
+/// The largest integer which a float can represent exactly, i.e., 2^53.
+const MAX_SAFE_INTEGER: u64 = 1 << 53;
+
+fn is_str_ty(ty: &TypeRef) -> bool {
+    matches!(ty.kind, TypeKind::Str | TypeKind::StrLit(_))
+}
+
+/// Returns the builtin function converting a value to the number type.
+fn number_conversion_func(ty: &TypeRef) -> Option<&'static str> {
+    match ty.kind {
+        TypeKind::Int | TypeKind::IntLit(_) => Some("int"),
+        TypeKind::Float | TypeKind::FloatLit(_) => Some("float"),
+        _ => None,
+    }
+}
+
 fn ty_str_to_pkgpath(ty_str: &str) -> &str {
     let splits: Vec<&str> = ty_str.rsplitn(2, '.').collect();
     let len = splits.len();
@@ -118,6 +136,7 @@ impl<'ctx> Resolver<'_> {
         def_range: Option<Range>,
         attr_range: Option<Range>,
     ) {
+        self.check_precision_loss(&ty, &expected_ty, &range);
         if !self.check_type(ty.clone(), expected_ty.clone(), &range) {
             let mut msgs = vec![Message {
                 range,
@@ -157,6 +176,152 @@ impl<'ctx> Resolver<'_> {
         }
     }
 
+    /// Report the possible precision loss of assigning a number, e.g. `x: float = 9007199254740993`
+    /// can not be represented exactly by a float. A float assigned to an int typed target is
+    /// already a type error and is not reported again.
+    fn check_precision_loss(&mut self, ty: &TypeRef, expected_ty: &TypeRef, range: &Range) {
+        let lossy = matches!(
+            (&ty.kind, &expected_ty.kind),
+            (TypeKind::IntLit(v), TypeKind::Float) if v.unsigned_abs() > MAX_SAFE_INTEGER
+        );
+        if lossy {
+            self.handler.add_diagnostic(Diagnostic::new_with_code(
+                Level::Warning,
+                "possible precision loss assigning int to float",
+                None,
+                range.clone(),
+                Some(DiagnosticId::Warning(WarningKind::PrecisionLossWarning)),
+                None,
+            ));
+        }
+    }
+
+    /// Report the comparison of a string with a number, e.g. `"1" == 1` never holds because the
+    /// string is not converted to a number implicitly. The explicit conversions such as
+    /// `int("1") == 1` are not reported.
+    pub(crate) fn check_implicit_coercion_compare(
+        &mut self,
+        left: &'ctx ast::NodeRef<ast::Expr>,
+        left_ty: &TypeRef,
+        right: &'ctx ast::NodeRef<ast::Expr>,
+        right_ty: &TypeRef,
+        op: &ast::CmpOp,
+    ) {
+        if !self.options.implicit_coercion_check
+            || !matches!(op, ast::CmpOp::Eq | ast::CmpOp::NotEq)
+        {
+            return;
+        }
+        let (str_expr, str_ty, number_ty) = if is_str_ty(left_ty) {
+            (left, left_ty, right_ty)
+        } else {
+            (right, right_ty, left_ty)
+        };
+        if !is_str_ty(str_ty) {
+            return;
+        }
+        if let Some(func) = number_conversion_func(number_ty) {
+            self.add_implicit_coercion_warning(
+                str_expr,
+                func,
+                format!(
+                    "comparing str with {}, the str is never implicitly converted to {}",
+                    func, func
+                ),
+            );
+        }
+    }
+
+    /// Report the int value assigned to a float typed target, e.g. `x: float = count`.
+    pub(crate) fn check_implicit_coercion_assign(
+        &mut self,
+        value: &'ctx ast::NodeRef<ast::Expr>,
+        value_ty: &TypeRef,
+        expected_ty: &TypeRef,
+    ) {
+        if !self.options.implicit_coercion_check {
+            return;
+        }
+        if matches!(value_ty.kind, TypeKind::Int | TypeKind::IntLit(_))
+            && matches!(expected_ty.kind, TypeKind::Float)
+        {
+            self.add_implicit_coercion_warning(
+                value,
+                "float",
+                "int is implicitly converted to float".to_string(),
+            );
+        }
+    }
+
+    fn add_implicit_coercion_warning(
+        &mut self,
+        expr: &'ctx ast::NodeRef<ast::Expr>,
+        func: &str,
+        message: String,
+    ) {
+        let replacement = format!("{}({})", func, print_ast_node(ASTNode::Expr(expr)));
+        self.handler.add_warning(
+            WarningKind::ImplicitCoercionWarning,
+            &[Message {
+                range: expr.get_span_pos(),
+                style: Style::LineAndColumn,
+                message,
+                note: Some(format!(
+                    "Consider converting it explicitly: {}",
+                    replacement
+                )),
+                suggested_replacement: Some(vec![replacement]),
+            }],
+        );
+    }
+
+    /// Warns the top level variable or the schema attribute `target` without the type annotation,
+    /// the suggested replacement annotates the inferred type `value_ty`, e.g. `port: int`.
+    pub(crate) fn check_missing_type_annotation(
+        &mut self,
+        target: &'ctx ast::NodeRef<ast::Target>,
+        value_ty: &TypeRef,
+    ) {
+        // The local variables of the lambdas and the comprehensions are not checked.
+        if !self.options.explicit_type_check
+            || !self.ctx.in_lambda_expr.is_empty()
+            || (self.scope_level != 0 && self.ctx.schema.is_none())
+        {
+            return;
+        }
+        let name = &target.node.name;
+        let ty = self.ctx.ty_ctx.infer_to_variable_type(value_ty.clone());
+        let message = if matches!(
+            ty.kind,
+            TypeKind::Any | TypeKind::None | TypeKind::Void | TypeKind::Module(_)
+        ) {
+            Message {
+                range: name.get_span_pos(),
+                style: Style::LineAndColumn,
+                message: format!(
+                    "'{}' has no type annotation and its type can not be inferred",
+                    name.node
+                ),
+                note: Some("Consider annotating the type explicitly".to_string()),
+                suggested_replacement: None,
+            }
+        } else {
+            let replacement = format!("{}: {}", name.node, ty.ty_hint());
+            Message {
+                range: name.get_span_pos(),
+                style: Style::LineAndColumn,
+                message: format!("'{}' has no type annotation", name.node),
+                note: Some(format!(
+                    "Consider annotating the inferred type: {}",
+                    replacement
+                )),
+                suggested_replacement: Some(vec![replacement]),
+            }
+        };
+        self.handler
+            .add_warning(WarningKind::MissingTypeAnnotationWarning, &[message]);
+    }
+
     // Upgrade the dict type into schema type if it is expected to schema
     pub(crate) fn upgrade_dict_to_schema(&mut self, ty: TypeRef, expected_ty: TypeRef) -> TypeRef {
         match (&ty.kind, &expected_ty.kind) {
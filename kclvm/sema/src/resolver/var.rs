@@ -5,6 +5,8 @@ use kclvm_ast::pos::GetPos;
 use kclvm_error::diagnostic::Range;
 use kclvm_error::*;
 
+use crate::ty::{ModuleKind, TypeKind};
+
 use super::node::ResolvedResult;
 use super::scope::{ScopeObject, ScopeObjectKind};
 
@@ -24,6 +26,9 @@ impl<'ctx> Resolver<'_> {
         }
         if names.len() == 1 {
             let name = &names[0];
+            if !self.ctx.l_value {
+                self.check_deprecated_builtin(name, &range);
+            }
             let scope_schema_ty = self.ctx.schema.clone();
             if let Some(schema_ty) = &scope_schema_ty {
                 let mut schema_ty = schema_ty.borrow_mut();
@@ -127,12 +132,28 @@ impl<'ctx> Resolver<'_> {
             let mut tys = self.resolve_var(&[names[0].clone()], pkgpath, range.clone());
             let mut ty = tys[0].clone();
 
-            for name in &names[1..] {
+            for (i, name) in names.iter().enumerate().skip(1) {
                 // Store and config attr check
                 if self.ctx.l_value {
                     self.must_check_config_attr(name, &ty, &range, None);
                 }
+                let system_module = match &ty.kind {
+                    TypeKind::Module(module_ty)
+                        if module_ty.kind == ModuleKind::System && !self.ctx.l_value =>
+                    {
+                        Some(module_ty.pkgpath.clone())
+                    }
+                    _ => None,
+                };
                 ty = self.load_attr(ty, name, range.clone());
+                if let Some(pkgpath) = system_module {
+                    self.report_deprecated_builtin(
+                        &format!("{}.{}", pkgpath, name),
+                        &ty,
+                        &names[..i],
+                        &range,
+                    );
+                }
                 tys.push(ty.clone());
             }
             tys
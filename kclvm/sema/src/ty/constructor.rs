@@ -188,11 +188,19 @@ impl Type {
                 return_ty,
                 is_variadic,
                 kw_only_index,
+                deprecation: None,
             }),
             flags: TypeFlags::FUNCTION,
             is_type_alias: false,
         }
     }
+    /// Marks the builtin function type as deprecated, the warnings are reported on the uses of it.
+    pub fn deprecated(mut self, deprecation: Deprecation) -> Type {
+        if let TypeKind::Function(func_ty) = &mut self.kind {
+            func_ty.deprecation = Some(deprecation);
+        }
+        self
+    }
     /// Construct a module type.
     pub fn module(pkgpath: &str, imported: &[String], kind: ModuleKind) -> Type {
         Type {
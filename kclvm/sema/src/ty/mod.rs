@@ -20,6 +20,7 @@ pub use unify::*;
 pub use walker::walk_type;
 
 use super::resolver::doc::Example;
+use crate::builtin::Deprecation;
 
 #[cfg(test)]
 mod tests;
@@ -501,6 +502,8 @@ pub struct FunctionType {
     pub return_ty: TypeRef,
     pub is_variadic: bool,
     pub kw_only_index: Option<usize>,
+    /// The deprecation of the builtin function, `None` for the functions not deprecated.
+    pub deprecation: Option<Deprecation>,
 }
 
 impl FunctionType {
@@ -540,6 +543,7 @@ impl FunctionType {
             return_ty: Type::any_ref(),
             is_variadic: true,
             kw_only_index: None,
+            deprecation: None,
         }
     }
 }
@@ -0,0 +1,40 @@
+#![no_main]
+use kcl_language_server::from_lsp::kcl_pos;
+use kcl_language_server::to_lsp::{lsp_location, lsp_pos};
+use kclvm_error::Position as KCLPos;
+use libfuzzer_sys::arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use lsp_types::Position;
+
+#[derive(Arbitrary, Debug)]
+struct Positions {
+    start_line: u64,
+    start_column: Option<u64>,
+    end_line: u64,
+    end_column: Option<u64>,
+    lsp_line: u32,
+    lsp_character: u32,
+}
+
+fuzz_target!(|positions: Positions| {
+    let file = "/fuzz/main.k";
+    let start = KCLPos {
+        filename: file.to_string(),
+        line: positions.start_line,
+        column: positions.start_column,
+    };
+    let end = KCLPos {
+        filename: file.to_string(),
+        line: positions.end_line,
+        column: positions.end_column,
+    };
+    lsp_pos(&start);
+    if let Some(location) = lsp_location(file.to_string(), &start, &end) {
+        assert!(location.range.start <= location.range.end);
+    }
+    let pos = kcl_pos(
+        file,
+        Position::new(positions.lsp_line, positions.lsp_character),
+    );
+    assert_eq!(pos.line, positions.lsp_line as u64 + 1);
+});
@@ -54,11 +54,15 @@ pub(crate) enum ScalarStyle {
 #[derive(Debug)]
 pub(crate) struct Sequence {
     pub tag: Option<String>,
+    /// Emit the sequence in the flow style `[a, b]` instead of the block style.
+    pub flow: bool,
 }
 
 #[derive(Debug)]
 pub(crate) struct Mapping {
     pub tag: Option<String>,
+    /// Emit the mapping in the flow style `{a: b}` instead of the block style.
+    pub flow: bool,
 }
 
 impl<'a> Emitter<'a> {
@@ -140,7 +144,11 @@ impl<'a> Emitter<'a> {
                         tag.as_ptr()
                     });
                     let implicit = tag.is_null();
-                    let style = sys::YAML_ANY_SEQUENCE_STYLE;
+                    let style = if sequence.flow {
+                        sys::YAML_FLOW_SEQUENCE_STYLE
+                    } else {
+                        sys::YAML_ANY_SEQUENCE_STYLE
+                    };
                     sys::yaml_sequence_start_event_initialize(
                         sys_event, anchor, tag, implicit, style,
                     )
@@ -153,7 +161,11 @@ impl<'a> Emitter<'a> {
                         tag.as_ptr()
                     });
                     let implicit = tag.is_null();
-                    let style = sys::YAML_ANY_MAPPING_STYLE;
+                    let style = if mapping.flow {
+                        sys::YAML_FLOW_MAPPING_STYLE
+                    } else {
+                        sys::YAML_ANY_MAPPING_STYLE
+                    };
                     sys::yaml_mapping_start_event_initialize(
                         sys_event, anchor, tag, implicit, style,
                     )
@@ -43,6 +43,7 @@ type Result<T, E = Error> = std::result::Result<T, E>;
 /// ```
 pub struct Serializer<W> {
     depth: usize,
+    flow_threshold: Option<usize>,
     state: State,
     emitter: Emitter<'static>,
     writer: PhantomData<W>,
@@ -69,12 +70,22 @@ where
         emitter.emit(Event::StreamStart).unwrap();
         Serializer {
             depth: 0,
+            flow_threshold: None,
             state: State::NothingInParticular,
             emitter,
             writer: PhantomData,
         }
     }
 
+    /// Emits the sequences and mappings nested deeper than `depth` levels in
+    /// the flow style, e.g. with the depth `1`, `{a: {b: [1, 2]}}` is emitted
+    /// as `a: {b: [1, 2]}`. `None` always emits the block style, except for
+    /// the empty sequences and mappings which are always `[]` and `{}`.
+    pub fn with_flow_threshold(mut self, depth: Option<usize>) -> Self {
+        self.flow_threshold = depth;
+        self
+    }
+
     /// Calls [`.flush()`](io::Write::flush) on the underlying `io::Write`
     /// object.
     pub fn flush(&mut self) -> Result<()> {
@@ -104,7 +115,9 @@ where
         self.flush_mapping_start()?;
         self.value_start()?;
         let tag = self.take_tag();
-        self.emitter.emit(Event::SequenceStart(Sequence { tag }))?;
+        let flow = self.is_flow();
+        self.emitter
+            .emit(Event::SequenceStart(Sequence { tag, flow }))?;
         Ok(())
     }
 
@@ -117,7 +130,9 @@ where
         self.flush_mapping_start()?;
         self.value_start()?;
         let tag = self.take_tag();
-        self.emitter.emit(Event::MappingStart(Mapping { tag }))?;
+        let flow = self.is_flow();
+        self.emitter
+            .emit(Event::MappingStart(Mapping { tag, flow }))?;
         Ok(())
     }
 
@@ -142,6 +157,11 @@ where
         Ok(())
     }
 
+    /// Whether the collection at the current depth is emitted in the flow style.
+    fn is_flow(&self) -> bool {
+        matches!(self.flow_threshold, Some(threshold) if self.depth > threshold)
+    }
+
     fn take_tag(&mut self) -> Option<String> {
         let state = mem::replace(&mut self.state, State::NothingInParticular);
         if let State::FoundTag(mut tag) = state {
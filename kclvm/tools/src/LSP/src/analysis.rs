@@ -23,6 +23,8 @@ pub struct Analysis {
     pub workspaces: Arc<RwLock<HashMap<WorkSpaceKind, DBState>>>,
 }
 
+/// The analysis state of a workspace. The databases are immutable and shared by the requests, a
+/// compilation builds the next database aside and swaps the state as a whole when it completes.
 #[derive(Clone)]
 pub enum DBState {
     Ready(Arc<AnalysisDatabase>),
@@ -32,6 +34,17 @@ pub enum DBState {
     Failed(String),
 }
 
+impl DBState {
+    /// Returns the database of the last completed compilation, which is still served while the
+    /// next one is compiling.
+    pub fn latest_db(&self) -> Option<Arc<AnalysisDatabase>> {
+        match self {
+            DBState::Ready(db) | DBState::Compiling(db) => Some(db.clone()),
+            DBState::Init | DBState::Failed(_) => None,
+        }
+    }
+}
+
 /// AnalysisDatabase holds the result of the compile
 #[derive(Default, Clone)]
 pub struct AnalysisDatabase {
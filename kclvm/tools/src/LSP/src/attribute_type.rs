@@ -0,0 +1,105 @@
+//! The effective type of a schema attribute with the schema declaring it, e.g.
+//!
+//! ```kcl
+//! schema Base:
+//!     port?: int = 80
+//!
+//! schema Server(Base):
+//!     name: str
+//! ```
+//!
+//! The attribute `port` of `Server` is an optional `int` with a default value declared in `Base`.
+//! The attributes of the mixins of a schema are declared in the mixins.
+
+use indexmap::IndexMap;
+use kclvm_sema::ty::SchemaType;
+
+use crate::lsp_ext::AttributeTypeResult;
+
+/// Returns the type of the attribute `attr_name` of the schema `schema_name`, or `None` if the
+/// schema or the attribute can not be found. The schema name is either the name of a schema in
+/// the main package or the full name with the package path, e.g. `pkg.Server`.
+pub(crate) fn attribute_type(
+    schema_map: &IndexMap<String, Vec<SchemaType>>,
+    schema_name: &str,
+    attr_name: &str,
+) -> Option<AttributeTypeResult> {
+    let schema = schema_map
+        .values()
+        .flatten()
+        .find(|schema| !schema.is_instance && schema.full_ty_str() == schema_name)?;
+    let declared_in = declaring_schema(schema, attr_name)?;
+    let attr = declared_in.attrs.get(attr_name)?;
+    Some(AttributeTypeResult {
+        ty: attr.ty.ty_str(),
+        declared_in: declared_in.full_ty_str(),
+        required: !attr.is_optional,
+        has_default: attr.has_default,
+    })
+}
+
+/// Returns the schema declaring the attribute, which is the schema itself, one of its mixins, one
+/// of its base schemas or its protocol.
+fn declaring_schema<'a>(schema: &'a SchemaType, attr_name: &str) -> Option<&'a SchemaType> {
+    if let Some(attr) = schema.attrs.get(attr_name) {
+        // The attributes of the mixins are merged into the schema, the mixin declaring the
+        // attribute is found by the range of the attribute.
+        let mixin = schema.mixins.iter().find_map(|mixin| {
+            declaring_schema(mixin, attr_name)
+                .filter(|declared_in| declared_in.attrs[attr_name].range == attr.range)
+        });
+        return Some(mixin.unwrap_or(schema));
+    }
+    match &schema.base {
+        Some(base) => declaring_schema(base, attr_name),
+        None => schema
+            .protocol
+            .as_ref()
+            .and_then(|protocol| declaring_schema(protocol, attr_name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proc_macro_crate::bench_test;
+
+    use super::attribute_type;
+    use crate::lsp_ext::AttributeTypeResult;
+    use crate::tests::compile_test_file;
+
+    #[test]
+    #[bench_test]
+    fn attribute_type_test() {
+        let (_, _, _, _, schema_map) = compile_test_file("src/test_data/attribute_type/main.k");
+
+        assert_eq!(
+            attribute_type(&schema_map, "Server", "port"),
+            Some(AttributeTypeResult {
+                ty: "int".to_string(),
+                declared_in: "Base".to_string(),
+                required: false,
+                has_default: true,
+            })
+        );
+        assert_eq!(
+            attribute_type(&schema_map, "Server", "name"),
+            Some(AttributeTypeResult {
+                ty: "str".to_string(),
+                declared_in: "Server".to_string(),
+                required: true,
+                has_default: false,
+            })
+        );
+        assert_eq!(
+            attribute_type(&schema_map, "Server", "labels"),
+            Some(AttributeTypeResult {
+                ty: "{str:str}".to_string(),
+                declared_in: "LabelMixin".to_string(),
+                required: false,
+                has_default: true,
+            })
+        );
+        assert!(attribute_type(&schema_map, "Server", "not_exist").is_none());
+        assert!(attribute_type(&schema_map, "NotExist", "name").is_none());
+    }
+}
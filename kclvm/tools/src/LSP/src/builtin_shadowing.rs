@@ -0,0 +1,138 @@
+//! The opt-in lint of the variables and the schema attributes named the same as the builtin
+//! functions, e.g.
+//!
+//! ```kcl
+//! len = 3
+//! count = len([1, 2, 3])
+//! ```
+//!
+//! `len` is reported because the call resolves to the variable instead of the builtin. The config
+//! keys, e.g. `{len = 3}`, and the nested targets, e.g. `app.len = 3`, are not in any scope, so
+//! they do not shadow the builtins and are not reported.
+
+use std::collections::HashSet;
+
+use indexmap::IndexSet;
+use kclvm_ast::ast::{self, Program};
+use kclvm_ast::pos::GetPos;
+use kclvm_error::{Diagnostic, DiagnosticId, Level, WarningKind};
+use kclvm_sema::builtin::BUILTIN_FUNCTIONS;
+
+/// Returns the warnings of the first declarations of the builtin names in the modules and the
+/// schemas. The range of a warning is the declared name.
+pub(crate) fn builtin_shadowing_diags(program: &Program) -> IndexSet<Diagnostic> {
+    let mut diags = IndexSet::new();
+    for module in program.modules.values() {
+        let module = match module.read() {
+            Ok(module) => module,
+            Err(_) => continue,
+        };
+        let mut module_names = HashSet::new();
+        for stmt in &module.body {
+            match &stmt.node {
+                ast::Stmt::Schema(schema_stmt) => {
+                    let mut schema_names = HashSet::new();
+                    let scope = format!("schema '{}'", schema_stmt.name.node);
+                    for stmt in &schema_stmt.body {
+                        for name in declared_names(stmt) {
+                            if schema_names.insert(name.node.clone()) {
+                                diags.extend(shadowing_diag(name, &scope));
+                            }
+                        }
+                    }
+                }
+                _ => {
+                    for name in declared_names(stmt) {
+                        if module_names.insert(name.node.clone()) {
+                            diags.extend(shadowing_diag(name, "the module"));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    diags
+}
+
+/// Returns the names declared by the statement in its scope, the targets with the paths, e.g.
+/// `app.len = 3`, declare nothing.
+fn declared_names(stmt: &ast::NodeRef<ast::Stmt>) -> Vec<&ast::Node<String>> {
+    match &stmt.node {
+        ast::Stmt::Assign(assign_stmt) => assign_stmt
+            .targets
+            .iter()
+            .filter(|target| target.node.paths.is_empty())
+            .map(|target| &target.node.name)
+            .collect(),
+        ast::Stmt::Unification(unification_stmt) => {
+            match unification_stmt.target.node.names.as_slice() {
+                [name] => vec![name],
+                _ => vec![],
+            }
+        }
+        ast::Stmt::SchemaAttr(schema_attr) => vec![schema_attr.name.as_ref()],
+        _ => vec![],
+    }
+}
+
+fn shadowing_diag(name: &ast::Node<String>, scope: &str) -> Option<Diagnostic> {
+    if !BUILTIN_FUNCTIONS.contains_key(&name.node) {
+        return None;
+    }
+    Some(Diagnostic::new_with_code(
+        Level::Warning,
+        &format!("'{}' shadows a builtin", name.node),
+        Some(&format!(
+            "The builtin '{}' is inaccessible in {}",
+            name.node, scope
+        )),
+        (name.get_pos(), name.get_end_pos()),
+        Some(DiagnosticId::Warning(WarningKind::BuiltinShadowingWarning)),
+        None,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use proc_macro_crate::bench_test;
+
+    use super::builtin_shadowing_diags;
+    use crate::tests::compile_test_file;
+
+    #[test]
+    #[bench_test]
+    fn builtin_shadowing_diags_test() {
+        let (_, program, _, _, _) = compile_test_file("src/test_data/builtin_shadowing/main.k");
+        let diags = builtin_shadowing_diags(&program);
+        let messages: Vec<(u64, Option<u64>, &str, Option<&str>)> = diags
+            .iter()
+            .map(|diag| {
+                let msg = &diag.messages[0];
+                (
+                    msg.range.0.line,
+                    msg.range.0.column,
+                    msg.message.as_str(),
+                    msg.note.as_deref(),
+                )
+            })
+            .collect();
+        // `length` and the config key are not reported.
+        assert_eq!(
+            messages,
+            vec![
+                (
+                    3,
+                    Some(4),
+                    "'max' shadows a builtin",
+                    Some("The builtin 'max' is inaccessible in schema 'Item'")
+                ),
+                (
+                    5,
+                    Some(0),
+                    "'len' shadows a builtin",
+                    Some("The builtin 'len' is inaccessible in the module")
+                ),
+            ]
+        );
+    }
+}
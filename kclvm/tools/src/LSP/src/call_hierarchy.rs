@@ -0,0 +1,401 @@
+//! Call hierarchy for KCL functions, e.g.
+//!
+//! ```kcl
+//! add = lambda x, y { x + y }
+//! double = lambda x { add(x, x) }
+//! ```
+//!
+//! The incoming calls of `add` list `double`, and the outgoing calls of `double` list `add`.
+//! Calls at the top level of a file are attributed to the file itself. The calls are read from
+//! the reference index of the global state: every call of a function has a callable scope owned
+//! by the callee name at the call site.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use indexmap::IndexMap;
+use kclvm_ast::ast::{self, Program};
+use kclvm_ast::pos::GetPos;
+use kclvm_ast::walker::MutSelfWalker;
+use kclvm_error::Position as KCLPos;
+use kclvm_sema::core::global_state::GlobalState;
+use kclvm_sema::core::scope::{LocalSymbolScopeKind, Scope, ScopeRef};
+use kclvm_sema::core::symbol::{SymbolKind, SymbolRef};
+use kclvm_sema::ty::TypeKind;
+use lsp_types::{
+    CallHierarchyIncomingCall, CallHierarchyItem, CallHierarchyOutgoingCall, Position, Range, Url,
+};
+
+use crate::goto_def::find_def;
+use crate::to_lsp::lsp_pos;
+
+/// Returns the call hierarchy item of the function at the position.
+pub fn prepare_call_hierarchy(
+    kcl_pos: &KCLPos,
+    gs: &GlobalState,
+) -> Option<Vec<CallHierarchyItem>> {
+    let def = find_def(kcl_pos, gs, true)?;
+    if !is_function(def, gs) {
+        return None;
+    }
+    Some(vec![symbol_to_call_hierarchy_item(def, gs)?])
+}
+
+/// Returns the callers of the function at the position.
+pub fn incoming_calls(
+    kcl_pos: &KCLPos,
+    program: &Program,
+    gs: &GlobalState,
+) -> Option<Vec<CallHierarchyIncomingCall>> {
+    let def = find_def(kcl_pos, gs, true)?;
+    let mut references: Vec<(KCLPos, KCLPos)> = gs
+        .get_symbols()
+        .get_symbol(def)?
+        .get_references()
+        .into_iter()
+        .filter_map(|reference| gs.get_symbols().get_symbol(reference))
+        .map(|reference| reference.get_range())
+        .collect();
+    references.sort_by(|(a, _), (b, _)| {
+        (&a.filename, a.line, a.column).cmp(&(&b.filename, b.line, b.column))
+    });
+    let mut bodies = FunctionBodies::new(program, gs);
+    let mut callers: IndexMap<(Option<SymbolRef>, String), Vec<Range>> = IndexMap::new();
+    for (_, end) in references {
+        // The callable scope of a call starts at the end of the callee name.
+        let scope = match gs.look_up_scope(&end) {
+            Some(scope) => scope,
+            None => continue,
+        };
+        if let Some((callee, range)) = call_of_scope(scope, gs) {
+            if callee == def {
+                callers
+                    .entry((bodies.enclosing_caller(scope), end.filename.clone()))
+                    .or_default()
+                    .push(range);
+            }
+        }
+    }
+    Some(
+        callers
+            .into_iter()
+            .filter_map(|((caller, filename), from_ranges)| {
+                let from = match caller {
+                    Some(caller) => symbol_to_call_hierarchy_item(caller, gs)?,
+                    None => file_to_call_hierarchy_item(&filename)?,
+                };
+                Some(CallHierarchyIncomingCall { from, from_ranges })
+            })
+            .collect(),
+    )
+}
+
+/// Returns the callees of the function at the position.
+pub fn outgoing_calls(
+    kcl_pos: &KCLPos,
+    program: &Program,
+    gs: &GlobalState,
+) -> Option<Vec<CallHierarchyOutgoingCall>> {
+    let def = find_def(kcl_pos, gs, true)?;
+    let mut bodies = FunctionBodies::new(program, gs);
+    let body = bodies.body_scope(def)?;
+    let mut callees: IndexMap<SymbolRef, Vec<Range>> = IndexMap::new();
+    collect_calls(body, &mut bodies, &mut callees);
+    Some(
+        callees
+            .into_iter()
+            .filter_map(|(callee, from_ranges)| {
+                Some(CallHierarchyOutgoingCall {
+                    to: symbol_to_call_hierarchy_item(callee, gs)?,
+                    from_ranges,
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Collects the calls in the scope and its children by the callee. The bodies of the nested
+/// functions are skipped, their calls belong to the nested functions. No call is followed, so
+/// recursive functions terminate.
+fn collect_calls(
+    scope: ScopeRef,
+    bodies: &mut FunctionBodies,
+    callees: &mut IndexMap<SymbolRef, Vec<Range>>,
+) {
+    let children = match bodies.gs.get_scopes().get_scope(&scope) {
+        Some(scope) => scope.get_children(),
+        None => return,
+    };
+    for child in children {
+        if bodies.caller_of_scope(child).is_some() {
+            continue;
+        }
+        if let Some((callee, range)) = call_of_scope(child, bodies.gs) {
+            callees.entry(callee).or_default().push(range);
+        }
+        collect_calls(child, bodies, callees);
+    }
+}
+
+/// Returns the called function and the range of the callee name if the scope is the callable
+/// scope of a call of a user defined function.
+fn call_of_scope(scope: ScopeRef, gs: &GlobalState) -> Option<(SymbolRef, Range)> {
+    let scope = gs.get_scopes().try_get_local_scope(&scope)?;
+    if !matches!(scope.get_kind(), LocalSymbolScopeKind::Callable) {
+        return None;
+    }
+    let name = gs.get_symbols().get_symbol(scope.get_owner()?)?;
+    let callee = name.get_definition()?;
+    if !is_function(callee, gs) {
+        return None;
+    }
+    let (start, end) = name.get_range();
+    Some((
+        callee,
+        Range {
+            start: lsp_pos(&start),
+            end: lsp_pos(&end),
+        },
+    ))
+}
+
+/// Maps the scopes to the functions and schemas whose bodies they are. A schema owns its scope,
+/// a function is the variable a lambda is assigned to, which is looked up in the assignments of
+/// the file once per file.
+struct FunctionBodies<'a> {
+    program: &'a Program,
+    gs: &'a GlobalState,
+    /// The positions of the assigned variables and the lambdas by the filename.
+    lambdas: HashMap<String, Vec<(KCLPos, KCLPos)>>,
+}
+
+impl<'a> FunctionBodies<'a> {
+    fn new(program: &'a Program, gs: &'a GlobalState) -> Self {
+        Self {
+            program,
+            gs,
+            lambdas: HashMap::new(),
+        }
+    }
+
+    /// Returns the function or the schema whose body is the scope.
+    fn caller_of_scope(&mut self, scope: ScopeRef) -> Option<SymbolRef> {
+        let gs = self.gs;
+        let scope = gs.get_scopes().try_get_local_scope(&scope)?;
+        match scope.get_kind() {
+            LocalSymbolScopeKind::SchemaDef => scope.get_owner(),
+            LocalSymbolScopeKind::Lambda => {
+                let (start, _) = scope.get_range()?;
+                let name = self
+                    .file_lambdas(&start.filename)
+                    .iter()
+                    .find(|(_, lambda)| *lambda == start)
+                    .map(|(name, _)| name.clone())?;
+                find_def(&name, gs, true)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the innermost function or schema containing the scope, `None` at the top level.
+    fn enclosing_caller(&mut self, scope: ScopeRef) -> Option<SymbolRef> {
+        let mut scope = Some(scope);
+        while let Some(current) = scope {
+            if let Some(caller) = self.caller_of_scope(current) {
+                return Some(caller);
+            }
+            scope = self.gs.get_scopes().get_scope(&current)?.get_parent();
+        }
+        None
+    }
+
+    /// Returns the body scope of the function or the schema.
+    fn body_scope(&mut self, def: SymbolRef) -> Option<ScopeRef> {
+        let (start, _) = self.gs.get_symbols().get_symbol(def)?.get_range();
+        let body_start = match def.get_kind() {
+            SymbolKind::Schema => start,
+            _ => self
+                .file_lambdas(&start.filename)
+                .iter()
+                .find(|(name, _)| *name == start)
+                .map(|(_, lambda)| lambda.clone())?,
+        };
+        let mut scope = self.gs.look_up_scope(&body_start);
+        while let Some(current) = scope {
+            if self.caller_of_scope(current) == Some(def) {
+                return Some(current);
+            }
+            scope = self.gs.get_scopes().get_scope(&current)?.get_parent();
+        }
+        None
+    }
+
+    fn file_lambdas(&mut self, filename: &str) -> &[(KCLPos, KCLPos)] {
+        let program = self.program;
+        self.lambdas.entry(filename.to_string()).or_insert_with(|| {
+            let mut collector = LambdaCollector { lambdas: vec![] };
+            if let Ok(Some(module)) = program.get_module(filename) {
+                for stmt in &module.body {
+                    collector.walk_stmt(&stmt.node);
+                }
+            }
+            collector.lambdas
+        })
+    }
+}
+
+/// Collects the positions of the variables assigned with lambdas and of the lambdas.
+struct LambdaCollector {
+    lambdas: Vec<(KCLPos, KCLPos)>,
+}
+
+impl MutSelfWalker for LambdaCollector {
+    fn walk_assign_stmt(&mut self, assign_stmt: &ast::AssignStmt) {
+        if let ast::Expr::Lambda(_) = &assign_stmt.value.node {
+            if let Some(target) = assign_stmt.targets.first() {
+                self.lambdas
+                    .push((target.node.name.get_pos(), assign_stmt.value.get_pos()));
+            }
+        }
+        self.walk_expr(&assign_stmt.value.node);
+    }
+}
+
+/// Whether the symbol is a user defined function, i.e., a variable bound to a lambda.
+fn is_function(symbol_ref: SymbolRef, gs: &GlobalState) -> bool {
+    if !matches!(symbol_ref.get_kind(), SymbolKind::Value) {
+        return false;
+    }
+    match gs.get_symbols().get_symbol(symbol_ref) {
+        Some(symbol) => match &symbol.get_sema_info().ty {
+            Some(ty) => matches!(ty.kind, TypeKind::Function(_)),
+            None => false,
+        },
+        None => false,
+    }
+}
+
+fn symbol_to_call_hierarchy_item(
+    symbol_ref: SymbolRef,
+    gs: &GlobalState,
+) -> Option<CallHierarchyItem> {
+    let symbol = gs.get_symbols().get_symbol(symbol_ref)?;
+    let (start, end) = symbol.get_range();
+    let range = Range {
+        start: lsp_pos(&start),
+        end: lsp_pos(&end),
+    };
+    Some(CallHierarchyItem {
+        name: symbol.get_name(),
+        kind: match symbol_ref.get_kind() {
+            SymbolKind::Schema => lsp_types::SymbolKind::STRUCT,
+            _ => lsp_types::SymbolKind::FUNCTION,
+        },
+        tags: None,
+        detail: symbol.get_sema_info().ty.as_ref().map(|ty| ty.ty_str()),
+        uri: Url::from_file_path(&start.filename).ok()?,
+        range,
+        selection_range: range,
+        data: None,
+    })
+}
+
+fn file_to_call_hierarchy_item(filename: &str) -> Option<CallHierarchyItem> {
+    let range = Range::new(Position::new(0, 0), Position::new(0, 0));
+    Some(CallHierarchyItem {
+        name: Path::new(filename).file_name()?.to_str()?.to_string(),
+        kind: lsp_types::SymbolKind::FILE,
+        tags: None,
+        detail: None,
+        uri: Url::from_file_path(filename).ok()?,
+        range,
+        selection_range: range,
+        data: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use kclvm_error::Position as KCLPos;
+    use lsp_types::{Position, Range};
+    use proc_macro_crate::bench_test;
+
+    use super::{incoming_calls, outgoing_calls, prepare_call_hierarchy};
+    use crate::tests::compile_test_file;
+
+    fn range(line: u32, start: u32, end: u32) -> Range {
+        Range::new(Position::new(line, start), Position::new(line, end))
+    }
+
+    #[test]
+    #[bench_test]
+    fn call_hierarchy_test() {
+        let (file, program, _, gs, _) =
+            compile_test_file("src/test_data/call_hierarchy/call_hierarchy.k");
+
+        // add = lambda x: int, y: int -> int {
+        let add = KCLPos {
+            filename: file.clone(),
+            line: 1,
+            column: Some(1),
+        };
+        let items = prepare_call_hierarchy(&add, &gs).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].name, "add");
+        assert_eq!(items[0].kind, lsp_types::SymbolKind::FUNCTION);
+
+        // `add` is called from `double` and the top level of the file.
+        let calls = incoming_calls(&add, &program, &gs).unwrap();
+        let got: Vec<(String, Vec<Range>)> = calls
+            .into_iter()
+            .map(|call| (call.from.name, call.from_ranges))
+            .collect();
+        assert_eq!(
+            got,
+            vec![
+                ("double".to_string(), vec![range(4, 4, 7)]),
+                ("call_hierarchy.k".to_string(), vec![range(10, 4, 7)]),
+            ]
+        );
+
+        // double = lambda x: int -> int {
+        let double = KCLPos {
+            filename: file.clone(),
+            line: 4,
+            column: Some(1),
+        };
+        let calls = outgoing_calls(&double, &program, &gs).unwrap();
+        let got: Vec<(String, Vec<Range>)> = calls
+            .into_iter()
+            .map(|call| (call.to.name, call.from_ranges))
+            .collect();
+        assert_eq!(got, vec![("add".to_string(), vec![range(4, 4, 7)])]);
+    }
+
+    #[test]
+    #[bench_test]
+    fn recursive_call_hierarchy_test() {
+        let (file, program, _, gs, _) =
+            compile_test_file("src/test_data/call_hierarchy/call_hierarchy.k");
+
+        // fib = lambda n: int -> int {
+        let fib = KCLPos {
+            filename: file.clone(),
+            line: 7,
+            column: Some(1),
+        };
+        let calls = outgoing_calls(&fib, &program, &gs).unwrap();
+        let got: Vec<(String, Vec<Range>)> = calls
+            .into_iter()
+            .map(|call| (call.to.name, call.from_ranges))
+            .collect();
+        assert_eq!(
+            got,
+            vec![("fib".to_string(), vec![range(7, 21, 24), range(7, 34, 37)])]
+        );
+
+        let calls = incoming_calls(&fib, &program, &gs).unwrap();
+        let got: Vec<String> = calls.into_iter().map(|call| call.from.name).collect();
+        assert_eq!(got, vec!["fib".to_string(), "call_hierarchy.k".to_string()]);
+    }
+}
@@ -0,0 +1,134 @@
+//! Rewrites the imports of a file into the canonical form, which is the import path resolved
+//! relative to the module root, e.g. `import ..pkg.sub` in `app/main.k` is `import pkg.sub`. The
+//! imports are sorted by the canonical paths and the aliases are kept, so the same packages are
+//! imported with the same names. A canonical file is not changed.
+
+use std::collections::HashMap;
+
+use kclvm_ast::ast;
+use kclvm_ast::pos::GetPos;
+use kclvm_config::vfs::{fix_import_path_with_base, RelativeImportBase};
+use kclvm_parser::parse_single_file;
+use lsp_types::{CodeAction, CodeActionKind, CodeActionOrCommand, Range, TextEdit, Url};
+
+use crate::to_lsp::lsp_pos;
+
+/// Returns the edits rewriting the imports of the file to the canonical form, `root` is the
+/// module root which the import paths are resolved against with the relative import `base`. The
+/// sorted imports take the places of the imports in the file, so the statements between the
+/// imports are not moved.
+pub(crate) fn canonicalize_imports(
+    file: &str,
+    src: &str,
+    root: &str,
+    base: RelativeImportBase,
+) -> Vec<TextEdit> {
+    let module = match parse_single_file(file, Some(src.to_string())) {
+        Ok(result) if result.errors.is_empty() => result.module,
+        _ => return vec![],
+    };
+    let imports: Vec<(&ast::NodeRef<ast::Stmt>, &ast::ImportStmt)> = module
+        .body
+        .iter()
+        .filter_map(|stmt| match &stmt.node {
+            ast::Stmt::Import(import_stmt) => Some((stmt, import_stmt)),
+            _ => None,
+        })
+        .collect();
+    let mut canonical: Vec<String> = imports
+        .iter()
+        .map(|(_, import_stmt)| {
+            let path = fix_import_path_with_base(root, file, &import_stmt.rawpath, base);
+            // The relative paths beyond the module root can not be resolved.
+            let path = if path.is_empty() {
+                import_stmt.rawpath.clone()
+            } else {
+                path
+            };
+            import_text(&path, import_stmt)
+        })
+        .collect();
+    canonical.sort();
+    imports
+        .iter()
+        .zip(canonical)
+        .filter(|((_, import_stmt), new_text)| {
+            *new_text != import_text(&import_stmt.rawpath, import_stmt)
+        })
+        .map(|((stmt, _), new_text)| TextEdit {
+            range: Range::new(lsp_pos(&stmt.get_pos()), lsp_pos(&stmt.get_end_pos())),
+            new_text,
+        })
+        .collect()
+}
+
+fn import_text(path: &str, import_stmt: &ast::ImportStmt) -> String {
+    match &import_stmt.asname {
+        Some(asname) => format!("import {} as {}", path, asname.node),
+        None => format!("import {}", path),
+    }
+}
+
+/// Returns the `source.organizeImports` action canonicalizing the imports, or `None` if the
+/// imports are already canonical.
+pub(crate) fn canonicalize_imports_code_action(
+    uri: &Url,
+    file: &str,
+    src: &str,
+    root: &str,
+    base: RelativeImportBase,
+) -> Option<CodeActionOrCommand> {
+    let edits = canonicalize_imports(file, src, root, base);
+    if edits.is_empty() {
+        return None;
+    }
+    let mut changes = HashMap::new();
+    changes.insert(uri.clone(), edits);
+    Some(CodeActionOrCommand::CodeAction(CodeAction {
+        title: "Canonicalize imports".to_string(),
+        kind: Some(CodeActionKind::SOURCE_ORGANIZE_IMPORTS),
+        edit: Some(lsp_types::WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use kclvm_config::vfs::RelativeImportBase;
+    use lsp_types::{Position, Range, TextEdit};
+    use proc_macro_crate::bench_test;
+
+    use super::canonicalize_imports;
+
+    #[test]
+    #[bench_test]
+    fn canonicalize_imports_test() {
+        let root = std::env::current_dir().unwrap();
+        let file = root.join("app").join("main.k");
+        let file = file.to_str().unwrap();
+        let root = root.to_str().unwrap();
+
+        let src = "import ..pkg.sub as s\nimport .helper\n\na = s.x + helper.y\n";
+        let edits = canonicalize_imports(file, src, root, RelativeImportBase::FileDir);
+        assert_eq!(
+            edits,
+            vec![
+                TextEdit {
+                    range: Range::new(Position::new(0, 0), Position::new(0, 21)),
+                    new_text: "import app.helper".to_string(),
+                },
+                TextEdit {
+                    range: Range::new(Position::new(1, 0), Position::new(1, 14)),
+                    new_text: "import pkg.sub as s".to_string(),
+                },
+            ]
+        );
+
+        // The canonical imports are left unchanged.
+        let src = "import app.helper\nimport pkg.sub as s\n\na = s.x + helper.y\n";
+        assert!(canonicalize_imports(file, src, root, RelativeImportBase::FileDir).is_empty());
+    }
+}
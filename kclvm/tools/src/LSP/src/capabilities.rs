@@ -1,10 +1,14 @@
 use lsp_types::{
-    ClientCapabilities, CodeActionKind, CodeActionOptions, CodeActionProviderCapability,
-    CompletionOptions, HoverProviderCapability, OneOf, SemanticTokensFullOptions,
-    SemanticTokensLegend, SemanticTokensOptions, ServerCapabilities, SignatureHelpOptions,
-    TextDocumentSyncCapability, TextDocumentSyncKind, WorkDoneProgressOptions,
+    CallHierarchyServerCapability, ClientCapabilities, CodeActionKind, CodeActionOptions,
+    CodeActionProviderCapability, CompletionOptions, ExecuteCommandOptions,
+    FoldingRangeProviderCapability, HoverProviderCapability, MarkupKind, OneOf,
+    SemanticTokensFullOptions, SemanticTokensLegend, SemanticTokensOptions, ServerCapabilities,
+    SignatureHelpOptions, TextDocumentSyncCapability, TextDocumentSyncKind,
+    WorkDoneProgressOptions,
 };
 
+use crate::commands::COMMANDS;
+use crate::formatting::SOURCE_FIX_ALL_FORMAT;
 use crate::semantic_token::LEGEND_TYPE;
 
 /// Returns the capabilities of this LSP server implementation given the capabilities of the client.
@@ -20,11 +24,12 @@ pub fn server_capabilities(client_caps: &ClientCapabilities) -> ServerCapabiliti
                         token_modifiers: vec![],
                     },
                     range: Some(false),
-                    full: Some(SemanticTokensFullOptions::Bool(true)),
+                    full: Some(SemanticTokensFullOptions::Delta { delta: Some(true) }),
                 },
             ),
         ),
         document_symbol_provider: Some(OneOf::Left(true)),
+        folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
         completion_provider: Some(CompletionOptions {
             resolve_provider: None,
             trigger_characters: Some(vec![
@@ -52,7 +57,14 @@ pub fn server_capabilities(client_caps: &ClientCapabilities) -> ServerCapabiliti
                         // Advertise support for all built-in CodeActionKinds.
                         // Ideally we would base this off of the client capabilities
                         // but the client is supposed to fall back gracefully for unknown values.
-                        code_action_kinds: Some(vec![CodeActionKind::QUICKFIX]),
+                        code_action_kinds: Some(vec![
+                            CodeActionKind::QUICKFIX,
+                            CodeActionKind::SOURCE_FIX_ALL,
+                            CodeActionKind::new(SOURCE_FIX_ALL_FORMAT),
+                            CodeActionKind::SOURCE_ORGANIZE_IMPORTS,
+                            CodeActionKind::REFACTOR_REWRITE,
+                            CodeActionKind::REFACTOR_INLINE,
+                        ]),
                         resolve_provider: None,
                         work_done_progress_options: Default::default(),
                     })
@@ -63,6 +75,11 @@ pub fn server_capabilities(client_caps: &ClientCapabilities) -> ServerCapabiliti
         references_provider: Some(OneOf::Left(true)),
         rename_provider: Some(OneOf::Left(true)),
         inlay_hint_provider: Some(lsp_types::OneOf::Left(true)),
+        call_hierarchy_provider: Some(CallHierarchyServerCapability::Simple(true)),
+        execute_command_provider: Some(ExecuteCommandOptions {
+            commands: COMMANDS.iter().map(|command| command.to_string()).collect(),
+            work_done_progress_options: WorkDoneProgressOptions::default(),
+        }),
         signature_help_provider: Some(SignatureHelpOptions {
             trigger_characters: Some(vec!["(".to_owned(), ",".to_owned()]),
             retrigger_characters: None,
@@ -73,3 +90,24 @@ pub fn server_capabilities(client_caps: &ClientCapabilities) -> ServerCapabiliti
         ..Default::default()
     }
 }
+
+/// Whether the client renders the Markdown hover contents. The clients which do not declare the
+/// `hover.contentFormat` capability are assumed to render Markdown.
+pub(crate) fn hover_markdown_supported(client_caps: &ClientCapabilities) -> bool {
+    client_caps
+        .text_document
+        .as_ref()
+        .and_then(|it| it.hover.as_ref())
+        .and_then(|it| it.content_format.as_ref())
+        .map_or(true, |formats| formats.contains(&MarkupKind::Markdown))
+}
+
+/// Whether the client accepts the `LocationLink` results of the `textDocument/definition` requests.
+pub(crate) fn definition_link_supported(client_caps: &ClientCapabilities) -> bool {
+    client_caps
+        .text_document
+        .as_ref()
+        .and_then(|it| it.definition.as_ref())
+        .and_then(|it| it.link_support)
+        .unwrap_or(false)
+}
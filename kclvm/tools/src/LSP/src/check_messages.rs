@@ -0,0 +1,124 @@
+//! The opt-in lint of the schema and rule checks without the failure messages, e.g.
+//!
+//! ```kcl
+//! schema Server:
+//!     port: int
+//!
+//!     check:
+//!         port > 0
+//! ```
+//!
+//! `port > 0` is reported and the quick fix appends a placeholder message derived from the check,
+//! e.g. `port > 0, "port > 0 must hold"`.
+
+use indexmap::IndexSet;
+use kclvm_ast::ast::{self, Program};
+use kclvm_ast::pos::GetPos;
+use kclvm_ast_pretty::{print_ast_node, ASTNode};
+use kclvm_error::{Diagnostic, DiagnosticId, Level, WarningKind};
+
+/// Returns the warnings of the checks without the messages. The range of a warning ends at the
+/// end of the check, where the suggested message is inserted.
+pub(crate) fn missing_check_message_diags(program: &Program) -> IndexSet<Diagnostic> {
+    let mut diags = IndexSet::new();
+    for module in program.modules.values() {
+        let module = match module.read() {
+            Ok(module) => module,
+            Err(_) => continue,
+        };
+        for stmt in &module.body {
+            let checks = match &stmt.node {
+                ast::Stmt::Schema(schema_stmt) => &schema_stmt.checks,
+                ast::Stmt::Rule(rule_stmt) => &rule_stmt.checks,
+                _ => continue,
+            };
+            for check in checks.iter().filter(|check| check.node.msg.is_none()) {
+                let test = print_ast_node(ASTNode::Expr(&check.node.test));
+                // The message goes after the `if` condition, e.g. `x > 0 if x, "msg"`.
+                let end = match &check.node.if_cond {
+                    Some(if_cond) => if_cond.get_end_pos(),
+                    None => check.node.test.get_end_pos(),
+                };
+                let suggestion = format!(
+                    ", \"{} must hold\"",
+                    test.replace('\\', "\\\\").replace('"', "\\\"")
+                );
+                diags.insert(Diagnostic::new_with_code(
+                    Level::Warning,
+                    &format!("Check '{}' has no failure message", test),
+                    Some("Consider adding a message describing the check"),
+                    (check.node.test.get_pos(), end),
+                    Some(DiagnosticId::Warning(
+                        WarningKind::MissingCheckMessageWarning,
+                    )),
+                    Some(vec![suggestion]),
+                ));
+            }
+        }
+    }
+    diags
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{CodeActionOrCommand, Position, Range, TextEdit, Url};
+    use proc_macro_crate::bench_test;
+
+    use super::missing_check_message_diags;
+    use crate::quick_fix::quick_fix;
+    use crate::tests::compile_test_file;
+    use crate::to_lsp::kcl_diag_to_lsp_diags_by_file;
+
+    #[test]
+    #[bench_test]
+    fn missing_check_message_diags_test() {
+        let (file, program, _, _, _) = compile_test_file("src/test_data/check_messages/main.k");
+        let diags = missing_check_message_diags(&program);
+        let messages: Vec<(u64, &str)> = diags
+            .iter()
+            .map(|diag| {
+                (
+                    diag.messages[0].range.0.line,
+                    diag.messages[0].message.as_str(),
+                )
+            })
+            .collect();
+        // The check with a message is not reported.
+        assert_eq!(
+            messages,
+            vec![
+                (6, "Check 'port > 0' has no failure message"),
+                (7, "Check 'name != \"\"' has no failure message"),
+            ]
+        );
+
+        let uri = Url::from_file_path(&file).unwrap();
+        let lsp_diags: Vec<lsp_types::Diagnostic> = diags
+            .iter()
+            .flat_map(|diag| kcl_diag_to_lsp_diags_by_file(diag, &file, None))
+            .collect();
+        let edits: Vec<TextEdit> = quick_fix(&uri, &lsp_diags)
+            .into_iter()
+            .flat_map(|action| match action {
+                CodeActionOrCommand::CodeAction(action) => {
+                    action.edit.unwrap().changes.unwrap()[&uri].clone()
+                }
+                CodeActionOrCommand::Command(_) => vec![],
+            })
+            .collect();
+        // The messages are inserted at the ends of the checks.
+        assert_eq!(
+            edits,
+            vec![
+                TextEdit {
+                    range: Range::new(Position::new(5, 16), Position::new(5, 16)),
+                    new_text: ", \"port > 0 must hold\"".to_string(),
+                },
+                TextEdit {
+                    range: Range::new(Position::new(6, 18), Position::new(6, 18)),
+                    new_text: ", \"name != \\\"\\\" must hold\"".to_string(),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! The codemods of the `kcl.applyCodemod` command, which are AST transformations applied across
+//! the files of a scope, e.g. renaming a deprecated attribute in a repository. The codemods are
+//! registered in `CODEMODS` by their ids. A transformed file is printed again with the AST
+//! printer, so the edit rewrites the whole file in the KCL code style.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::anyhow;
+use kclvm_ast::ast;
+use kclvm_ast::walker::MutSelfMutWalker;
+use kclvm_ast::{walk_if_mut, walk_list_mut};
+use kclvm_ast_pretty::print_ast_module;
+use kclvm_parser::{get_kcl_files, parse_single_file};
+use lsp_types::{Position, Range, TextEdit, Url, WorkspaceEdit};
+
+use crate::from_lsp::file_path_from_url;
+use crate::lsp_ext::ApplyCodemodParams;
+
+/// A registered codemod, `apply` transforms a module with the arguments of the command and
+/// returns whether the module is changed.
+struct Codemod {
+    id: &'static str,
+    apply: fn(&mut ast::Module, &HashMap<String, String>) -> anyhow::Result<bool>,
+}
+
+const CODEMODS: &[Codemod] = &[Codemod {
+    id: "renameIdentifier",
+    apply: rename_identifier,
+}];
+
+/// Applies the codemod to the `.k` files in the scope, the directories of the scope are walked
+/// recursively. `load_src` returns the source of a file, e.g. the unsaved content of an opened
+/// document. The files which fail to parse are skipped.
+pub(crate) fn apply_codemod(
+    params: &ApplyCodemodParams,
+    load_src: &dyn Fn(&str) -> Option<String>,
+) -> anyhow::Result<WorkspaceEdit> {
+    let codemod = CODEMODS
+        .iter()
+        .find(|codemod| codemod.id == params.id)
+        .ok_or(anyhow!("Unknown codemod: {}", params.id))?;
+    let mut files = vec![];
+    for uri in &params.scope {
+        let path = file_path_from_url(uri)?;
+        if Path::new(&path).is_dir() {
+            files.extend(get_kcl_files(&path, true)?);
+        } else {
+            files.push(path);
+        }
+    }
+    let mut changes = HashMap::new();
+    for file in files {
+        let src = match load_src(&file) {
+            Some(src) => src,
+            None => continue,
+        };
+        let mut module = match parse_single_file(&file, Some(src)) {
+            Ok(result) if result.errors.is_empty() => result.module,
+            _ => continue,
+        };
+        if (codemod.apply)(&mut module, &params.arguments)? {
+            let uri = Url::from_file_path(&file)
+                .map_err(|_| anyhow!("can't convert file to url: {}", file))?;
+            changes.insert(
+                uri,
+                vec![TextEdit {
+                    range: Range::new(
+                        Position::new(0, 0),
+                        Position::new(i32::MAX as u32, i32::MAX as u32),
+                    ),
+                    new_text: print_ast_module(&module),
+                }],
+            );
+        }
+    }
+    Ok(WorkspaceEdit {
+        changes: Some(changes),
+        ..Default::default()
+    })
+}
+
+/// Renames the variables, the schema attributes and the config keys named by the argument `from`
+/// to the argument `to`. The attributes selected from a value, e.g. `from` in `a.from`, are kept.
+fn rename_identifier(
+    module: &mut ast::Module,
+    arguments: &HashMap<String, String>,
+) -> anyhow::Result<bool> {
+    let argument = |name: &str| {
+        arguments
+            .get(name)
+            .cloned()
+            .ok_or(anyhow!("Missing the argument of the codemod: {}", name))
+    };
+    let mut renamer = IdentifierRenamer {
+        from: argument("from")?,
+        to: argument("to")?,
+        changed: false,
+    };
+    for stmt in module.body.iter_mut() {
+        renamer.walk_stmt(&mut stmt.node);
+    }
+    Ok(renamer.changed)
+}
+
+struct IdentifierRenamer {
+    from: String,
+    to: String,
+    changed: bool,
+}
+
+impl IdentifierRenamer {
+    fn rename(&mut self, name: &mut String) {
+        if *name == self.from {
+            *name = self.to.clone();
+            self.changed = true;
+        }
+    }
+}
+
+impl<'ctx> MutSelfMutWalker<'ctx> for IdentifierRenamer {
+    fn walk_identifier(&mut self, identifier: &'ctx mut ast::Identifier) {
+        if let Some(name) = identifier.names.first_mut() {
+            self.rename(&mut name.node);
+        }
+    }
+    fn walk_target(&mut self, target: &'ctx mut ast::Target) {
+        self.rename(&mut target.name.node);
+        for path in target.paths.iter_mut() {
+            if let ast::MemberOrIndex::Index(index) = path {
+                self.walk_expr(&mut index.node)
+            }
+        }
+    }
+    fn walk_schema_attr(&mut self, schema_attr: &'ctx mut ast::SchemaAttr) {
+        self.rename(&mut schema_attr.name.node);
+        walk_list_mut!(self, walk_call_expr, schema_attr.decorators);
+        walk_if_mut!(self, walk_expr, schema_attr.value);
+        self.walk_type(&mut schema_attr.ty.node);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    use lsp_types::Url;
+    use proc_macro_crate::bench_test;
+
+    use super::apply_codemod;
+    use crate::lsp_ext::ApplyCodemodParams;
+
+    #[test]
+    #[bench_test]
+    fn rename_identifier_codemod_test() {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("test_data")
+            .join("codemod");
+        let mut params = ApplyCodemodParams {
+            id: "renameIdentifier".to_string(),
+            scope: vec![Url::from_file_path(&dir).unwrap()],
+            arguments: HashMap::from([
+                ("from".to_string(), "replicas".to_string()),
+                ("to".to_string(), "replica_count".to_string()),
+            ]),
+        };
+        let load_src = |file: &str| std::fs::read_to_string(file).ok();
+        let changes = apply_codemod(&params, &load_src).unwrap().changes.unwrap();
+        let mut got: Vec<(String, String)> = changes
+            .into_iter()
+            .map(|(uri, edits)| {
+                let path = uri.to_file_path().unwrap();
+                let name = path.file_name().unwrap().to_str().unwrap().to_string();
+                (name, edits[0].new_text.clone())
+            })
+            .collect();
+        got.sort();
+        // `other.k` does not use the name and is not changed.
+        assert_eq!(
+            got,
+            vec![
+                (
+                    "a.k".to_string(),
+                    "replica_count = 3\nscale = replica_count * 2\n".to_string()
+                ),
+                (
+                    "b.k".to_string(),
+                    "schema Deploy:\n    replica_count: int = 1\n".to_string()
+                ),
+            ]
+        );
+
+        params.id = "notExistCodemod".to_string();
+        assert!(apply_codemod(&params, &load_src).is_err());
+    }
+}
@@ -0,0 +1,239 @@
+//! The refactor collapsing a private variable which is used only once by the next statement into
+//! the use, e.g.
+//!
+//! ```kcl
+//! _port = 8080
+//! service = {port = _port}
+//! ```
+//!
+//! is collapsed into `service = {port = 8080}`. The public variables are the outputs of the
+//! configs, so only the private variables are collapsed.
+
+use std::collections::HashMap;
+
+use kclvm_ast::ast::{self, Program};
+use kclvm_ast::pos::{ContainsPos, GetPos};
+use kclvm_error::Position as KCLPos;
+use kclvm_sema::core::global_state::GlobalState;
+use lsp_types::{CodeAction, CodeActionKind, CodeActionOrCommand, Position, Range, TextEdit, Url};
+
+use crate::from_lsp::text_range;
+use crate::goto_def::find_def;
+use crate::to_lsp::lsp_pos;
+
+/// Returns the action collapsing the variable declared by the top level statement at the
+/// position, or `None` if the variable is not used exactly once by the next statement or its
+/// initializer may have side effects. The comments of the declaration are kept in its place.
+pub(crate) fn collapse_single_use_variable_code_action(
+    uri: &Url,
+    file: &str,
+    src: &str,
+    kcl_pos: &KCLPos,
+    program: &Program,
+    gs: &GlobalState,
+) -> Option<CodeActionOrCommand> {
+    let module = program.get_module(file).ok()??;
+    let index = module
+        .body
+        .iter()
+        .position(|stmt| stmt.contains_pos(kcl_pos))?;
+    let (stmt, next) = (&module.body[index], module.body.get(index + 1)?);
+    let (name, value) = match &stmt.node {
+        // The type annotation is checked against the value, which would be lost by collapsing.
+        ast::Stmt::Assign(assign_stmt)
+            if assign_stmt.ty.is_none()
+                && assign_stmt.targets.len() == 1
+                && assign_stmt.targets[0].node.paths.is_empty() =>
+        {
+            (&assign_stmt.targets[0].node.name, &assign_stmt.value)
+        }
+        _ => return None,
+    };
+    if !name.node.starts_with('_') || !is_side_effect_free(value) {
+        return None;
+    }
+    // The private variables can be assigned again, which are not collapsed either.
+    let reassigned = module.body.iter().any(|other| match &other.node {
+        ast::Stmt::Assign(assign_stmt) => {
+            !std::ptr::eq(other, stmt)
+                && assign_stmt
+                    .targets
+                    .iter()
+                    .any(|target| target.node.name.node == name.node)
+        }
+        ast::Stmt::AugAssign(aug_assign_stmt) => aug_assign_stmt.target.node.name.node == name.node,
+        _ => false,
+    });
+    if reassigned {
+        return None;
+    }
+    let symbol =
+        find_def(&name.get_pos(), gs, true).and_then(|def| gs.get_symbols().get_symbol(def))?;
+    let references = symbol.get_references();
+    if references.len() != 1 {
+        return None;
+    }
+    let (start, end) = gs
+        .get_symbols()
+        .get_symbol(*references.iter().next()?)?
+        .get_range();
+    if start.filename != module.filename || !next.contains_pos(&start) {
+        return None;
+    }
+
+    let mut new_text = source_text(src, &value.get_pos(), &value.get_end_pos())?.to_string();
+    let is_whole_value = match &next.node {
+        ast::Stmt::Assign(assign_stmt) => {
+            assign_stmt.value.get_pos() == start && assign_stmt.value.get_end_pos() == end
+        }
+        _ => false,
+    };
+    if !is_whole_value
+        && matches!(
+            value.node,
+            ast::Expr::Unary(_) | ast::Expr::Binary(_) | ast::Expr::If(_) | ast::Expr::Compare(_)
+        )
+    {
+        new_text = format!("({})", new_text);
+    }
+    let comments: Vec<&str> = module
+        .comments
+        .iter()
+        .filter(|comment| comment.line >= stmt.line && comment.line <= stmt.end_line)
+        .filter_map(|comment| source_text(src, &comment.get_pos(), &comment.get_end_pos()))
+        .collect();
+    let indent = " ".repeat(stmt.column as usize);
+    let kept_comments: String = comments
+        .iter()
+        .map(|comment| format!("{}{}\n", indent, comment))
+        .collect();
+    let edits = vec![
+        TextEdit {
+            range: Range::new(
+                Position::new(stmt.line as u32 - 1, 0),
+                Position::new(stmt.end_line as u32, 0),
+            ),
+            new_text: kept_comments,
+        },
+        TextEdit {
+            range: Range::new(lsp_pos(&start), lsp_pos(&end)),
+            new_text,
+        },
+    ];
+    let mut changes = HashMap::new();
+    changes.insert(uri.clone(), edits);
+    Some(CodeActionOrCommand::CodeAction(CodeAction {
+        title: format!("Collapse single-use variable '{}'", name.node),
+        kind: Some(CodeActionKind::REFACTOR_INLINE),
+        edit: Some(lsp_types::WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }))
+}
+
+/// Returns the source text between the positions.
+fn source_text<'a>(src: &'a str, start: &KCLPos, end: &KCLPos) -> Option<&'a str> {
+    src.get(text_range(src, Range::new(lsp_pos(start), lsp_pos(end))))
+}
+
+/// Whether evaluating the expression has no side effects, so it can be moved to the use. The
+/// calls, e.g. `option("env")` and `print(x)`, and the schema expressions running the checks are
+/// not side effect free.
+fn is_side_effect_free(expr: &ast::NodeRef<ast::Expr>) -> bool {
+    match &expr.node {
+        ast::Expr::Identifier(_)
+        | ast::Expr::NumberLit(_)
+        | ast::Expr::StringLit(_)
+        | ast::Expr::NameConstantLit(_) => true,
+        ast::Expr::Unary(unary_expr) => is_side_effect_free(&unary_expr.operand),
+        ast::Expr::Binary(binary_expr) => {
+            is_side_effect_free(&binary_expr.left) && is_side_effect_free(&binary_expr.right)
+        }
+        ast::Expr::Compare(compare) => {
+            is_side_effect_free(&compare.left)
+                && compare.comparators.iter().all(is_side_effect_free)
+        }
+        ast::Expr::If(if_expr) => {
+            is_side_effect_free(&if_expr.body)
+                && is_side_effect_free(&if_expr.cond)
+                && is_side_effect_free(&if_expr.orelse)
+        }
+        ast::Expr::Selector(selector_expr) => is_side_effect_free(&selector_expr.value),
+        ast::Expr::Subscript(subscript) => {
+            is_side_effect_free(&subscript.value)
+                && [
+                    &subscript.index,
+                    &subscript.lower,
+                    &subscript.upper,
+                    &subscript.step,
+                ]
+                .iter()
+                .all(|expr| expr.as_ref().map_or(true, is_side_effect_free))
+        }
+        ast::Expr::Paren(paren_expr) => is_side_effect_free(&paren_expr.expr),
+        ast::Expr::List(list_expr) => list_expr.elts.iter().all(is_side_effect_free),
+        ast::Expr::Config(config_expr) => config_expr.items.iter().all(|entry| {
+            entry.node.key.as_ref().map_or(true, is_side_effect_free)
+                && is_side_effect_free(&entry.node.value)
+        }),
+        ast::Expr::JoinedString(joined_string) => {
+            joined_string.values.iter().all(is_side_effect_free)
+        }
+        ast::Expr::FormattedValue(formatted_value) => is_side_effect_free(&formatted_value.value),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{CodeActionOrCommand, Position, Range, TextEdit, Url};
+    use proc_macro_crate::bench_test;
+
+    use super::collapse_single_use_variable_code_action;
+    use crate::from_lsp::kcl_pos;
+    use crate::tests::compile_test_file;
+
+    #[test]
+    #[bench_test]
+    fn collapse_single_use_variable_test() {
+        let (file, program, _, gs, _) = compile_test_file("src/test_data/collapse_variable/main.k");
+        let src = std::fs::read_to_string(&file).unwrap();
+        let uri = Url::from_file_path(&file).unwrap();
+        let action = |line| {
+            collapse_single_use_variable_code_action(
+                &uri,
+                &file,
+                &src,
+                &kcl_pos(&file, Position::new(line, 1)),
+                &program,
+                &gs,
+            )
+        };
+
+        let edits = match action(0) {
+            Some(CodeActionOrCommand::CodeAction(action)) => {
+                action.edit.unwrap().changes.unwrap()[&uri].clone()
+            }
+            _ => unreachable!(),
+        };
+        assert_eq!(
+            edits,
+            vec![
+                TextEdit {
+                    range: Range::new(Position::new(0, 0), Position::new(1, 0)),
+                    new_text: "# The service port.\n".to_string(),
+                },
+                TextEdit {
+                    range: Range::new(Position::new(1, 18), Position::new(1, 23)),
+                    new_text: "(8000 + 80)".to_string(),
+                },
+            ]
+        );
+
+        // The variables used more than once and the initializers with side effects.
+        assert!(action(3).is_none());
+        assert!(action(6).is_none());
+    }
+}
@@ -0,0 +1,698 @@
+//! The commands of the `workspace/executeCommand` request.
+//!
+//! + `kcl.gotoEnclosingSchemaDef`: Walks outward from the cursor to the nearest enclosing config
+//!   typed by a schema and returns the `Location` of the schema definition. The argument is a
+//!   `TextDocumentPositionParams`.
+//! + `kcl.sortSchemaAttributes`: Sorts the attributes of the schema at the cursor, required ones
+//!   first and then optional ones, alphabetically within each group. The result is a
+//!   `WorkspaceEdit`, or `null` when the attributes are already sorted. The argument is a
+//!   `TextDocumentPositionParams`.
+//! + `kcl.rebuildCaches`: Clears the AST cache, the resolver cache, the symbol and reference
+//!   index and the diagnostics, then compiles the workspaces again and republishes the
+//!   diagnostics. The result is a `RebuildCachesResult` with the counts rebuilt. The command has
+//!   no arguments.
+//! + `kcl.extractConfig`: Extracts the config at the selection into a variable of a new file next
+//!   to the document, and replaces the config with a reference to the variable imported from the
+//!   new file. The result is a `WorkspaceEdit` creating the file. The argument is an
+//!   `ExtractConfigParams`.
+//! + `kcl.applyCodemod`: Applies the registered codemod with the id across the files of the
+//!   scope, see the `codemod` module. The result is a `WorkspaceEdit`, and an unknown id is an
+//!   error. The argument is an `ApplyCodemodParams`.
+
+use std::path::Path;
+
+use crossbeam_channel::Sender;
+use kclvm_ast::ast;
+use kclvm_ast::pos::ContainsPos;
+use kclvm_ast::walker::MutSelfWalker;
+use kclvm_error::Position as KCLPos;
+use kclvm_parser::{parse_single_file, ModuleCache};
+use kclvm_sema::core::global_state::GlobalState;
+use kclvm_sema::core::scope::{LocalSymbolScopeKind, Scope};
+use kclvm_sema::core::symbol::SymbolKind;
+use lsp_types::{
+    CreateFile, DocumentChangeOperation, DocumentChanges, Location, OneOf,
+    OptionalVersionedTextDocumentIdentifier, Position, Range, ResourceOp, TextDocumentEdit,
+    TextEdit, Url, WorkspaceEdit,
+};
+
+use crate::analysis::DBState;
+use crate::from_lsp::kcl_pos;
+use crate::lsp_ext::RebuildCachesResult;
+use crate::schema_coverage::config_key;
+use crate::state::{LanguageServerSnapshot, Task};
+use crate::to_lsp::lsp_location;
+
+pub(crate) const GOTO_ENCLOSING_SCHEMA_DEF: &str = "kcl.gotoEnclosingSchemaDef";
+pub(crate) const SORT_SCHEMA_ATTRIBUTES: &str = "kcl.sortSchemaAttributes";
+pub(crate) const REBUILD_CACHES: &str = "kcl.rebuildCaches";
+pub(crate) const EXTRACT_CONFIG: &str = "kcl.extractConfig";
+pub(crate) const APPLY_CODEMOD: &str = "kcl.applyCodemod";
+
+/// All the commands supported by the language server.
+pub(crate) const COMMANDS: &[&str] = &[
+    GOTO_ENCLOSING_SCHEMA_DEF,
+    SORT_SCHEMA_ATTRIBUTES,
+    REBUILD_CACHES,
+    EXTRACT_CONFIG,
+    APPLY_CODEMOD,
+];
+
+/// Returns the location of the schema which types the nearest config enclosing the position, e.g.
+///
+/// ```kcl
+/// app = App {
+///     container = {
+///         name = "nginx"
+///     }
+/// }
+/// ```
+///
+/// From `"nginx"`, the enclosing config is typed by the schema `Container`. Dict configs which are
+/// not typed by a schema are skipped, so the cursor in a dict attribute value jumps to the schema
+/// owning the attribute.
+pub(crate) fn goto_enclosing_schema_def(kcl_pos: &KCLPos, gs: &GlobalState) -> Option<Location> {
+    let scopes = gs.get_scopes();
+    let mut scope_ref = gs.look_up_scope(kcl_pos)?;
+    loop {
+        if let Some(local_scope) = scopes.try_get_local_scope(&scope_ref) {
+            if let LocalSymbolScopeKind::Config = local_scope.get_kind() {
+                if let Some(owner) = local_scope.get_owner() {
+                    if let SymbolKind::Schema = owner.get_kind() {
+                        let schema = gs.get_symbols().get_symbol(owner)?;
+                        let (start, end) = schema.get_range();
+                        return lsp_location(start.filename.clone(), &start, &end);
+                    }
+                }
+            }
+        }
+        scope_ref = scopes.get_scope(&scope_ref)?.get_parent()?;
+    }
+}
+
+/// Clears all the caches of the language server and requests the workspaces to be compiled
+/// again. The databases are marked as compiling before returning, so the subsequent read-only
+/// requests, e.g. the hovers and the signature helps, answer from the previous databases until
+/// the new ones replace them, while the requests editing the files wait for the new databases.
+pub(crate) fn rebuild_caches(
+    snapshot: &LanguageServerSnapshot,
+    sender: &Sender<Task>,
+) -> anyhow::Result<RebuildCachesResult> {
+    let modules = match snapshot.module_cache.write() {
+        Ok(mut module_cache) => {
+            let modules = module_cache.ast_cache.len();
+            *module_cache = ModuleCache::default();
+            modules
+        }
+        Err(e) => {
+            return Err(anyhow::anyhow!(
+                "Failed to get module cache RwLock: {:?}",
+                e
+            ))
+        }
+    };
+    snapshot.scope_cache.write().clear();
+    match snapshot.gs_cache.lock() {
+        Ok(mut gs) => *gs = GlobalState::default(),
+        Err(e) => return Err(anyhow::anyhow!("Failed to get global state lock: {:?}", e)),
+    }
+    snapshot.semantic_tokens_cache.write().clear();
+    snapshot.folding_range_cache.clear();
+    let workspaces = {
+        let mut workspaces = snapshot.workspaces.write();
+        for state in workspaces.values_mut() {
+            *state = match &*state {
+                DBState::Ready(db) | DBState::Compiling(db) => DBState::Compiling(db.clone()),
+                DBState::Init | DBState::Failed(_) => DBState::Init,
+            };
+        }
+        workspaces.len()
+    };
+    sender.send(Task::RebuildWorkspaces)?;
+    Ok(RebuildCachesResult {
+        workspaces,
+        modules,
+        documents: snapshot.opened_files.read().len(),
+    })
+}
+
+/// The source lines of a schema attribute, including its decorators and the comment lines
+/// directly above it.
+struct AttrChunk {
+    is_optional: bool,
+    name: String,
+    lines: Vec<String>,
+}
+
+/// Adjacent attributes in a schema body, the lines are 1-based and inclusive.
+struct AttrRun {
+    start: usize,
+    end: usize,
+    indent: String,
+    chunks: Vec<AttrChunk>,
+}
+
+/// Returns the edit which sorts the attributes of the schema at the position, e.g.
+///
+/// ```kcl
+/// schema Server:
+///     port?: int = 80
+///     # The host name.
+///     name: str
+/// ```
+///
+/// is sorted to `name` and then `port`, the comment above `name` moves with it. The comments
+/// separated from the next attribute by blank lines move with the next attribute too. Only the
+/// attributes which are adjacent are sorted together, so an attribute is never moved across
+/// other statements of the schema body. The attributes are re-indented to the indentation of the
+/// first one and the blank lines between them are removed. Returns `None` when the attributes
+/// are already sorted.
+pub(crate) fn sort_schema_attrs(file: &str, src: &str, kcl_pos: &KCLPos) -> Option<TextEdit> {
+    let module = parse_single_file(file, Some(src.to_string())).ok()?.module;
+    let lines: Vec<&str> = src.lines().collect();
+    let (schema_line, schema) = module.body.iter().find_map(|stmt| match &stmt.node {
+        ast::Stmt::Schema(schema) if stmt.line <= kcl_pos.line && kcl_pos.line <= stmt.end_line => {
+            Some((stmt.line as usize, schema))
+        }
+        _ => None,
+    })?;
+    let mut runs: Vec<AttrRun> = vec![];
+    let mut in_run = false;
+    let mut prev_end = schema_line;
+    for stmt in &schema.body {
+        let end = (stmt.end_line as usize).min(lines.len());
+        if let ast::Stmt::SchemaAttr(attr) = &stmt.node {
+            let first_line = attr
+                .decorators
+                .iter()
+                .map(|decorator| decorator.line as usize)
+                .fold(stmt.line as usize, usize::min);
+            let mut start = first_line;
+            if in_run {
+                // The lines after the attribute before are only comments and blank lines.
+                start = prev_end + 1;
+            } else {
+                while start > prev_end + 1 && lines[start - 2].trim_start().starts_with('#') {
+                    start -= 1;
+                }
+            }
+            let indent = indent_of(lines[stmt.line as usize - 1]);
+            if !in_run {
+                runs.push(AttrRun {
+                    start,
+                    end,
+                    indent: indent.to_string(),
+                    chunks: vec![],
+                });
+                in_run = true;
+            }
+            let run = runs.last_mut()?;
+            run.end = end;
+            let chunk_lines = lines[start - 1..end]
+                .iter()
+                .enumerate()
+                // The blank lines above the attribute are removed, the comments are kept.
+                .filter(|(i, line)| start + i >= first_line || !line.trim().is_empty())
+                .map(|(_, line)| {
+                    let line = line.trim_end();
+                    if line.is_empty() {
+                        String::new()
+                    } else {
+                        let line = line.strip_prefix(indent).unwrap_or(line.trim_start());
+                        format!("{}{}", run.indent, line)
+                    }
+                })
+                .collect();
+            run.chunks.push(AttrChunk {
+                is_optional: attr.is_optional,
+                name: attr.name.node.clone(),
+                lines: chunk_lines,
+            });
+        } else {
+            in_run = false;
+        }
+        prev_end = end;
+    }
+    let start = runs.first()?.start;
+    let end = runs.last()?.end;
+    let mut new_lines: Vec<String> = vec![];
+    let mut next = start;
+    for mut run in runs {
+        new_lines.extend(
+            lines[next - 1..run.start - 1]
+                .iter()
+                .map(|line| line.to_string()),
+        );
+        run.chunks.sort_by(|a, b| {
+            a.is_optional
+                .cmp(&b.is_optional)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        for chunk in run.chunks {
+            new_lines.extend(chunk.lines);
+        }
+        next = run.end + 1;
+    }
+    let new_text = new_lines.join("\n");
+    if new_text == lines[start - 1..end].join("\n") {
+        return None;
+    }
+    Some(TextEdit {
+        range: Range {
+            start: Position::new(start as u32 - 1, 0),
+            end: Position::new(end as u32 - 1, lines[end - 1].encode_utf16().count() as u32),
+        },
+        new_text,
+    })
+}
+
+/// The name of the extracted variable when the config is not the value of an attribute or a
+/// variable.
+const EXTRACTED_NAME: &str = "extracted";
+
+/// Returns the edit which extracts the innermost config containing the selection into a new
+/// file, e.g.
+///
+/// ```kcl
+/// app = App {
+///     container = {
+///         image = "nginx"
+///     }
+/// }
+/// ```
+///
+/// The config of `container` is moved to `container = {...}` in the new file `container/main.k`,
+/// and replaced with `container.container` imported by `import .container`. The new file is in
+/// a new subpackage, so the variable is not an output of the package of the document. The
+/// variable and the subpackage are named after the attribute or the variable the config is
+/// assigned to. The config text is copied with its comments, and the imports used by the config
+/// are copied to the new file, the relative ones are relative to the parent package. The schemas
+/// defined in the package of the document are not visible in the new file, so they are not
+/// handled.
+pub(crate) fn extract_config(file: &str, src: &str, range: Range) -> Option<WorkspaceEdit> {
+    let module = parse_single_file(file, Some(src.to_string())).ok()?.module;
+    let lines: Vec<&str> = src.lines().collect();
+    let mut finder = ExtractTarget {
+        start: kcl_pos(file, range.start),
+        end: kcl_pos(file, range.end),
+        found: None,
+    };
+    for stmt in &module.body {
+        finder.walk_stmt(&stmt.node);
+    }
+    let (name, expr) = finder.found?;
+    let name = if name.chars().all(|c| c.is_alphanumeric() || c == '_') && !name.is_empty() {
+        name
+    } else {
+        EXTRACTED_NAME.to_string()
+    };
+    let dir = Path::new(file).parent()?;
+    let mut stem = name.clone();
+    let mut index = 1;
+    while dir.join(&stem).exists() || dir.join(format!("{}.k", stem)).exists() {
+        stem = format!("{}_{}", name, index);
+        index += 1;
+    }
+    let new_file = dir.join(&stem).join("main.k");
+
+    // The config text with the lines after the first one dedented by the closing line.
+    let (start_line, end_line) = (expr.line as usize, expr.end_line as usize);
+    let mut text_lines: Vec<String> = vec![];
+    for line_no in start_line..=end_line {
+        let line = lines.get(line_no - 1)?;
+        let start = if line_no == start_line {
+            expr.column as usize
+        } else {
+            0
+        };
+        let end = if line_no == end_line {
+            expr.end_column as usize
+        } else {
+            line.len()
+        };
+        text_lines.push(line.get(start..end)?.trim_end().to_string());
+    }
+    let indent = indent_of(lines.get(end_line - 1)?);
+    let text = text_lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| match line.strip_prefix(indent) {
+            Some(line) if i > 0 => line,
+            _ => line.as_str(),
+        })
+        .collect::<Vec<&str>>()
+        .join("\n");
+
+    let mut names = IdentifierCollector::default();
+    names.walk_expr(&expr.node);
+    let mut new_text = String::new();
+    let mut import_end = 0;
+    for stmt in &module.body {
+        if let ast::Stmt::Import(import_stmt) = &stmt.node {
+            import_end = stmt.end_line as u32;
+            if names.names.contains(&import_stmt.name) {
+                for line in lines.get(stmt.line as usize - 1..stmt.end_line as usize)? {
+                    // The relative import is one level deeper in the subpackage.
+                    if import_stmt.rawpath.starts_with('.') {
+                        new_text.push_str(&line.replacen("import .", "import ..", 1));
+                    } else {
+                        new_text.push_str(line);
+                    }
+                    new_text.push('\n');
+                }
+            }
+        }
+    }
+    if !new_text.is_empty() {
+        new_text.push('\n');
+    }
+    new_text.push_str(&format!("{} = {}\n", name, text));
+    let import_text = if import_end == 0 {
+        format!("import .{}\n\n", stem)
+    } else {
+        format!("import .{}\n", stem)
+    };
+
+    let uri = Url::from_file_path(file).ok()?;
+    let new_uri = Url::from_file_path(&new_file).ok()?;
+    let insert = |line: u32| Range::new(Position::new(line, 0), Position::new(line, 0));
+    Some(WorkspaceEdit {
+        document_changes: Some(DocumentChanges::Operations(vec![
+            DocumentChangeOperation::Op(ResourceOp::Create(CreateFile {
+                uri: new_uri.clone(),
+                options: None,
+                annotation_id: None,
+            })),
+            DocumentChangeOperation::Edit(TextDocumentEdit {
+                text_document: OptionalVersionedTextDocumentIdentifier {
+                    uri: new_uri,
+                    version: None,
+                },
+                edits: vec![OneOf::Left(TextEdit {
+                    range: insert(0),
+                    new_text,
+                })],
+            }),
+            DocumentChangeOperation::Edit(TextDocumentEdit {
+                text_document: OptionalVersionedTextDocumentIdentifier { uri, version: None },
+                edits: vec![
+                    OneOf::Left(TextEdit {
+                        range: insert(import_end),
+                        new_text: import_text,
+                    }),
+                    OneOf::Left(TextEdit {
+                        range: Range::new(
+                            Position::new(start_line as u32 - 1, expr.column as u32),
+                            Position::new(end_line as u32 - 1, expr.end_column as u32),
+                        ),
+                        new_text: format!("{}.{}", stem, name),
+                    }),
+                ],
+            }),
+        ])),
+        ..Default::default()
+    })
+}
+
+/// Finds the innermost config containing the selection which is the value of a variable or a
+/// config attribute.
+struct ExtractTarget {
+    start: KCLPos,
+    end: KCLPos,
+    found: Option<(String, ast::NodeRef<ast::Expr>)>,
+}
+
+impl ExtractTarget {
+    fn walk_value(&mut self, name: Option<String>, value: &ast::NodeRef<ast::Expr>) {
+        if let ast::Expr::Config(_) | ast::Expr::Schema(_) = &value.node {
+            if value.contains_pos(&self.start) && value.contains_pos(&self.end) {
+                let name = name.unwrap_or(EXTRACTED_NAME.to_string());
+                self.found = Some((name, value.clone()));
+            }
+        }
+        self.walk_expr(&value.node);
+    }
+}
+
+impl MutSelfWalker for ExtractTarget {
+    fn walk_assign_stmt(&mut self, assign_stmt: &ast::AssignStmt) {
+        let name = assign_stmt
+            .targets
+            .first()
+            .map(|target| target.node.name.node.clone());
+        self.walk_value(name, &assign_stmt.value);
+    }
+
+    fn walk_config_expr(&mut self, config_expr: &ast::ConfigExpr) {
+        for config_entry in &config_expr.items {
+            let name = config_entry.node.key.as_ref().and_then(config_key);
+            self.walk_value(name, &config_entry.node.value);
+        }
+    }
+}
+
+/// Collects the first names of the identifiers, e.g. `k8s` of `k8s.Deployment`.
+#[derive(Default)]
+struct IdentifierCollector {
+    names: Vec<String>,
+}
+
+impl MutSelfWalker for IdentifierCollector {
+    fn walk_identifier(&mut self, identifier: &ast::Identifier) {
+        if let Some(name) = identifier.names.first() {
+            self.names.push(name.node.clone());
+        }
+    }
+}
+
+/// Returns the leading whitespaces of the line.
+fn indent_of(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use kclvm_error::Position as KCLPos;
+    use lsp_types::{
+        DocumentChangeOperation, DocumentChanges, OneOf, Position, Range, ResourceOp, TextEdit, Url,
+    };
+    use proc_macro_crate::bench_test;
+
+    use super::{extract_config, goto_enclosing_schema_def, sort_schema_attrs};
+    use crate::tests::compile_test_file;
+
+    #[test]
+    #[bench_test]
+    fn goto_enclosing_schema_def_test() {
+        let (file, _, _, gs, _) =
+            compile_test_file("src/test_data/goto_enclosing_schema_def/main.k");
+
+        // name = "nginx" in the container config jumps to `schema Container`.
+        let pos = KCLPos {
+            filename: file.clone(),
+            line: 14,
+            column: Some(17),
+        };
+        let location = goto_enclosing_schema_def(&pos, &gs).unwrap();
+        assert_eq!(location.range.start, Position::new(3, 7));
+
+        // ports = [{port = 80}] jumps to `schema Port`.
+        let pos = KCLPos {
+            filename: file.clone(),
+            line: 15,
+            column: Some(26),
+        };
+        let location = goto_enclosing_schema_def(&pos, &gs).unwrap();
+        assert_eq!(location.range.start, Position::new(0, 7));
+
+        // The dict config of `labels` is skipped and jumps to `schema App`.
+        let pos = KCLPos {
+            filename: file.clone(),
+            line: 18,
+            column: Some(17),
+        };
+        let location = goto_enclosing_schema_def(&pos, &gs).unwrap();
+        assert_eq!(location.range.start, Position::new(7, 7));
+
+        // No enclosing config at the top level.
+        let pos = KCLPos {
+            filename: file,
+            line: 22,
+            column: Some(4),
+        };
+        assert!(goto_enclosing_schema_def(&pos, &gs).is_none());
+    }
+
+    #[test]
+    #[bench_test]
+    fn sort_schema_attrs_test() {
+        let file = "src/test_data/sort_schema_attrs/main.k";
+        let src = std::fs::read_to_string(file).unwrap();
+
+        let pos = KCLPos {
+            filename: file.to_string(),
+            line: 1,
+            column: Some(7),
+        };
+        let edit = sort_schema_attrs(file, &src, &pos).unwrap();
+        assert_eq!(edit.range.start, Position::new(2, 0));
+        assert_eq!(edit.range.end, Position::new(8, 17));
+        assert_eq!(
+            edit.new_text,
+            [
+                "    name: str  # The name of the server.",
+                "    # The replicas.",
+                "    replicas: int",
+                "    @deprecated",
+                "    host?: str",
+                "    # The port of the server.",
+                "    port?: int = 80",
+            ]
+            .join("\n")
+        );
+
+        // The attributes of `schema Sorted` are already sorted.
+        let pos = KCLPos {
+            filename: file.to_string(),
+            line: 15,
+            column: Some(4),
+        };
+        assert!(sort_schema_attrs(file, &src, &pos).is_none());
+
+        // The comment separated from `name` by a blank line is kept.
+        let pos = KCLPos {
+            filename: file.to_string(),
+            line: 18,
+            column: Some(7),
+        };
+        let edit = sort_schema_attrs(file, &src, &pos).unwrap();
+        assert_eq!(edit.range.start, Position::new(18, 0));
+        assert_eq!(edit.range.end, Position::new(21, 13));
+        assert_eq!(
+            edit.new_text,
+            ["    # The name.", "    name: str", "    port: int"].join("\n")
+        );
+    }
+
+    #[test]
+    #[bench_test]
+    fn extract_config_test() {
+        let file = std::fs::canonicalize("src/test_data/extract_config/main.k").unwrap();
+        let new_file = file.with_file_name("container").join("main.k");
+        let file = file.display().to_string();
+        let src = std::fs::read_to_string(&file).unwrap();
+
+        // The selection in the `container` config.
+        let range = Range::new(Position::new(9, 8), Position::new(10, 10));
+        let edit = extract_config(&file, &src, range).unwrap();
+        let operations = match edit.document_changes.unwrap() {
+            DocumentChanges::Operations(operations) => operations,
+            DocumentChanges::Edits(_) => unreachable!("test error"),
+        };
+        assert_eq!(operations.len(), 3);
+        match &operations[0] {
+            DocumentChangeOperation::Op(ResourceOp::Create(create)) => {
+                assert_eq!(create.uri, Url::from_file_path(&new_file).unwrap());
+            }
+            _ => unreachable!("test error"),
+        }
+        let edits = |operation: &DocumentChangeOperation| match operation {
+            DocumentChangeOperation::Edit(edit) => edit
+                .edits
+                .iter()
+                .map(|edit| match edit {
+                    OneOf::Left(edit) => edit.clone(),
+                    OneOf::Right(edit) => edit.text_edit.clone(),
+                })
+                .collect::<Vec<TextEdit>>(),
+            _ => unreachable!("test error"),
+        };
+        // The new file has the config with its comment and the import used by the config.
+        assert_eq!(
+            edits(&operations[1]),
+            vec![TextEdit {
+                range: Range::new(Position::new(0, 0), Position::new(0, 0)),
+                new_text: [
+                    "import math",
+                    "",
+                    "container = {",
+                    "    # The container image.",
+                    "    image = \"nginx\"",
+                    "    cpu = math.log10(100)",
+                    "}",
+                    "",
+                ]
+                .join("\n"),
+            }]
+        );
+        // The config is replaced with the variable imported after the existing imports.
+        assert_eq!(
+            edits(&operations[2]),
+            vec![
+                TextEdit {
+                    range: Range::new(Position::new(1, 0), Position::new(1, 0)),
+                    new_text: "import .container\n".to_string(),
+                },
+                TextEdit {
+                    range: Range::new(Position::new(8, 16), Position::new(12, 5)),
+                    new_text: "container.container".to_string(),
+                },
+            ]
+        );
+
+        // The output of the package is not changed by the extraction.
+        let dir = std::env::temp_dir().join("kcl_lsp_extract_config_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("container")).unwrap();
+        let new_src = apply_edits(&src, &edits(&operations[2]));
+        std::fs::write(dir.join("main.k"), &new_src).unwrap();
+        std::fs::write(
+            dir.join("container").join("main.k"),
+            &edits(&operations[1])[0].new_text,
+        )
+        .unwrap();
+        let output = |dir: &std::path::Path| {
+            let mut files: Vec<String> = std::fs::read_dir(dir)
+                .unwrap()
+                .map(|entry| entry.unwrap().path())
+                .filter(|path| path.extension().map_or(false, |ext| ext == "k"))
+                .map(|path| path.display().to_string())
+                .collect();
+            files.sort();
+            let args = kclvm_runner::ExecProgramArgs {
+                k_filename_list: files,
+                ..Default::default()
+            };
+            let result =
+                kclvm_runner::exec_program(kclvm_parser::ParseSessionRef::default(), &args)
+                    .unwrap();
+            assert!(result.err_message.is_empty(), "{}", result.err_message);
+            result.json_result
+        };
+        let before = output(Path::new(&file).parent().unwrap());
+        let after = output(&dir);
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(before, after);
+    }
+
+    /// Applies the sorted edits which do not overlap to the ASCII source.
+    fn apply_edits(src: &str, edits: &[TextEdit]) -> String {
+        let offset = |pos: Position| {
+            src.split_inclusive('\n')
+                .take(pos.line as usize)
+                .map(|line| line.len())
+                .sum::<usize>()
+                + pos.character as usize
+        };
+        let mut new_src = src.to_string();
+        for edit in edits.iter().rev() {
+            new_src.replace_range(
+                offset(edit.range.start)..offset(edit.range.end),
+                &edit.new_text,
+            );
+        }
+        new_src
+    }
+}
@@ -11,9 +11,10 @@ use kclvm_sema::{
     advanced_resolver::AdvancedResolver,
     core::global_state::GlobalState,
     namer::Namer,
-    resolver::{resolve_program_with_opts, scope::KCLScopeCache},
+    resolver::{scope::KCLScopeCache, Options},
     ty::SchemaType,
 };
+use kclvm_tools::check::check_program_with_opts;
 use std::collections::HashSet;
 use std::path::PathBuf;
 
@@ -28,6 +29,16 @@ pub struct Params {
     pub scope_cache: Option<KCLScopeCache>,
     pub vfs: Option<KCLVfs>,
     pub gs_cache: Option<KCLGlobalStateCache>,
+    /// The maximum nesting depth of the configs and the lists, the resolver default if not set.
+    pub max_schema_depth: Option<usize>,
+    /// Whether to report only the root causes of the cascading errors.
+    pub minimal_diagnostics: bool,
+    /// Whether to warn the implicit coercions, e.g. `"1" == 1`.
+    pub implicit_coercion_check: bool,
+    /// Whether to warn the top level variables and the schema attributes without the type annotations.
+    pub explicit_type_check: bool,
+    /// Whether to report the references to the names both imported and defined in the package.
+    pub ambiguous_reference_check: bool,
 }
 
 pub fn compile(
@@ -116,15 +127,17 @@ pub fn compile(
         }
     }
 
-    let prog_scope = resolve_program_with_opts(
-        &mut program,
-        kclvm_sema::resolver::Options {
-            merge_program: false,
-            type_erasure: false,
-            ..Default::default()
-        },
-        params.scope_cache.clone(),
-    );
+    // Only type check the program, the diagnostics never need the codegen and evaluation.
+    let mut resolve_opts = Options::default();
+    if let Some(max_schema_depth) = params.max_schema_depth {
+        resolve_opts.max_schema_depth = max_schema_depth;
+    }
+    resolve_opts.minimal_diagnostics = params.minimal_diagnostics;
+    resolve_opts.implicit_coercion_check = params.implicit_coercion_check;
+    resolve_opts.explicit_type_check = params.explicit_type_check;
+    resolve_opts.ambiguous_reference_check = params.ambiguous_reference_check;
+    let prog_scope =
+        check_program_with_opts(&mut program, resolve_opts, params.scope_cache.clone());
     let schema_map: IndexMap<String, Vec<SchemaType>> = filter_pkg_schemas(&prog_scope, None, None);
     diags.extend(prog_scope.handler.diagnostics);
 
@@ -19,7 +19,9 @@ use crate::goto_def::{find_def, find_symbol};
 use crate::to_lsp::lsp_pos;
 use indexmap::{IndexMap, IndexSet};
 use kclvm_ast::ast::{self, ImportStmt, Program, Stmt};
+use kclvm_ast::pos::GetPos;
 use kclvm_ast::MAIN_PKG;
+use kclvm_ast_pretty::{print_ast_node, ASTNode};
 use kclvm_config::modfile::KCL_FILE_EXTENSION;
 use kclvm_driver::toolchain::{get_real_path_from_external, Metadata, Toolchain};
 use kclvm_error::diagnostic::Range;
@@ -32,7 +34,7 @@ use kclvm_error::Position as KCLPos;
 use kclvm_sema::builtin::{BUILTIN_FUNCTIONS, STANDARD_SYSTEM_MODULES};
 use kclvm_sema::core::package::ModuleInfo;
 use kclvm_sema::core::scope::{LocalSymbolScopeKind, ScopeKind};
-use kclvm_sema::core::symbol::SymbolKind;
+use kclvm_sema::core::symbol::{SymbolKind, SymbolRef};
 use kclvm_sema::resolver::doc::{parse_schema_doc_string, SchemaDoc};
 use kclvm_sema::ty::{FunctionType, SchemaType, Type, TypeKind};
 use kclvm_utils::path::PathPrefix;
@@ -97,7 +99,7 @@ pub fn completion(
     match trigger_character {
         Some(c) => match c {
             '.' => completion_dot(program, pos, gs, tool),
-            '=' | ':' => completion_assign(pos, gs),
+            '=' | ':' => completion_assign(program, pos, gs),
             '\n' => completion_newline(program, pos, gs),
             _ => None,
         },
@@ -122,12 +124,15 @@ pub fn completion(
             }
 
             if let Some(scope) = gs.look_up_scope(pos) {
-                // Complete builtin functions in root scope and lambda
+                // Complete builtin functions in root scope, lambda and the bodies of comprehensions
                 match scope.get_kind() {
                     kclvm_sema::core::scope::ScopeKind::Local => {
                         if let Some(local_scope) = gs.get_scopes().try_get_local_scope(&scope) {
                             match local_scope.get_kind() {
-                                kclvm_sema::core::scope::LocalSymbolScopeKind::Lambda => {
+                                kclvm_sema::core::scope::LocalSymbolScopeKind::Lambda
+                                | kclvm_sema::core::scope::LocalSymbolScopeKind::List
+                                | kclvm_sema::core::scope::LocalSymbolScopeKind::Dict
+                                | kclvm_sema::core::scope::LocalSymbolScopeKind::Quant => {
                                     completions.extend(BUILTIN_FUNCTIONS.iter().map(
                                         |(name, ty)| KCLCompletionItem {
                                             label: func_ty_complete_label(
@@ -361,7 +366,11 @@ fn completion_dot(
 
 /// Get completion items for trigger '=' or ':'
 /// Now, just completion for schema attr value
-fn completion_assign(pos: &KCLPos, gs: &GlobalState) -> Option<lsp_types::CompletionResponse> {
+fn completion_assign(
+    program: &Program,
+    pos: &KCLPos,
+    gs: &GlobalState,
+) -> Option<lsp_types::CompletionResponse> {
     let mut items = IndexSet::new();
     if let Some(symbol_ref) = find_def(pos, gs, false) {
         if let Some(symbol) = gs.get_symbols().get_symbol(symbol_ref) {
@@ -392,6 +401,23 @@ fn completion_assign(pos: &KCLPos, gs: &GlobalState) -> Option<lsp_types::Comple
                                         },
                                     ),
                                 );
+                                // Complete the values allowed by the `in [...]` checks.
+                                items.extend(
+                                    check_membership_values(program, &symbol.get_name(), def, gs)
+                                        .into_iter()
+                                        .map(|value| KCLCompletionItem {
+                                            label: format!(" {}", value),
+                                            detail: Some(format!(
+                                                "{}: {}",
+                                                symbol.get_name(),
+                                                ty.ty_str()
+                                            )),
+                                            kind: Some(KCLCompletionItemKind::Variable),
+                                            documentation: sema_info.doc.clone(),
+                                            insert_text: Some(format!(" {}", value)),
+                                            additional_text_edits: None,
+                                        }),
+                                );
                                 return Some(into_completion_items(&items).into());
                             }
                             None => {}
@@ -405,6 +431,86 @@ fn completion_assign(pos: &KCLPos, gs: &GlobalState) -> Option<lsp_types::Comple
     None
 }
 
+/// Returns the literal values of the `attr in [...]` checks of the schema declaring the attribute,
+/// e.g. `"dev"` and `"prod"` for `env in ["dev", "prod"]`. The checks joined by `and` are also
+/// searched, the conditional checks and the lists with non-literal items are ignored.
+fn check_membership_values(
+    program: &Program,
+    attr_name: &str,
+    attr_ref: SymbolRef,
+    gs: &GlobalState,
+) -> Vec<String> {
+    let owner = match gs
+        .get_symbols()
+        .get_symbol(attr_ref)
+        .and_then(|attr| attr.get_owner())
+    {
+        Some(owner) => owner,
+        None => return vec![],
+    };
+    let mut values = vec![];
+    for module in program.modules.values() {
+        if let Ok(module) = module.read() {
+            for stmt in &module.body {
+                if let Stmt::Schema(schema_stmt) = &stmt.node {
+                    if find_def(&schema_stmt.name.get_pos(), gs, true) != Some(owner) {
+                        continue;
+                    }
+                    for check in &schema_stmt.checks {
+                        if check.node.if_cond.is_none() {
+                            collect_membership_values(
+                                &check.node.test.node,
+                                attr_name,
+                                &mut values,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+    values
+}
+
+fn collect_membership_values(expr: &ast::Expr, attr_name: &str, values: &mut Vec<String>) {
+    match expr {
+        ast::Expr::Binary(binary_expr) if binary_expr.op == ast::BinOp::And => {
+            collect_membership_values(&binary_expr.left.node, attr_name, values);
+            collect_membership_values(&binary_expr.right.node, attr_name, values);
+        }
+        ast::Expr::Compare(compare)
+            if compare.ops.len() == 1 && compare.ops[0] == ast::CmpOp::In =>
+        {
+            match (&compare.left.node, &compare.comparators[0].node) {
+                (ast::Expr::Identifier(identifier), ast::Expr::List(list))
+                    if identifier.names.len() == 1 && identifier.names[0].node == attr_name =>
+                {
+                    let literals: Option<Vec<String>> = list
+                        .elts
+                        .iter()
+                        .map(|elt| match &elt.node {
+                            ast::Expr::StringLit(string_lit) => {
+                                Some(format!("{:?}", string_lit.value))
+                            }
+                            ast::Expr::NumberLit(_) | ast::Expr::NameConstantLit(_) => {
+                                Some(print_ast_node(ASTNode::Expr(elt)))
+                            }
+                            _ => None,
+                        })
+                        .collect();
+                    for literal in literals.into_iter().flatten() {
+                        if !values.contains(&literal) {
+                            values.push(literal);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        _ => {}
+    }
+}
+
 fn completion_newline(
     program: &Program,
     pos: &KCLPos,
@@ -1450,6 +1556,28 @@ mod tests {
         assert_eq!(got, expect);
     }
 
+    #[test]
+    #[bench_test]
+    fn import_alias_dot_completion() {
+        let (file, program, _, gs, schema_map) =
+            compile_test_file("src/test_data/import_alias/main.k");
+
+        // n = c.name
+        let pos = KCLPos {
+            filename: file.to_owned(),
+            line: 3,
+            column: Some(6),
+        };
+        let tool = toolchain::default();
+        let got = completion(Some('.'), &program, &pos, &gs, &tool, None, &schema_map).unwrap();
+        let mut got_labels: Vec<String> = match got {
+            CompletionResponse::Array(arr) => arr.iter().map(|item| item.label.clone()).collect(),
+            CompletionResponse::List(_) => panic!("test failed"),
+        };
+        got_labels.sort();
+        assert_eq!(got_labels, vec!["Person", "name"]);
+    }
+
     #[test]
     #[bench_test]
     fn attr_value_completion() {
@@ -1560,6 +1688,31 @@ mod tests {
         assert_eq!(got_insert_test, expected_insert_test);
     }
 
+    #[test]
+    #[bench_test]
+    fn attr_check_value_completion() {
+        let (file, program, _, gs, schema_map) =
+            compile_test_file("src/test_data/completion_test/assign_check/completion.k");
+        let tool = toolchain::default();
+        let labels = |line, column| {
+            let pos = KCLPos {
+                filename: file.to_owned(),
+                line,
+                column: Some(column),
+            };
+            match completion(Some(':'), &program, &pos, &gs, &tool, None, &schema_map).unwrap() {
+                CompletionResponse::Array(arr) => arr
+                    .iter()
+                    .map(|item| item.label.clone())
+                    .collect::<Vec<String>>(),
+                CompletionResponse::List(_) => panic!("test failed"),
+            }
+        };
+        assert_eq!(labels(10, 8), vec![" \"\"", " \"dev\"", " \"prod\""]);
+        // The membership check joined with another check by `and`.
+        assert_eq!(labels(12, 13), vec![" 1", " 3"]);
+    }
+
     #[test]
     #[bench_test]
     fn schema_sig_completion() {
@@ -1863,6 +2016,49 @@ mod tests {
         }
     }
 
+    #[test]
+    #[bench_test]
+    fn comprehension_scope_completion() {
+        let (file, program, _, gs, schema_map) =
+            compile_test_file("src/test_data/completion_test/comprehension/comprehension.k");
+        let tool = toolchain::default();
+        let labels_at = |line: u64, column: u64| -> Vec<String> {
+            let pos = KCLPos {
+                filename: file.to_owned(),
+                line,
+                column: Some(column),
+            };
+            match completion(None, &program, &pos, &gs, &tool, None, &schema_map).unwrap() {
+                CompletionResponse::Array(arr) => {
+                    arr.iter().map(|item| item.label.clone()).collect()
+                }
+                CompletionResponse::List(_) => panic!("test failed"),
+            }
+        };
+        let len_label = func_ty_complete_label(
+            &"len".to_string(),
+            &BUILTIN_FUNCTIONS.get("len").unwrap().into_func_type(),
+        );
+
+        // The loop variable, the outer bindings and the builtin functions in `x * x`.
+        let labels = labels_at(2, 12);
+        assert!(labels.contains(&"x".to_string()));
+        assert!(labels.contains(&"items".to_string()));
+        assert!(labels.contains(&len_label));
+        assert!(!labels.contains(&"k".to_string()));
+
+        // The loop variable of the dict comprehension in `k * 2`.
+        let labels = labels_at(3, 20);
+        assert!(labels.contains(&"k".to_string()));
+        assert!(!labels.contains(&"x".to_string()));
+
+        // The loop variables are not offered outside the comprehensions.
+        let labels = labels_at(4, 8);
+        assert!(labels.contains(&"squares".to_string()));
+        assert!(!labels.contains(&"x".to_string()));
+        assert!(!labels.contains(&"k".to_string()));
+    }
+
     #[test]
     #[bench_test]
     fn join_str_inner_completion() {
@@ -0,0 +1,142 @@
+use kclvm_config::vfs::RelativeImportBase;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::magic_numbers::MagicNumberContext;
+use crate::quick_fix::FixSafety;
+use crate::todo_comments::TodoSeverity;
+
+/// User configurations of the language server, which are sent by the client
+/// in the `initializationOptions` of the `initialize` request.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct Config {
+    /// The highest safety level of the fixes applied by the `source.fixAll` code action.
+    pub fix_all_safety: FixSafety,
+    /// Whether to add the content-based anchors to the data of the published diagnostics.
+    pub diagnostic_anchors: bool,
+    /// Whether to report the warnings as errors, which is useful for the projects failing on warnings in CI.
+    pub warnings_as_errors: bool,
+    /// The locale of the diagnostic and the hover messages, e.g. `zh`, the messages are in English
+    /// by default.
+    pub locale: Option<String>,
+    /// Whether to warn the top level schemas which are never referenced in the workspace.
+    pub unused_schemas: bool,
+    /// The entry files exempt from the unused schema warnings, matched by the end of the file
+    /// paths, e.g. `main.k`. The main package files are exempt if not set.
+    pub unused_schema_entry_files: Option<Vec<String>>,
+    /// Whether to warn the private symbols, e.g. `_helper`, which are never used in the files
+    /// declaring them.
+    pub unused_private_symbols: bool,
+    /// Whether to warn the files with the CRLF, CR or mixed line endings.
+    pub line_endings: bool,
+    /// Whether to warn the files indented by different widths or by both tabs and spaces.
+    pub inconsistent_indentation: bool,
+    /// The expected indentation width in spaces, the most used width of a file if not set.
+    pub indent_width: Option<usize>,
+    /// Whether to remap the diagnostics of the generated files to their original sources with
+    /// the source maps next to them, e.g. `main.k.map`.
+    pub source_maps: bool,
+    /// The maximum nesting depth of the configs and the lists, the deeper expressions are reported
+    /// instead of analyzed.
+    pub max_schema_depth: Option<usize>,
+    /// Whether to suppress the errors caused by the reported ones, e.g. the uses of a failed
+    /// import, so only the root causes are published.
+    pub minimal_diagnostics: bool,
+    /// Whether to warn the schema and rule checks without the failure messages.
+    pub missing_check_messages: bool,
+    /// Whether to warn the variables and the schema attributes shadowing the builtin functions,
+    /// e.g. `len`.
+    pub builtin_shadowing: bool,
+    /// Whether to report the number literals which are not named by the constants.
+    pub magic_numbers: bool,
+    /// The numbers which are not reported as the magic numbers, `0` and `1` if not set.
+    pub magic_number_allowlist: Option<Vec<f64>>,
+    /// The contexts whose numbers are not reported as the magic numbers, e.g. `checks`.
+    pub magic_number_ignored_contexts: Vec<MagicNumberContext>,
+    /// Whether to report the comments containing the task markers, e.g. `TODO`.
+    pub todo_comments: bool,
+    /// The task markers of the comments, `TODO`, `FIXME` and `XXX` if not set.
+    pub todo_keywords: Option<Vec<String>>,
+    /// The severity of the task marker diagnostics, `hint` by default.
+    pub todo_severity: TodoSeverity,
+    /// The number of the problems of a file from which a summary diagnostic counting them is
+    /// published at the top of the file, no summary is published if not set.
+    pub diagnostic_summary_threshold: Option<usize>,
+    /// The base which the relative imports are resolved against, e.g. `pkgRoot`, the directory
+    /// of the importing file by default.
+    pub relative_import_base: RelativeImportBase,
+    /// Whether to warn the implicit coercions, e.g. `"1" == 1`, which can be fixed by the quick
+    /// fixes converting the values.
+    pub implicit_coercions: bool,
+    /// Whether to warn the top level variables and the schema attributes without the type
+    /// annotations, e.g. `port = 80`, which can be fixed by the quick fixes adding the types.
+    pub explicit_types: bool,
+    /// Whether to report the references to the names both imported and declared in the package,
+    /// e.g. `_m` of `import math as _m` and `_m = 1`.
+    pub ambiguous_references: bool,
+}
+
+impl Config {
+    /// Parses the config from the initialization options with the messages of the invalid options.
+    /// The options are validated one by one, so an invalid option falls back to its default and
+    /// the other options are kept.
+    pub(crate) fn from_initialization_options(options: Option<&Value>) -> (Self, Vec<String>) {
+        let options = match options {
+            Some(Value::Object(options)) => options,
+            None | Some(Value::Null) => return (Self::default(), vec![]),
+            Some(options) => {
+                return (
+                    Self::default(),
+                    vec![format!(
+                        "invalid initialization options {}, expected an object",
+                        options
+                    )],
+                )
+            }
+        };
+        let mut valid_options = Map::new();
+        let mut errors = vec![];
+        for (key, value) in options {
+            let option = Map::from_iter([(key.clone(), value.clone())]);
+            match serde_json::from_value::<Config>(Value::Object(option)) {
+                Ok(_) => {
+                    valid_options.insert(key.clone(), value.clone());
+                }
+                Err(err) => {
+                    errors.push(format!("invalid initialization option '{}': {}", key, err))
+                }
+            }
+        }
+        let config = serde_json::from_value(Value::Object(valid_options)).unwrap_or_default();
+        (config, errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::Config;
+
+    #[test]
+    fn from_initialization_options_test() {
+        let options = json!({
+            "explicitTypes": true,
+            "maxSchemaDepth": "deep",
+            "locale": "zh",
+        });
+        let (config, errors) = Config::from_initialization_options(Some(&options));
+        // The invalid option does not discard the others.
+        assert!(config.explicit_types);
+        assert_eq!(config.locale.as_deref(), Some("zh"));
+        assert_eq!(config.max_schema_depth, None);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("invalid initialization option 'maxSchemaDepth'"));
+
+        let (config, errors) = Config::from_initialization_options(Some(&json!(1)));
+        assert!(!config.explicit_types);
+        assert_eq!(errors.len(), 1);
+        assert!(Config::from_initialization_options(None).1.is_empty());
+    }
+}
@@ -0,0 +1,195 @@
+//! The schemas which a dict literal structurally conforms to, e.g.
+//!
+//! ```kcl
+//! schema Server:
+//!     name: str
+//!     port?: int
+//!
+//! config = {name = "nginx"}
+//! ```
+//!
+//! The literal `config` conforms to `Server`, because it sets all the required attributes of
+//! `Server` and every key is an attribute of `Server`. It is an exact match if the literal sets
+//! all the required and optional attributes.
+
+use std::collections::HashSet;
+
+use indexmap::{IndexMap, IndexSet};
+use kclvm_ast::ast::{self, Program};
+use kclvm_ast::pos::ContainsPos;
+use kclvm_ast::walker::MutSelfWalker;
+use kclvm_error::Position as KCLPos;
+use kclvm_sema::ty::SchemaType;
+
+use crate::lsp_ext::ConformingSchema;
+use crate::schema_coverage::config_key;
+
+/// Returns the schemas which the innermost dict literal at the position conforms to, the exact
+/// matches first and then the others by the ratio of the attributes set, descending. Config
+/// literals of schema instances, e.g. `Server {}`, are not candidates.
+pub(crate) fn conforming_schemas(
+    program: &Program,
+    schema_map: &IndexMap<String, Vec<SchemaType>>,
+    kcl_pos: &KCLPos,
+) -> Vec<ConformingSchema> {
+    let module = match program.get_module(&kcl_pos.filename) {
+        Ok(Some(module)) => module,
+        _ => return vec![],
+    };
+    let mut finder = ConfigFinder {
+        pos: kcl_pos,
+        keys: None,
+    };
+    for stmt in &module.body {
+        finder.walk_stmt(&stmt.node);
+    }
+    let keys = match finder.keys {
+        Some(keys) => keys,
+        None => return vec![],
+    };
+    let mut seen = HashSet::new();
+    let mut candidates: Vec<(ConformingSchema, f64)> = vec![];
+    for schema in schema_map.values().flatten() {
+        if schema.is_instance || schema.is_mixin || schema.is_protocol || schema.is_rule {
+            continue;
+        }
+        let name = schema.full_ty_str();
+        if !seen.insert(name.clone()) {
+            continue;
+        }
+        let mut attrs = IndexMap::new();
+        collect_attrs(schema, &mut attrs);
+        let conforms = keys.iter().all(|key| attrs.contains_key(key))
+            && attrs
+                .iter()
+                .all(|(attr, is_optional)| *is_optional || keys.contains(attr));
+        if conforms {
+            let ratio = keys.len() as f64 / attrs.len().max(1) as f64;
+            candidates.push((
+                ConformingSchema {
+                    name,
+                    exact: keys.len() == attrs.len(),
+                },
+                ratio,
+            ));
+        }
+    }
+    candidates.sort_by(|(a, a_ratio), (b, b_ratio)| {
+        b.exact
+            .cmp(&a.exact)
+            .then_with(|| b_ratio.total_cmp(a_ratio))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    candidates
+        .into_iter()
+        .map(|(candidate, _)| candidate)
+        .collect()
+}
+
+/// Collects the attributes of the schema, its base schemas and its mixins, mapped to whether the
+/// attribute is optional. The internal attributes, e.g. `__settings__`, are skipped.
+fn collect_attrs(schema: &SchemaType, attrs: &mut IndexMap<String, bool>) {
+    if let Some(base) = &schema.base {
+        collect_attrs(base, attrs);
+    }
+    for mixin in &schema.mixins {
+        collect_attrs(mixin, attrs);
+    }
+    for (name, attr) in &schema.attrs {
+        if !name.starts_with("__") {
+            attrs.insert(name.clone(), attr.is_optional);
+        }
+    }
+}
+
+/// Finds the keys of the innermost dict literal containing the position. The outer literals are
+/// visited before the inner ones, so the last match is the innermost one.
+struct ConfigFinder<'a> {
+    pos: &'a KCLPos,
+    keys: Option<IndexSet<String>>,
+}
+
+impl<'a> ConfigFinder<'a> {
+    fn visit(&mut self, expr: &ast::NodeRef<ast::Expr>) {
+        if let ast::Expr::Config(config_expr) = &expr.node {
+            if expr.contains_pos(self.pos) {
+                self.keys = Some(
+                    config_expr
+                        .items
+                        .iter()
+                        .filter_map(|entry| entry.node.key.as_ref().and_then(config_key))
+                        .collect(),
+                );
+            }
+        }
+    }
+}
+
+impl<'a> MutSelfWalker for ConfigFinder<'a> {
+    fn walk_assign_stmt(&mut self, assign_stmt: &ast::AssignStmt) {
+        self.visit(&assign_stmt.value);
+        self.walk_expr(&assign_stmt.value.node);
+    }
+
+    fn walk_list_expr(&mut self, list_expr: &ast::ListExpr) {
+        for elt in &list_expr.elts {
+            self.visit(elt);
+            self.walk_expr(&elt.node);
+        }
+    }
+
+    fn walk_config_expr(&mut self, config_expr: &ast::ConfigExpr) {
+        for entry in &config_expr.items {
+            if let Some(key) = &entry.node.key {
+                self.walk_expr(&key.node);
+            }
+            self.visit(&entry.node.value);
+            self.walk_expr(&entry.node.value.node);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kclvm_error::Position as KCLPos;
+    use proc_macro_crate::bench_test;
+
+    use super::conforming_schemas;
+    use crate::lsp_ext::ConformingSchema;
+    use crate::tests::compile_test_file;
+
+    #[test]
+    #[bench_test]
+    fn conforming_schemas_test() {
+        let (file, program, _, _, schema_map) =
+            compile_test_file("src/test_data/conforming_schemas/main.k");
+
+        // name = "nginx" in `config` matches `Server` exactly and `Service` without `replicas`.
+        let pos = KCLPos {
+            filename: file.clone(),
+            line: 15,
+            column: Some(4),
+        };
+        assert_eq!(
+            conforming_schemas(&program, &schema_map, &pos),
+            vec![
+                ConformingSchema {
+                    name: "Server".to_string(),
+                    exact: true,
+                },
+                ConformingSchema {
+                    name: "Service".to_string(),
+                    exact: false,
+                },
+            ]
+        );
+
+        // `other` has no schema with the attribute `image`.
+        let pos = KCLPos {
+            filename: file,
+            line: 19,
+            column: Some(10),
+        };
+        assert!(conforming_schemas(&program, &schema_map, &pos).is_empty());
+    }
+}
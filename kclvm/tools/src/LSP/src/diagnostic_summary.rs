@@ -0,0 +1,92 @@
+//! The summary diagnostic of a file with many problems, which is an information diagnostic at the
+//! top of the file counting the problems by the severities, e.g. `12 problems: 3 errors, 9
+//! warnings`, so the status of the file is visible in the gutter of the first line.
+
+use lsp_types::{Diagnostic, DiagnosticSeverity, Range};
+
+/// Prepends the summary diagnostic to the diagnostics of a file if there are at least `threshold`
+/// of them. The summary spans the empty range at the start of the file and the other diagnostics
+/// are not changed.
+pub(crate) fn prepend_summary(diagnostics: &mut Vec<Diagnostic>, threshold: usize) {
+    if diagnostics.is_empty() || diagnostics.len() < threshold {
+        return;
+    }
+    let count = |severity: DiagnosticSeverity| {
+        diagnostics
+            .iter()
+            .filter(|diag| diag.severity == Some(severity))
+            .count()
+    };
+    let counts = [
+        (count(DiagnosticSeverity::ERROR), "error", "errors"),
+        (count(DiagnosticSeverity::WARNING), "warning", "warnings"),
+        (count(DiagnosticSeverity::INFORMATION), "info", "infos"),
+        (count(DiagnosticSeverity::HINT), "hint", "hints"),
+    ];
+    let details: Vec<String> = counts
+        .iter()
+        .filter(|(count, _, _)| *count > 0)
+        .map(|(count, singular, plural)| plural_count(*count, singular, plural))
+        .collect();
+    let mut message = plural_count(diagnostics.len(), "problem", "problems");
+    if !details.is_empty() {
+        message = format!("{}: {}", message, details.join(", "));
+    }
+    diagnostics.insert(
+        0,
+        Diagnostic {
+            range: Range::default(),
+            severity: Some(DiagnosticSeverity::INFORMATION),
+            message,
+            ..Default::default()
+        },
+    );
+}
+
+fn plural_count(count: usize, singular: &str, plural: &str) -> String {
+    format!("{} {}", count, if count == 1 { singular } else { plural })
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{Diagnostic, DiagnosticSeverity, Position, Range};
+    use proc_macro_crate::bench_test;
+
+    use super::prepend_summary;
+
+    fn diag(line: u32, severity: DiagnosticSeverity) -> Diagnostic {
+        Diagnostic {
+            range: Range::new(Position::new(line, 0), Position::new(line, 4)),
+            severity: Some(severity),
+            message: format!("problem at line {}", line),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    #[bench_test]
+    fn prepend_summary_test() {
+        let diags = vec![
+            diag(2, DiagnosticSeverity::WARNING),
+            diag(4, DiagnosticSeverity::ERROR),
+            diag(5, DiagnosticSeverity::WARNING),
+            diag(7, DiagnosticSeverity::WARNING),
+        ];
+        let mut summarized = diags.clone();
+        prepend_summary(&mut summarized, 3);
+        assert_eq!(summarized.len(), 5);
+        assert_eq!(summarized[0].message, "4 problems: 1 error, 3 warnings");
+        assert_eq!(
+            summarized[0].severity,
+            Some(DiagnosticSeverity::INFORMATION)
+        );
+        assert_eq!(summarized[0].range, Range::default());
+        // The real diagnostics follow the summary unchanged.
+        assert_eq!(summarized[1..], diags[..]);
+
+        // The files with fewer problems than the threshold are not summarized.
+        let mut unchanged = diags.clone();
+        prepend_summary(&mut unchanged, 5);
+        assert_eq!(unchanged, diags);
+    }
+}
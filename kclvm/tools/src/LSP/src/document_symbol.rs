@@ -6,82 +6,119 @@ use kclvm_sema::core::symbol::SymbolKind as KCLSymbolKind;
 use lsp_types::Range;
 use lsp_types::{DocumentSymbol, DocumentSymbolResponse, SymbolKind};
 
+use crate::lsp_ext::OutlineNode;
 use crate::to_lsp::lsp_pos;
 
 pub fn document_symbol(file: &str, gs: &GlobalState) -> Option<lsp_types::DocumentSymbolResponse> {
     let mut document_symbols: Vec<DocumentSymbol> = vec![];
+    for (symbol, attrs) in file_symbols(file, gs) {
+        if let Some(mut document_symbol) = symbol_to_document_symbol(symbol) {
+            if let Some(attrs) = attrs {
+                document_symbol.children = Some(
+                    attrs
+                        .into_iter()
+                        .filter_map(symbol_to_document_symbol)
+                        .collect(),
+                );
+                document_symbol.name = format!("schema {}", document_symbol.name);
+            }
+            document_symbols.push(document_symbol);
+        }
+    }
+    Some(DocumentSymbolResponse::Nested(document_symbols))
+}
 
+/// Returns the outline of the file with the resolved type and the doc summary of each symbol, the
+/// attributes of a schema are its children.
+pub(crate) fn outline_with_types(file: &str, gs: &GlobalState) -> Vec<OutlineNode> {
+    file_symbols(file, gs)
+        .into_iter()
+        .filter_map(|(symbol, attrs)| {
+            let mut node = symbol_to_outline_node(symbol)?;
+            if let Some(attrs) = attrs {
+                node.children = attrs
+                    .into_iter()
+                    .filter_map(symbol_to_outline_node)
+                    .collect();
+            }
+            Some(node)
+        })
+        .collect()
+}
+
+/// Returns the top level symbols defined in the file, the attributes of a schema are returned
+/// along with it.
+fn file_symbols<'a>(
+    file: &str,
+    gs: &'a GlobalState,
+) -> Vec<(&'a KCLSymbol, Option<Vec<&'a KCLSymbol>>)> {
+    let mut symbols = vec![];
     let dummy_pos = Position {
         filename: file.to_string(),
         line: 1,
         column: Some(0),
     };
-    if let Some(scope) = gs.get_scopes().get_root_scope(MAIN_PKG.to_owned()) {
-        if let Some(defs) = gs.get_all_defs_in_scope(scope, &dummy_pos) {
-            for symbol_ref in defs {
-                match gs.get_symbols().get_symbol(symbol_ref) {
-                    Some(symbol) => {
-                        let def = symbol.get_definition();
-                        match def {
-                            Some(def) => {
-                                let symbol_range = symbol.get_range();
-                                // filter current file symbols
-                                if symbol_range.0.filename == file {
-                                    match def.get_kind() {
-                                        KCLSymbolKind::Schema => {
-                                            match &mut symbol_to_document_symbol(symbol) {
-                                                Some(schema_symbol) => {
-                                                    let module_info = gs
-                                                        .get_packages()
-                                                        .get_module_info(&dummy_pos.filename);
-                                                    let attrs = symbol.get_all_attributes(
-                                                        gs.get_symbols(),
-                                                        module_info,
-                                                    );
-                                                    let mut children = vec![];
-
-                                                    for attr in attrs {
-                                                        match gs.get_symbols().get_symbol(attr) {
-                                                            Some(attr_symbol) => {
-                                                                match symbol_to_document_symbol(
-                                                                    attr_symbol,
-                                                                ) {
-                                                                    Some(symbol) => {
-                                                                        children.push(symbol)
-                                                                    }
-                                                                    None => {}
-                                                                }
-                                                            }
-                                                            None => {}
-                                                        }
-                                                    }
-
-                                                    schema_symbol.children = Some(children);
-                                                    schema_symbol.name =
-                                                        format!("schema {}", schema_symbol.name);
-                                                    document_symbols.push(schema_symbol.clone());
-                                                }
-                                                None => {}
-                                            }
-                                        }
-                                        _ => {
-                                            if let Some(symbol) = symbol_to_document_symbol(symbol)
-                                            {
-                                                document_symbols.push(symbol)
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                            None => {}
-                        }
-                    }
-                    None => {}
-                }
+    let scope = match gs.get_scopes().get_root_scope(MAIN_PKG.to_owned()) {
+        Some(scope) => scope,
+        None => return symbols,
+    };
+    let defs = gs
+        .get_all_defs_in_scope(scope, &dummy_pos)
+        .unwrap_or_default();
+    for symbol_ref in defs {
+        let symbol = match gs.get_symbols().get_symbol(symbol_ref) {
+            Some(symbol) => symbol,
+            None => continue,
+        };
+        let def = match symbol.get_definition() {
+            Some(def) => def,
+            None => continue,
+        };
+        // filter current file symbols
+        if symbol.get_range().0.filename != file {
+            continue;
+        }
+        match def.get_kind() {
+            KCLSymbolKind::Schema => {
+                let module_info = gs.get_packages().get_module_info(&dummy_pos.filename);
+                let attrs = symbol
+                    .get_all_attributes(gs.get_symbols(), module_info)
+                    .into_iter()
+                    .filter_map(|attr| gs.get_symbols().get_symbol(attr))
+                    .collect();
+                symbols.push((symbol, Some(attrs)));
             }
+            _ => symbols.push((symbol, None)),
         }
     }
-    Some(DocumentSymbolResponse::Nested(document_symbols))
+    symbols
+}
+
+fn symbol_to_outline_node(symbol: &KCLSymbol) -> Option<OutlineNode> {
+    let def = symbol.get_definition()?;
+    let sema_info = symbol.get_sema_info();
+    let symbol_range = symbol.get_range();
+    Some(OutlineNode {
+        name: symbol.get_name(),
+        kind: symbol_kind_to_document_symbol_kind(def.get_kind())?,
+        range: Range {
+            start: lsp_pos(&symbol_range.0),
+            end: lsp_pos(&symbol_range.1),
+        },
+        ty: sema_info.ty.as_ref().map(|ty| ty.ty_str()),
+        doc: sema_info.doc.as_deref().and_then(doc_summary),
+        children: vec![],
+    })
+}
+
+/// Returns the first line of the doc string without the quotes.
+fn doc_summary(doc: &str) -> Option<String> {
+    doc.trim()
+        .trim_matches(|c| c == '"' || c == '\'')
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
 }
 
 fn symbol_to_document_symbol(symbol: &KCLSymbol) -> Option<DocumentSymbol> {
@@ -136,7 +173,10 @@ mod tests {
     use lsp_types::{DocumentSymbol, DocumentSymbolResponse, Position, Range, SymbolKind};
     use proc_macro_crate::bench_test;
 
-    use crate::{document_symbol::document_symbol, tests::compile_test_file};
+    use crate::{
+        document_symbol::{document_symbol, outline_with_types},
+        tests::compile_test_file,
+    };
 
     #[allow(deprecated)]
     fn build_document_symbol(
@@ -207,4 +247,44 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    #[bench_test]
+    fn outline_with_types_test() {
+        let (file, _, _, gs, _) = compile_test_file("src/test_data/outline_with_types/main.k");
+
+        let mut outline = outline_with_types(file.as_str(), &gs);
+        outline.sort_by(|a, b| a.name.cmp(&b.name));
+        let got: Vec<(String, Option<String>, Option<String>)> = outline
+            .iter()
+            .map(|node| (node.name.clone(), node.ty.clone(), node.doc.clone()))
+            .collect();
+        assert_eq!(
+            got,
+            vec![
+                (
+                    "Server".to_string(),
+                    Some("Server".to_string()),
+                    Some("A server of the app.".to_string())
+                ),
+                ("server".to_string(), Some("Server".to_string()), None),
+            ]
+        );
+
+        let mut attrs: Vec<(String, Option<String>)> = outline[0]
+            .children
+            .iter()
+            .map(|attr| (attr.name.clone(), attr.ty.clone()))
+            .collect();
+        attrs.sort();
+        assert_eq!(
+            attrs,
+            vec![
+                ("labels".to_string(), Some("{str:str}".to_string())),
+                ("name".to_string(), Some("str".to_string())),
+                ("port".to_string(), Some("int".to_string())),
+            ]
+        );
+        assert!(outline[1].children.is_empty());
+    }
 }
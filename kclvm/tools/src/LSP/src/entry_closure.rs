@@ -0,0 +1,130 @@
+//! The files needed to evaluate an entry file, which are the entry file and the files defining
+//! the symbols it refers to, transitively, e.g. for the entry file
+//!
+//! ```kcl
+//! import app
+//! import db
+//!
+//! server = app.Server {}
+//! ```
+//!
+//! the file of `app` defining `Server` is needed together with the files its schema refers to,
+//! the other files of `app` and the files of the unused import `db` are not needed. The system
+//! modules and the plugins are not files of the program.
+
+use std::collections::VecDeque;
+
+use indexmap::IndexSet;
+use kclvm_ast::ast::{self, Program};
+use kclvm_sema::core::global_state::GlobalState;
+use kclvm_sema::core::symbol::SymbolKind;
+use lsp_types::Url;
+
+use crate::lsp_ext::EntryClosureResult;
+
+/// Returns the closure of the entry file in the program.
+pub(crate) fn entry_closure(
+    entry: &str,
+    program: &Program,
+    gs: &GlobalState,
+) -> EntryClosureResult {
+    let mut needed: IndexSet<String> = IndexSet::new();
+    let mut imported: IndexSet<String> = IndexSet::new();
+    let mut files = VecDeque::new();
+    if program.get_module_ref(entry).is_some() {
+        needed.insert(entry.to_string());
+        files.push_back(entry.to_string());
+    }
+    while let Some(file) = files.pop_front() {
+        for def_file in referred_files(&file, program, gs) {
+            if needed.insert(def_file.clone()) {
+                files.push_back(def_file);
+            }
+        }
+        if let Some(module) = program.get_module_ref(&file) {
+            if let Ok(module) = module.read() {
+                for stmt in &module.body {
+                    if let ast::Stmt::Import(import_stmt) = &stmt.node {
+                        for module in program.get_modules_for_pkg(&import_stmt.path.node) {
+                            if let Ok(module) = module.read() {
+                                imported.insert(module.filename.clone());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    let mut unused_files: Vec<&String> = imported
+        .iter()
+        .filter(|file| !needed.contains(*file))
+        .collect();
+    unused_files.sort();
+    EntryClosureResult {
+        files: needed
+            .iter()
+            .filter_map(|file| Url::from_file_path(file).ok())
+            .collect(),
+        unused_files: unused_files
+            .into_iter()
+            .filter_map(|file| Url::from_file_path(file).ok())
+            .collect(),
+    }
+}
+
+/// Returns the other files of the program defining the symbols referred in the file. The
+/// packages are skipped, an import needs only the files of the members it refers to.
+fn referred_files(file: &str, program: &Program, gs: &GlobalState) -> IndexSet<String> {
+    let mut files = IndexSet::new();
+    let file_sema = match gs.get_sema_db().get_file_sema(file) {
+        Some(file_sema) => file_sema,
+        None => return files,
+    };
+    let symbols = gs.get_symbols();
+    for symbol_ref in file_sema.get_symbols() {
+        let def = match symbols
+            .get_symbol(*symbol_ref)
+            .and_then(|symbol| symbol.get_definition())
+        {
+            Some(def) if def.get_kind() != SymbolKind::Package => def,
+            _ => continue,
+        };
+        if let Some(def_symbol) = symbols.get_symbol(def) {
+            let def_file = def_symbol.get_range().0.filename;
+            if def_file != file && program.get_module_ref(&def_file).is_some() {
+                files.insert(def_file);
+            }
+        }
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use lsp_types::Url;
+    use proc_macro_crate::bench_test;
+
+    use super::entry_closure;
+    use crate::tests::compile_test_file;
+
+    #[test]
+    #[bench_test]
+    fn entry_closure_test() {
+        let (file, program, _, gs, _) = compile_test_file("src/test_data/entry_closure/main.k");
+        let dir = Path::new(&file).parent().unwrap();
+        let uri = |path: &str| Url::from_file_path(dir.join(path)).unwrap();
+        let result = entry_closure(&file, &program, &gs);
+        // `Server` refers to `DEFAULT_PORT` of the other file of `app`.
+        assert_eq!(
+            result.files,
+            vec![uri("main.k"), uri("app/server.k"), uri("app/defaults.k")]
+        );
+        // The member of `app` not referred and the unused import `db`.
+        assert_eq!(
+            result.unused_files,
+            vec![uri("app/unused.k"), uri("db/db.k")]
+        );
+    }
+}
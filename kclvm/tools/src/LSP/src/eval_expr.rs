@@ -0,0 +1,188 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use kclvm_ast::ast;
+use kclvm_ast::walker::MutSelfWalker;
+use kclvm_error::Level;
+use kclvm_evaluator::Evaluator;
+use kclvm_parser::{load_program, parse_expr, parse_single_file, LoadProgramOptions, ParseSession};
+use kclvm_sema::resolver::resolve_program;
+use lsp_types::Range;
+
+use crate::from_lsp::text_range;
+
+/// Builtin functions with side effects which can not be evaluated in the selected expression.
+const SIDE_EFFECT_FUNCTIONS: &[&str] = &["print"];
+/// System modules whose functions access the file system or the runtime environment.
+const SIDE_EFFECT_MODULES: &[&str] = &["file", "runtime"];
+
+/// Evaluates the expression in the `range` of the file and returns the value in the JSON format.
+pub(crate) fn eval_expression(file: &str, src: &str, range: Range) -> anyhow::Result<String> {
+    match src.get(text_range(src, range)) {
+        Some(expr) => eval_expr(file, src, expr),
+        None => Err(anyhow!("Invalid expression range: {:?}", range)),
+    }
+}
+
+/// Evaluates `expr` with all the top level bindings of the file `src` in scope.
+pub(crate) fn eval_expr(file: &str, src: &str, expr: &str) -> anyhow::Result<String> {
+    let expr = expr.trim();
+    let expr_node = parse_expr(expr).ok_or(anyhow!("No expression is selected"))?;
+    let mut checker = SideEffectChecker::default();
+    checker.walk_expr(&expr_node.node);
+    if let Some(name) = checker.call {
+        return Err(anyhow!(
+            "Can not evaluate the expression with side effects: '{}'",
+            name
+        ));
+    }
+    let code = format!("{}\n({})\n", top_level_bindings(file, src)?, expr);
+    let sess = Arc::new(ParseSession::default());
+    let mut program = load_program(
+        sess,
+        &[file],
+        Some(LoadProgramOptions {
+            k_code_list: vec![code],
+            ..Default::default()
+        }),
+        None,
+    )?
+    .program;
+    let scope = resolve_program(&mut program);
+    if let Some(diag) = scope
+        .handler
+        .diagnostics
+        .iter()
+        .find(|diag| diag.level == Level::Error)
+    {
+        let msg = diag
+            .messages
+            .iter()
+            .map(|msg| msg.message.clone())
+            .collect::<Vec<String>>()
+            .join("\n");
+        return Err(anyhow!("Failed to evaluate the expression: {}", msg));
+    }
+    let evaluator = Evaluator::new(&program);
+    match catch_unwind(AssertUnwindSafe(|| evaluator.run_as_function())) {
+        Ok(value) => Ok(value.to_json_string()),
+        Err(err) => Err(anyhow!(
+            "Failed to evaluate the expression: {}",
+            kclvm_error::err_to_str(err)
+        )),
+    }
+}
+
+/// Returns the source code of the import, type, schema, rule and variable
+/// definitions at the top level of the file. The file must parse, and the
+/// bindings are checked for the side effects like the expression since any of
+/// them is evaluated with it.
+fn top_level_bindings(file: &str, src: &str) -> anyhow::Result<String> {
+    let result = parse_single_file(file, Some(src.to_string()))
+        .map_err(|err| anyhow!("Failed to parse the file: {}", err))?;
+    if let Some(diag) = result.errors.iter().find(|diag| diag.level == Level::Error) {
+        let msg = diag
+            .messages
+            .iter()
+            .map(|msg| format!("line {}: {}", msg.range.0.line, msg.message))
+            .collect::<Vec<String>>()
+            .join("\n");
+        return Err(anyhow!("Failed to parse the file: {}", msg));
+    }
+    let lines: Vec<&str> = src.lines().collect();
+    let mut bindings = vec![];
+    for stmt in &result.module.body {
+        if matches!(
+            stmt.node,
+            ast::Stmt::Import(_)
+                | ast::Stmt::TypeAlias(_)
+                | ast::Stmt::Schema(_)
+                | ast::Stmt::Rule(_)
+                | ast::Stmt::Assign(_)
+                | ast::Stmt::AugAssign(_)
+                | ast::Stmt::Unification(_)
+        ) {
+            let mut checker = SideEffectChecker::default();
+            checker.walk_stmt(&stmt.node);
+            if let Some(name) = checker.call {
+                return Err(anyhow!(
+                    "Can not evaluate the expression, the top level binding at line {} has side effects: '{}'",
+                    stmt.line,
+                    name
+                ));
+            }
+            let start = (stmt.line as usize).saturating_sub(1);
+            let end = (stmt.end_line as usize).min(lines.len());
+            if start < end {
+                bindings.extend_from_slice(&lines[start..end]);
+            }
+        }
+    }
+    Ok(bindings.join("\n"))
+}
+
+/// Finds the first call of the functions with side effects in the expression.
+#[derive(Default)]
+struct SideEffectChecker {
+    call: Option<String>,
+}
+
+impl MutSelfWalker for SideEffectChecker {
+    fn walk_call_expr(&mut self, call_expr: &ast::CallExpr) {
+        if let ast::Expr::Identifier(identifier) = &call_expr.func.node {
+            let names = identifier.get_names();
+            let is_side_effect = match names.as_slice() {
+                [name] => SIDE_EFFECT_FUNCTIONS.contains(&name.as_str()),
+                [module, ..] => SIDE_EFFECT_MODULES.contains(&module.as_str()),
+                _ => false,
+            };
+            if is_side_effect && self.call.is_none() {
+                self.call = Some(identifier.get_name());
+            }
+        }
+        self.walk_expr(&call_expr.func.node);
+        for arg in &call_expr.args {
+            self.walk_expr(&arg.node);
+        }
+        for keyword in &call_expr.keywords {
+            self.walk_keyword(&keyword.node);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::eval_expr;
+
+    #[test]
+    fn eval_expr_in_empty_scope_test() {
+        let value = eval_expr("test.k", "", "1 + len([1,2,3])").unwrap();
+        assert_eq!(value, "4");
+    }
+
+    #[test]
+    fn eval_expr_with_top_level_bindings_test() {
+        let src = "a = 1\nb = a + 1\nb\n";
+        let value = eval_expr("test.k", src, "a + b").unwrap();
+        assert_eq!(value, "3");
+    }
+
+    #[test]
+    fn eval_expr_error_test() {
+        let err = eval_expr("test.k", "", "c + 1").unwrap_err();
+        assert!(err.to_string().contains("name 'c' is not defined"));
+        let err = eval_expr("test.k", "", "print(1)").unwrap_err();
+        assert!(err.to_string().contains("side effects"));
+        // The bindings evaluated with the expression are checked as well.
+        let src = "a = 1\nb = file.read(\"a.txt\")\n";
+        let err = eval_expr("test.k", src, "a + 1").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Can not evaluate the expression, the top level binding at line 2 has side effects: 'file.read'"
+        );
+        // The file which fails to parse is reported instead of evaluating without the bindings.
+        let err = eval_expr("test.k", "a = 1\nb = (\n", "a + 1").unwrap_err();
+        assert!(err.to_string().starts_with("Failed to parse the file"));
+    }
+}
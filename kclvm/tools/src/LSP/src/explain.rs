@@ -0,0 +1,285 @@
+//! The catalog of diagnostic code explanations used by the `kcl/explainCode` request.
+//! The codes are the names produced by `kcl_diag_id_to_lsp_diag_code`, e.g. `TypeError`.
+
+use crate::lsp_ext::ExplainCodeResult;
+
+struct Explanation {
+    code: &'static str,
+    title: &'static str,
+    description: &'static str,
+    failing_example: &'static str,
+    fix_example: &'static str,
+}
+
+const EXPLANATIONS: &[Explanation] = &[
+    Explanation {
+        code: "InvalidSyntax",
+        title: "Invalid syntax",
+        description: "The code does not conform to the KCL syntax and can not be parsed.",
+        failing_example: "a, b = 1, 2\n",
+        fix_example: "a = 1\nb = 2\n",
+    },
+    Explanation {
+        code: "TabError",
+        title: "Inconsistent use of tabs and spaces",
+        description: "The indentation mixes tabs and spaces, which makes the block structure ambiguous.",
+        failing_example: "schema Person:\n\tname: str\n    age: int\n",
+        fix_example: "schema Person:\n    name: str\n    age: int\n",
+    },
+    Explanation {
+        code: "IndentationError",
+        title: "Invalid indentation",
+        description: "The indentation of a statement does not match any outer indentation level.",
+        failing_example: "schema Person:\n    name: str\n  age: int\n",
+        fix_example: "schema Person:\n    name: str\n    age: int\n",
+    },
+    Explanation {
+        code: "CannotFindModule",
+        title: "Cannot find the module",
+        description: "The imported package can not be found in the current program, the vendor path or the dependencies declared in kcl.mod.",
+        failing_example: "import not_exist\n",
+        fix_example: "import math\n\na = math.log10(100)\n",
+    },
+    Explanation {
+        code: "TypeError",
+        title: "Type error",
+        description: "The type of a value does not match the type which is expected by the type annotation, the schema attribute or the operator.",
+        failing_example: "schema Person:\n    age: int\n\np = Person {\n    age = \"18\"\n}\n",
+        fix_example: "schema Person:\n    age: int\n\np = Person {\n    age = 18\n}\n",
+    },
+    Explanation {
+        code: "CompileError",
+        title: "Compile error",
+        description: "The program can not be compiled, e.g. a name is used but never defined.",
+        failing_example: "number = 1\nresult = nubmer + 1\n",
+        fix_example: "number = 1\nresult = number + 1\n",
+    },
+    Explanation {
+        code: "ImmutableError",
+        title: "Immutable variable changed",
+        description: "A variable declared without the `_` prefix at the top level is immutable and can not be assigned twice.",
+        failing_example: "a = 1\na = 2\n",
+        fix_example: "_a = 1\n_a = 2\na = _a\n",
+    },
+    Explanation {
+        code: "UniqueKeyError",
+        title: "Duplicate name",
+        description: "The same name is defined more than once, e.g. two schemas with the same name in a package.",
+        failing_example: "schema Person:\n    name: str\n\nschema Person:\n    age: int\n",
+        fix_example: "schema Person:\n    name: str\n\nschema Student:\n    age: int\n",
+    },
+    Explanation {
+        code: "AmbiguousReferenceError",
+        title: "Ambiguous reference",
+        description: "A name is both imported and defined in the package, so a reference to it may be the module or the definition.",
+        failing_example: "import math as _m\n\n_m = 1\nx = _m\n",
+        fix_example: "import math\n\n_m = 1\nx = _m\n",
+    },
+    Explanation {
+        code: "IllegalAttributeError",
+        title: "Illegal attribute",
+        description: "The key of a config attribute must be a string.",
+        failing_example: "x = {None: None}\n",
+        fix_example: "x = {\"None\": None}\n",
+    },
+    Explanation {
+        code: "IllegalInheritError",
+        title: "Illegal inheritance",
+        description: "A schema can only inherit from another schema, mixins must be declared with the `mixin` keyword.",
+        failing_example: "schema FullnameMixin:\n    fullName: str\n\nschema Scholar(FullnameMixin):\n    school: str\n",
+        fix_example: "mixin FullnameMixin:\n    fullName: str\n\nschema Scholar:\n    mixin [FullnameMixin]\n    school: str\n",
+    },
+    Explanation {
+        code: "EvaluationError",
+        title: "Evaluation error",
+        description: "An error occurred when the program is evaluated, e.g. a schema check fails or an assertion is false.",
+        failing_example: "schema Server:\n    port: int\n\n    check:\n        1 <= port <= 65535\n\ns = Server {port = 0}\n",
+        fix_example: "schema Server:\n    port: int\n\n    check:\n        1 <= port <= 65535\n\ns = Server {port = 8080}\n",
+    },
+    Explanation {
+        code: "UnusedImportWarning",
+        title: "Unused import",
+        description: "A module is imported but never used in the file, the import can be removed safely.",
+        failing_example: "import math\n\na = 1\n",
+        fix_example: "a = 1\n",
+    },
+    Explanation {
+        code: "ReimportWarning",
+        title: "Module reimported",
+        description: "The same module is imported more than once in the file.",
+        failing_example: "import math\nimport math\n\na = math.log10(100)\n",
+        fix_example: "import math\n\na = math.log10(100)\n",
+    },
+    Explanation {
+        code: "ImportPositionWarning",
+        title: "Import not at the top of the file",
+        description: "Import statements should be placed at the top of the file before other statements.",
+        failing_example: "a = 1\nimport math\n\nb = math.log10(100)\n",
+        fix_example: "import math\n\na = 1\nb = math.log10(100)\n",
+    },
+    Explanation {
+        code: "DuplicateAssignmentWarning",
+        title: "Duplicate attribute assignment",
+        description: "The same attribute is assigned more than once in one config, the later assignment overrides the earlier one.",
+        failing_example: "config = {\n    a = 1\n    a = 2\n}\n",
+        fix_example: "config = {\n    a = 2\n}\n",
+    },
+    Explanation {
+        code: "PrecisionLossWarning",
+        title: "Possible precision loss",
+        description: "An int is assigned to a float typed target but is too large to be represented exactly by a float. A float assigned to an int typed target, e.g. `x: int = 3.5` or `x: int = 3.0`, is not a precision loss warning but a `TypeError`, since KCL never truncates a float to an int implicitly.",
+        failing_example: "x: float = 9007199254740993\n",
+        fix_example: "x: int = 9007199254740993\n",
+    },
+    Explanation {
+        code: "UnusedSchemaWarning",
+        title: "Unused schema",
+        description: "A top level schema is never referenced in the workspace. The lint is opt-in with the `unusedSchemas` option, and the schemas in the entry files are exempt.",
+        failing_example: "# pkg/lib.k\nschema Server:\n    name: str\n\nschema Orphan:\n    name: str\n\n# main.k\nimport pkg.lib\n\nserver = lib.Server {name = \"nginx\"}\n",
+        fix_example: "# pkg/lib.k\nschema Server:\n    name: str\n\n# main.k\nimport pkg.lib\n\nserver = lib.Server {name = \"nginx\"}\n",
+    },
+    Explanation {
+        code: "ConstantAssertWarning",
+        title: "Constant assert condition",
+        description: "The condition of an assert statement is statically constant. An always true assertion is dead code and an always false one fails the evaluation unconditionally.",
+        failing_example: "replicas = 3\nassert True\nassert 1 > 2, \"never holds\"\n",
+        fix_example: "replicas = 3\nassert replicas > 0, \"replicas must be positive\"\n",
+    },
+    Explanation {
+        code: "ImplicitCoercionWarning",
+        title: "Implicit coercion",
+        description: "A value is compared with or assigned to a value of another type without an explicit conversion, e.g. a string compared with a number is never equal to it. The lint is opt-in.",
+        failing_example: "port = \"8080\"\nis_default = port == 8080\n",
+        fix_example: "port = \"8080\"\nis_default = int(port) == 8080\n",
+    },
+    Explanation {
+        code: "UnusedPrivateSymbolWarning",
+        title: "Unused private symbol",
+        description: "A private symbol, whose name starts with `_`, is declared at the top level of a file but never used in the file. The lint is opt-in with the `unusedPrivateSymbols` option.",
+        failing_example: "_double = lambda x { x * 2 }
+_unused = lambda x { x + 1 }
+
+replicas = _double(2)
+",
+        fix_example: "_double = lambda x { x * 2 }
+
+replicas = _double(2)
+",
+    },
+    Explanation {
+        code: "LineEndingWarning",
+        title: "Line endings not LF",
+        description: "The file contains CRLF or CR line endings, or mixes them with LF. A lone CR is not a line break of KCL, so the positions reported in such a file may not match the editor. The lint is opt-in with the `lineEndings` option.",
+        failing_example: "a = 1\r\nb = 2\r\n",
+        fix_example: "a = 1\nb = 2\n",
+    },
+    Explanation {
+        code: "DefaultViolatesCheckWarning",
+        title: "Default value violates a check",
+        description: "The default value of a schema attribute violates a check of the schema, so every instance keeping the default fails the evaluation. Only the checks which can be evaluated with the constant defaults are reported.",
+        failing_example: "schema Server:\n    port: int = 0\n\n    check:\n        1 <= port <= 65535\n",
+        fix_example: "schema Server:\n    port: int = 80\n\n    check:\n        1 <= port <= 65535\n",
+    },
+    Explanation {
+        code: "TodoCommentWarning",
+        title: "Task marker in a comment",
+        description: "A comment contains a task marker, e.g. `TODO` or `FIXME`, which is surfaced as a hint to track the unfinished work. The lint is opt-in with the `todoComments` option, and the markers and the severity are configured by `todoKeywords` and `todoSeverity`.",
+        failing_example: "# TODO: use the production port\nport = 8080\n",
+        fix_example: "port = 80\n",
+    },
+    Explanation {
+        code: "IncompatibleComparisonWarning",
+        title: "Equality of disjoint types",
+        description: "Two values whose types have no value in common are compared with `==` or `!=`, so the comparison is always False or always True. An ordered comparison, e.g. `<`, of such values is a type error instead.",
+        failing_example: "ports = [80, 443]\nhas_default = ports == 80\n",
+        fix_example: "ports = [80, 443]\nhas_default = 80 in ports\n",
+    },
+    Explanation {
+        code: "UnsatisfiableCheckWarning",
+        title: "Check never satisfied by the attribute type",
+        description: "A schema check compares an attribute with literals which are not values of the attribute type, e.g. an `int` attribute with a string, so every instance of the schema fails the check.",
+        failing_example: "schema Server:\n    port: int\n\n    check:\n        port == \"80\"\n",
+        fix_example: "schema Server:\n    port: int\n\n    check:\n        port == 80\n",
+    },
+    Explanation {
+        code: "MissingTypeAnnotationWarning",
+        title: "Declaration without a type annotation",
+        description: "A top level variable or a schema attribute is declared without a type annotation, which is reported only when the explicit types are required, e.g. by a style guide. The suggested fix annotates the inferred type.",
+        failing_example: "schema Server:\n    port = 80\n",
+        fix_example: "schema Server:\n    port: int = 80\n",
+    },
+    Explanation {
+        code: "MissingCheckMessageWarning",
+        title: "Check without a failure message",
+        description: "A schema or rule check has no failure message, which is reported only when the messages are required. A failed check without a message only shows the expression, the suggested fix appends a placeholder message to be reworded.",
+        failing_example: "schema Server:\n    port: int\n\n    check:\n        port > 0\n",
+        fix_example: "schema Server:\n    port: int\n\n    check:\n        port > 0, \"port must be positive\"\n",
+    },
+    Explanation {
+        code: "InconsistentIndentationWarning",
+        title: "Inconsistent indentation",
+        description: "The blocks of a file are indented by different widths, or by both tabs and spaces. The expected width is the `indentWidth` option, or the width of the most blocks of the file. The lint is opt-in with the `inconsistentIndentation` option.",
+        failing_example: "schema Person:\n    name: str\n\nperson = {\n  name = \"Alice\"\n}\n",
+        fix_example: "schema Person:\n    name: str\n\nperson = {\n    name = \"Alice\"\n}\n",
+    },
+    Explanation {
+        code: "BuiltinShadowingWarning",
+        title: "Builtin shadowing",
+        description: "A variable or a schema attribute is named the same as a builtin function, e.g. `len`, so the builtin can not be called in the module or the schema declaring it. The config keys and the nested targets, e.g. `app.len = 1`, do not shadow the builtins. The lint is opt-in with the `builtinShadowing` option.",
+        failing_example: "len = 3\nitems = [1, 2, 3]\ncount = len(items)\n",
+        fix_example: "length = 3\nitems = [1, 2, 3]\ncount = len(items)\n",
+    },
+    Explanation {
+        code: "DeprecatedBuiltinWarning",
+        title: "Deprecated builtin",
+        description: "A builtin function or a system module function marked as deprecated in the builtin catalog is used in the main package, which fails once it is removed. The warning tells the version deprecating it and how to replace it, and the quick fix switches to the replacement if it takes the same arguments. `datetime.date` is deprecated in favor of `datetime.now` with the same format.",
+        failing_example: "import datetime\n\ntoday = datetime.date()\n",
+        fix_example: "import datetime\n\ntoday = datetime.now(\"%Y-%m-%d %H:%M:%S\")\n",
+    },
+    Explanation {
+        code: "MagicNumberWarning",
+        title: "Magic number",
+        description: "A number literal is used without a name, which hides what the number means and lets its copies diverge. The code action extracts it into a private constant named after the key or the attribute it is assigned to. The lint is opt-in with the `magicNumbers` option; the numbers of `magicNumberAllowlist`, `0` and `1` by default, and the contexts of `magicNumberIgnoredContexts`, e.g. `checks`, are not reported.",
+        failing_example: "session = {\n    timeout = 3600\n}\n",
+        fix_example: "_TIMEOUT = 3600\nsession = {\n    timeout = _TIMEOUT\n}\n",
+    },
+];
+
+/// Returns the explanation of the diagnostic code, or `None` if the code is unknown.
+pub(crate) fn explain_code(code: &str) -> Option<ExplainCodeResult> {
+    EXPLANATIONS
+        .iter()
+        .find(|explanation| explanation.code == code)
+        .map(|explanation| ExplainCodeResult {
+            code: explanation.code.to_string(),
+            title: explanation.title.to_string(),
+            description: explanation.description.to_string(),
+            failing_example: explanation.failing_example.to_string(),
+            fix_example: explanation.fix_example.to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use kclvm_error::{DiagnosticId, ErrorKind};
+
+    use super::explain_code;
+    use crate::to_lsp::kcl_diag_id_to_lsp_diag_code;
+
+    #[test]
+    fn explain_code_test() {
+        let code = match kcl_diag_id_to_lsp_diag_code(DiagnosticId::Error(ErrorKind::TypeError)) {
+            lsp_types::NumberOrString::String(code) => code,
+            lsp_types::NumberOrString::Number(_) => unreachable!(),
+        };
+        let explanation = explain_code(&code).unwrap();
+        assert_eq!(explanation.code, "TypeError");
+        assert!(!explanation.title.is_empty());
+        assert!(!explanation.description.is_empty());
+        assert!(!explanation.failing_example.is_empty());
+        assert!(!explanation.fix_example.is_empty());
+        assert_ne!(explanation.failing_example, explanation.fix_example);
+
+        assert!(explain_code("NotExistCode").is_none());
+    }
+}
@@ -1,6 +1,7 @@
 use crate::to_lsp::lsp_location;
 use kclvm_error::Position as KCLPos;
 use kclvm_sema::core::global_state::GlobalState;
+use kclvm_sema::core::symbol::{KCLSymbol, SymbolKind};
 use lsp_types::Location;
 use std::collections::HashSet;
 
@@ -10,14 +11,21 @@ pub fn find_refs(kcl_pos: &KCLPos, gs: &GlobalState) -> Option<Vec<Location>> {
             Some(symbol) => match symbol.get_definition() {
                 Some(def_ref) => {
                     if let Some(def) = gs.get_symbols().get_symbol(def_ref) {
+                        let is_package = matches!(def_ref.get_kind(), SymbolKind::Package);
+                        if is_package {
+                            if let Some(alias_refs) =
+                                find_import_alias_refs(kcl_pos, gs, def, &symbol.get_name())
+                            {
+                                return Some(alias_refs);
+                            }
+                        }
                         let refs = def.get_references();
                         let mut refs_locs: HashSet<(KCLPos, KCLPos)> = refs
                             .iter()
-                            .filter_map(|symbol| {
-                                gs.get_symbols()
-                                    .get_symbol(*symbol)
-                                    .map(|sym| sym.get_range())
-                            })
+                            .filter_map(|symbol| gs.get_symbols().get_symbol(*symbol))
+                            // The uses of the package by its aliases are not renamed with it.
+                            .filter(|sym| !is_package || !is_alias_ref(gs, def, *sym))
+                            .map(|sym| sym.get_range())
                             .collect();
                         refs_locs.insert(symbol.get_range());
                         refs_locs.insert(def.get_range());
@@ -40,16 +48,91 @@ pub fn find_refs(kcl_pos: &KCLPos, gs: &GlobalState) -> Option<Vec<Location>> {
     None
 }
 
+/// Finds the references of an import alias, e.g. `c` of `import a.b as c`. The alias is
+/// only bound in the current file, so the other imports and the package itself are excluded,
+/// including the import of the same package without the alias in the file.
+fn find_import_alias_refs(
+    kcl_pos: &KCLPos,
+    gs: &GlobalState,
+    package: &KCLSymbol,
+    name: &str,
+) -> Option<Vec<Location>> {
+    if !gs
+        .get_packages()
+        .get_module_info(&kcl_pos.filename)?
+        .is_import_alias(name, &package.get_name())
+    {
+        return None;
+    }
+    let refs_locs: HashSet<(KCLPos, KCLPos)> = package
+        .get_references()
+        .iter()
+        .filter_map(|symbol| gs.get_symbols().get_symbol(*symbol))
+        .filter(|sym| sym.get_name() == name)
+        .map(|sym| sym.get_range())
+        .filter(|(start, _)| start.filename == kcl_pos.filename)
+        .collect();
+    let mut res: Vec<Location> = refs_locs
+        .iter()
+        .filter_map(|(start, end)| lsp_location(start.filename.clone(), start, end))
+        .collect();
+    res.sort_by_key(|e| (e.range.start.line, e.range.start.character));
+    Some(res)
+}
+
+/// Whether the reference of the package is the name of an import alias in its file.
+fn is_alias_ref(gs: &GlobalState, package: &KCLSymbol, reference: &KCLSymbol) -> bool {
+    let (start, _) = reference.get_range();
+    gs.get_packages()
+        .get_module_info(&start.filename)
+        .map_or(false, |module_info| {
+            module_info.is_import_alias(&reference.get_name(), &package.get_name())
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use crate::find_refs::find_refs;
     use crate::from_lsp::file_path_from_url;
-    use lsp_types::Location;
+    use lsp_types::{Location, Position, Range};
     use std::path::{Path, PathBuf};
 
     use crate::tests::compile_test_file;
     use kclvm_error::Position as KCLPos;
 
+    #[test]
+    fn find_refs_mixed_import_alias_test() {
+        let (file, _program, _, gs, _) =
+            compile_test_file("src/test_data/import_alias_mixed/main.k");
+        let refs_at = |line: u64| -> Vec<Range> {
+            let pos = KCLPos {
+                filename: file.clone(),
+                line,
+                column: Some(4),
+            };
+            find_refs(&pos, &gs)
+                .unwrap()
+                .into_iter()
+                .map(|loc| loc.range)
+                .collect()
+        };
+
+        // The alias `c` at `n = c.name` excludes the import without the alias.
+        let alias_refs = refs_at(4);
+        assert_eq!(
+            alias_refs,
+            vec![
+                Range::new(Position::new(0, 19), Position::new(0, 20)),
+                Range::new(Position::new(3, 4), Position::new(3, 5)),
+            ]
+        );
+
+        // The package `sub` at `m = sub.name` excludes the uses of the alias.
+        let package_refs = refs_at(5);
+        assert!(package_refs.contains(&Range::new(Position::new(4, 4), Position::new(4, 7))));
+        assert!(alias_refs.iter().all(|range| !package_refs.contains(range)));
+    }
+
     #[macro_export]
     macro_rules! find_ref_test_snapshot {
         ($name:ident, $file:expr, $line:expr, $column: expr) => {
@@ -0,0 +1,292 @@
+//! The folding ranges of a KCL file, which are the multi-line blocks, e.g. the schemas, the rules
+//! and the if statements, the multi-line brackets, e.g. the configs, the lists and the calls, the
+//! consecutive imports and the consecutive comments.
+//!
+//! The folding ranges of the opened documents are cached by the document versions, so folding and
+//! unfolding an unchanged document does not parse it again.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use kclvm_ast::ast;
+use kclvm_ast::pos::GetPos;
+use kclvm_error::Position as KCLPos;
+use kclvm_parser::parse_single_file;
+use lsp_types::{FoldingRange, FoldingRangeKind, Url};
+use parking_lot::RwLock;
+
+/// The folding ranges of the opened documents keyed by the uris, with the versions which the
+/// ranges are computed at.
+#[derive(Clone, Default)]
+pub(crate) struct FoldingRangeCache {
+    inner: Arc<RwLock<HashMap<Url, (i32, Vec<FoldingRange>)>>>,
+}
+
+impl FoldingRangeCache {
+    /// Returns the cached folding ranges of the document at the version, or computes and caches
+    /// them if the version of the document is not cached.
+    pub(crate) fn get_or_compute(
+        &self,
+        uri: &Url,
+        version: i32,
+        compute: impl FnOnce() -> Vec<FoldingRange>,
+    ) -> Vec<FoldingRange> {
+        if let Some((cached_version, ranges)) = self.inner.read().get(uri) {
+            if *cached_version == version {
+                return ranges.clone();
+            }
+        }
+        let ranges = compute();
+        self.inner
+            .write()
+            .insert(uri.clone(), (version, ranges.clone()));
+        ranges
+    }
+
+    /// Removes the folding ranges of the document when it is changed or closed.
+    pub(crate) fn invalidate(&self, uri: &Url) {
+        self.inner.write().remove(uri);
+    }
+
+    /// Removes the folding ranges of all the documents.
+    pub(crate) fn clear(&self) {
+        self.inner.write().clear();
+    }
+}
+
+/// Returns the folding ranges of the file sorted by the lines, or no range if the file can not be
+/// parsed. The brackets are folded up to the lines of the closing brackets, which stay visible.
+pub(crate) fn folding_ranges(file: &str, src: &str) -> Vec<FoldingRange> {
+    let module = match parse_single_file(file, Some(src.to_string())) {
+        Ok(result) => result.module,
+        Err(_) => return vec![],
+    };
+    let mut ranges = vec![];
+    let imports: Vec<&ast::NodeRef<ast::Stmt>> = module
+        .body
+        .iter()
+        .filter(|stmt| matches!(stmt.node, ast::Stmt::Import(_)))
+        .collect();
+    if let (Some(first), Some(last)) = (imports.first(), imports.last()) {
+        push_range(
+            &mut ranges,
+            first.line,
+            last.end_line,
+            Some(FoldingRangeKind::Imports),
+        );
+    }
+    for stmt in &module.body {
+        stmt_ranges(stmt, &mut ranges);
+    }
+    let mut comment_lines: Option<(u64, u64)> = None;
+    for comment in &module.comments {
+        comment_lines = match comment_lines {
+            Some((start, end)) if comment.line == end + 1 => Some((start, comment.line)),
+            Some((start, end)) => {
+                push_range(&mut ranges, start, end, Some(FoldingRangeKind::Comment));
+                Some((comment.line, comment.line))
+            }
+            None => Some((comment.line, comment.line)),
+        };
+    }
+    if let Some((start, end)) = comment_lines {
+        push_range(&mut ranges, start, end, Some(FoldingRangeKind::Comment));
+    }
+    ranges.sort_by_key(|range| (range.start_line, range.end_line));
+    ranges.dedup();
+    ranges
+}
+
+/// Adds the range of the 1-based KCL lines if it spans more than one line.
+fn push_range(
+    ranges: &mut Vec<FoldingRange>,
+    start_line: u64,
+    end_line: u64,
+    kind: Option<FoldingRangeKind>,
+) {
+    if end_line > start_line {
+        ranges.push(FoldingRange {
+            start_line: (start_line - 1) as u32,
+            end_line: (end_line - 1) as u32,
+            kind,
+            ..Default::default()
+        });
+    }
+}
+
+/// Adds the range of the brackets from `start` to `end`, the line of the closing bracket is not
+/// folded.
+fn push_bracket_range(ranges: &mut Vec<FoldingRange>, start: KCLPos, end: KCLPos) {
+    if end.line > start.line {
+        push_range(ranges, start.line, end.line - 1, None);
+    }
+}
+
+fn stmts_ranges(stmts: &[ast::NodeRef<ast::Stmt>], ranges: &mut Vec<FoldingRange>) {
+    for stmt in stmts {
+        stmt_ranges(stmt, ranges);
+    }
+}
+
+fn stmt_ranges(stmt: &ast::NodeRef<ast::Stmt>, ranges: &mut Vec<FoldingRange>) {
+    match &stmt.node {
+        ast::Stmt::Schema(schema_stmt) => {
+            push_range(ranges, stmt.line, stmt.end_line, None);
+            stmts_ranges(&schema_stmt.body, ranges);
+            // The `check:` line is right before the first check.
+            if let (Some(first), Some(last)) =
+                (schema_stmt.checks.first(), schema_stmt.checks.last())
+            {
+                push_range(ranges, first.line - 1, last.end_line, None);
+            }
+        }
+        ast::Stmt::Rule(_) => push_range(ranges, stmt.line, stmt.end_line, None),
+        ast::Stmt::If(if_stmt) => {
+            if let Some(last) = if_stmt.body.last() {
+                push_range(ranges, stmt.line, last.end_line, None);
+            }
+            stmts_ranges(&if_stmt.body, ranges);
+            match if_stmt.orelse.as_slice() {
+                // The `elif` branch is an if statement.
+                [elif] if matches!(elif.node, ast::Stmt::If(_)) => stmt_ranges(elif, ranges),
+                // The `else:` line is right before the first statement of the branch.
+                orelse => {
+                    if let (Some(first), Some(last)) = (orelse.first(), orelse.last()) {
+                        push_range(ranges, first.line - 1, last.end_line, None);
+                    }
+                    stmts_ranges(orelse, ranges);
+                }
+            }
+        }
+        ast::Stmt::Assign(assign_stmt) => expr_ranges(&assign_stmt.value, ranges),
+        ast::Stmt::AugAssign(aug_assign_stmt) => expr_ranges(&aug_assign_stmt.value, ranges),
+        ast::Stmt::Unification(unification_stmt) => {
+            expr_ranges(&unification_stmt.value.node.config, ranges)
+        }
+        ast::Stmt::SchemaAttr(schema_attr) => {
+            if let Some(value) = &schema_attr.value {
+                expr_ranges(value, ranges);
+            }
+        }
+        ast::Stmt::Expr(expr_stmt) => {
+            for expr in &expr_stmt.exprs {
+                expr_ranges(expr, ranges);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn expr_ranges(expr: &ast::NodeRef<ast::Expr>, ranges: &mut Vec<FoldingRange>) {
+    match &expr.node {
+        ast::Expr::Config(config_expr) => {
+            push_bracket_range(ranges, expr.get_pos(), expr.get_end_pos());
+            for entry in &config_expr.items {
+                expr_ranges(&entry.node.value, ranges);
+            }
+        }
+        ast::Expr::ConfigIfEntry(config_if_entry_expr) => {
+            push_range(ranges, expr.line, expr.end_line, None);
+            for entry in &config_if_entry_expr.items {
+                expr_ranges(&entry.node.value, ranges);
+            }
+            if let Some(orelse) = &config_if_entry_expr.orelse {
+                expr_ranges(orelse, ranges);
+            }
+        }
+        ast::Expr::Schema(schema_expr) => {
+            for arg in &schema_expr.args {
+                expr_ranges(arg, ranges);
+            }
+            expr_ranges(&schema_expr.config, ranges);
+        }
+        ast::Expr::List(list_expr) => {
+            push_bracket_range(ranges, expr.get_pos(), expr.get_end_pos());
+            for elt in &list_expr.elts {
+                expr_ranges(elt, ranges);
+            }
+        }
+        ast::Expr::ListComp(list_comp) => {
+            push_bracket_range(ranges, expr.get_pos(), expr.get_end_pos());
+            expr_ranges(&list_comp.elt, ranges);
+        }
+        ast::Expr::DictComp(dict_comp) => {
+            push_bracket_range(ranges, expr.get_pos(), expr.get_end_pos());
+            expr_ranges(&dict_comp.entry.value, ranges);
+        }
+        ast::Expr::Call(call_expr) => {
+            push_bracket_range(ranges, call_expr.func.get_end_pos(), expr.get_end_pos());
+            for arg in &call_expr.args {
+                expr_ranges(arg, ranges);
+            }
+            for keyword in &call_expr.keywords {
+                if let Some(value) = &keyword.node.value {
+                    expr_ranges(value, ranges);
+                }
+            }
+        }
+        ast::Expr::Lambda(lambda_expr) => {
+            push_bracket_range(ranges, expr.get_pos(), expr.get_end_pos());
+            stmts_ranges(&lambda_expr.body, ranges);
+        }
+        ast::Expr::Paren(paren_expr) => expr_ranges(&paren_expr.expr, ranges),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use lsp_types::{FoldingRangeKind, Url};
+    use proc_macro_crate::bench_test;
+
+    use super::{folding_ranges, FoldingRangeCache};
+
+    #[test]
+    #[bench_test]
+    fn folding_ranges_test() {
+        let path = "src/test_data/folding_range/main.k";
+        let src = std::fs::read_to_string(path).unwrap();
+        let ranges: Vec<(u32, u32, Option<FoldingRangeKind>)> = folding_ranges(path, &src)
+            .into_iter()
+            .map(|range| (range.start_line, range.end_line, range.kind))
+            .collect();
+        assert_eq!(
+            ranges,
+            vec![
+                (0, 1, Some(FoldingRangeKind::Imports)),
+                (3, 4, Some(FoldingRangeKind::Comment)),
+                (5, 8, None),
+                (10, 15, None),
+                (12, 13, None),
+            ]
+        );
+    }
+
+    #[test]
+    #[bench_test]
+    fn folding_range_cache_test() {
+        let cache = FoldingRangeCache::default();
+        let uri = Url::parse("file:///main.k").unwrap();
+        let computed = Cell::new(0);
+        let compute = || {
+            computed.set(computed.get() + 1);
+            vec![]
+        };
+
+        // The ranges of the same version are computed only once.
+        cache.get_or_compute(&uri, 1, compute);
+        cache.get_or_compute(&uri, 1, compute);
+        assert_eq!(computed.get(), 1);
+
+        cache.get_or_compute(&uri, 2, compute);
+        assert_eq!(computed.get(), 2);
+        cache.invalidate(&uri);
+        cache.get_or_compute(&uri, 2, compute);
+        assert_eq!(computed.get(), 3);
+        cache.clear();
+        cache.get_or_compute(&uri, 2, compute);
+        assert_eq!(computed.get(), 4);
+    }
+}
@@ -1,5 +1,16 @@
-use kclvm_tools::format::{format_source, FormatOptions};
-use lsp_types::{Position, Range, TextEdit};
+use std::collections::HashMap;
+
+use kclvm_parser::parse_single_file;
+use kclvm_tools::format::{format_source, reindent_source, FormatOptions};
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, Diagnostic, Position, Range, TextEdit, Url,
+    WorkspaceEdit,
+};
+
+use crate::from_lsp::utf16_text_range;
+
+/// The kind of the code action which applies all the safe fixes and then formats the document.
+pub(crate) const SOURCE_FIX_ALL_FORMAT: &str = "source.fixAll.format";
 
 pub fn format(
     file: String,
@@ -28,14 +39,148 @@ pub fn format(
     }
 }
 
+/// Returns the code action which reindents the lines of the selection to the indentation of the
+/// surrounding block, e.g. the code pasted into a schema body. The indentation of the block is
+/// one level deeper than the previous non-blank line if the line opens a block, e.g. ends with
+/// `:` or `{`, otherwise it is the same with the previous non-blank line.
+pub(crate) fn reindent_code_action(
+    uri: &Url,
+    src: &str,
+    range: Range,
+) -> Option<CodeActionOrCommand> {
+    let lines: Vec<&str> = src.lines().collect();
+    let start = range.start.line as usize;
+    let mut end = range.end.line as usize;
+    if range.end.character == 0 && end > start {
+        end -= 1;
+    }
+    if start == end && range.start == range.end || end >= lines.len() {
+        return None;
+    }
+    let indent = match lines[..start]
+        .iter()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+    {
+        Some(line) => {
+            let content = line.trim_start_matches([' ', '\t']);
+            let indent = line[..line.len() - content.len()].replace('\t', "    ");
+            if content.trim_end().ends_with([':', '{', '[', '(']) {
+                format!("{}    ", indent)
+            } else {
+                indent
+            }
+        }
+        None => String::new(),
+    };
+    let block = lines[start..=end].join("\n");
+    let new_text = reindent_source(&block, &indent);
+    if new_text == block {
+        return None;
+    }
+    let mut changes = HashMap::new();
+    changes.insert(
+        uri.clone(),
+        vec![TextEdit {
+            range: Range::new(
+                Position::new(start as u32, 0),
+                Position::new(end as u32, lines[end].encode_utf16().count() as u32),
+            ),
+            new_text,
+        }],
+    );
+    Some(CodeActionOrCommand::CodeAction(CodeAction {
+        title: "Reindent pasted block".to_string(),
+        kind: Some(CodeActionKind::REFACTOR_REWRITE),
+        edit: Some(WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }))
+}
+
+/// Returns the code action which applies the edits of the `source.fixAll` action and then formats
+/// the fixed document, the result is one edit replacing the whole document. If the fixed document
+/// can not be parsed, the formatting is skipped and only the fix edits are returned.
+pub(crate) fn fix_all_and_format_code_action(
+    uri: &Url,
+    file: &str,
+    src: &str,
+    fix_all: Option<&CodeActionOrCommand>,
+) -> Option<CodeActionOrCommand> {
+    let (mut fix_edits, diagnostics): (Vec<TextEdit>, Vec<Diagnostic>) = match fix_all {
+        Some(CodeActionOrCommand::CodeAction(action)) => (
+            action
+                .edit
+                .as_ref()
+                .and_then(|edit| edit.changes.as_ref())
+                .and_then(|changes| changes.get(uri))
+                .cloned()
+                .unwrap_or_default(),
+            action.diagnostics.clone().unwrap_or_default(),
+        ),
+        _ => (vec![], vec![]),
+    };
+    // Apply the edits from the end of the document, so the ranges of the remaining edits are not
+    // shifted by the applied ones.
+    let mut fixed = src.to_string();
+    fix_edits.sort_by(|a, b| b.range.start.cmp(&a.range.start));
+    for edit in &fix_edits {
+        let range = utf16_text_range(&fixed, edit.range)?;
+        fixed.replace_range(range, &edit.new_text);
+    }
+    let parsed = matches!(
+        parse_single_file(file, Some(fixed.clone())),
+        Ok(result) if result.errors.is_empty()
+    );
+    let edits = if parsed {
+        match format(file.to_string(), fixed.clone(), None) {
+            Ok(Some(edits)) => edits,
+            Ok(None) if src != fixed => vec![TextEdit {
+                range: Range::new(
+                    Position::new(0, 0),
+                    Position::new(i32::MAX as u32, i32::MAX as u32),
+                ),
+                new_text: fixed,
+            }],
+            _ => fix_edits,
+        }
+    } else {
+        fix_edits
+    };
+    if edits.is_empty() {
+        return None;
+    }
+    let mut changes = HashMap::new();
+    changes.insert(uri.clone(), edits);
+    Some(CodeActionOrCommand::CodeAction(CodeAction {
+        title: "Fix all auto-fixable problems and format".to_string(),
+        kind: Some(CodeActionKind::new(SOURCE_FIX_ALL_FORMAT)),
+        diagnostics: if diagnostics.is_empty() {
+            None
+        } else {
+            Some(diagnostics)
+        },
+        edit: Some(WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use std::{ops::Index, path::PathBuf};
 
-    use super::format;
-    use lsp_types::{Position, Range, TextEdit};
+    use super::{fix_all_and_format_code_action, format, reindent_code_action};
+    use lsp_types::{
+        CodeAction, CodeActionOrCommand, Diagnostic, Position, Range, TextEdit, Url, WorkspaceEdit,
+    };
     use proc_macro_crate::bench_test;
 
+    use crate::quick_fix::{fix_all, FixSafety};
     use crate::{from_lsp::text_range, tests::compile_test_file};
 
     #[test]
@@ -132,4 +277,125 @@ mod tests {
         }];
         assert_eq!(got, expected)
     }
+
+    #[test]
+    #[bench_test]
+    fn reindent_code_action_test() {
+        let uri = Url::from_file_path(std::env::current_dir().unwrap().join("main.k")).unwrap();
+        let src = "schema Person:\nname: str\nage: int\n";
+        let range = Range::new(Position::new(1, 0), Position::new(3, 0));
+        let action = match reindent_code_action(&uri, src, range) {
+            Some(CodeActionOrCommand::CodeAction(action)) => action,
+            _ => unreachable!(),
+        };
+        let edits = &action.edit.unwrap().changes.unwrap()[&uri];
+        assert_eq!(
+            edits,
+            &vec![TextEdit {
+                range: Range::new(Position::new(1, 0), Position::new(2, 8)),
+                new_text: "    name: str\n    age: int".to_string(),
+            }]
+        );
+
+        // The block is already indented.
+        let src = "schema Person:\n    name: str\n    age: int\n";
+        assert!(reindent_code_action(&uri, src, range).is_none());
+    }
+
+    #[test]
+    #[bench_test]
+    fn fix_all_and_format_code_action_test() {
+        let path = std::env::current_dir().unwrap().join("main.k");
+        let file = path.to_str().unwrap().to_string();
+        let uri = Url::from_file_path(&path).unwrap();
+        let unused_import = Diagnostic {
+            range: Range::new(Position::new(0, 0), Position::new(0, 11)),
+            code: Some(lsp_types::NumberOrString::String(
+                "UnusedImportWarning".to_string(),
+            )),
+            message: "Module 'math' imported but unused".to_string(),
+            data: Some(serde_json::json!({ "fix_safety": "safe" })),
+            ..Default::default()
+        };
+        let diagnostics = vec![unused_import.clone()];
+        let fixes = fix_all(&uri, &diagnostics, FixSafety::Safe);
+        let edits = |action: Option<CodeActionOrCommand>| match action {
+            Some(CodeActionOrCommand::CodeAction(action)) => {
+                assert_eq!(action.diagnostics, Some(vec![unused_import.clone()]));
+                action.edit.unwrap().changes.unwrap()[&uri].clone()
+            }
+            _ => panic!("expected a fix all and format code action"),
+        };
+
+        // The unused import is removed and the rest of the file is formatted in one edit.
+        let src = "import math\na=1\n";
+        let got = edits(fix_all_and_format_code_action(
+            &uri,
+            &file,
+            src,
+            fixes.as_ref(),
+        ));
+        assert_eq!(
+            got,
+            vec![TextEdit {
+                range: Range::new(
+                    Position::new(0, 0),
+                    Position::new(i32::MAX as u32, i32::MAX as u32),
+                ),
+                new_text: "a = 1\n".to_string(),
+            }]
+        );
+
+        // The fixed file can not be parsed, so only the fixes are applied.
+        let src = "import math\na=(1\n";
+        let got = edits(fix_all_and_format_code_action(
+            &uri,
+            &file,
+            src,
+            fixes.as_ref(),
+        ));
+        assert_eq!(
+            got,
+            vec![TextEdit {
+                range: Range::new(Position::new(0, 0), Position::new(0, 11)),
+                new_text: "".to_string(),
+            }]
+        );
+
+        // The characters of the edit ranges are counted in the UTF-16 code units.
+        let fix = |range: Range| {
+            let mut changes = std::collections::HashMap::new();
+            changes.insert(
+                uri.clone(),
+                vec![TextEdit {
+                    range,
+                    new_text: "\"y\"".to_string(),
+                }],
+            );
+            CodeActionOrCommand::CodeAction(CodeAction {
+                edit: Some(WorkspaceEdit {
+                    changes: Some(changes),
+                    ..Default::default()
+                }),
+                diagnostics: Some(vec![unused_import.clone()]),
+                ..Default::default()
+            })
+        };
+        let src = "a = \"😀\" + \"x\"\n";
+        let got = edits(fix_all_and_format_code_action(
+            &uri,
+            &file,
+            src,
+            Some(&fix(Range::new(Position::new(0, 11), Position::new(0, 14)))),
+        ));
+        assert_eq!(got[0].new_text, "a = \"😀\" + \"y\"\n");
+        // The edit in the middle of a character or out of the document is not applied.
+        for range in [
+            Range::new(Position::new(0, 6), Position::new(0, 6)),
+            Range::new(Position::new(0, 11), Position::new(0, 40)),
+            Range::new(Position::new(3, 0), Position::new(3, 0)),
+        ] {
+            assert!(fix_all_and_format_code_action(&uri, &file, src, Some(&fix(range))).is_none());
+        }
+    }
 }
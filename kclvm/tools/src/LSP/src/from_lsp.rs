@@ -16,10 +16,10 @@ pub(crate) fn abs_path(uri: &Url) -> anyhow::Result<AbsPathBuf> {
 
 // Convert pos format
 // The position in lsp protocol is different with position in ast node whose line number is 1 based.
-pub(crate) fn kcl_pos(file: &str, pos: Position) -> KCLPos {
+pub fn kcl_pos(file: &str, pos: Position) -> KCLPos {
     KCLPos {
         filename: kclvm_utils::path::convert_windows_drive_letter(file).adjust_canonicalization(),
-        line: (pos.line + 1) as u64,
+        line: pos.line as u64 + 1,
         column: Some(pos.character as u64),
     }
 }
@@ -48,6 +48,42 @@ pub(crate) fn text_range(text: &str, range: lsp_types::Range) -> Range<usize> {
     Range { start, end }
 }
 
+/// Converts the given lsp range, whose characters are counted in the UTF-16 code units, to the
+/// byte range of the text. Returns `None` if the range is not in the text or does not fall on
+/// the char boundaries.
+pub(crate) fn utf16_text_range(text: &str, range: lsp_types::Range) -> Option<Range<usize>> {
+    let start = utf16_offset(text, range.start)?;
+    let end = utf16_offset(text, range.end)?;
+    if start > end {
+        return None;
+    }
+    Some(Range { start, end })
+}
+
+/// Converts the lsp position to the byte offset of the text, see [`utf16_text_range`].
+fn utf16_offset(text: &str, pos: Position) -> Option<usize> {
+    if pos.line as usize > text.matches('\n').count() {
+        return None;
+    }
+    let line_start: usize = text
+        .split_inclusive('\n')
+        .take(pos.line as usize)
+        .map(|line| line.len())
+        .sum();
+    let line = text[line_start..].split('\n').next()?;
+    let mut units = 0;
+    for (offset, c) in line.char_indices() {
+        if units == pos.character as usize {
+            return Some(line_start + offset);
+        }
+        units += c.len_utf16();
+        if units > pos.character as usize {
+            return None;
+        }
+    }
+    (units == pos.character as usize).then_some(line_start + line.len())
+}
+
 /// Converts the specified `url` to a utf8 encoded file path string. Returns an error if the url could not be
 /// converted to a valid utf8 encoded file path string.
 pub(crate) fn file_path_from_url(url: &Url) -> anyhow::Result<String> {
@@ -6,13 +6,21 @@
 //! + mixin definition
 //! + schema attr
 //! + attr type
+//! + package, with the location links if the client supports them
+//! + package member in an import path, e.g. `import pkg.Schema`
+//! + top level variable declared more than once, e.g. a config unified in several files
+//! + schema attr named by a string key of a dict typed by the schema, e.g. `{"name": "nginx"}`
 
-use crate::to_lsp::lsp_location;
+use crate::to_lsp::{lsp_location, lsp_pos};
 use indexmap::IndexSet;
+use kclvm_ast::ast::{self, Program};
+use kclvm_ast::pos::{ContainsPos, GetPos};
+use kclvm_ast::walker::MutSelfWalker;
 use kclvm_error::Position as KCLPos;
 use kclvm_sema::core::global_state::GlobalState;
-use kclvm_sema::core::symbol::SymbolRef;
-use lsp_types::GotoDefinitionResponse;
+use kclvm_sema::core::scope::Scope;
+use kclvm_sema::core::symbol::{SymbolKind, SymbolRef};
+use lsp_types::{GotoDefinitionResponse, LocationLink, Position, Range, Url};
 
 /// Navigates to the definition of an identifier.
 pub fn goto_def(kcl_pos: &KCLPos, gs: &GlobalState) -> Option<lsp_types::GotoDefinitionResponse> {
@@ -50,6 +58,244 @@ pub fn goto_def(kcl_pos: &KCLPos, gs: &GlobalState) -> Option<lsp_types::GotoDef
     positions_to_goto_def_resp(&res)
 }
 
+/// Navigates to the definitions at the position. The package links are tried first if the
+/// client supports them, then the declarations of a variable declared more than once, the
+/// definition, the member of an import path and the schema attr of a dict key.
+pub(crate) fn goto_definition(
+    program: &Program,
+    kcl_pos: &KCLPos,
+    gs: &GlobalState,
+    definition_link: bool,
+) -> Option<GotoDefinitionResponse> {
+    definition_link
+        .then(|| goto_package_links(kcl_pos, gs))
+        .flatten()
+        .or_else(|| goto_declarations(program, kcl_pos, gs))
+        .or_else(|| goto_def(kcl_pos, gs))
+        .or_else(|| goto_import_member(program, kcl_pos, gs))
+        .or_else(|| goto_dict_key_attr(program, kcl_pos, gs))
+}
+
+/// Navigates to the files of the package at the position with the location links. The target
+/// range of a link covers the whole file and the target selection range is the first definition
+/// of the package in the file, or the start of the file if nothing is defined in it. Returns
+/// `None` if the position is not a package.
+pub fn goto_package_links(kcl_pos: &KCLPos, gs: &GlobalState) -> Option<GotoDefinitionResponse> {
+    let symbol = gs
+        .get_symbols()
+        .get_symbol(find_symbol(kcl_pos, gs, true)?)?;
+    let def_ref = symbol.get_definition()?;
+    if !matches!(def_ref.get_kind(), SymbolKind::Package) {
+        return None;
+    }
+    let def = gs.get_symbols().get_symbol(def_ref)?;
+    let pkg_info = gs.get_packages().get_package_info(&def.get_name())?;
+    if pkg_info.is_system() {
+        return None;
+    }
+    let (origin_start, origin_end) = symbol.get_range();
+    let member_ranges: Vec<(KCLPos, KCLPos)> = def
+        .get_all_attributes(gs.get_symbols(), None)
+        .into_iter()
+        .filter_map(|member| gs.get_symbols().get_symbol(member))
+        .map(|member| member.get_range())
+        .collect();
+    let mut links = vec![];
+    for file in pkg_info.get_kfile_paths() {
+        let target_selection_range = match member_ranges
+            .iter()
+            .filter(|(start, _)| start.filename == *file)
+            .min_by_key(|(start, _)| (start.line, start.column))
+        {
+            Some((start, end)) => Range::new(lsp_pos(start), lsp_pos(end)),
+            None => Range::default(),
+        };
+        let file_end = match std::fs::read_to_string(file) {
+            Ok(src) => {
+                let last_line = src.split('\n').last().unwrap_or_default();
+                Position::new(
+                    src.matches('\n').count() as u32,
+                    last_line.encode_utf16().count() as u32,
+                )
+            }
+            Err(_) => target_selection_range.end,
+        };
+        links.push(LocationLink {
+            origin_selection_range: Some(Range::new(lsp_pos(&origin_start), lsp_pos(&origin_end))),
+            target_uri: Url::from_file_path(file).ok()?,
+            target_range: Range::new(
+                Position::new(0, 0),
+                file_end.max(target_selection_range.end),
+            ),
+            target_selection_range,
+        });
+    }
+    if links.is_empty() {
+        None
+    } else {
+        Some(GotoDefinitionResponse::Link(links))
+    }
+}
+
+/// Navigates to the member of a package named by the import path at the position, e.g.
+/// `import pkg.Schema` jumps to the declaration of `Schema` in the package `pkg`. Such an import
+/// path does not resolve to a package and has no definition, so it is handled separately.
+pub(crate) fn goto_import_member(
+    program: &Program,
+    kcl_pos: &KCLPos,
+    gs: &GlobalState,
+) -> Option<GotoDefinitionResponse> {
+    let module = program.get_module(&kcl_pos.filename).ok()??;
+    for stmt in &module.body {
+        if let ast::Stmt::Import(import_stmt) = &stmt.node {
+            if !stmt.contains_pos(kcl_pos) {
+                continue;
+            }
+            let (pkgpath, member) = import_stmt.path.node.rsplit_once('.')?;
+            let symbols = gs.get_symbols();
+            let pkg = symbols.get_symbol(symbols.get_symbol_by_fully_qualified_name(pkgpath)?)?;
+            let member = symbols.get_symbol(pkg.get_attribute(member, symbols, None)?)?;
+            let mut res = IndexSet::new();
+            res.insert(member.get_range());
+            return positions_to_goto_def_resp(&res);
+        }
+    }
+    None
+}
+
+/// Navigates to all the declarations of the top level variable at the position, e.g. both
+/// `config: Config {...}` of a config unified in two files of the package. The later declarations
+/// are the references of the first one, so they are found by the references which are the targets
+/// of the top level statements. Returns `None` if the variable is declared only once.
+pub(crate) fn goto_declarations(
+    program: &Program,
+    kcl_pos: &KCLPos,
+    gs: &GlobalState,
+) -> Option<GotoDefinitionResponse> {
+    let def_ref = find_def(kcl_pos, gs, true)?;
+    if !matches!(def_ref.get_kind(), SymbolKind::Value) {
+        return None;
+    }
+    let def = gs.get_symbols().get_symbol(def_ref)?;
+    let def_range = def.get_range();
+    // A local variable shadowing the top level one is not declared by the top level statements.
+    if !is_top_level_target(program, &def_range) {
+        return None;
+    }
+    let mut declarations: Vec<(KCLPos, KCLPos)> = def
+        .get_references()
+        .into_iter()
+        .filter_map(|reference| gs.get_symbols().get_symbol(reference))
+        .map(|reference| reference.get_range())
+        .filter(|range| is_top_level_target(program, range))
+        .collect();
+    if declarations.is_empty() {
+        return None;
+    }
+    declarations.push(def_range);
+    declarations.sort_by(|(a, _), (b, _)| {
+        (&a.filename, a.line, a.column).cmp(&(&b.filename, b.line, b.column))
+    });
+    positions_to_goto_def_resp(&declarations.into_iter().collect())
+}
+
+/// Returns whether the range is the name of an assignment or unification target of a top level
+/// statement, e.g. `config` of `config: Config {...}`.
+fn is_top_level_target(program: &Program, range: &(KCLPos, KCLPos)) -> bool {
+    let module = match program.get_module(&range.0.filename) {
+        Ok(Some(module)) => module,
+        _ => return false,
+    };
+    let stmt = match module.body.iter().find(|stmt| stmt.contains_pos(&range.0)) {
+        Some(stmt) => stmt,
+        None => return false,
+    };
+    match &stmt.node {
+        ast::Stmt::Assign(assign_stmt) => assign_stmt.targets.iter().any(|target| {
+            target.node.paths.is_empty() && target.node.name.get_span_pos() == *range
+        }),
+        ast::Stmt::Unification(unification_stmt) => {
+            let names = &unification_stmt.target.node.names;
+            names.len() == 1 && names[0].get_span_pos() == *range
+        }
+        _ => false,
+    }
+}
+
+/// Navigates to the schema attribute named by the string key of a dict at the position, e.g.
+/// `"name"` of `server: Server = {"name": "nginx"}` jumps to the attribute `name` of `Server`.
+/// The dict is typed by its expected schema type, which owns the scope of the dict entries.
+/// Returns `None` if the dict is not typed by a schema or the key is not an attribute of it.
+pub(crate) fn goto_dict_key_attr(
+    program: &Program,
+    kcl_pos: &KCLPos,
+    gs: &GlobalState,
+) -> Option<GotoDefinitionResponse> {
+    let module = program.get_module(&kcl_pos.filename).ok()??;
+    let mut finder = StringKeyFinder {
+        pos: kcl_pos,
+        key: None,
+    };
+    for stmt in &module.body {
+        finder.walk_stmt(&stmt.node);
+    }
+    let (name, key_pos) = finder.key?;
+    let scope = gs.look_up_scope(&key_pos)?;
+    let schema = gs.get_scopes().get_scope(&scope)?.get_owner()?;
+    if !matches!(schema.get_kind(), SymbolKind::Schema) {
+        return None;
+    }
+    let symbols = gs.get_symbols();
+    let attr = symbols
+        .get_symbol(schema)?
+        .get_attribute(&name, symbols, None)?;
+    if !matches!(attr.get_kind(), SymbolKind::Attribute) {
+        return None;
+    }
+    let mut res = IndexSet::new();
+    res.insert(symbols.get_symbol(attr)?.get_range());
+    positions_to_goto_def_resp(&res)
+}
+
+/// Finds the string key of a config entry at the position with the start of the key.
+struct StringKeyFinder<'a> {
+    pos: &'a KCLPos,
+    key: Option<(String, KCLPos)>,
+}
+
+impl<'a> StringKeyFinder<'a> {
+    fn find_key(&mut self, entries: &[ast::NodeRef<ast::ConfigEntry>]) {
+        for entry in entries {
+            if let Some(key) = &entry.node.key {
+                if let ast::Expr::StringLit(string_lit) = &key.node {
+                    if key.contains_pos(self.pos) {
+                        self.key = Some((string_lit.value.clone(), key.get_pos()));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<'a> MutSelfWalker for StringKeyFinder<'a> {
+    fn walk_config_expr(&mut self, config_expr: &ast::ConfigExpr) {
+        self.find_key(&config_expr.items);
+        for entry in &config_expr.items {
+            self.walk_expr(&entry.node.value.node);
+        }
+    }
+
+    fn walk_config_if_entry_expr(&mut self, config_if_entry_expr: &ast::ConfigIfEntryExpr) {
+        self.find_key(&config_if_entry_expr.items);
+        for entry in &config_if_entry_expr.items {
+            self.walk_expr(&entry.node.value.node);
+        }
+        if let Some(orelse) = &config_if_entry_expr.orelse {
+            self.walk_expr(&orelse.node);
+        }
+    }
+}
+
 pub(crate) fn find_def(kcl_pos: &KCLPos, gs: &GlobalState, exact: bool) -> Option<SymbolRef> {
     let def = if exact {
         match gs.look_up_exact_symbol(kcl_pos) {
@@ -106,9 +352,12 @@ fn positions_to_goto_def_resp(
 
 #[cfg(test)]
 mod tests {
-    use super::goto_def;
+    use super::{
+        goto_declarations, goto_def, goto_dict_key_attr, goto_import_member, goto_package_links,
+    };
     use crate::{from_lsp::file_path_from_url, tests::compile_test_file};
     use kclvm_error::Position as KCLPos;
+    use lsp_types::{Position, Range};
     use std::path::{Path, PathBuf};
 
     #[macro_export]
@@ -586,4 +835,143 @@ mod tests {
         8,
         13
     );
+
+    #[test]
+    fn goto_package_links_test() {
+        let (file, _program, _, gs, _) = compile_test_file(
+            "src/test_data/goto_def_test/goto_import_pkg_test/goto_import_pkg_test.k",
+        );
+        // import ..pkg
+        let pos = KCLPos {
+            filename: file.clone(),
+            line: 1,
+            column: Some(11),
+        };
+        let links = match goto_package_links(&pos, &gs) {
+            Some(lsp_types::GotoDefinitionResponse::Link(links)) => links,
+            _ => unreachable!("test error"),
+        };
+        let got: Vec<(String, Range, Range)> = links
+            .into_iter()
+            .map(|link| {
+                let path = file_path_from_url(&link.target_uri).unwrap();
+                let name = Path::new(&path).file_name().unwrap().to_str().unwrap();
+                (
+                    name.to_string(),
+                    link.target_range,
+                    link.target_selection_range,
+                )
+            })
+            .collect();
+        let range = |start: (u32, u32), end: (u32, u32)| {
+            Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1))
+        };
+        // The selection ranges are the first definitions `Person` and `Person1`.
+        assert_eq!(
+            got,
+            vec![
+                (
+                    "schema_def.k".to_string(),
+                    range((0, 0), (10, 0)),
+                    range((0, 7), (0, 13))
+                ),
+                (
+                    "schema_def1.k".to_string(),
+                    range((0, 0), (3, 0)),
+                    range((0, 7), (0, 14))
+                ),
+            ]
+        );
+        // Not a package.
+        let pos = KCLPos {
+            filename: file,
+            line: 1,
+            column: Some(1),
+        };
+        assert!(goto_package_links(&pos, &gs).is_none());
+    }
+
+    #[test]
+    fn goto_import_member_test() {
+        let (file, program, _, gs, _) =
+            compile_test_file("src/test_data/goto_def_test/goto_import_member_test/main.k");
+        // import .pkg.Server
+        let pos = KCLPos {
+            filename: file,
+            line: 2,
+            column: Some(13),
+        };
+        match goto_import_member(&program, &pos, &gs) {
+            Some(lsp_types::GotoDefinitionResponse::Scalar(loc)) => {
+                let path = file_path_from_url(&loc.uri).unwrap();
+                assert!(path.ends_with("server.k"), "{}", path);
+                assert_eq!(
+                    loc.range,
+                    Range::new(Position::new(0, 7), Position::new(0, 13))
+                );
+            }
+            _ => unreachable!("test error"),
+        }
+    }
+
+    #[test]
+    fn goto_declarations_test() {
+        let (file, program, _, gs, _) =
+            compile_test_file("src/test_data/goto_def_test/goto_declarations_test/main.k");
+        // name = config.name
+        let pos = KCLPos {
+            filename: file.clone(),
+            line: 8,
+            column: Some(8),
+        };
+        let ranges: Vec<Range> = match goto_declarations(&program, &pos, &gs) {
+            Some(lsp_types::GotoDefinitionResponse::Array(locations)) => {
+                locations.into_iter().map(|loc| loc.range).collect()
+            }
+            _ => unreachable!("test error"),
+        };
+        assert_eq!(
+            ranges,
+            vec![
+                Range::new(Position::new(4, 0), Position::new(4, 6)),
+                Range::new(Position::new(5, 0), Position::new(5, 6)),
+            ]
+        );
+        // `name` is declared once and navigated by `goto_def`.
+        let pos = KCLPos {
+            filename: file,
+            line: 8,
+            column: Some(1),
+        };
+        assert!(goto_declarations(&program, &pos, &gs).is_none());
+        assert!(goto_def(&pos, &gs).is_some());
+    }
+
+    #[test]
+    fn goto_dict_key_attr_test() {
+        let (file, program, _, gs, _) =
+            compile_test_file("src/test_data/goto_def_test/goto_dict_key_attr_test/main.k");
+        // server: Server = {"name": "nginx"}
+        let pos = KCLPos {
+            filename: file.clone(),
+            line: 5,
+            column: Some(20),
+        };
+        match goto_dict_key_attr(&program, &pos, &gs) {
+            Some(lsp_types::GotoDefinitionResponse::Scalar(loc)) => {
+                assert_eq!(
+                    loc.range,
+                    Range::new(Position::new(1, 4), Position::new(1, 8))
+                );
+            }
+            _ => unreachable!("test error"),
+        }
+        // The keys of the dict which is not typed by a schema.
+        let pos = KCLPos {
+            filename: file,
+            line: 6,
+            column: Some(10),
+        };
+        assert!(goto_dict_key_attr(&program, &pos, &gs).is_none());
+    }
 }
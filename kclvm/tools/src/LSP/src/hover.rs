@@ -1,13 +1,20 @@
+use kclvm_ast::ast::{self, Program};
+use kclvm_ast::walker::MutSelfWalker;
+use kclvm_ast_pretty::{print_ast_node, ASTNode};
 use kclvm_error::Position as KCLPos;
 use kclvm_sema::{
     builtin::BUILTIN_DECORATORS,
-    core::global_state::GlobalState,
+    core::{
+        global_state::GlobalState,
+        symbol::{KCLSymbol, SymbolKind, SymbolRef},
+    },
     ty::{FunctionType, Type, ANY_TYPE_STR},
 };
-use lsp_types::{Hover, HoverContents, MarkedString};
+use lsp_types::{Hover, HoverContents, MarkedString, MarkupContent, MarkupKind};
 
 use crate::goto_def::find_def;
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 enum MarkedStringType {
     String,
     LanguageString,
@@ -15,159 +22,322 @@ enum MarkedStringType {
 
 /// Returns a short text describing element at position.
 /// Specifically, the doc for schema and schema attr(todo)
-pub fn hover(kcl_pos: &KCLPos, gs: &GlobalState) -> Option<lsp_types::Hover> {
-    let mut docs: Vec<(String, MarkedStringType)> = vec![];
+pub fn hover(kcl_pos: &KCLPos, program: &Program, gs: &GlobalState) -> Option<lsp_types::Hover> {
+    let def_ref = find_def(kcl_pos, gs, true)?;
+    if let SymbolKind::Package = def_ref.get_kind() {
+        // The same package may be imported both with and without an alias, the name at the
+        // position tells which of the imports it refers to.
+        let pkg = gs.get_symbols().get_symbol(def_ref)?.get_name();
+        let name = gs
+            .look_up_exact_symbol(kcl_pos)
+            .and_then(|symbol| gs.get_symbols().get_symbol(symbol))
+            .map(|symbol| symbol.get_name());
+        if let Some(name) = name.filter(|name| *name != pkg) {
+            let is_alias = gs
+                .get_packages()
+                .get_module_info(&kcl_pos.filename)
+                .map_or(false, |module_info| {
+                    module_info.is_import_alias(&name, &pkg)
+                });
+            return docs_to_hover(vec![package_doc(&pkg, is_alias.then_some(name))]);
+        }
+    }
+    hover_content(def_ref, &kcl_pos.filename, program, gs)
+        .and_then(|content| docs_to_hover(content.docs))
+}
 
-    let def = find_def(kcl_pos, gs, true);
-    match def {
-        Some(def_ref) => match gs.get_symbols().get_symbol(def_ref) {
-            Some(obj) => match def_ref.get_kind() {
-                kclvm_sema::core::symbol::SymbolKind::Schema => match &obj.get_sema_info().ty {
-                    Some(ty) => {
-                        // Build hover content for schema definition
-                        // Schema Definition hover
-                        // ```
-                        // pkg
-                        // ----------------
-                        // schema Foo(Base)[param: type]:
-                        //     attr1: type
-                        //     attr2? type = defalut_value
-                        // -----------------
-                        // doc
-                        // ```
-                        let schema_ty = ty.into_schema_type();
-                        let (pkgpath, rest_sign) = schema_ty.schema_ty_signature_str();
-                        if !pkgpath.is_empty() {
-                            docs.push((pkgpath.clone(), MarkedStringType::String));
-                        }
+/// The hover content of a symbol as plain data, which is shared by the LSP hover and the tools
+/// rendering the symbol docs outside the editors, e.g. a docs site.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HoverContent {
+    /// The content in Markdown, the code blocks are fenced as `kcl`.
+    pub markdown: String,
+    /// The content without the Markdown marks, the same as the hover for the plain text clients.
+    pub plain: String,
+    /// The signature or the type of the symbol, e.g. `port: int`.
+    pub detail: String,
+    /// The sections of the content, which the LSP hover shows one by one.
+    docs: Vec<(String, MarkedStringType)>,
+}
 
-                        // The attr of schema_ty does not contain the attrs from inherited base schema.
-                        // Use the api provided by GlobalState to get all attrs
-                        let module_info = gs.get_packages().get_module_info(&kcl_pos.filename);
-                        let schema_attrs = obj.get_all_attributes(gs.get_symbols(), module_info);
-                        let mut attrs: Vec<String> = vec![];
-                        for schema_attr in schema_attrs {
-                            if let kclvm_sema::core::symbol::SymbolKind::Attribute =
-                                schema_attr.get_kind()
-                            {
-                                let attr = gs.get_symbols().get_symbol(schema_attr).unwrap();
-                                let name = attr.get_name();
-                                let attr_symbol =
-                                    gs.get_symbols().get_attr_symbol(schema_attr).unwrap();
-                                let default_value_content = match attr_symbol.get_default_value() {
-                                    Some(s) => format!(" = {}", s),
-                                    None => "".to_string(),
-                                };
-                                let attr_ty_str = match &attr.get_sema_info().ty {
-                                    Some(ty) => ty_hover_content(ty),
-                                    None => ANY_TYPE_STR.to_string(),
-                                };
-                                attrs.push(format!(
-                                    "    {}{}: {}{}",
-                                    name,
-                                    if attr_symbol.is_optional() { "?" } else { "" },
-                                    attr_ty_str,
-                                    default_value_content
-                                ));
-                            }
-                        }
+/// Returns the hover content of the symbol, or `None` if nothing is shown for it. `filename` is the
+/// file the symbol is looked up from, which resolves the import aliases and the visible attributes.
+pub fn hover_content(
+    symbol: SymbolRef,
+    filename: &str,
+    program: &Program,
+    gs: &GlobalState,
+) -> Option<HoverContent> {
+    let docs = hover_docs(symbol, filename, program, gs);
+    let detail = docs
+        .iter()
+        .find(|(_, ty)| matches!(ty, MarkedStringType::LanguageString))
+        .or(docs.first())
+        .map(|(doc, _)| doc.clone())?;
+    let markdown = docs
+        .iter()
+        .map(|(doc, ty)| match ty {
+            MarkedStringType::String => doc.clone(),
+            MarkedStringType::LanguageString => format!("```kcl\n{}\n```", doc),
+        })
+        .collect::<Vec<String>>()
+        .join("\n\n");
+    let plain = match hover_to_plain_text(docs_to_hover(docs.clone())?).contents {
+        HoverContents::Markup(markup) => markup.value,
+        _ => return None,
+    };
+    Some(HoverContent {
+        markdown,
+        plain,
+        detail,
+        docs,
+    })
+}
 
-                        let merged_doc = format!("{}\n{}", rest_sign.clone(), attrs.join("\n"));
-                        docs.push((merged_doc, MarkedStringType::LanguageString));
-
-                        if !schema_ty.doc.is_empty() {
-                            docs.push((schema_ty.doc.clone(), MarkedStringType::String));
-
-                            // Add examples to the hover content
-                            if !schema_ty.examples.is_empty() {
-                                let examples = schema_ty
-                                    .examples
-                                    .iter()
-                                    .map(|(_, example)| format!("{}\n", example.value))
-                                    .collect::<Vec<String>>()
-                                    .join("\n");
-                                docs.push((examples, MarkedStringType::LanguageString));
-                            }
-                        }
+/// Returns the hover docs of the symbol, `filename` is the file the symbol is looked up from.
+fn hover_docs(
+    def_ref: SymbolRef,
+    filename: &str,
+    program: &Program,
+    gs: &GlobalState,
+) -> Vec<(String, MarkedStringType)> {
+    let mut docs: Vec<(String, MarkedStringType)> = vec![];
+    let obj = match gs.get_symbols().get_symbol(def_ref) {
+        Some(obj) => obj,
+        None => return docs,
+    };
+    match def_ref.get_kind() {
+        kclvm_sema::core::symbol::SymbolKind::Schema => match &obj.get_sema_info().ty {
+            Some(ty) => {
+                // Build hover content for schema definition
+                // Schema Definition hover
+                // ```
+                // pkg
+                // ----------------
+                // schema Foo(Base)[param: type]:
+                //     attr1: type
+                //     attr2? type = defalut_value
+                // -----------------
+                // doc
+                // ```
+                let schema_ty = ty.into_schema_type();
+                let (pkgpath, rest_sign) = schema_ty.schema_ty_signature_str();
+                if !pkgpath.is_empty() {
+                    docs.push((pkgpath.clone(), MarkedStringType::String));
+                }
+
+                // The attr of schema_ty does not contain the attrs from inherited base schema.
+                // Use the api provided by GlobalState to get all attrs
+                let module_info = gs.get_packages().get_module_info(filename);
+                let schema_attrs = obj.get_all_attributes(gs.get_symbols(), module_info);
+                let mut attrs: Vec<String> = vec![];
+                for schema_attr in schema_attrs {
+                    if let kclvm_sema::core::symbol::SymbolKind::Attribute = schema_attr.get_kind()
+                    {
+                        let attr = gs.get_symbols().get_symbol(schema_attr).unwrap();
+                        let name = attr.get_name();
+                        let attr_symbol = gs.get_symbols().get_attr_symbol(schema_attr).unwrap();
+                        let default_value_content = match attr_symbol.get_default_value() {
+                            Some(s) => format!(" = {}", s),
+                            None => "".to_string(),
+                        };
+                        let attr_ty_str = match &attr.get_sema_info().ty {
+                            Some(ty) => ty_hover_content(ty),
+                            None => ANY_TYPE_STR.to_string(),
+                        };
+                        attrs.push(format!(
+                            "    {}{}: {}{}",
+                            name,
+                            if attr_symbol.is_optional() { "?" } else { "" },
+                            attr_ty_str,
+                            default_value_content
+                        ));
                     }
-                    _ => {}
-                },
-                kclvm_sema::core::symbol::SymbolKind::Attribute => {
-                    let sema_info = obj.get_sema_info();
-                    let attr_symbol = gs.get_symbols().get_attr_symbol(def_ref).unwrap();
-                    let default_value_content = match attr_symbol.get_default_value() {
-                        Some(s) => format!(" = {}", s),
-                        None => "".to_string(),
-                    };
-                    match &sema_info.ty {
-                        Some(ty) => {
-                            docs.push((
-                                format!(
-                                    "{}: {}{}",
-                                    &obj.get_name(),
-                                    ty.ty_hint(),
-                                    default_value_content
-                                ),
-                                MarkedStringType::LanguageString,
-                            ));
-                            if let Some(doc) = &sema_info.doc {
-                                if !doc.is_empty() {
-                                    docs.push((doc.clone(), MarkedStringType::String));
-                                }
-                            }
-                        }
-                        _ => {}
+                }
+
+                let merged_doc = format!("{}\n{}", rest_sign.clone(), attrs.join("\n"));
+                docs.push((merged_doc, MarkedStringType::LanguageString));
+
+                if !schema_ty.doc.is_empty() {
+                    docs.push((schema_ty.doc.clone(), MarkedStringType::String));
+
+                    // Add examples to the hover content
+                    if !schema_ty.examples.is_empty() {
+                        let examples = schema_ty
+                            .examples
+                            .iter()
+                            .map(|(_, example)| format!("{}\n", example.value))
+                            .collect::<Vec<String>>()
+                            .join("\n");
+                        docs.push((examples, MarkedStringType::LanguageString));
                     }
                 }
-                kclvm_sema::core::symbol::SymbolKind::Value
-                | kclvm_sema::core::symbol::SymbolKind::Function => match &obj.get_sema_info().ty {
-                    Some(ty) => match &ty.kind {
-                        kclvm_sema::ty::TypeKind::Function(func_ty) => {
-                            docs.append(&mut build_func_hover_content(
-                                func_ty.clone(),
-                                obj.get_name().clone(),
-                            ));
-                        }
-                        _ => {
-                            docs.push((
-                                format!("{}: {}", &obj.get_name(), ty.ty_str()),
-                                MarkedStringType::LanguageString,
-                            ));
-                        }
-                    },
-                    _ => {}
-                },
-                kclvm_sema::core::symbol::SymbolKind::Expression => return None,
-                kclvm_sema::core::symbol::SymbolKind::Comment => return None,
-                kclvm_sema::core::symbol::SymbolKind::Decorator => {
-                    match BUILTIN_DECORATORS.get(&obj.get_name()) {
-                        Some(ty) => {
-                            let mut hover_content = build_func_hover_content(
-                                ty.into_func_type(),
-                                obj.get_name().clone(),
-                            );
-
-                            docs.append(&mut hover_content);
+            }
+            _ => {}
+        },
+        kclvm_sema::core::symbol::SymbolKind::Attribute => {
+            let sema_info = obj.get_sema_info();
+            let attr_symbol = gs.get_symbols().get_attr_symbol(def_ref).unwrap();
+            let default_value_content = match attr_symbol.get_default_value() {
+                Some(s) => format!(" = {}", s),
+                None => "".to_string(),
+            };
+            match &sema_info.ty {
+                Some(ty) => {
+                    docs.push((
+                        format!(
+                            "{}: {}{}",
+                            &obj.get_name(),
+                            ty.ty_hint(),
+                            default_value_content
+                        ),
+                        MarkedStringType::LanguageString,
+                    ));
+                    if let Some(doc) = &sema_info.doc {
+                        if !doc.is_empty() {
+                            docs.push((doc.clone(), MarkedStringType::String));
                         }
-                        None => todo!(),
+                    }
+                    let constraints = attr_constraints(program, gs, obj);
+                    if !constraints.is_empty() {
+                        docs.push((
+                            constraints
+                                .iter()
+                                .map(|c| format!("Constraint: {}", c))
+                                .collect::<Vec<String>>()
+                                .join("\n\n"),
+                            MarkedStringType::String,
+                        ));
                     }
                 }
+                _ => {}
+            }
+        }
+        kclvm_sema::core::symbol::SymbolKind::Value
+        | kclvm_sema::core::symbol::SymbolKind::Function => match &obj.get_sema_info().ty {
+            Some(ty) => match &ty.kind {
+                kclvm_sema::ty::TypeKind::Function(func_ty) => {
+                    docs.append(&mut build_func_hover_content(
+                        func_ty.clone(),
+                        obj.get_name().clone(),
+                    ));
+                }
                 _ => {
-                    let ty_str = match &obj.get_sema_info().ty {
-                        Some(ty) => ty.ty_str(),
-                        None => "".to_string(),
-                    };
                     docs.push((
-                        format!("{}: {}", &obj.get_name(), ty_str),
+                        format!("{}: {}", &obj.get_name(), ty.ty_str()),
                         MarkedStringType::LanguageString,
                     ));
                 }
             },
-            None => {}
+            _ => {}
         },
-        None => {}
+        kclvm_sema::core::symbol::SymbolKind::Expression => {}
+        kclvm_sema::core::symbol::SymbolKind::Comment => {}
+        kclvm_sema::core::symbol::SymbolKind::Decorator => {
+            match BUILTIN_DECORATORS.get(&obj.get_name()) {
+                Some(ty) => {
+                    let mut hover_content =
+                        build_func_hover_content(ty.into_func_type(), obj.get_name().clone());
+
+                    docs.append(&mut hover_content);
+                }
+                None => todo!(),
+            }
+        }
+        kclvm_sema::core::symbol::SymbolKind::Package => {
+            let pkg = obj.get_name();
+            let alias = gs
+                .get_packages()
+                .get_module_info(filename)
+                .and_then(|module_info| module_info.get_import_alias(&pkg));
+            docs.push(package_doc(&pkg, alias));
+        }
+        _ => {
+            let ty_str = match &obj.get_sema_info().ty {
+                Some(ty) => ty.ty_str(),
+                None => "".to_string(),
+            };
+            docs.push((
+                format!("{}: {}", &obj.get_name(), ty_str),
+                MarkedStringType::LanguageString,
+            ));
+        }
+    }
+    docs
+}
+
+/// Returns the constraints of the schema attribute from the check blocks of its schema and the
+/// base schemas, which are inherited by the schema. A check with a failure message is described
+/// by the message, otherwise by the check expression.
+fn attr_constraints(program: &Program, gs: &GlobalState, attr: &KCLSymbol) -> Vec<String> {
+    let schema_ty = match attr
+        .get_owner()
+        .and_then(|owner| gs.get_symbols().get_symbol(owner))
+        .and_then(|schema| schema.get_sema_info().ty.clone())
+    {
+        Some(ty) if ty.is_schema() => ty.into_schema_type(),
+        _ => return vec![],
+    };
+    let attr_name = attr.get_name();
+    let mut constraints = vec![];
+    let mut schema_ty = Some(&schema_ty);
+    while let Some(ty) = schema_ty {
+        if let Ok(Some(module)) = program.get_module(&ty.filename) {
+            for stmt in &module.body {
+                if let ast::Stmt::Schema(schema_stmt) = &stmt.node {
+                    if schema_stmt.name.node == ty.name {
+                        constraints.append(&mut check_constraints(schema_stmt, &attr_name));
+                    }
+                }
+            }
+        }
+        schema_ty = ty.base.as_deref();
+    }
+    constraints
+}
+
+/// Returns the constraints of the checks of the schema statement referencing the attribute.
+fn check_constraints(schema_stmt: &ast::SchemaStmt, attr_name: &String) -> Vec<String> {
+    let mut constraints = vec![];
+    for check in &schema_stmt.checks {
+        let mut collector = IdentifierCollector::default();
+        collector.walk_expr(&check.node.test.node);
+        if !collector.names.contains(attr_name) {
+            continue;
+        }
+        let constraint = match &check.node.msg {
+            Some(msg) => match &msg.node {
+                ast::Expr::StringLit(string_lit) => string_lit.value.clone(),
+                _ => print_ast_node(ASTNode::Expr(msg)),
+            },
+            None => print_ast_node(ASTNode::Expr(&check.node.test)),
+        };
+        constraints.push(constraint);
+    }
+    constraints
+}
+
+/// The import of the package, e.g. `import a.b as c`.
+fn package_doc(pkg: &str, alias: Option<String>) -> (String, MarkedStringType) {
+    let content = match alias {
+        Some(alias) => format!("import {} as {}", pkg, alias),
+        None => format!("import {}", pkg),
+    };
+    (content, MarkedStringType::LanguageString)
+}
+
+/// Collects the root names of all the identifiers in an expression.
+#[derive(Default)]
+struct IdentifierCollector {
+    names: Vec<String>,
+}
+
+impl MutSelfWalker for IdentifierCollector {
+    fn walk_identifier(&mut self, identifier: &ast::Identifier) {
+        if let Some(name) = identifier.names.first() {
+            self.names.push(name.node.clone());
+        }
     }
-    docs_to_hover(docs)
 }
 
 fn ty_hover_content(ty: &Type) -> String {
@@ -209,6 +379,59 @@ fn docs_to_hover(docs: Vec<(String, MarkedStringType)>) -> Option<lsp_types::Hov
     }
 }
 
+/// Converts the hover to the plain text for the clients which do not render Markdown, the code
+/// fences are unwrapped and the Markdown marks such as the backticks and the bullets are removed.
+pub(crate) fn hover_to_plain_text(hover: Hover) -> Hover {
+    let docs = match hover.contents {
+        HoverContents::Scalar(doc) => vec![doc],
+        HoverContents::Array(docs) => docs,
+        HoverContents::Markup(markup) => match markup.kind {
+            MarkupKind::PlainText => {
+                return Hover {
+                    contents: HoverContents::Markup(markup),
+                    range: hover.range,
+                }
+            }
+            MarkupKind::Markdown => vec![MarkedString::String(markup.value)],
+        },
+    };
+    let value = docs
+        .into_iter()
+        .map(|doc| match doc {
+            MarkedString::String(markdown) => strip_markdown(&markdown),
+            MarkedString::LanguageString(code) => code.value,
+        })
+        .collect::<Vec<String>>()
+        .join("\n\n");
+    Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::PlainText,
+            value,
+        }),
+        range: hover.range,
+    }
+}
+
+/// Removes the Markdown marks of the text line by line, e.g. `- **name**: \`str\`` is
+/// flattened to `name: str`. The lines of the code fences are removed and the code is kept.
+fn strip_markdown(markdown: &str) -> String {
+    markdown
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("```"))
+        .map(|line| {
+            let text = line.trim_start();
+            let indent = &line[..line.len() - text.len()];
+            let text = text.trim_start_matches(['#', '>']).trim_start();
+            let text = ["- ", "* ", "+ "]
+                .iter()
+                .find_map(|bullet| text.strip_prefix(bullet))
+                .unwrap_or(text);
+            format!("{}{}", indent, text.replace("**", "").replace('`', ""))
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
 // Build hover content for function call
 // ```
 // pkg
@@ -263,19 +486,19 @@ mod tests {
     use std::path::PathBuf;
 
     use kclvm_error::Position as KCLPos;
-    use lsp_types::{LanguageString, MarkedString};
+    use lsp_types::{HoverContents, LanguageString, MarkedString, MarkupKind};
     use proc_macro_crate::bench_test;
 
     use crate::tests::compile_test_file;
 
-    use super::hover;
+    use super::{hover, hover_content, hover_to_plain_text};
+    use crate::goto_def::find_def;
 
     #[test]
     fn schema_doc_hover_test() {
         let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
 
-        let (file, _program, _, gs, _) =
-            compile_test_file("src/test_data/goto_def_test/goto_def.k");
+        let (file, program, _, gs, _) = compile_test_file("src/test_data/goto_def_test/goto_def.k");
 
         let mut expected_path = path;
         expected_path.push("src/test_data/goto_def_test/pkg/schema_def.k");
@@ -286,7 +509,7 @@ mod tests {
             line: 4,
             column: Some(11),
         };
-        let got = hover(&pos, &gs).unwrap();
+        let got = hover(&pos, &program, &gs).unwrap();
         match got.contents {
             lsp_types::HoverContents::Array(vec) => {
                 if let MarkedString::String(s) = vec[0].clone() {
@@ -313,7 +536,7 @@ mod tests {
             line: 5,
             column: Some(7),
         };
-        let got = hover(&pos, &gs).unwrap();
+        let got = hover(&pos, &program, &gs).unwrap();
         match got.contents {
             lsp_types::HoverContents::Scalar(marked_string) => {
                 if let MarkedString::LanguageString(s) = marked_string {
@@ -324,6 +547,32 @@ mod tests {
         }
     }
 
+    #[test]
+    #[bench_test]
+    fn hover_to_plain_text_test() {
+        let hover = docs_to_hover(vec![
+            (
+                "schema Server:\n    port: int".to_string(),
+                MarkedStringType::LanguageString,
+            ),
+            (
+                "# Server\n\nThe **server** config:\n- `port`: the port\n\n```kcl\ns = Server {port = 80}\n```".to_string(),
+                MarkedStringType::String,
+            ),
+        ])
+        .unwrap();
+        match hover_to_plain_text(hover).contents {
+            HoverContents::Markup(markup) => {
+                assert_eq!(markup.kind, MarkupKind::PlainText);
+                assert_eq!(
+                    markup.value,
+                    "schema Server:\n    port: int\n\nServer\n\nThe server config:\nport: the port\n\ns = Server {port = 80}"
+                );
+            }
+            _ => unreachable!("test error"),
+        }
+    }
+
     #[test]
     #[bench_test]
     fn test_docs_to_hover_multiple_docs() {
@@ -372,14 +621,14 @@ mod tests {
     #[test]
     #[bench_test]
     fn schema_doc_hover_test1() {
-        let (file, _program, _, gs, _) = compile_test_file("src/test_data/hover_test/hover.k");
+        let (file, program, _, gs, _) = compile_test_file("src/test_data/hover_test/hover.k");
 
         let pos = KCLPos {
             filename: file.clone(),
             line: 16,
             column: Some(8),
         };
-        let got = hover(&pos, &gs).unwrap();
+        let got = hover(&pos, &program, &gs).unwrap();
 
         match got.contents {
             lsp_types::HoverContents::Array(vec) => {
@@ -400,14 +649,14 @@ mod tests {
     #[test]
     #[bench_test]
     fn schema_attr_hover_test() {
-        let (file, _program, _, gs, _) = compile_test_file("src/test_data/hover_test/hover.k");
+        let (file, program, _, gs, _) = compile_test_file("src/test_data/hover_test/hover.k");
 
         let pos = KCLPos {
             filename: file.clone(),
             line: 17,
             column: Some(7),
         };
-        let got = hover(&pos, &gs).unwrap();
+        let got = hover(&pos, &program, &gs).unwrap();
 
         match got.contents {
             lsp_types::HoverContents::Array(vec) => {
@@ -426,7 +675,7 @@ mod tests {
             line: 18,
             column: Some(7),
         };
-        let got = hover(&pos, &gs).unwrap();
+        let got = hover(&pos, &program, &gs).unwrap();
 
         match got.contents {
             lsp_types::HoverContents::Array(vec) => {
@@ -444,14 +693,14 @@ mod tests {
     #[test]
     #[bench_test]
     fn lambda_doc_hover_test() {
-        let (file, _program, _, gs, _) = compile_test_file("src/test_data/hover_test/lambda.k");
+        let (file, program, _, gs, _) = compile_test_file("src/test_data/hover_test/lambda.k");
 
         let pos = KCLPos {
             filename: file.clone(),
             line: 1,
             column: Some(1),
         };
-        let got = hover(&pos, &gs).unwrap();
+        let got = hover(&pos, &program, &gs).unwrap();
 
         match got.contents {
             lsp_types::HoverContents::Array(vec) => {
@@ -469,14 +718,14 @@ mod tests {
     #[test]
     #[bench_test]
     fn func_def_hover() {
-        let (file, _program, _, gs, _) = compile_test_file("src/test_data/hover_test/hover.k");
+        let (file, program, _, gs, _) = compile_test_file("src/test_data/hover_test/hover.k");
 
         let pos = KCLPos {
             filename: file.clone(),
             line: 22,
             column: Some(18),
         };
-        let got = hover(&pos, &gs).unwrap();
+        let got = hover(&pos, &program, &gs).unwrap();
 
         match got.contents {
             lsp_types::HoverContents::Array(vec) => {
@@ -499,7 +748,7 @@ mod tests {
             line: 23,
             column: Some(14),
         };
-        let got = hover(&pos, &gs).unwrap();
+        let got = hover(&pos, &program, &gs).unwrap();
 
         match got.contents {
             lsp_types::HoverContents::Array(vec) => {
@@ -525,7 +774,7 @@ mod tests {
             line: 25,
             column: Some(4),
         };
-        let got = hover(&pos, &gs).unwrap();
+        let got = hover(&pos, &program, &gs).unwrap();
 
         match got.contents {
             lsp_types::HoverContents::Array(vec) => {
@@ -544,13 +793,13 @@ mod tests {
     #[test]
     #[bench_test]
     fn complex_select_hover() {
-        let (file, _program, _, gs, _) = compile_test_file("src/test_data/hover_test/fib.k");
+        let (file, program, _, gs, _) = compile_test_file("src/test_data/hover_test/fib.k");
         let pos = KCLPos {
             filename: file.clone(),
             line: 14,
             column: Some(22),
         };
-        let got = hover(&pos, &gs).unwrap();
+        let got = hover(&pos, &program, &gs).unwrap();
         match got.contents {
             lsp_types::HoverContents::Scalar(marked_string) => {
                 if let MarkedString::LanguageString(s) = marked_string {
@@ -564,14 +813,14 @@ mod tests {
     #[test]
     #[bench_test]
     fn assignment_ty_in_lambda_hover() {
-        let (file, _program, _, gs, _) =
+        let (file, program, _, gs, _) =
             compile_test_file("src/test_data/hover_test/ty_in_lambda.k");
         let pos = KCLPos {
             filename: file.clone(),
             line: 3,
             column: Some(8),
         };
-        let got = hover(&pos, &gs).unwrap();
+        let got = hover(&pos, &program, &gs).unwrap();
         match got.contents {
             lsp_types::HoverContents::Scalar(marked_string) => {
                 if let MarkedString::LanguageString(s) = marked_string {
@@ -585,13 +834,13 @@ mod tests {
     #[test]
     #[bench_test]
     fn str_var_func_hover() {
-        let (file, _program, _, gs, _) = compile_test_file("src/test_data/hover_test/hover.k");
+        let (file, program, _, gs, _) = compile_test_file("src/test_data/hover_test/hover.k");
         let pos = KCLPos {
             filename: file.clone(),
             line: 28,
             column: Some(12),
         };
-        let got = hover(&pos, &gs).unwrap();
+        let got = hover(&pos, &program, &gs).unwrap();
         match got.contents {
             lsp_types::HoverContents::Array(vec) => {
                 assert_eq!(vec.len(), 3);
@@ -612,13 +861,13 @@ mod tests {
     #[test]
     #[bench_test]
     fn import_pkg_hover() {
-        let (file, _program, _, gs, _) = compile_test_file("src/test_data/hover_test/import_pkg.k");
+        let (file, program, _, gs, _) = compile_test_file("src/test_data/hover_test/import_pkg.k");
         let pos = KCLPos {
             filename: file.clone(),
             line: 3,
             column: Some(7),
         };
-        let got = hover(&pos, &gs).unwrap();
+        let got = hover(&pos, &program, &gs).unwrap();
         match got.contents {
             lsp_types::HoverContents::Array(vec) => {
                 assert_eq!(vec.len(), 2);
@@ -633,16 +882,57 @@ mod tests {
         }
     }
 
+    #[test]
+    #[bench_test]
+    fn import_alias_hover() {
+        let (file, program, _, gs, _) = compile_test_file("src/test_data/import_alias/main.k");
+        // n = c.name
+        let pos = KCLPos {
+            filename: file.clone(),
+            line: 3,
+            column: Some(4),
+        };
+        let got = hover(&pos, &program, &gs).unwrap();
+        match got.contents {
+            lsp_types::HoverContents::Scalar(MarkedString::LanguageString(s)) => {
+                assert_eq!(s.value, "import pkg.sub as c");
+            }
+            _ => unreachable!("test error"),
+        }
+    }
+
+    #[test]
+    #[bench_test]
+    fn mixed_import_alias_hover() {
+        let (file, program, _, gs, _) =
+            compile_test_file("src/test_data/import_alias_mixed/main.k");
+        // n = c.name and m = sub.name
+        for (line, expect) in [(4, "import pkg.sub as c"), (5, "import pkg.sub")] {
+            let pos = KCLPos {
+                filename: file.clone(),
+                line,
+                column: Some(4),
+            };
+            let got = hover(&pos, &program, &gs).unwrap();
+            match got.contents {
+                lsp_types::HoverContents::Scalar(MarkedString::LanguageString(s)) => {
+                    assert_eq!(s.value, expect);
+                }
+                _ => unreachable!("test error"),
+            }
+        }
+    }
+
     #[test]
     #[bench_test]
     fn expr_after_config_if_hover() {
-        let (file, _program, _, gs, _) = compile_test_file("src/test_data/hover_test/hover.k");
+        let (file, program, _, gs, _) = compile_test_file("src/test_data/hover_test/hover.k");
         let pos = KCLPos {
             filename: file.clone(),
             line: 41,
             column: Some(13),
         };
-        let got = hover(&pos, &gs).unwrap();
+        let got = hover(&pos, &program, &gs).unwrap();
         match got.contents {
             lsp_types::HoverContents::Scalar(marked_string) => {
                 if let MarkedString::LanguageString(s) = marked_string {
@@ -656,13 +946,13 @@ mod tests {
     #[test]
     #[bench_test]
     fn schema_scope_variable_hover() {
-        let (file, _program, _, gs, _) = compile_test_file("src/test_data/hover_test/fib.k");
+        let (file, program, _, gs, _) = compile_test_file("src/test_data/hover_test/fib.k");
         let pos = KCLPos {
             filename: file.clone(),
             line: 3,
             column: Some(11),
         };
-        let got = hover(&pos, &gs).unwrap();
+        let got = hover(&pos, &program, &gs).unwrap();
         match got.contents {
             lsp_types::HoverContents::Scalar(marked_string) => {
                 if let MarkedString::LanguageString(s) = marked_string {
@@ -676,13 +966,13 @@ mod tests {
     #[test]
     #[bench_test]
     fn decorator_hover() {
-        let (file, _program, _, gs, _) = compile_test_file("src/test_data/hover_test/decorator.k");
+        let (file, program, _, gs, _) = compile_test_file("src/test_data/hover_test/decorator.k");
         let pos = KCLPos {
             filename: file.clone(),
             line: 1,
             column: Some(1),
         };
-        let got = hover(&pos, &gs).unwrap();
+        let got = hover(&pos, &program, &gs).unwrap();
         let expect_content = vec![
             MarkedString::LanguageString(LanguageString {
                 language: "KCL".to_string(),
@@ -704,7 +994,7 @@ mod tests {
             line: 3,
             column: Some(8),
         };
-        let got = hover(&pos, &gs).unwrap();
+        let got = hover(&pos, &program, &gs).unwrap();
         match got.contents {
             lsp_types::HoverContents::Array(vec) => {
                 assert_eq!(vec, expect_content);
@@ -716,13 +1006,13 @@ mod tests {
     #[test]
     #[bench_test]
     fn inherit_schema_attr_hover() {
-        let (file, _program, _, gs, _) = compile_test_file("src/test_data/hover_test/inherit.k");
+        let (file, program, _, gs, _) = compile_test_file("src/test_data/hover_test/inherit.k");
         let pos = KCLPos {
             filename: file.clone(),
             line: 5,
             column: Some(9),
         };
-        let got = hover(&pos, &gs).unwrap();
+        let got = hover(&pos, &program, &gs).unwrap();
 
         let expect_content = vec![
             MarkedString::String("__main__".to_string()),
@@ -741,17 +1031,116 @@ mod tests {
         }
     }
 
+    #[test]
+    #[bench_test]
+    fn schema_attr_check_msg_hover() {
+        let (file, program, _, gs, _) = compile_test_file("src/test_data/hover_test/check_msg.k");
+        let pos = KCLPos {
+            filename: file.clone(),
+            line: 2,
+            column: Some(5),
+        };
+        let got = hover(&pos, &program, &gs).unwrap();
+        let expect_content = vec![
+            MarkedString::LanguageString(LanguageString {
+                language: "KCL".to_string(),
+                value: "port: int".to_string(),
+            }),
+            MarkedString::String("Constraint: port must be between 1 and 65535".to_string()),
+        ];
+        match got.contents {
+            lsp_types::HoverContents::Array(vec) => {
+                assert_eq!(vec, expect_content);
+            }
+            _ => unreachable!("test error"),
+        }
+
+        // The check without a failure message falls back to the expression.
+        let pos = KCLPos {
+            filename: file.clone(),
+            line: 3,
+            column: Some(5),
+        };
+        let got = hover(&pos, &program, &gs).unwrap();
+        let expect_content = vec![
+            MarkedString::LanguageString(LanguageString {
+                language: "KCL".to_string(),
+                value: "name: str".to_string(),
+            }),
+            MarkedString::String("Constraint: len(name) > 0".to_string()),
+        ];
+        match got.contents {
+            lsp_types::HoverContents::Array(vec) => {
+                assert_eq!(vec, expect_content);
+            }
+            _ => unreachable!("test error"),
+        }
+
+        // The checks of the base schemas are inherited.
+        let pos = KCLPos {
+            filename: file.clone(),
+            line: 16,
+            column: Some(5),
+        };
+        let got = hover(&pos, &program, &gs).unwrap();
+        let expect_content = vec![
+            MarkedString::LanguageString(LanguageString {
+                language: "KCL".to_string(),
+                value: "replicas: int".to_string(),
+            }),
+            MarkedString::String(
+                "Constraint: replicas <= 10\n\nConstraint: replicas must be positive".to_string(),
+            ),
+        ];
+        match got.contents {
+            lsp_types::HoverContents::Array(vec) => {
+                assert_eq!(vec, expect_content);
+            }
+            _ => unreachable!("test error"),
+        }
+    }
+
+    #[test]
+    #[bench_test]
+    fn schema_attr_hover_content() {
+        let (file, program, _, gs, _) = compile_test_file("src/test_data/hover_test/check_msg.k");
+        let pos = KCLPos {
+            filename: file.clone(),
+            line: 2,
+            column: Some(5),
+        };
+        let def = find_def(&pos, &gs, true).unwrap();
+        let content = hover_content(def, &file, &program, &gs).unwrap();
+        assert_eq!(content.detail, "port: int");
+        assert_eq!(
+            content.markdown,
+            "```kcl\nport: int\n```\n\nConstraint: port must be between 1 and 65535"
+        );
+
+        // The LSP hover wraps the same content.
+        match hover_to_plain_text(hover(&pos, &program, &gs).unwrap()).contents {
+            HoverContents::Markup(markup) => {
+                assert_eq!(markup.value, content.plain);
+                assert_eq!(
+                    content.plain,
+                    "port: int\n\nConstraint: port must be between 1 and 65535"
+                );
+            }
+            _ => unreachable!("test error"),
+        }
+    }
+
     #[test]
     #[bench_test]
     fn dict_key_in_schema() {
-        let (file, _program, _, gs, _) =
+        let (file, program, _, gs, _) =
             compile_test_file("src/test_data/hover_test/dict_key_in_schema/dict_key_in_schema.k");
         let pos = KCLPos {
             filename: file.clone(),
             line: 5,
             column: Some(5),
         };
-        let got = hover(&pos, &gs).unwrap();
+        let got = hover(&pos, &program, &gs).unwrap();
 
         match got.contents {
             lsp_types::HoverContents::Scalar(marked_string) => {
@@ -767,7 +1156,7 @@ mod tests {
             line: 9,
             column: Some(5),
         };
-        let got = hover(&pos, &gs).unwrap();
+        let got = hover(&pos, &program, &gs).unwrap();
         let expected =
             lsp_types::HoverContents::Scalar(MarkedString::LanguageString(LanguageString {
                 language: "KCL".to_string(),
@@ -780,7 +1169,7 @@ mod tests {
             line: 13,
             column: Some(5),
         };
-        let got = hover(&pos, &gs).unwrap();
+        let got = hover(&pos, &program, &gs).unwrap();
         let expected =
             lsp_types::HoverContents::Scalar(MarkedString::LanguageString(LanguageString {
                 language: "KCL".to_string(),
@@ -792,7 +1181,7 @@ mod tests {
     #[test]
     #[bench_test]
     fn schema_doc_with_examples_hover_test() {
-        let (file, _program, _, gs, _) =
+        let (file, program, _, gs, _) =
             compile_test_file("src/test_data/hover_test/schema_with_examples.k");
 
         let pos = KCLPos {
@@ -800,7 +1189,7 @@ mod tests {
             line: 1,
             column: Some(8),
         };
-        let got = hover(&pos, &gs).unwrap();
+        let got = hover(&pos, &program, &gs).unwrap();
 
         let expect_content = vec![
             lsp_types::MarkedString::String("__main__".to_string()),
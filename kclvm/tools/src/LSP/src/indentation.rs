@@ -0,0 +1,317 @@
+//! The opt-in lint of the files indented by different widths, e.g. a schema indented by 4 spaces
+//! and a config indented by 2 spaces in the same file, or indented by both tabs and spaces. The
+//! width of a file is the configured one, or the width of the most indented blocks of the file.
+
+use std::collections::HashMap;
+
+use kclvm_ast_pretty::{Config, NoHook, Printer};
+use kclvm_error::{Diagnostic, DiagnosticId, Level, Position as KCLPos, WarningKind};
+use kclvm_parser::parse_single_file;
+use lsp_types::{CodeAction, CodeActionKind, CodeActionOrCommand, Position, Range, TextEdit, Url};
+
+use crate::quick_fix::convert_code_to_kcl_diag_id;
+
+/// Returns the leading whitespaces of the lines which start the statements or the entries of the
+/// blocks, with the 1-based line numbers. The blank lines, the lines in the multi-line strings
+/// and the continuation lines are skipped, which are the lines after a backslash, in parentheses,
+/// or in a bracket followed by the content on its line, e.g. the aligned arguments of
+/// `f(a,\n  b)` or items of `[1,\n 2]`.
+fn indented_lines(src: &str) -> Vec<(u64, &str)> {
+    let mut lines = vec![];
+    // The open brackets with whether they end their lines, e.g. `{` of `a = {`.
+    let mut brackets: Vec<(u8, bool)> = vec![];
+    let mut long_string: Option<&str> = None;
+    let mut continued = false;
+    for (idx, line) in src.lines().enumerate() {
+        let in_block = match brackets.last() {
+            None => true,
+            Some((b'(', _)) => false,
+            Some((_, ends_line)) => *ends_line,
+        };
+        if long_string.is_none() && !continued && in_block && !line.trim().is_empty() {
+            let content = line.trim_start_matches([' ', '\t']);
+            lines.push((idx as u64 + 1, &line[..line.len() - content.len()]));
+        }
+        continued = false;
+        let bytes = line.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            if let Some(quotes) = long_string {
+                match line[i..].find(quotes) {
+                    Some(end) => {
+                        i += end + quotes.len();
+                        long_string = None;
+                        continue;
+                    }
+                    None => break,
+                }
+            }
+            match bytes[i] {
+                b'#' => break,
+                quote @ (b'"' | b'\'') => {
+                    let quotes = if quote == b'"' { "\"\"\"" } else { "'''" };
+                    if line[i..].starts_with(quotes) {
+                        long_string = Some(quotes);
+                        i += quotes.len();
+                        continue;
+                    }
+                    // The single-quoted strings end at the line end.
+                    i += 1;
+                    while i < bytes.len() && bytes[i] != quote {
+                        i += if bytes[i] == b'\\' { 2 } else { 1 };
+                    }
+                }
+                bracket @ (b'(' | b'[' | b'{') => {
+                    let rest = line[i + 1..].trim_start();
+                    brackets.push((bracket, rest.is_empty() || rest.starts_with('#')));
+                }
+                b')' | b']' | b'}' => {
+                    brackets.pop();
+                }
+                b'\\' if i + 1 == bytes.len() => continued = true,
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+    lines
+}
+
+/// Returns the widths by which the space indented lines are indented deeper than the previous
+/// lines, with the line numbers.
+fn indent_steps(src: &str) -> Vec<(u64, usize)> {
+    let mut steps = vec![];
+    let mut prev = 0;
+    for (line, leading) in indented_lines(src) {
+        if leading.contains('\t') {
+            continue;
+        }
+        if leading.len() > prev {
+            steps.push((line, leading.len() - prev));
+        }
+        prev = leading.len();
+    }
+    steps
+}
+
+/// Returns the indentation width of the file, which is `configured` if set, otherwise the most
+/// used width, the narrower width wins a tie. `None` if no line is indented.
+pub(crate) fn expected_indent_width(src: &str, configured: Option<usize>) -> Option<usize> {
+    if configured.is_some() {
+        return configured;
+    }
+    let mut counts: HashMap<usize, usize> = HashMap::new();
+    for (_, step) in indent_steps(src) {
+        *counts.entry(step).or_default() += 1;
+    }
+    counts
+        .into_iter()
+        .max_by(|(a, a_count), (b, b_count)| a_count.cmp(b_count).then(b.cmp(a)))
+        .map(|(width, _)| width)
+}
+
+/// Returns the warning of the file if it is indented by both tabs and spaces, or by a width other
+/// than the expected one. The warning is at the indentation of the first inconsistent line.
+pub(crate) fn inconsistent_indentation_diag(
+    filename: &str,
+    src: &str,
+    configured_width: Option<usize>,
+) -> Option<Diagnostic> {
+    let lines = indented_lines(src);
+    let has_tabs = lines.iter().any(|(_, leading)| leading.contains('\t'));
+    let has_spaces = lines.iter().any(|(_, leading)| leading.contains(' '));
+    let (line, message) = if has_tabs && has_spaces {
+        let (line, _) = lines.iter().find(|(_, leading)| leading.contains('\t'))?;
+        (
+            *line,
+            "The file is indented by both tabs and spaces".to_string(),
+        )
+    } else {
+        let expected = expected_indent_width(src, configured_width)?;
+        let (line, step) = indent_steps(src)
+            .into_iter()
+            .find(|(_, step)| *step != expected)?;
+        (
+            line,
+            format!(
+                "Inconsistent indentation of {} spaces, the file is indented by {} spaces",
+                step, expected
+            ),
+        )
+    };
+    let leading = lines
+        .iter()
+        .find(|(l, _)| *l == line)
+        .map_or(0, |(_, leading)| leading.len());
+    let pos = |column| KCLPos {
+        filename: filename.to_string(),
+        line,
+        column: Some(column as u64),
+    };
+    Some(Diagnostic::new_with_code(
+        Level::Warning,
+        &message,
+        None,
+        (pos(0), pos(leading)),
+        Some(DiagnosticId::Warning(
+            WarningKind::InconsistentIndentationWarning,
+        )),
+        None,
+    ))
+}
+
+/// Returns the quick fix formatting the document with the expected indentation width if an
+/// inconsistent indentation warning is in `diags`. The document which can not be parsed is not
+/// formatted.
+pub(crate) fn normalize_indentation_code_action(
+    uri: &Url,
+    file: &str,
+    src: &str,
+    diags: &[lsp_types::Diagnostic],
+    configured_width: Option<usize>,
+) -> Option<CodeActionOrCommand> {
+    let diag = diags.iter().find(|diag| {
+        diag.code.as_ref().and_then(convert_code_to_kcl_diag_id)
+            == Some(DiagnosticId::Warning(
+                WarningKind::InconsistentIndentationWarning,
+            ))
+    })?;
+    let width = expected_indent_width(src, configured_width)?;
+    let module = match parse_single_file(file, Some(src.to_string())) {
+        Ok(result) if result.errors.is_empty() => result.module,
+        _ => return None,
+    };
+    let mut printer = Printer::new(
+        Config {
+            indent_len: width,
+            ..Default::default()
+        },
+        &NoHook,
+    );
+    printer.write_module(&module);
+    if printer.out == src {
+        return None;
+    }
+    let mut changes = HashMap::new();
+    changes.insert(
+        uri.clone(),
+        vec![TextEdit {
+            range: Range::new(
+                Position::new(0, 0),
+                Position::new(i32::MAX as u32, i32::MAX as u32),
+            ),
+            new_text: printer.out,
+        }],
+    );
+    Some(CodeActionOrCommand::CodeAction(CodeAction {
+        title: format!("Normalize indentation to {} spaces", width),
+        kind: Some(CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![diag.clone()]),
+        edit: Some(lsp_types::WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{CodeActionOrCommand, Url};
+    use proc_macro_crate::bench_test;
+
+    use super::{inconsistent_indentation_diag, normalize_indentation_code_action};
+    use crate::to_lsp::kcl_diag_to_lsp_diags_by_file;
+
+    const MIXED_WIDTHS: &str = "schema Person:\n    name: str\n    age: int\n\nperson = {\n  name = \"Alice\"\n  age = 18\n}\n\nconfig = {\n    name = \"kcl\"\n}\n";
+
+    #[test]
+    #[bench_test]
+    fn inconsistent_indentation_diag_test() {
+        // The file is indented by 4 spaces in the most blocks.
+        let diag = inconsistent_indentation_diag("main.k", MIXED_WIDTHS, None).unwrap();
+        let (start, end) = &diag.messages[0].range;
+        assert_eq!(
+            diag.messages[0].message,
+            "Inconsistent indentation of 2 spaces, the file is indented by 4 spaces"
+        );
+        assert_eq!(
+            (start.line, start.column, end.column),
+            (6, Some(0), Some(2))
+        );
+
+        let diag = inconsistent_indentation_diag("main.k", MIXED_WIDTHS, Some(2)).unwrap();
+        assert_eq!(diag.messages[0].range.0.line, 2);
+
+        let diag = inconsistent_indentation_diag(
+            "main.k",
+            "a = {\n\tb = 1\n}\nc = {\n    d = 1\n}\n",
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            diag.messages[0].message,
+            "The file is indented by both tabs and spaces"
+        );
+        assert_eq!(diag.messages[0].range.0.line, 2);
+
+        assert!(inconsistent_indentation_diag("main.k", "a = {\n    b = 1\n}\n", None).is_none());
+    }
+
+    #[test]
+    #[bench_test]
+    fn continuation_lines_test() {
+        // The aligned arguments and items and the continued lines are not indentation steps.
+        let src = r#"schema Person:
+    name: str
+    age: int
+
+person = Person {
+    name = "Alice"
+    age = max(18,
+              20)
+}
+values = [1,
+          2]
+labels = {"a": "(",
+          "b": "[ # not a comment"}
+total = 1 + \
+        2
+"#;
+        assert!(inconsistent_indentation_diag("main.k", src, None).is_none());
+        // The hanging arguments in parentheses are not the blocks either.
+        let src = "a = {\n    b = 1\n}\nc = max(\n  1,\n  2\n)\n";
+        assert!(inconsistent_indentation_diag("main.k", src, None).is_none());
+        // The blocks in the brackets ending their lines are still checked.
+        let src = "a = {\n    b = [\n      1\n    ]\n}\n";
+        let diag = inconsistent_indentation_diag("main.k", src, Some(4)).unwrap();
+        assert_eq!(diag.messages[0].range.0.line, 3);
+    }
+
+    #[test]
+    #[bench_test]
+    fn normalize_indentation_code_action_test() {
+        let path = std::env::current_dir().unwrap().join("main.k");
+        let file = path.to_str().unwrap();
+        let uri = Url::from_file_path(&path).unwrap();
+        let diags = kcl_diag_to_lsp_diags_by_file(
+            &inconsistent_indentation_diag(file, MIXED_WIDTHS, None).unwrap(),
+            file,
+            None,
+        );
+        let action = match normalize_indentation_code_action(&uri, file, MIXED_WIDTHS, &diags, None)
+        {
+            Some(CodeActionOrCommand::CodeAction(action)) => action,
+            _ => unreachable!("test error"),
+        };
+        assert_eq!(action.title, "Normalize indentation to 4 spaces");
+        let edits = &action.edit.unwrap().changes.unwrap()[&uri];
+        assert_eq!(edits.len(), 1);
+        assert!(edits[0]
+            .new_text
+            .contains("person = {\n    name = \"Alice\"\n    age = 18\n}"));
+        assert!(inconsistent_indentation_diag(file, &edits[0].new_text, None).is_none());
+
+        assert!(normalize_indentation_code_action(&uri, file, MIXED_WIDTHS, &[], None).is_none());
+    }
+}
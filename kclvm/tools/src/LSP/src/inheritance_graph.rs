@@ -0,0 +1,225 @@
+//! The inheritance graph of the schemas of a package, e.g.
+//!
+//! ```kcl
+//! protocol NameProtocol:
+//!     name: str
+//!
+//! mixin NameMixin for NameProtocol:
+//!     fullName: str = "${name} Doe"
+//!
+//! schema Base:
+//!     name: str
+//!
+//! schema Derived(Base):
+//!     mixin [NameMixin]
+//! ```
+//!
+//! `Derived` extends `Base` and mixes `NameMixin` in, and `NameMixin` implements `NameProtocol`.
+//! The schemas of the other packages are the nodes of the graph if they are inherited by the
+//! package. The inheritance cycles are reported by the resolver as errors, and the schemas and
+//! the edges in the cycles are flagged in the graph.
+
+use std::collections::HashSet;
+
+use indexmap::IndexMap;
+use kclvm_ast::ast::{self, Program};
+use kclvm_ast::pos::GetPos;
+use kclvm_error::Position as KCLPos;
+use kclvm_sema::core::global_state::GlobalState;
+use kclvm_sema::core::symbol::SymbolKind;
+use lsp_types::Location;
+
+use crate::goto_def::find_def;
+use crate::lsp_ext::{
+    InheritanceEdge, InheritanceGraphResult, InheritanceNode, InheritanceRelation,
+};
+use crate::to_lsp::lsp_location;
+
+/// Returns the inheritance graph of the package of the file, or `None` if the file is not in the
+/// program. The nodes and the edges are sorted, so the result is stable across the compilations.
+pub(crate) fn inheritance_graph(
+    program: &Program,
+    gs: &GlobalState,
+    file: &str,
+) -> Option<InheritanceGraphResult> {
+    let (pkgpath, _) = program
+        .pkgs
+        .iter()
+        .find(|(_, files)| files.iter().any(|filename| filename == file))?;
+    let mut nodes: IndexMap<String, Option<Location>> = IndexMap::new();
+    let mut edges = vec![];
+    for module in program.get_modules_for_pkg(pkgpath) {
+        let module = match module.read() {
+            Ok(module) => module,
+            Err(_) => continue,
+        };
+        for stmt in &module.body {
+            let schema_stmt = match &stmt.node {
+                ast::Stmt::Schema(schema_stmt) => schema_stmt,
+                _ => continue,
+            };
+            let from = match schema_node(&schema_stmt.name.get_pos(), gs, &mut nodes) {
+                Some(from) => from,
+                None => continue,
+            };
+            let targets = schema_stmt
+                .parent_name
+                .iter()
+                .map(|name| (name, InheritanceRelation::Extends))
+                .chain(
+                    schema_stmt
+                        .mixins
+                        .iter()
+                        .map(|name| (name, InheritanceRelation::Mixin)),
+                )
+                .chain(
+                    schema_stmt
+                        .for_host_name
+                        .iter()
+                        .map(|name| (name, InheritanceRelation::Implements)),
+                );
+            for (name, relation) in targets {
+                let to = name
+                    .node
+                    .names
+                    .last()
+                    .and_then(|name| schema_node(&name.get_pos(), gs, &mut nodes));
+                if let Some(to) = to {
+                    edges.push(InheritanceEdge {
+                        from: from.clone(),
+                        to,
+                        relation,
+                        in_cycle: false,
+                    });
+                }
+            }
+        }
+    }
+    edges.sort_by(|a, b| (&a.from, &a.to, a.relation).cmp(&(&b.from, &b.to, b.relation)));
+    edges.dedup();
+    let in_cycle: Vec<bool> = edges
+        .iter()
+        .map(|edge| reaches(&edges, &edge.to, &edge.from))
+        .collect();
+    for (edge, in_cycle) in edges.iter_mut().zip(in_cycle) {
+        edge.in_cycle = in_cycle;
+    }
+    let mut nodes: Vec<InheritanceNode> = nodes
+        .into_iter()
+        .map(|(name, location)| InheritanceNode {
+            in_cycle: edges.iter().any(|edge| edge.in_cycle && edge.from == name),
+            name,
+            location,
+        })
+        .collect();
+    nodes.sort_by(|a, b| a.name.cmp(&b.name));
+    Some(InheritanceGraphResult { nodes, edges })
+}
+
+/// Returns the qualified name of the schema defined at or referenced by the position, and adds
+/// the schema to the nodes.
+fn schema_node(
+    pos: &KCLPos,
+    gs: &GlobalState,
+    nodes: &mut IndexMap<String, Option<Location>>,
+) -> Option<String> {
+    let def = find_def(pos, gs, true)?;
+    if !matches!(def.get_kind(), SymbolKind::Schema) {
+        return None;
+    }
+    let name = gs.get_symbols().get_fully_qualified_name(def)?;
+    if !nodes.contains_key(&name) {
+        let location = gs.get_symbols().get_symbol(def).and_then(|symbol| {
+            let (start, end) = symbol.get_range();
+            lsp_location(start.filename.clone(), &start, &end)
+        });
+        nodes.insert(name.clone(), location);
+    }
+    Some(name)
+}
+
+/// Returns whether `to` is reachable from `from` along the edges.
+fn reaches(edges: &[InheritanceEdge], from: &str, to: &str) -> bool {
+    let mut visited = HashSet::new();
+    let mut stack = vec![from];
+    while let Some(name) = stack.pop() {
+        if name == to {
+            return true;
+        }
+        if visited.insert(name) {
+            stack.extend(
+                edges
+                    .iter()
+                    .filter(|edge| edge.from == name)
+                    .map(|edge| edge.to.as_str()),
+            );
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use proc_macro_crate::bench_test;
+
+    use super::inheritance_graph;
+    use crate::lsp_ext::InheritanceRelation;
+    use crate::tests::compile_test_file;
+
+    #[test]
+    #[bench_test]
+    fn inheritance_graph_test() {
+        let (file, program, _, gs, _) = compile_test_file("src/test_data/inheritance_graph/main.k");
+
+        let graph = inheritance_graph(&program, &gs, &file).unwrap();
+        let nodes: Vec<(&str, bool)> = graph
+            .nodes
+            .iter()
+            .map(|node| (node.name.as_str(), node.location.is_some()))
+            .collect();
+        assert_eq!(
+            nodes,
+            vec![
+                ("__main__.Base", true),
+                ("__main__.Derived", true),
+                ("__main__.NameMixin", true),
+                ("__main__.NameProtocol", true),
+            ]
+        );
+        let edges: Vec<(&str, &str, InheritanceRelation, bool)> = graph
+            .edges
+            .iter()
+            .map(|edge| {
+                (
+                    edge.from.as_str(),
+                    edge.to.as_str(),
+                    edge.relation,
+                    edge.in_cycle,
+                )
+            })
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                (
+                    "__main__.Derived",
+                    "__main__.Base",
+                    InheritanceRelation::Extends,
+                    false
+                ),
+                (
+                    "__main__.Derived",
+                    "__main__.NameMixin",
+                    InheritanceRelation::Mixin,
+                    false
+                ),
+                (
+                    "__main__.NameMixin",
+                    "__main__.NameProtocol",
+                    InheritanceRelation::Implements,
+                    false
+                ),
+            ]
+        );
+    }
+}
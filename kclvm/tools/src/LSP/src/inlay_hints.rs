@@ -84,6 +84,7 @@ fn generate_inlay_hint(hint: &SymbolHint) -> KCLInlayHint {
         }]),
         SymbolHintKind::VarHint(_) => None,
         SymbolHintKind::KeyTypeHint(_) => None,
+        SymbolHintKind::SchemaTypeHint(_) => None,
     };
     KCLInlayHint {
         position,
@@ -133,11 +134,21 @@ fn get_hint_label(hint: &SymbolHint) -> (InlayHintLabelPart, LspPosition, InlayH
             lsp_pos(&hint.pos),
             InlayHintKind::TYPE,
         ),
+        SymbolHintKind::SchemaTypeHint(ty) => (
+            InlayHintLabelPart {
+                value: format!("{ty} "),
+                ..Default::default()
+            },
+            lsp_pos(&hint.pos),
+            InlayHintKind::TYPE,
+        ),
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use lsp_types::{InlayHintKind, InlayHintLabel};
+
     use super::inlay_hints;
     use crate::tests::compile_test_file;
 
@@ -177,4 +188,35 @@ mod tests {
         test_config_key_ty_1,
         "src/test_data/inlay_hints/config_key1/config_key.k"
     );
+
+    #[test]
+    fn test_implicit_schema_hint() {
+        let (file, _, _, gs, _) =
+            compile_test_file("src/test_data/inlay_hints/implicit_schema/implicit_schema.k");
+        let res = inlay_hints(&file, &gs).unwrap();
+        let schema_hints: Vec<(u32, u32, String)> = res
+            .into_iter()
+            .filter_map(|hint| match hint.label {
+                InlayHintLabel::LabelParts(parts)
+                    if hint.kind == Some(InlayHintKind::TYPE)
+                        && !parts[0].value.starts_with(':') =>
+                {
+                    Some((
+                        hint.position.line,
+                        hint.position.character,
+                        parts[0].value.clone(),
+                    ))
+                }
+                _ => None,
+            })
+            .collect();
+        // The literals with the explicit schema names have no hints.
+        assert_eq!(
+            schema_hints,
+            vec![
+                (7, 16, "Service ".to_string()),
+                (12, 14, "Service ".to_string())
+            ]
+        );
+    }
 }
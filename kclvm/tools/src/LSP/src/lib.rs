@@ -1,20 +1,51 @@
 pub mod analysis;
+mod attribute_type;
+mod builtin_shadowing;
+pub mod call_hierarchy;
+mod canonical_imports;
 pub mod capabilities;
+mod check_messages;
+mod codemod;
+mod collapse_variable;
+mod commands;
 pub mod completion;
+mod conforming_schemas;
+mod diagnostic_summary;
 pub mod document_symbol;
+mod entry_closure;
+pub mod eval_expr;
+mod explain;
 pub mod find_refs;
+mod folding_range;
 pub mod formatting;
 pub mod goto_def;
 pub mod hover;
+mod indentation;
+mod inheritance_graph;
 pub mod inlay_hints;
+mod line_endings;
+mod locale;
+pub mod lsp_ext;
+mod magic_numbers;
+mod options;
+mod positioned_walker;
+mod provenance;
 pub mod quick_fix;
 pub mod rename;
+mod render_config;
 pub mod request;
+mod schema_coverage;
+mod schema_diff;
+mod schema_instantiations;
 pub mod semantic_token;
 pub mod signature_help;
+mod source_map;
+mod string_literals;
+mod validate_mod;
 
 pub mod app;
 pub mod compile;
+mod config;
 mod dispatcher;
 mod error;
 pub mod from_lsp;
@@ -23,5 +54,9 @@ mod state;
 #[cfg(test)]
 mod tests;
 pub mod to_lsp;
+mod todo_comments;
+mod unused_private;
+mod unused_schema;
 mod util;
+pub mod watch;
 mod word_index;
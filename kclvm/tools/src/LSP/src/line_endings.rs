@@ -0,0 +1,144 @@
+//! The opt-in lint of the files which are not in the LF line endings. The lexer treats `\r\n` as
+//! `\n`, so the positions in a CRLF file are the same as in the file normalized to LF, but a lone
+//! `\r` is not a line break of KCL while the editors break the line there.
+
+use std::collections::HashMap;
+
+use kclvm_error::{Diagnostic, DiagnosticId, Level, Position as KCLPos, WarningKind};
+use lsp_types::{CodeAction, CodeActionKind, CodeActionOrCommand, Position, Range, TextEdit, Url};
+
+use crate::quick_fix::convert_code_to_kcl_diag_id;
+
+/// Returns the warning of the file if it contains CRLF or CR line endings. The warning is at the
+/// first line ending which is not LF.
+pub(crate) fn line_ending_diag(filename: &str, src: &str) -> Option<Diagnostic> {
+    let first_cr = src.find('\r')?;
+    let has_lf = src
+        .match_indices('\n')
+        .any(|(idx, _)| idx == 0 || src.as_bytes()[idx - 1] != b'\r');
+    let has_crlf = src.contains("\r\n");
+    let has_cr = src
+        .match_indices('\r')
+        .any(|(idx, _)| src.as_bytes().get(idx + 1) != Some(&b'\n'));
+    let message = match (has_lf, has_crlf, has_cr) {
+        (false, true, false) => "The file uses CRLF line endings, expected LF",
+        (false, false, true) => "The file uses CR line endings, expected LF",
+        _ => "The file has mixed line endings, expected LF",
+    };
+    let line_start = src[..first_cr].rfind('\n').map_or(0, |idx| idx + 1);
+    let pos = KCLPos {
+        filename: filename.to_string(),
+        line: src[..first_cr].matches('\n').count() as u64 + 1,
+        column: Some(src[line_start..first_cr].chars().count() as u64),
+    };
+    Some(Diagnostic::new_with_code(
+        Level::Warning,
+        message,
+        None,
+        (pos.clone(), pos),
+        Some(DiagnosticId::Warning(WarningKind::LineEndingWarning)),
+        None,
+    ))
+}
+
+/// Rewrites all the CRLF and CR line endings to LF.
+pub(crate) fn normalize_line_endings(src: &str) -> String {
+    src.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+/// Returns the quick fix rewriting the document to the LF line endings if a line ending warning
+/// is in `diags`. It needs the document source, so it is not built by `quick_fix`.
+pub(crate) fn normalize_line_endings_code_action(
+    uri: &Url,
+    src: &str,
+    diags: &[lsp_types::Diagnostic],
+) -> Option<CodeActionOrCommand> {
+    let diag = diags.iter().find(|diag| {
+        diag.code.as_ref().and_then(convert_code_to_kcl_diag_id)
+            == Some(DiagnosticId::Warning(WarningKind::LineEndingWarning))
+    })?;
+    if !src.contains('\r') {
+        return None;
+    }
+    let mut changes = HashMap::new();
+    changes.insert(
+        uri.clone(),
+        vec![TextEdit {
+            range: Range::new(
+                Position::new(0, 0),
+                Position::new(i32::MAX as u32, i32::MAX as u32),
+            ),
+            new_text: normalize_line_endings(src),
+        }],
+    );
+    Some(CodeActionOrCommand::CodeAction(CodeAction {
+        title: "Normalize line endings to LF".to_string(),
+        kind: Some(CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![diag.clone()]),
+        edit: Some(lsp_types::WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{CodeActionOrCommand, Position, Range, Url};
+    use proc_macro_crate::bench_test;
+
+    use super::{line_ending_diag, normalize_line_endings_code_action};
+    use crate::to_lsp::kcl_diag_to_lsp_diags_by_file;
+
+    #[test]
+    #[bench_test]
+    fn line_ending_diag_test() {
+        let src = "schema Person:\r\n    name: str\r\n\r\np = Person {name = \"Alice\"}\r\n";
+        let diag = line_ending_diag("main.k", src).unwrap();
+        let (start, _) = &diag.messages[0].range;
+        assert_eq!(
+            diag.messages[0].message,
+            "The file uses CRLF line endings, expected LF"
+        );
+        assert_eq!((start.line, start.column), (1, Some(14)));
+
+        let diag = line_ending_diag("main.k", "a = 1\nb = 2\r\nc = 3\rd = 4\n").unwrap();
+        let (start, _) = &diag.messages[0].range;
+        assert_eq!(
+            diag.messages[0].message,
+            "The file has mixed line endings, expected LF"
+        );
+        assert_eq!((start.line, start.column), (2, Some(5)));
+
+        assert!(line_ending_diag("main.k", "a = 1\nb = 2\n").is_none());
+    }
+
+    #[test]
+    #[bench_test]
+    fn normalize_line_endings_code_action_test() {
+        let src = "a = 1\r\nb = 2\r\n";
+        let diags = kcl_diag_to_lsp_diags_by_file(
+            &line_ending_diag("main.k", src).unwrap(),
+            "main.k",
+            None,
+        );
+        let uri = Url::from_file_path(std::env::current_dir().unwrap().join("main.k")).unwrap();
+        let action = match normalize_line_endings_code_action(&uri, src, &diags) {
+            Some(CodeActionOrCommand::CodeAction(action)) => action,
+            _ => unreachable!("test error"),
+        };
+        let edits = &action.edit.unwrap().changes.unwrap()[&uri];
+        assert_eq!(edits.len(), 1);
+        assert_eq!(
+            edits[0].range,
+            Range::new(
+                Position::new(0, 0),
+                Position::new(i32::MAX as u32, i32::MAX as u32)
+            )
+        );
+        assert_eq!(edits[0].new_text, "a = 1\nb = 2\n");
+
+        assert!(normalize_line_endings_code_action(&uri, src, &[]).is_none());
+    }
+}
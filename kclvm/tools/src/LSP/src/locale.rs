@@ -0,0 +1,250 @@
+//! The message catalog to localize the diagnostic and the hover messages with the `locale` of the
+//! user configurations, e.g. `zh` for Chinese. The messages are matched by the diagnostic codes,
+//! or [HOVER] for the labels of the hover texts, and the English templates, in which `{}` is an
+//! interpolated value such as a name or a type, and the interpolated values are kept
+//! untranslated. The messages which are not in the catalog or have no translation for the locale
+//! fall back to English. The code and the documents shown by the hovers are not translated.
+
+use lsp_types::{Hover, HoverContents, MarkedString, MarkupContent};
+
+/// The catalog key of the labels of the hover texts, e.g. `Constraint: {}`.
+pub(crate) const HOVER: &str = "Hover";
+
+/// The English template of a message and its translations keyed by the locale.
+struct Template {
+    en: &'static str,
+    translations: &'static [(&'static str, &'static str)],
+}
+
+/// The message templates keyed by the diagnostic code.
+const CATALOG: &[(&str, &[Template])] = &[
+    (
+        "UnusedImportWarning",
+        &[Template {
+            en: "Module '{}' imported but unused",
+            translations: &[("zh", "模块 '{}' 已导入但未使用")],
+        }],
+    ),
+    (
+        "ReimportWarning",
+        &[Template {
+            en: "Module '{}' is reimported multiple times",
+            translations: &[("zh", "模块 '{}' 被重复导入")],
+        }],
+    ),
+    (
+        "ImportPositionWarning",
+        &[Template {
+            en: "The import stmt should be placed at the top of the module",
+            translations: &[("zh", "导入语句应放在模块的顶部")],
+        }],
+    ),
+    (
+        "DuplicateAssignmentWarning",
+        &[
+            Template {
+                en: "duplicate assignment of '{}'",
+                translations: &[("zh", "重复赋值 '{}'")],
+            },
+            Template {
+                en: "'{}' is overridden by the later assignment",
+                translations: &[("zh", "'{}' 被后面的赋值覆盖")],
+            },
+        ],
+    ),
+    (
+        "ImmutableError",
+        &[
+            Template {
+                en: "Can not change the value of '{}', because it was declared immutable",
+                translations: &[("zh", "无法修改 '{}' 的值，因为它被声明为不可变的")],
+            },
+            Template {
+                en: "The variable '{}' is declared here",
+                translations: &[("zh", "变量 '{}' 在此处声明")],
+            },
+        ],
+    ),
+    (
+        "CannotFindModule",
+        &[Template {
+            en: "Cannot find the module {} from {}",
+            translations: &[("zh", "无法找到模块 {}，查找路径为 {}")],
+        }],
+    ),
+    (
+        HOVER,
+        &[Template {
+            en: "Constraint: {}",
+            translations: &[("zh", "约束：{}")],
+        }],
+    ),
+];
+
+/// Returns the message localized to the `locale`, e.g. `zh` or `zh-CN`, or the original message
+/// if it has no translation.
+pub(crate) fn localize_message(code: &str, message: &str, locale: &str) -> String {
+    let locale = locale.to_lowercase();
+    let templates = match CATALOG.iter().find(|(c, _)| *c == code) {
+        Some((_, templates)) => templates,
+        None => return message.to_string(),
+    };
+    for template in templates.iter() {
+        let translated = match template
+            .translations
+            .iter()
+            .find(|(key, _)| locale == *key || locale.starts_with(&format!("{}-", key)))
+        {
+            Some((_, translated)) => translated,
+            None => continue,
+        };
+        if let Some(values) = match_template(template.en, message) {
+            let mut parts = translated.split("{}");
+            let mut localized = parts.next().unwrap_or_default().to_string();
+            for (part, value) in parts.zip(values) {
+                localized.push_str(value);
+                localized.push_str(part);
+            }
+            return localized;
+        }
+    }
+    message.to_string()
+}
+
+/// Returns the hover with the labels localized to the `locale`. The paragraphs of the texts are
+/// localized one by one, and the code blocks are kept.
+pub(crate) fn localize_hover(hover: Hover, locale: &str) -> Hover {
+    let localize = |text: &str| {
+        text.split("\n\n")
+            .map(|paragraph| localize_message(HOVER, paragraph, locale))
+            .collect::<Vec<String>>()
+            .join("\n\n")
+    };
+    let localize_marked = |doc: MarkedString| match doc {
+        MarkedString::String(text) => MarkedString::String(localize(&text)),
+        MarkedString::LanguageString(code) => MarkedString::LanguageString(code),
+    };
+    let contents = match hover.contents {
+        HoverContents::Scalar(doc) => HoverContents::Scalar(localize_marked(doc)),
+        HoverContents::Array(docs) => {
+            HoverContents::Array(docs.into_iter().map(localize_marked).collect())
+        }
+        HoverContents::Markup(markup) => HoverContents::Markup(MarkupContent {
+            kind: markup.kind,
+            value: localize(&markup.value),
+        }),
+    };
+    Hover {
+        contents,
+        range: hover.range,
+    }
+}
+
+/// Returns the interpolated values if the message matches the template.
+fn match_template<'a>(template: &str, message: &'a str) -> Option<Vec<&'a str>> {
+    let parts: Vec<&str> = template.split("{}").collect();
+    let (first, parts) = parts.split_first()?;
+    let mut rest = message.strip_prefix(first)?;
+    let mut values = vec![];
+    for (i, part) in parts.iter().enumerate() {
+        if i + 1 == parts.len() {
+            // The last literal part is the suffix of the message.
+            values.push(rest.strip_suffix(part)?);
+            return Some(values);
+        }
+        let end = rest.find(part)?;
+        values.push(&rest[..end]);
+        rest = &rest[end + part.len()..];
+    }
+    // The template has no interpolated value.
+    if rest.is_empty() {
+        Some(values)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kclvm_error::{Message, Position, Style};
+    use lsp_types::{
+        DiagnosticSeverity, Hover, HoverContents, LanguageString, MarkedString, NumberOrString,
+    };
+
+    use super::{localize_hover, localize_message};
+    use crate::to_lsp::kcl_msg_to_lsp_diags;
+
+    #[test]
+    fn localize_message_test() {
+        let msg = Message {
+            range: (Position::dummy_pos(), Position::dummy_pos()),
+            style: Style::LineAndColumn,
+            message: "Module 'abc' imported but unused".to_string(),
+            note: None,
+            suggested_replacement: None,
+        };
+        let diag = |locale| {
+            kcl_msg_to_lsp_diags(
+                &msg,
+                DiagnosticSeverity::WARNING,
+                vec![],
+                Some(NumberOrString::String("UnusedImportWarning".to_string())),
+                None,
+                locale,
+            )
+        };
+        assert_eq!(diag(None).message, "Module 'abc' imported but unused");
+        assert_eq!(diag(Some("en")).message, "Module 'abc' imported but unused");
+        assert_eq!(diag(Some("zh-CN")).message, "模块 'abc' 已导入但未使用");
+
+        // The messages without translations fall back to English.
+        assert_eq!(
+            localize_message(
+                "UnusedImportWarning",
+                "Module 'abc' imported but unused",
+                "fr"
+            ),
+            "Module 'abc' imported but unused"
+        );
+        assert_eq!(
+            localize_message("TypeError", "expected str, got int(1)", "zh"),
+            "expected str, got int(1)"
+        );
+        // Several interpolated values.
+        assert_eq!(
+            localize_message(
+                "CannotFindModule",
+                "Cannot find the module abc from /path/abc",
+                "zh"
+            ),
+            "无法找到模块 abc，查找路径为 /path/abc"
+        );
+    }
+    #[test]
+    fn localize_hover_test() {
+        let code = MarkedString::LanguageString(LanguageString {
+            language: "KCL".to_string(),
+            value: "port: int".to_string(),
+        });
+        let hover = Hover {
+            contents: HoverContents::Array(vec![
+                code.clone(),
+                MarkedString::String("The port.".to_string()),
+                MarkedString::String(
+                    "Constraint: port > 0\n\nConstraint: port < 65536".to_string(),
+                ),
+            ]),
+            range: None,
+        };
+        // The code and the documents are kept.
+        assert_eq!(
+            localize_hover(hover.clone(), "zh").contents,
+            HoverContents::Array(vec![
+                code,
+                MarkedString::String("The port.".to_string()),
+                MarkedString::String("约束：port > 0\n\n约束：port < 65536".to_string()),
+            ])
+        );
+        assert_eq!(localize_hover(hover.clone(), "en"), hover);
+    }
+}
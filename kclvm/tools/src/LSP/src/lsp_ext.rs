@@ -0,0 +1,581 @@
+//! KCL specific extensions of the language server protocol.
+
+use std::collections::HashMap;
+
+use lsp_types::{
+    request::Request, Location, Position, Range, SymbolKind, TextDocumentIdentifier,
+    TextDocumentPositionParams, Url,
+};
+use serde::{Deserialize, Serialize};
+
+/// Evaluates the expression in the selected range of a document, e.g. the
+/// editor selection `1 + len([1, 2, 3])` evaluates to `4`.
+pub enum EvalExpression {}
+
+impl Request for EvalExpression {
+    type Params = EvalExpressionParams;
+    type Result = EvalExpressionResult;
+    const METHOD: &'static str = "kcl/evalExpression";
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvalExpressionParams {
+    pub text_document: TextDocumentIdentifier,
+    /// The range of the expression to evaluate.
+    pub range: Range,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvalExpressionResult {
+    /// The evaluated value in the JSON format.
+    pub value: String,
+}
+
+/// Explains a diagnostic code, e.g. `TypeError`, with a description and examples.
+pub enum ExplainCode {}
+
+impl Request for ExplainCode {
+    type Params = ExplainCodeParams;
+    type Result = Option<ExplainCodeResult>;
+    const METHOD: &'static str = "kcl/explainCode";
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplainCodeParams {
+    /// The diagnostic code, which is the `code` field of the LSP diagnostic.
+    pub code: String,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplainCodeResult {
+    pub code: String,
+    pub title: String,
+    pub description: String,
+    /// A minimal example which reports the diagnostic.
+    pub failing_example: String,
+    /// The example fixed.
+    pub fix_example: String,
+}
+
+/// Returns the effective type of a schema attribute and the schema declaring it.
+pub enum AttributeType {}
+
+impl Request for AttributeType {
+    type Params = AttributeTypeParams;
+    type Result = Option<AttributeTypeResult>;
+    const METHOD: &'static str = "kcl/attributeType";
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributeTypeParams {
+    /// A document of the program which defines the schema.
+    pub text_document: TextDocumentIdentifier,
+    /// The schema name, e.g. `Server` or `pkg.Server` for a schema in another package.
+    pub schema_name: String,
+    pub attribute_name: String,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributeTypeResult {
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// The schema declaring the attribute, which is the schema itself or one of its base schemas.
+    pub declared_in: String,
+    pub required: bool,
+    pub has_default: bool,
+}
+
+/// Returns how many times each schema of the program is instantiated and how many times each of
+/// its attributes is set, which helps to spot the unused schemas and attributes.
+pub enum SchemaCoverageRequest {}
+
+impl Request for SchemaCoverageRequest {
+    type Params = SchemaCoverageParams;
+    type Result = Option<Vec<SchemaCoverage>>;
+    const METHOD: &'static str = "kcl/schemaCoverage";
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaCoverageParams {
+    /// A document of the program.
+    pub text_document: TextDocumentIdentifier,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaCoverage {
+    pub name: String,
+    pub instances: usize,
+    /// The attributes declared in the schema, inherited attributes are reported by the base schemas.
+    pub attributes: Vec<AttributeCoverage>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributeCoverage {
+    pub name: String,
+    pub set_count: usize,
+}
+
+/// Returns the schemas which the dict literal at the position structurally conforms to, ranked by
+/// the match quality, which helps to convert the literal to a schema instance.
+pub enum ConformingSchemas {}
+
+impl Request for ConformingSchemas {
+    type Params = ConformingSchemasParams;
+    type Result = Option<Vec<ConformingSchema>>;
+    const METHOD: &'static str = "kcl/conformingSchemas";
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConformingSchemasParams {
+    pub text_document: TextDocumentIdentifier,
+    /// A position in the dict literal.
+    pub position: Position,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConformingSchema {
+    pub name: String,
+    /// Whether the literal sets all the required and optional attributes of the schema.
+    pub exact: bool,
+}
+
+/// Returns the locations of the schema expressions constructing the schema at the position, e.g.
+/// `Server {...}`, without the other references to the schema name, e.g. the type annotations.
+pub enum SchemaInstantiations {}
+
+impl Request for SchemaInstantiations {
+    type Params = lsp_types::TextDocumentPositionParams;
+    type Result = Option<Vec<Location>>;
+    const METHOD: &'static str = "kcl/schemaInstantiations";
+}
+
+/// Returns the document symbols with the resolved type and the doc summary of each symbol in one
+/// round trip, instead of a `textDocument/documentSymbol` request and a hover request per symbol.
+pub enum OutlineWithTypes {}
+
+impl Request for OutlineWithTypes {
+    type Params = OutlineWithTypesParams;
+    type Result = Option<Vec<OutlineNode>>;
+    const METHOD: &'static str = "kcl/outlineWithTypes";
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutlineWithTypesParams {
+    pub text_document: TextDocumentIdentifier,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutlineNode {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub range: Range,
+    #[serde(rename = "type")]
+    pub ty: Option<String>,
+    /// The first line of the doc string.
+    pub doc: Option<String>,
+    pub children: Vec<OutlineNode>,
+}
+
+/// The result of the `kcl.rebuildCaches` command.
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RebuildCachesResult {
+    /// The workspaces which are compiled again.
+    pub workspaces: usize,
+    /// The cached modules which are cleared.
+    pub modules: usize,
+    /// The opened documents which are re-indexed.
+    pub documents: usize,
+}
+
+/// The argument of the `kcl.extractConfig` command.
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractConfigParams {
+    pub text_document: TextDocumentIdentifier,
+    /// The selection in the config to extract.
+    pub range: Range,
+}
+
+/// The argument of the `kcl.applyCodemod` command.
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyCodemodParams {
+    /// The id of the registered codemod, e.g. `renameIdentifier`.
+    pub id: String,
+    /// The files and the directories to apply the codemod to.
+    pub scope: Vec<Url>,
+    /// The arguments of the codemod, e.g. `from` and `to` of `renameIdentifier`.
+    #[serde(default)]
+    pub arguments: HashMap<String, String>,
+}
+
+/// Returns the `option("...")` declarations of the program with their types, defaults and docs,
+/// which helps to render an input form of the options.
+pub enum ListOptions {}
+
+impl Request for ListOptions {
+    type Params = ListOptionsParams;
+    type Result = Option<Vec<OptionDeclaration>>;
+    const METHOD: &'static str = "kcl/listOptions";
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListOptionsParams {
+    /// A document of the program.
+    pub text_document: TextDocumentIdentifier,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OptionDeclaration {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: Option<String>,
+    /// The default value in the KCL syntax, e.g. `"nginx"` or `1`.
+    pub default: Option<String>,
+    /// The `help` argument of the option.
+    pub doc: Option<String>,
+    pub required: bool,
+    /// The locations of all the declarations of the option.
+    pub locations: Vec<Location>,
+    /// A note on the merged declarations if the option is declared more than once.
+    pub note: Option<String>,
+}
+
+/// Returns the `option("...")` declarations reachable from an entry file through the imports,
+/// which are the options to prompt for before rendering the entry.
+pub enum RequiredOptions {}
+
+impl Request for RequiredOptions {
+    type Params = RequiredOptionsParams;
+    type Result = Option<Vec<OptionDeclaration>>;
+    const METHOD: &'static str = "kcl/requiredOptions";
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequiredOptionsParams {
+    /// The entry file.
+    pub text_document: TextDocumentIdentifier,
+}
+
+/// Returns the files needed to evaluate an entry file, e.g. to vendor the entry with only the
+/// files of the imported packages it uses.
+pub enum EntryClosure {}
+
+impl Request for EntryClosure {
+    type Params = EntryClosureParams;
+    type Result = Option<EntryClosureResult>;
+    const METHOD: &'static str = "kcl/entryClosure";
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryClosureParams {
+    /// The entry file.
+    pub text_document: TextDocumentIdentifier,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryClosureResult {
+    /// The entry file followed by the files it needs in the order they are reached.
+    pub files: Vec<Url>,
+    /// The files of the imported packages which are not needed, e.g. the files of an unused
+    /// import, sorted by the paths.
+    pub unused_files: Vec<Url>,
+}
+
+/// Returns the inheritance graph of the schemas of a package, whose edges are the base schemas,
+/// the mixins and the protocols implemented by the mixins.
+pub enum InheritanceGraph {}
+
+impl Request for InheritanceGraph {
+    type Params = InheritanceGraphParams;
+    type Result = Option<InheritanceGraphResult>;
+    const METHOD: &'static str = "kcl/inheritanceGraph";
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InheritanceGraphParams {
+    /// A document of the package.
+    pub text_document: TextDocumentIdentifier,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InheritanceGraphResult {
+    /// The schemas sorted by the names.
+    pub nodes: Vec<InheritanceNode>,
+    /// The edges sorted by the sources, the targets and the relations.
+    pub edges: Vec<InheritanceEdge>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InheritanceNode {
+    /// The qualified schema name, e.g. `pkg.Server`.
+    pub name: String,
+    pub location: Option<Location>,
+    /// Whether the schema is in an inheritance cycle, which is an error.
+    pub in_cycle: bool,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InheritanceEdge {
+    /// The qualified name of the inheriting schema.
+    pub from: String,
+    /// The qualified name of the inherited schema.
+    pub to: String,
+    pub relation: InheritanceRelation,
+    pub in_cycle: bool,
+}
+
+#[derive(Debug, Eq, PartialEq, PartialOrd, Ord, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InheritanceRelation {
+    /// The base schema of `schema Derived(Base)`.
+    Extends,
+    /// A mixin of `mixin [NameMixin]`.
+    Mixin,
+    /// The protocol of `mixin NameMixin for NameProtocol`.
+    Implements,
+}
+
+/// Resolves the definitions of many positions in one request, e.g. to index a workspace. The
+/// results are in the order of the positions and a position without definitions resolves to an
+/// empty list.
+pub enum DefinitionsBatch {}
+
+impl Request for DefinitionsBatch {
+    type Params = DefinitionsBatchParams;
+    type Result = Vec<Vec<Location>>;
+    const METHOD: &'static str = "kcl/definitionsBatch";
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefinitionsBatchParams {
+    pub positions: Vec<TextDocumentPositionParams>,
+}
+
+/// Lists the string literals of the program of a document, e.g. to catalog the user-facing
+/// strings for the translation.
+pub enum StringLiterals {}
+
+impl Request for StringLiterals {
+    type Params = StringLiteralsParams;
+    type Result = Option<Vec<StringLiteral>>;
+    const METHOD: &'static str = "kcl/stringLiterals";
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StringLiteralsParams {
+    pub text_document: TextDocumentIdentifier,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StringLiteral {
+    pub location: Location,
+    /// The value of the string, where the interpolations are kept as `${...}`, e.g.
+    /// `Hello, ${name}!`.
+    pub text: String,
+    /// Whether the string has interpolations, e.g. `"${name}"`.
+    pub interpolated: bool,
+    /// Whether the string is a raw string, e.g. `r"\d+"`, which is never interpolated.
+    pub raw: bool,
+    /// The static segments and the interpolations of an interpolated string in order, empty for
+    /// the other strings.
+    pub segments: Vec<StringSegment>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StringSegment {
+    pub range: Range,
+    /// The text of a static segment, or the expression of an interpolation, e.g. `name`.
+    pub text: String,
+    pub interpolated: bool,
+}
+
+/// Validates the `kcl.mod` file of a project and returns the diagnostics of the dependency
+/// declarations with the normalized content of the file.
+pub enum ValidateMod {}
+
+impl Request for ValidateMod {
+    type Params = ValidateModParams;
+    type Result = Option<ValidateModResult>;
+    const METHOD: &'static str = "kcl/validateMod";
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidateModParams {
+    /// The `kcl.mod` file or a document of the project.
+    pub text_document: TextDocumentIdentifier,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidateModResult {
+    /// The `kcl.mod` file the diagnostics and the edit are in.
+    pub uri: Url,
+    pub diagnostics: Vec<lsp_types::Diagnostic>,
+    /// The edit replacing the whole file with the normalized content, or `None` if the file is
+    /// already normalized or can not be parsed.
+    pub normalized: Option<lsp_types::TextEdit>,
+}
+
+/// Renders the config of an entry file evaluated with the options and the overrides, e.g. to
+/// preview the final config in a diff view. The whole output is returned however large it is.
+pub enum RenderConfig {}
+
+impl Request for RenderConfig {
+    type Params = RenderConfigParams;
+    type Result = RenderConfigResult;
+    const METHOD: &'static str = "kcl/renderConfig";
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderConfigParams {
+    /// The entry file.
+    pub text_document: TextDocumentIdentifier,
+    /// The values of the options in the JSON format keyed by the option names, e.g. `"prod"` or
+    /// `3`.
+    #[serde(default)]
+    pub options: HashMap<String, String>,
+    /// The override specs, e.g. `app.replicas=3`.
+    #[serde(default)]
+    pub overrides: Vec<String>,
+    #[serde(default)]
+    pub format: RenderFormat,
+}
+
+#[derive(Debug, Default, Eq, PartialEq, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RenderFormat {
+    #[default]
+    Yaml,
+    Json,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderConfigResult {
+    /// The rendered config, or `None` if the config can not be evaluated.
+    pub output: Option<String>,
+    /// The error messages of the evaluation.
+    pub diagnostics: Vec<String>,
+}
+
+/// Renders the config of the entry files like `kcl/renderConfig`, with the location of the
+/// expression producing each key of the output, e.g. to jump from a rendered value to the file
+/// overriding it.
+pub enum RenderWithProvenance {}
+
+impl Request for RenderWithProvenance {
+    type Params = RenderWithProvenanceParams;
+    type Result = RenderWithProvenanceResult;
+    const METHOD: &'static str = "kcl/renderWithProvenance";
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderWithProvenanceParams {
+    /// The first entry file.
+    pub text_document: TextDocumentIdentifier,
+    /// The other entry files evaluated after the first one in order, e.g. the file of the
+    /// production environment overriding the base config.
+    #[serde(default)]
+    pub files: Vec<Url>,
+    #[serde(default)]
+    pub options: HashMap<String, String>,
+    #[serde(default)]
+    pub overrides: Vec<String>,
+    #[serde(default)]
+    pub format: RenderFormat,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderWithProvenanceResult {
+    pub output: Option<String>,
+    /// The locations of the expressions producing the values keyed by the dotted paths of the
+    /// output, e.g. `app.replicas`. The values set by the overrides have no locations.
+    pub provenance: HashMap<String, Location>,
+    /// The dotted paths of the output produced by the conditional config entries, e.g.
+    /// `if env == "prod": replicas = 3`, which have no locations because the branches taken are
+    /// only known at runtime, sorted.
+    pub ambiguous: Vec<String>,
+    pub diagnostics: Vec<String>,
+}
+
+/// Returns the attribute-level changes between two versions of a schema, e.g. to review the
+/// compatibility of a schema change before releasing a package.
+pub enum SchemaDiff {}
+
+impl Request for SchemaDiff {
+    type Params = SchemaDiffParams;
+    type Result = Option<Vec<AttributeChange>>;
+    const METHOD: &'static str = "kcl/schemaDiff";
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaDiffParams {
+    pub before: SchemaRef,
+    pub after: SchemaRef,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaRef {
+    /// The document defining the schema.
+    pub text_document: TextDocumentIdentifier,
+    /// The name of the schema, e.g. `Server`.
+    pub name: String,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributeChange {
+    pub name: String,
+    pub kind: AttributeChangeKind,
+    /// The type of an added or a removed attribute, the types of a type change or the defaults
+    /// of a default change, otherwise `None`.
+    pub before: Option<String>,
+    pub after: Option<String>,
+    /// Whether the configs valid for the schema before may be invalid for the schema after.
+    pub breaking: bool,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AttributeChangeKind {
+    Added,
+    Removed,
+    TypeChanged,
+    RequiredToOptional,
+    OptionalToRequired,
+    DefaultChanged,
+}
@@ -0,0 +1,373 @@
+//! The opt-in lint of the number literals which are not named, e.g.
+//!
+//! ```kcl
+//! session = Session {
+//!     timeout = 3600
+//! }
+//! ```
+//!
+//! `3600` is reported and the code action extracts it into the private constant `_TIMEOUT` named
+//! after the config key. The top level assignments of the literals, e.g. `_TIMEOUT = 3600`, are
+//! the constants themselves and are not reported, nor are the literals of the allowlist, `0` and
+//! `1` by default.
+
+use std::collections::{HashMap, HashSet};
+
+use indexmap::IndexSet;
+use kclvm_ast::ast::{self, Program};
+use kclvm_ast::pos::GetPos;
+use kclvm_ast::MAIN_PKG;
+use kclvm_error::{Diagnostic, DiagnosticId, Level, WarningKind};
+use kclvm_parser::parse_single_file;
+use lsp_types::{CodeAction, CodeActionKind, CodeActionOrCommand, Position, TextEdit, Url};
+use serde::Deserialize;
+
+use crate::positioned_walker::{walk_stmt, ExprContext, PositionedVisitor};
+use crate::quick_fix::{convert_code_to_kcl_diag_id, extract_suggested_replacements};
+
+/// The numbers which are not reported if the allowlist is not configured.
+pub(crate) const DEFAULT_MAGIC_NUMBER_ALLOWLIST: &[f64] = &[0.0, 1.0];
+
+/// The name of the extracted constant when the literal is not the value of an attribute, a
+/// config key or a keyword argument.
+const DEFAULT_CONSTANT_NAME: &str = "VALUE";
+
+/// The contexts whose number literals can be exempt from the lint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum MagicNumberContext {
+    /// The check blocks of the schemas and the rules, e.g. `replicas <= 10`.
+    Checks,
+    /// The default values of the schema attributes, e.g. `port: int = 8080`.
+    SchemaDefaults,
+    /// The indexes and the slices, e.g. `items[2]`.
+    Subscripts,
+}
+
+/// Returns a hint over each number literal of the main package which is not in `allowlist` and
+/// not in the `ignored` contexts. The suggested replacement is the name of the constant to extract the literal into.
+/// The literals with the unit suffixes, e.g. `1Gi`, are reported even if the number is allowed.
+pub(crate) fn magic_number_diags(
+    program: &Program,
+    allowlist: &[f64],
+    ignored: &[MagicNumberContext],
+) -> IndexSet<Diagnostic> {
+    let mut diags = IndexSet::new();
+    // The imported and the external packages are not the code of the workspace.
+    for module in program.get_modules_for_pkg(MAIN_PKG) {
+        let module = match module.read() {
+            Ok(module) => module,
+            Err(_) => continue,
+        };
+        let mut collector = MagicNumberCollector {
+            allowlist,
+            ignored,
+            taken_names: module_names(&module.body),
+            hints: vec![],
+            diags: IndexSet::new(),
+        };
+        for stmt in &module.body {
+            match &stmt.node {
+                ast::Stmt::Assign(assign_stmt) if is_number(&assign_stmt.value) => {}
+                _ => walk_stmt(&mut collector, &stmt.node),
+            }
+        }
+        diags.extend(collector.diags);
+    }
+    diags
+}
+
+/// Whether the expression is a number literal, or a negated one, e.g. `-1`.
+fn is_number(expr: &ast::NodeRef<ast::Expr>) -> bool {
+    match &expr.node {
+        ast::Expr::NumberLit(_) => true,
+        ast::Expr::Unary(unary_expr) => matches!(unary_expr.operand.node, ast::Expr::NumberLit(_)),
+        _ => false,
+    }
+}
+
+/// Returns the names declared at the top level of the module, which the extracted constants must
+/// not redeclare.
+fn module_names(stmts: &[ast::NodeRef<ast::Stmt>]) -> HashSet<String> {
+    let mut names = HashSet::new();
+    for stmt in stmts {
+        match &stmt.node {
+            ast::Stmt::Assign(assign_stmt) => {
+                for target in &assign_stmt.targets {
+                    names.insert(target.node.name.node.clone());
+                }
+            }
+            ast::Stmt::Schema(schema_stmt) => {
+                names.insert(schema_stmt.name.node.clone());
+            }
+            ast::Stmt::Rule(rule_stmt) => {
+                names.insert(rule_stmt.name.node.clone());
+            }
+            _ => {}
+        }
+    }
+    names
+}
+
+/// Returns the private constant name of the hint in the upper snake case, e.g. `_MAX_RETRIES` of
+/// `maxRetries`, which is suffixed by a number if the module declares it already.
+fn constant_name(hint: Option<&str>, taken_names: &HashSet<String>) -> String {
+    let mut base = String::new();
+    let mut prev_lower = false;
+    for c in hint.unwrap_or("").trim_start_matches('_').chars() {
+        if c.is_uppercase() && prev_lower {
+            base.push('_');
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        base.push(if c.is_alphanumeric() {
+            c.to_ascii_uppercase()
+        } else {
+            '_'
+        });
+    }
+    if base.is_empty() {
+        base = DEFAULT_CONSTANT_NAME.to_string();
+    }
+    let name = format!("_{}", base);
+    if !taken_names.contains(&name) {
+        return name;
+    }
+    (2..)
+        .map(|idx| format!("{}_{}", name, idx))
+        .find(|name| !taken_names.contains(name))
+        .unwrap()
+}
+
+/// Collects the number literals with the name the current literal would be extracted into.
+struct MagicNumberCollector<'a> {
+    allowlist: &'a [f64],
+    ignored: &'a [MagicNumberContext],
+    taken_names: HashSet<String>,
+    /// The names of the entered contexts, the last one is the name of the current literal.
+    hints: Vec<Option<String>>,
+    diags: IndexSet<Diagnostic>,
+}
+
+impl<'a> PositionedVisitor for MagicNumberCollector<'a> {
+    fn visit_expr(&mut self, expr: &ast::NodeRef<ast::Expr>) -> bool {
+        if let ast::Expr::NumberLit(number_lit) = &expr.node {
+            self.number(expr, number_lit);
+        }
+        true
+    }
+
+    fn enter(&mut self, context: ExprContext) -> bool {
+        let hint = match context {
+            ExprContext::Value(name) => name.map(|name| name.to_string()),
+            ExprContext::SchemaDefault(name) if self.scans(MagicNumberContext::SchemaDefaults) => {
+                Some(name.to_string())
+            }
+            ExprContext::Check if self.scans(MagicNumberContext::Checks) => None,
+            ExprContext::Subscript if self.scans(MagicNumberContext::Subscripts) => {
+                self.hints.last().cloned().flatten()
+            }
+            _ => return false,
+        };
+        self.hints.push(hint);
+        true
+    }
+
+    fn exit(&mut self) {
+        self.hints.pop();
+    }
+}
+
+impl<'a> MagicNumberCollector<'a> {
+    fn scans(&self, context: MagicNumberContext) -> bool {
+        !self.ignored.contains(&context)
+    }
+
+    fn number(&mut self, expr: &ast::NodeRef<ast::Expr>, number_lit: &ast::NumberLit) {
+        let value = match number_lit.value {
+            ast::NumberLitValue::Int(value) => value as f64,
+            ast::NumberLitValue::Float(value) => value,
+        };
+        if number_lit.binary_suffix.is_none() && self.allowlist.contains(&value) {
+            return;
+        }
+        let hint = self.hints.last().cloned().flatten();
+        let name = constant_name(hint.as_deref(), &self.taken_names);
+        // The constants extracted from the other literals must not redeclare it.
+        self.taken_names.insert(name.clone());
+        self.diags.insert(Diagnostic::new_with_code(
+            Level::Note,
+            &format!("Magic number {}", number_lit.to_string()),
+            Some(&format!(
+                "Consider extracting it into the constant '{}'",
+                name
+            )),
+            (expr.get_pos(), expr.get_end_pos()),
+            Some(DiagnosticId::Warning(WarningKind::MagicNumberWarning)),
+            Some(vec![name]),
+        ));
+    }
+}
+
+/// Returns the refactors extracting the literals of the magic number hints in `diags` into the
+/// constants named by the suggested replacements, which are declared before the top level
+/// statements containing the literals.
+pub(crate) fn extract_magic_number_code_actions(
+    uri: &Url,
+    file: &str,
+    src: &str,
+    diags: &[lsp_types::Diagnostic],
+) -> Vec<CodeActionOrCommand> {
+    let diags: Vec<&lsp_types::Diagnostic> = diags
+        .iter()
+        .filter(|diag| {
+            diag.code.as_ref().and_then(convert_code_to_kcl_diag_id)
+                == Some(DiagnosticId::Warning(WarningKind::MagicNumberWarning))
+        })
+        .collect();
+    if diags.is_empty() {
+        return vec![];
+    }
+    let module = match parse_single_file(file, Some(src.to_string())) {
+        Ok(result) if result.errors.is_empty() => result.module,
+        _ => return vec![],
+    };
+    let lines: Vec<&str> = src.lines().collect();
+    let mut code_actions = vec![];
+    for diag in diags {
+        let (start, end) = (diag.range.start, diag.range.end);
+        let name = match extract_suggested_replacements(&diag.data).pop() {
+            Some(name) => name,
+            None => continue,
+        };
+        let literal: String = match lines.get(start.line as usize) {
+            Some(line) if start.line == end.line => line
+                .chars()
+                .skip(start.character as usize)
+                .take((end.character - start.character) as usize)
+                .collect(),
+            _ => continue,
+        };
+        // The KCL lines are 1-based.
+        let line = start.line as u64 + 1;
+        let stmt_line = module.body.iter().find_map(|stmt| {
+            if stmt.line > line || stmt.end_line < line {
+                return None;
+            }
+            let decorators = match &stmt.node {
+                ast::Stmt::Schema(schema_stmt) => &schema_stmt.decorators,
+                ast::Stmt::Rule(rule_stmt) => &rule_stmt.decorators,
+                _ => return Some(stmt.line),
+            };
+            Some(
+                decorators
+                    .iter()
+                    .map(|decorator| decorator.line)
+                    .fold(stmt.line, u64::min),
+            )
+        });
+        let stmt_line = match stmt_line {
+            Some(stmt_line) => stmt_line,
+            None => continue,
+        };
+        let insert_pos = Position::new(stmt_line as u32 - 1, 0);
+        let mut changes = HashMap::new();
+        changes.insert(
+            uri.clone(),
+            vec![
+                TextEdit {
+                    range: lsp_types::Range::new(insert_pos, insert_pos),
+                    new_text: format!("{} = {}\n", name, literal),
+                },
+                TextEdit {
+                    range: diag.range,
+                    new_text: name.clone(),
+                },
+            ],
+        );
+        code_actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+            title: format!("Extract `{}` into the constant `{}`", literal, name),
+            kind: Some(CodeActionKind::REFACTOR_EXTRACT),
+            diagnostics: Some(vec![diag.clone()]),
+            edit: Some(lsp_types::WorkspaceEdit {
+                changes: Some(changes),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }));
+    }
+    code_actions
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{CodeActionOrCommand, Position, Range, TextEdit, Url};
+    use proc_macro_crate::bench_test;
+
+    use super::{
+        extract_magic_number_code_actions, magic_number_diags, MagicNumberContext,
+        DEFAULT_MAGIC_NUMBER_ALLOWLIST,
+    };
+    use crate::tests::compile_test_file;
+    use crate::to_lsp::kcl_diag_to_lsp_diags_by_file;
+
+    #[test]
+    #[bench_test]
+    fn magic_number_diags_test() {
+        let (file, program, _, _, _) = compile_test_file("src/test_data/magic_numbers/main.k");
+        let messages = |ignored: &[MagicNumberContext]| -> Vec<(u64, String, Vec<String>)> {
+            magic_number_diags(&program, DEFAULT_MAGIC_NUMBER_ALLOWLIST, ignored)
+                .iter()
+                .map(|diag| {
+                    let msg = &diag.messages[0];
+                    (
+                        msg.range.0.line,
+                        msg.message.clone(),
+                        msg.suggested_replacement.clone().unwrap_or_default(),
+                    )
+                })
+                .collect()
+        };
+        // `0`, `1`, the constant `_MAX_RETRIES` and the imported package are not reported.
+        assert_eq!(
+            messages(&[]),
+            vec![
+                (10, "Magic number 5".to_string(), vec!["_VALUE".to_string()]),
+                (
+                    13,
+                    "Magic number 3600".to_string(),
+                    vec!["_TIMEOUT".to_string()]
+                ),
+                (
+                    18,
+                    "Magic number 7200".to_string(),
+                    vec!["_TIMEOUT_2".to_string()]
+                ),
+            ]
+        );
+        assert_eq!(messages(&[MagicNumberContext::Checks]).len(), 2);
+
+        let diags = magic_number_diags(&program, DEFAULT_MAGIC_NUMBER_ALLOWLIST, &[]);
+        let lsp_diags = kcl_diag_to_lsp_diags_by_file(&diags[1], &file, None);
+        let uri = Url::from_file_path(&file).unwrap();
+        let src = std::fs::read_to_string(&file).unwrap();
+        let code_actions = extract_magic_number_code_actions(&uri, &file, &src, &lsp_diags);
+        let action = match &code_actions[..] {
+            [CodeActionOrCommand::CodeAction(action)] => action,
+            _ => panic!("expected one code action, got {:?}", code_actions),
+        };
+        assert_eq!(action.title, "Extract `3600` into the constant `_TIMEOUT`");
+        assert_eq!(
+            action.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri],
+            vec![
+                TextEdit {
+                    range: Range::new(Position::new(11, 0), Position::new(11, 0)),
+                    new_text: "_TIMEOUT = 3600\n".to_string(),
+                },
+                TextEdit {
+                    range: Range::new(Position::new(12, 14), Position::new(12, 18)),
+                    new_text: "_TIMEOUT".to_string(),
+                },
+            ]
+        );
+    }
+}
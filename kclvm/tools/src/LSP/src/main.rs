@@ -1,25 +1,62 @@
 mod analysis;
 mod app;
+mod attribute_type;
+mod builtin_shadowing;
+mod call_hierarchy;
+mod canonical_imports;
 mod capabilities;
+mod check_messages;
+mod codemod;
+mod collapse_variable;
+mod commands;
 mod compile;
 mod completion;
+mod config;
+mod conforming_schemas;
+mod diagnostic_summary;
 mod dispatcher;
 mod document_symbol;
+mod entry_closure;
 mod error;
+mod eval_expr;
+mod explain;
 mod find_refs;
+mod folding_range;
 mod formatting;
 mod from_lsp;
 mod goto_def;
 mod hover;
+mod indentation;
+mod inheritance_graph;
 mod inlay_hints;
+mod line_endings;
+mod locale;
+mod lsp_ext;
+mod magic_numbers;
 mod notification;
+mod options;
+mod positioned_walker;
+mod provenance;
 mod quick_fix;
+mod render_config;
 mod request;
+mod schema_coverage;
+mod schema_diff;
+mod schema_instantiations;
 mod semantic_token;
 mod signature_help;
+mod source_map;
 mod state;
+mod string_literals;
 mod to_lsp;
+mod todo_comments;
+mod unused_private;
+mod unused_schema;
 mod util;
+mod validate_mod;
+// The embedding API of the diagnostics is only used through the library.
+#[allow(dead_code)]
+mod watch;
 mod word_index;
 
 #[cfg(test)]
@@ -1,6 +1,6 @@
 use lsp_types::notification::{
     Cancel, DidChangeTextDocument, DidChangeWatchedFiles, DidCloseTextDocument,
-    DidOpenTextDocument, DidSaveTextDocument,
+    DidOpenTextDocument, DidSaveTextDocument, SetTrace,
 };
 use std::collections::HashSet;
 
@@ -22,6 +22,7 @@ impl LanguageServerState {
             .on::<DidCloseTextDocument>(LanguageServerState::on_did_close_text_document)?
             .on::<DidChangeWatchedFiles>(LanguageServerState::on_did_change_watched_files)?
             .on::<Cancel>(LanguageServerState::cancel)?
+            .on::<SetTrace>(LanguageServerState::on_set_trace)?
             .finish();
         Ok(())
     }
@@ -35,8 +36,14 @@ impl LanguageServerState {
         Ok(())
     }
 
+    /// Called when a `$/setTrace` notification was received.
+    fn on_set_trace(&mut self, params: lsp_types::SetTraceParams) -> anyhow::Result<()> {
+        self.trace = params.value;
+        Ok(())
+    }
+
     /// Called when a `DidOpenTextDocument` notification was received.
-    fn on_did_open_text_document(
+    pub(crate) fn on_did_open_text_document(
         &mut self,
         params: lsp_types::DidOpenTextDocumentParams,
     ) -> anyhow::Result<()> {
@@ -75,7 +82,7 @@ impl LanguageServerState {
     }
 
     /// Called when a `DidChangeTextDocument` notification was received.
-    fn on_did_change_text_document(
+    pub(crate) fn on_did_change_text_document(
         &mut self,
         params: lsp_types::DidChangeTextDocumentParams,
     ) -> anyhow::Result<()> {
@@ -86,6 +93,7 @@ impl LanguageServerState {
 
         let path = from_lsp::abs_path(&text_document.uri)?;
         self.log_message(format!("on did_change file: {:?}", path));
+        self.folding_range_cache.invalidate(&text_document.uri);
 
         // Update vfs
         let vfs = &mut *self.vfs.write();
@@ -105,12 +113,14 @@ impl LanguageServerState {
     }
 
     /// Called when a `DidCloseTextDocument` notification was received.
-    fn on_did_close_text_document(
+    pub(crate) fn on_did_close_text_document(
         &mut self,
         params: lsp_types::DidCloseTextDocumentParams,
     ) -> anyhow::Result<()> {
         let path = from_lsp::abs_path(&params.text_document.uri)?;
         self.log_message(format!("on did_close file: {:?}", path));
+        self.folding_range_cache
+            .invalidate(&params.text_document.uri);
 
         if let Some(id) = self.vfs.read().file_id(&path.clone().into()) {
             self.opened_files.write().remove(&id);
@@ -0,0 +1,292 @@
+//! The `option("...")` declarations of a program, e.g.
+//!
+//! ```kcl
+//! name = option("name", type="str", default="nginx", help="The server name")
+//! ```
+//!
+//! declares the option `name` of the type `str` with the default value `"nginx"`. The declarations
+//! of the same option are merged, the first declared type, default value and doc are used.
+
+use std::collections::VecDeque;
+
+use indexmap::{IndexMap, IndexSet};
+use kclvm_ast::ast::{self, Module, Program};
+use kclvm_ast::pos::GetPos;
+use kclvm_ast::walker::MutSelfWalker;
+use kclvm_ast_pretty::{print_ast_node, ASTNode};
+use kclvm_sema::core::global_state::GlobalState;
+use kclvm_sema::core::symbol::SymbolKind;
+use lsp_types::{Location, Range, Url};
+
+use crate::goto_def::find_def;
+use crate::lsp_ext::OptionDeclaration;
+use crate::to_lsp::lsp_pos;
+
+const OPTION_FUNCTION: &str = "option";
+
+/// Returns the option declarations of the program in the order they are first declared.
+pub(crate) fn list_options(program: &Program, gs: &GlobalState) -> Vec<OptionDeclaration> {
+    let mut collector = OptionCollector {
+        gs,
+        options: IndexMap::new(),
+    };
+    for module in program.modules.values() {
+        if let Ok(module) = module.read() {
+            collector.walk_module(&module);
+        }
+    }
+    collector.into_declarations()
+}
+
+/// Returns the option declarations reachable from the entry file, which are the declarations of
+/// the entry file and of all the files of the packages it imports transitively. The other files
+/// of the entry package are not reachable, e.g. the other entries in the same directory.
+pub(crate) fn required_options(
+    entry: &str,
+    program: &Program,
+    gs: &GlobalState,
+) -> Vec<OptionDeclaration> {
+    let mut collector = OptionCollector {
+        gs,
+        options: IndexMap::new(),
+    };
+    let mut visited_pkgs: IndexSet<String> = IndexSet::new();
+    let mut modules = VecDeque::new();
+    modules.extend(program.get_module_ref(entry));
+    while let Some(module) = modules.pop_front() {
+        let module = match module.read() {
+            Ok(module) => module,
+            Err(_) => continue,
+        };
+        collector.walk_module(&module);
+        for stmt in &module.body {
+            if let ast::Stmt::Import(import_stmt) = &stmt.node {
+                // The system modules and the plugins are not in the program.
+                if visited_pkgs.insert(import_stmt.path.node.clone()) {
+                    modules.extend(program.get_modules_for_pkg(&import_stmt.path.node));
+                }
+            }
+        }
+    }
+    collector.into_declarations()
+}
+
+/// Merges the declarations of the same option, the conflicting types and default values are
+/// reported in the note.
+fn merge_declarations(declarations: Vec<OptionDeclaration>) -> OptionDeclaration {
+    let mut merged = declarations[0].clone();
+    for declaration in &declarations[1..] {
+        merged.ty = merged.ty.or(declaration.ty.clone());
+        merged.default = merged.default.or(declaration.default.clone());
+        merged.doc = merged.doc.or(declaration.doc.clone());
+        merged.required |= declaration.required;
+        merged.locations.extend(declaration.locations.clone());
+    }
+    if declarations.len() > 1 {
+        let mut note = format!("declared {} times", declarations.len());
+        for (field, values) in [
+            (
+                "types",
+                declarations.iter().filter_map(|d| d.ty.clone()).collect(),
+            ),
+            (
+                "default values",
+                declarations
+                    .iter()
+                    .filter_map(|d| d.default.clone())
+                    .collect::<Vec<String>>(),
+            ),
+        ] {
+            let mut distinct: Vec<String> = vec![];
+            for value in values {
+                if !distinct.contains(&value) {
+                    distinct.push(value);
+                }
+            }
+            if distinct.len() > 1 {
+                note.push_str(&format!(
+                    ", the declarations have different {}: {}",
+                    field,
+                    distinct.join(", ")
+                ));
+            }
+        }
+        merged.note = Some(note);
+    }
+    merged
+}
+
+struct OptionCollector<'a> {
+    gs: &'a GlobalState,
+    options: IndexMap<String, Vec<OptionDeclaration>>,
+}
+
+impl<'a> MutSelfWalker for OptionCollector<'a> {
+    fn walk_call_expr(&mut self, call_expr: &ast::CallExpr) {
+        if let Some(declaration) = self.option_declaration(call_expr) {
+            self.options
+                .entry(declaration.name.clone())
+                .or_default()
+                .push(declaration);
+        }
+        self.walk_expr(&call_expr.func.node);
+        for arg in &call_expr.args {
+            self.walk_expr(&arg.node);
+        }
+        for keyword in &call_expr.keywords {
+            self.walk_keyword(&keyword.node);
+        }
+    }
+}
+
+impl<'a> OptionCollector<'a> {
+    fn walk_module(&mut self, module: &Module) {
+        for stmt in &module.body {
+            self.walk_stmt(&stmt.node);
+        }
+    }
+
+    fn into_declarations(self) -> Vec<OptionDeclaration> {
+        self.options.into_values().map(merge_declarations).collect()
+    }
+
+    /// Returns the declaration if the call is a call of the builtin `option` function with a
+    /// string literal key, the options with computed keys are unknown before the evaluation.
+    fn option_declaration(&self, call_expr: &ast::CallExpr) -> Option<OptionDeclaration> {
+        let func = match &call_expr.func.node {
+            ast::Expr::Identifier(identifier) if identifier.names.len() == 1 => {
+                &identifier.names[0]
+            }
+            _ => return None,
+        };
+        if func.node != OPTION_FUNCTION {
+            return None;
+        }
+        // A user defined `option` shadows the builtin function.
+        let def = find_def(&func.get_pos(), self.gs, true)?;
+        if !matches!(def.get_kind(), SymbolKind::Function) {
+            return None;
+        }
+        let name = match &call_arg(call_expr, 0, "key")?.node {
+            ast::Expr::StringLit(string_lit) => string_lit.value.clone(),
+            _ => return None,
+        };
+        let string_arg = |index, keyword| match call_arg(call_expr, index, keyword) {
+            Some(arg) => match &arg.node {
+                ast::Expr::StringLit(string_lit) => Some(string_lit.value.clone()),
+                _ => None,
+            },
+            None => None,
+        };
+        let required = match call_arg(call_expr, 2, "required") {
+            Some(arg) => matches!(
+                &arg.node,
+                ast::Expr::NameConstantLit(lit) if lit.value == ast::NameConstant::True
+            ),
+            None => false,
+        };
+        Some(OptionDeclaration {
+            name,
+            ty: string_arg(1, "type"),
+            default: call_arg(call_expr, 3, "default")
+                .map(|arg| print_ast_node(ASTNode::Expr(arg))),
+            doc: string_arg(4, "help"),
+            required,
+            locations: vec![Location {
+                uri: Url::from_file_path(&func.filename).ok()?,
+                range: Range {
+                    start: lsp_pos(&call_expr.func.get_pos()),
+                    end: lsp_pos(&call_expr.func.get_end_pos()),
+                },
+            }],
+            note: None,
+        })
+    }
+}
+
+/// Returns the argument of the call by the position or the keyword.
+fn call_arg<'a>(
+    call_expr: &'a ast::CallExpr,
+    index: usize,
+    keyword: &str,
+) -> Option<&'a ast::NodeRef<ast::Expr>> {
+    match call_expr.args.get(index) {
+        Some(arg) => Some(arg),
+        None => call_expr
+            .keywords
+            .iter()
+            .find(|kw| kw.node.arg.node.get_name() == keyword)
+            .and_then(|kw| kw.node.value.as_ref()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{Location, Position, Range, Url};
+    use proc_macro_crate::bench_test;
+
+    use super::{list_options, required_options};
+    use crate::lsp_ext::OptionDeclaration;
+    use crate::tests::compile_test_file;
+
+    #[test]
+    #[bench_test]
+    fn list_options_test() {
+        let (file, program, _, gs, _) = compile_test_file("src/test_data/list_options/main.k");
+        let uri = Url::from_file_path(&file).unwrap();
+        let location = |line, start, end| Location {
+            uri: uri.clone(),
+            range: Range::new(Position::new(line, start), Position::new(line, end)),
+        };
+        assert_eq!(
+            list_options(&program, &gs),
+            vec![
+                OptionDeclaration {
+                    name: "name".to_string(),
+                    ty: Some("str".to_string()),
+                    default: Some("\"nginx\"".to_string()),
+                    doc: Some("The server name".to_string()),
+                    required: false,
+                    locations: vec![location(0, 7, 13), location(4, 16, 22)],
+                    note: Some("declared 2 times".to_string()),
+                },
+                OptionDeclaration {
+                    name: "replicas".to_string(),
+                    ty: Some("int".to_string()),
+                    default: None,
+                    doc: None,
+                    required: true,
+                    locations: vec![location(1, 11, 17)],
+                    note: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    #[bench_test]
+    fn required_options_test() {
+        let (file, program, _, gs, _) = compile_test_file("src/test_data/required_options/main.k");
+        let pkg_file = std::path::Path::new(&file)
+            .parent()
+            .unwrap()
+            .join("pkg")
+            .join("pkg.k");
+        // The option `port` of the other entry is not reachable from `main.k`.
+        assert_eq!(
+            required_options(&file, &program, &gs),
+            vec![OptionDeclaration {
+                name: "name".to_string(),
+                ty: Some("str".to_string()),
+                default: Some("\"nginx\"".to_string()),
+                doc: None,
+                required: false,
+                locations: vec![Location {
+                    uri: Url::from_file_path(pkg_file).unwrap(),
+                    range: Range::new(Position::new(0, 7), Position::new(0, 13)),
+                }],
+                note: None,
+            }]
+        );
+    }
+}
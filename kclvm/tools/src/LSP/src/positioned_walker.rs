@@ -0,0 +1,309 @@
+//! The walker of the statements and the expressions which passes the expression nodes with their
+//! positions, which the AST walkers do not pass to the literals, e.g. to report the number
+//! literals or to collect the string literals at their ranges.
+
+use kclvm_ast::ast;
+
+/// The places of the expressions walked by [walk_stmts], which a visitor can skip or scope its
+/// state by, e.g. by the name a literal would be extracted into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ExprContext<'a> {
+    /// The value bound to the name, e.g. of an assignment, a config entry, a keyword argument or
+    /// an argument default. The name is `None` for a value bound to no name, e.g. an expression
+    /// statement, a condition or an assertion.
+    Value(Option<&'a str>),
+    /// The default value of the schema attribute.
+    SchemaDefault(&'a str),
+    /// The check expressions of the schemas and the rules.
+    Check,
+    /// The index and the slice of a subscript.
+    Subscript,
+    /// The key of a config entry.
+    ConfigKey,
+    /// The decorators of the schemas, the rules and the schema attributes.
+    Decorator,
+}
+
+pub(crate) trait PositionedVisitor {
+    /// Visits the expression before its children, which are skipped if `false` is returned, e.g.
+    /// when the visitor walks them by itself.
+    fn visit_expr(&mut self, expr: &ast::NodeRef<ast::Expr>) -> bool;
+
+    /// Enters the context of the expressions walked next, which are skipped if `false` is
+    /// returned. Otherwise [PositionedVisitor::exit] is called after they are walked.
+    fn enter(&mut self, _context: ExprContext) -> bool {
+        true
+    }
+
+    /// Exits the context entered last.
+    fn exit(&mut self) {}
+}
+
+fn in_context<V: PositionedVisitor>(v: &mut V, context: ExprContext, walk: impl FnOnce(&mut V)) {
+    if v.enter(context) {
+        walk(v);
+        v.exit();
+    }
+}
+
+pub(crate) fn walk_stmts<V: PositionedVisitor>(v: &mut V, stmts: &[ast::NodeRef<ast::Stmt>]) {
+    for stmt in stmts {
+        walk_stmt(v, &stmt.node);
+    }
+}
+
+pub(crate) fn walk_stmt<V: PositionedVisitor>(v: &mut V, stmt: &ast::Stmt) {
+    match stmt {
+        ast::Stmt::Expr(expr_stmt) => in_context(v, ExprContext::Value(None), |v| {
+            walk_exprs(v, &expr_stmt.exprs)
+        }),
+        ast::Stmt::Unification(unification_stmt) => {
+            let name = unification_stmt.target.node.names.last();
+            in_context(
+                v,
+                ExprContext::Value(name.map(|name| name.node.as_str())),
+                |v| walk_schema_expr(v, &unification_stmt.value.node),
+            )
+        }
+        ast::Stmt::Assign(assign_stmt) => {
+            let name = assign_stmt.targets.last();
+            in_context(
+                v,
+                ExprContext::Value(name.map(|target| target.node.name.node.as_str())),
+                |v| walk_expr(v, &assign_stmt.value),
+            )
+        }
+        ast::Stmt::AugAssign(aug_assign_stmt) => in_context(
+            v,
+            ExprContext::Value(Some(&aug_assign_stmt.target.node.name.node)),
+            |v| walk_expr(v, &aug_assign_stmt.value),
+        ),
+        ast::Stmt::Assert(assert_stmt) => in_context(v, ExprContext::Value(None), |v| {
+            walk_expr(v, &assert_stmt.test);
+            walk_opt_expr(v, &assert_stmt.if_cond);
+            walk_opt_expr(v, &assert_stmt.msg);
+        }),
+        ast::Stmt::If(if_stmt) => {
+            in_context(v, ExprContext::Value(None), |v| walk_expr(v, &if_stmt.cond));
+            walk_stmts(v, &if_stmt.body);
+            walk_stmts(v, &if_stmt.orelse);
+        }
+        ast::Stmt::SchemaAttr(schema_attr) => {
+            walk_decorators(v, &schema_attr.decorators);
+            in_context(v, ExprContext::SchemaDefault(&schema_attr.name.node), |v| {
+                walk_opt_expr(v, &schema_attr.value)
+            });
+        }
+        ast::Stmt::Schema(schema_stmt) => {
+            walk_decorators(v, &schema_stmt.decorators);
+            if let Some(args) = &schema_stmt.args {
+                walk_arguments(v, &args.node);
+            }
+            walk_stmts(v, &schema_stmt.body);
+            if let Some(index_signature) = &schema_stmt.index_signature {
+                in_context(v, ExprContext::Value(None), |v| {
+                    walk_opt_expr(v, &index_signature.node.value)
+                });
+            }
+            walk_checks(v, &schema_stmt.checks);
+        }
+        ast::Stmt::Rule(rule_stmt) => {
+            walk_decorators(v, &rule_stmt.decorators);
+            if let Some(args) = &rule_stmt.args {
+                walk_arguments(v, &args.node);
+            }
+            walk_checks(v, &rule_stmt.checks);
+        }
+        // The import paths and the literal types are not expressions.
+        ast::Stmt::TypeAlias(_) | ast::Stmt::Import(_) => {}
+    }
+}
+
+pub(crate) fn walk_exprs<V: PositionedVisitor>(v: &mut V, exprs: &[ast::NodeRef<ast::Expr>]) {
+    for expr in exprs {
+        walk_expr(v, expr);
+    }
+}
+
+pub(crate) fn walk_opt_expr<V: PositionedVisitor>(
+    v: &mut V,
+    expr: &Option<ast::NodeRef<ast::Expr>>,
+) {
+    if let Some(expr) = expr {
+        walk_expr(v, expr);
+    }
+}
+
+pub(crate) fn walk_expr<V: PositionedVisitor>(v: &mut V, expr: &ast::NodeRef<ast::Expr>) {
+    if !v.visit_expr(expr) {
+        return;
+    }
+    match &expr.node {
+        ast::Expr::JoinedString(joined_string) => walk_exprs(v, &joined_string.values),
+        ast::Expr::FormattedValue(formatted_value) => walk_expr(v, &formatted_value.value),
+        ast::Expr::Unary(unary_expr) => walk_expr(v, &unary_expr.operand),
+        ast::Expr::Binary(binary_expr) => {
+            walk_expr(v, &binary_expr.left);
+            walk_expr(v, &binary_expr.right);
+        }
+        ast::Expr::Compare(compare) => {
+            walk_expr(v, &compare.left);
+            walk_exprs(v, &compare.comparators);
+        }
+        ast::Expr::If(if_expr) => {
+            walk_expr(v, &if_expr.body);
+            walk_expr(v, &if_expr.cond);
+            walk_expr(v, &if_expr.orelse);
+        }
+        ast::Expr::Selector(selector_expr) => walk_expr(v, &selector_expr.value),
+        ast::Expr::Call(call_expr) => walk_call_expr(v, call_expr),
+        ast::Expr::Paren(paren_expr) => walk_expr(v, &paren_expr.expr),
+        ast::Expr::Quant(quant_expr) => {
+            walk_expr(v, &quant_expr.target);
+            walk_expr(v, &quant_expr.test);
+            walk_opt_expr(v, &quant_expr.if_cond);
+        }
+        ast::Expr::List(list_expr) => walk_exprs(v, &list_expr.elts),
+        ast::Expr::ListIfItem(list_if_item_expr) => {
+            walk_expr(v, &list_if_item_expr.if_cond);
+            walk_exprs(v, &list_if_item_expr.exprs);
+            walk_opt_expr(v, &list_if_item_expr.orelse);
+        }
+        ast::Expr::ListComp(list_comp) => {
+            walk_expr(v, &list_comp.elt);
+            walk_comp_clauses(v, &list_comp.generators);
+        }
+        ast::Expr::Starred(starred_expr) => walk_expr(v, &starred_expr.value),
+        ast::Expr::DictComp(dict_comp) => {
+            walk_opt_expr(v, &dict_comp.entry.key);
+            walk_expr(v, &dict_comp.entry.value);
+            walk_comp_clauses(v, &dict_comp.generators);
+        }
+        ast::Expr::ConfigIfEntry(config_if_entry_expr) => {
+            walk_expr(v, &config_if_entry_expr.if_cond);
+            walk_config_entries(v, &config_if_entry_expr.items);
+            walk_opt_expr(v, &config_if_entry_expr.orelse);
+        }
+        ast::Expr::CompClause(comp_clause) => walk_comp_clause(v, comp_clause),
+        ast::Expr::Schema(schema_expr) => walk_schema_expr(v, schema_expr),
+        ast::Expr::Config(config_expr) => walk_config_entries(v, &config_expr.items),
+        ast::Expr::Check(check_expr) => walk_check_expr(v, check_expr),
+        ast::Expr::Lambda(lambda_expr) => {
+            if let Some(args) = &lambda_expr.args {
+                walk_arguments(v, &args.node);
+            }
+            walk_stmts(v, &lambda_expr.body);
+        }
+        ast::Expr::Subscript(subscript) => {
+            walk_expr(v, &subscript.value);
+            in_context(v, ExprContext::Subscript, |v| {
+                walk_opt_expr(v, &subscript.index);
+                walk_opt_expr(v, &subscript.lower);
+                walk_opt_expr(v, &subscript.upper);
+                walk_opt_expr(v, &subscript.step);
+            });
+        }
+        ast::Expr::Keyword(keyword) => walk_opt_expr(v, &keyword.value),
+        ast::Expr::Arguments(arguments) => walk_arguments(v, arguments),
+        ast::Expr::Target(_)
+        | ast::Expr::Identifier(_)
+        | ast::Expr::NumberLit(_)
+        | ast::Expr::StringLit(_)
+        | ast::Expr::NameConstantLit(_)
+        | ast::Expr::Missing(_) => {}
+    }
+}
+
+fn walk_decorators<V: PositionedVisitor>(v: &mut V, decorators: &[ast::NodeRef<ast::CallExpr>]) {
+    in_context(v, ExprContext::Decorator, |v| {
+        for decorator in decorators {
+            walk_call_expr(v, &decorator.node);
+        }
+    });
+}
+
+fn walk_checks<V: PositionedVisitor>(v: &mut V, checks: &[ast::NodeRef<ast::CheckExpr>]) {
+    in_context(v, ExprContext::Check, |v| {
+        for check in checks {
+            walk_check_expr(v, &check.node);
+        }
+    });
+}
+
+fn walk_check_expr<V: PositionedVisitor>(v: &mut V, check_expr: &ast::CheckExpr) {
+    walk_expr(v, &check_expr.test);
+    walk_opt_expr(v, &check_expr.if_cond);
+    walk_opt_expr(v, &check_expr.msg);
+}
+
+fn walk_call_expr<V: PositionedVisitor>(v: &mut V, call_expr: &ast::CallExpr) {
+    walk_expr(v, &call_expr.func);
+    walk_exprs(v, &call_expr.args);
+    walk_keywords(v, &call_expr.keywords);
+}
+
+fn walk_keywords<V: PositionedVisitor>(v: &mut V, keywords: &[ast::NodeRef<ast::Keyword>]) {
+    for keyword in keywords {
+        let name = keyword.node.arg.node.names.last();
+        in_context(
+            v,
+            ExprContext::Value(name.map(|name| name.node.as_str())),
+            |v| walk_opt_expr(v, &keyword.node.value),
+        );
+    }
+}
+
+fn walk_schema_expr<V: PositionedVisitor>(v: &mut V, schema_expr: &ast::SchemaExpr) {
+    walk_exprs(v, &schema_expr.args);
+    walk_keywords(v, &schema_expr.kwargs);
+    walk_expr(v, &schema_expr.config);
+}
+
+fn walk_config_entries<V: PositionedVisitor>(
+    v: &mut V,
+    entries: &[ast::NodeRef<ast::ConfigEntry>],
+) {
+    for entry in entries {
+        in_context(v, ExprContext::ConfigKey, |v| {
+            walk_opt_expr(v, &entry.node.key)
+        });
+        let name = entry.node.key.as_ref().and_then(last_name);
+        in_context(v, ExprContext::Value(name), |v| {
+            walk_expr(v, &entry.node.value)
+        });
+    }
+}
+
+/// The last segment of the name of a key, e.g. `timeout` of `session.timeout`.
+fn last_name(expr: &ast::NodeRef<ast::Expr>) -> Option<&str> {
+    match &expr.node {
+        ast::Expr::Identifier(identifier) => identifier.names.last().map(|name| name.node.as_str()),
+        ast::Expr::StringLit(string_lit) => Some(&string_lit.value),
+        _ => None,
+    }
+}
+
+fn walk_arguments<V: PositionedVisitor>(v: &mut V, arguments: &ast::Arguments) {
+    for (arg, default) in arguments.args.iter().zip(&arguments.defaults) {
+        let name = arg.node.names.last();
+        in_context(
+            v,
+            ExprContext::Value(name.map(|name| name.node.as_str())),
+            |v| walk_opt_expr(v, default),
+        );
+    }
+}
+
+fn walk_comp_clauses<V: PositionedVisitor>(
+    v: &mut V,
+    comp_clauses: &[ast::NodeRef<ast::CompClause>],
+) {
+    for comp_clause in comp_clauses {
+        walk_comp_clause(v, &comp_clause.node);
+    }
+}
+
+fn walk_comp_clause<V: PositionedVisitor>(v: &mut V, comp_clause: &ast::CompClause) {
+    walk_expr(v, &comp_clause.iter);
+    walk_exprs(v, &comp_clause.ifs);
+}
@@ -0,0 +1,367 @@
+//! The provenance of a rendered config, which is the location of the expression producing each
+//! key of the output, e.g. for the entry files
+//!
+//! ```kcl
+//! # base.k
+//! app: App {replicas = 1}
+//! # prod.k
+//! app: App {replicas = 3}
+//! ```
+//!
+//! `app.replicas` is produced by `3` in `prod.k`. The provenance follows the merge of the top
+//! level configs in the evaluation order: the unions merge the keys, the overrides replace the
+//! keys with their nested keys, and the later entries win. A value without its own entry, e.g. the
+//! default value of a schema attribute, is produced by the nearest config containing it.
+//!
+//! The branch of a conditional entry, e.g. `if env == "prod": replicas = 3`, is only known at
+//! runtime, so the keys the branches may produce are ambiguous until a later unconditional entry
+//! produces them.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use indexmap::IndexMap;
+use kclvm_ast::ast;
+use kclvm_ast::pos::GetPos;
+use kclvm_error::Position as KCLPos;
+use kclvm_parser::{parse_single_file, ParseSessionRef};
+use kclvm_query::r#override::parse_override_spec;
+use kclvm_runner::exec_program;
+use lsp_types::{Location, Range, Url};
+
+use crate::lsp_ext::{RenderFormat, RenderWithProvenanceResult};
+use crate::render_config::exec_program_args;
+use crate::to_lsp::lsp_pos;
+
+/// Evaluates the entry `files` whose contents are `codes` with the options and the overrides, and
+/// returns the output with the provenance of its keys.
+pub(crate) fn render_with_provenance(
+    files: &[String],
+    codes: &[String],
+    options: &HashMap<String, String>,
+    overrides: &[String],
+    format: RenderFormat,
+) -> RenderWithProvenanceResult {
+    let exec_args = exec_program_args(files, codes, options, overrides);
+    let result = match exec_program(ParseSessionRef::default(), &exec_args) {
+        Ok(result) if result.err_message.is_empty() => result,
+        Ok(result) => return failed_result(result.err_message),
+        Err(err) => return failed_result(err.to_string()),
+    };
+    let mut collector = ProvenanceCollector::default();
+    for (file, code) in files.iter().zip(codes) {
+        let uri = match Url::from_file_path(Path::new(file)) {
+            Ok(uri) => uri,
+            Err(_) => continue,
+        };
+        if let Ok(parsed) = parse_single_file(file, Some(code.clone())) {
+            for stmt in &parsed.module.body {
+                collector.record_stmt(stmt, &uri);
+            }
+        }
+    }
+    for spec in overrides
+        .iter()
+        .filter_map(|spec| parse_override_spec(spec).ok())
+    {
+        collector.record_override(&spec.field_path);
+    }
+    let mut provenance = HashMap::new();
+    let mut ambiguous = vec![];
+    if let Ok(value) = serde_json::from_str::<serde_json::Value>(&result.json_result) {
+        let mut paths = vec![];
+        output_paths(&value, "", &mut paths);
+        for path in paths {
+            match collector.lookup(&path) {
+                Some(Origin::Located(location)) => {
+                    provenance.insert(path, location.clone());
+                }
+                Some(Origin::Ambiguous) => ambiguous.push(path),
+                None => {}
+            }
+        }
+    }
+    ambiguous.sort();
+    RenderWithProvenanceResult {
+        output: Some(match format {
+            RenderFormat::Yaml => result.yaml_result,
+            RenderFormat::Json => result.json_result,
+        }),
+        provenance,
+        ambiguous,
+        diagnostics: vec![],
+    }
+}
+
+fn failed_result(message: String) -> RenderWithProvenanceResult {
+    RenderWithProvenanceResult {
+        output: None,
+        provenance: HashMap::new(),
+        ambiguous: vec![],
+        diagnostics: vec![message],
+    }
+}
+
+/// Collects the dotted paths of the keys of the output value, the lists are not expanded.
+fn output_paths(value: &serde_json::Value, prefix: &str, paths: &mut Vec<String>) {
+    if let serde_json::Value::Object(map) = value {
+        for (key, value) in map {
+            let path = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            output_paths(value, &path, paths);
+            paths.push(path);
+        }
+    }
+}
+
+/// The origin of a path, which is ambiguous if a conditional entry may produce it.
+#[derive(Clone)]
+enum Origin {
+    Located(Location),
+    Ambiguous,
+}
+
+#[derive(Default)]
+struct ProvenanceCollector {
+    origins: IndexMap<String, Origin>,
+    /// The paths set by the overrides, which are not produced by any expression.
+    overridden: HashSet<String>,
+    /// The depth of the conditional entries being recorded.
+    conditional: usize,
+}
+
+impl ProvenanceCollector {
+    fn record_stmt(&mut self, stmt: &ast::NodeRef<ast::Stmt>, uri: &Url) {
+        match &stmt.node {
+            ast::Stmt::Assign(assign_stmt) => {
+                for target in &assign_stmt.targets {
+                    if let Some(path) = target_path(&target.node) {
+                        self.record(
+                            path,
+                            &assign_stmt.value,
+                            &ast::ConfigEntryOperation::Override,
+                            uri,
+                        );
+                    }
+                }
+            }
+            ast::Stmt::Unification(unification_stmt) => {
+                let value = &unification_stmt.value;
+                let path = unification_stmt.target.node.get_name();
+                self.locate(path.clone(), &value.get_pos(), &value.get_end_pos(), uri);
+                if let ast::Expr::Config(config_expr) = &value.node.config.node {
+                    self.record_entries(&path, &config_expr.items, uri);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn record(
+        &mut self,
+        path: String,
+        expr: &ast::NodeRef<ast::Expr>,
+        operation: &ast::ConfigEntryOperation,
+        uri: &Url,
+    ) {
+        if *operation == ast::ConfigEntryOperation::Override {
+            self.remove_subtree(&path);
+        }
+        self.locate(path.clone(), &expr.get_pos(), &expr.get_end_pos(), uri);
+        let config = match &expr.node {
+            ast::Expr::Config(config_expr) => Some(config_expr),
+            ast::Expr::Schema(schema_expr) => match &schema_expr.config.node {
+                ast::Expr::Config(config_expr) => Some(config_expr),
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some(config_expr) = config {
+            self.record_entries(&path, &config_expr.items, uri);
+        }
+    }
+
+    fn record_entries(&mut self, path: &str, items: &[ast::NodeRef<ast::ConfigEntry>], uri: &Url) {
+        for entry in items {
+            match &entry.node.key {
+                Some(key) => {
+                    if let Some(key) = config_key(key) {
+                        self.record(
+                            format!("{}.{}", path, key),
+                            &entry.node.value,
+                            &entry.node.operation,
+                            uri,
+                        );
+                    }
+                }
+                // The keys of the unpacked configs, e.g. `**base`, are unknown statically.
+                None => {
+                    if let ast::Expr::ConfigIfEntry(if_entry) = &entry.node.value.node {
+                        self.record_if_entry(path, if_entry, uri);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Records the keys of both branches as ambiguous, either of which may produce the keys.
+    fn record_if_entry(&mut self, path: &str, if_entry: &ast::ConfigIfEntryExpr, uri: &Url) {
+        self.conditional += 1;
+        self.record_branches(path, if_entry, uri);
+        self.conditional -= 1;
+    }
+
+    fn record_branches(&mut self, path: &str, if_entry: &ast::ConfigIfEntryExpr, uri: &Url) {
+        self.record_entries(path, &if_entry.items, uri);
+        if let Some(orelse) = &if_entry.orelse {
+            match &orelse.node {
+                ast::Expr::ConfigIfEntry(if_entry) => self.record_branches(path, if_entry, uri),
+                ast::Expr::Config(config_expr) => {
+                    self.record_entries(path, &config_expr.items, uri)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn record_override(&mut self, path: &str) {
+        self.remove_subtree(path);
+        self.overridden.insert(path.to_string());
+    }
+
+    fn locate(&mut self, path: String, start: &KCLPos, end: &KCLPos, uri: &Url) {
+        let origin = if self.conditional > 0 {
+            Origin::Ambiguous
+        } else {
+            Origin::Located(Location {
+                uri: uri.clone(),
+                range: Range::new(lsp_pos(start), lsp_pos(end)),
+            })
+        };
+        self.origins.insert(path, origin);
+    }
+
+    /// Removes the origins of the path and its nested paths, which become ambiguous instead if
+    /// the removing entry is conditional.
+    fn remove_subtree(&mut self, path: &str) {
+        let prefix = format!("{}.", path);
+        let in_subtree = |key: &String| key == path || key.starts_with(&prefix);
+        if self.conditional > 0 {
+            for (_, origin) in self.origins.iter_mut().filter(|(key, _)| in_subtree(key)) {
+                *origin = Origin::Ambiguous;
+            }
+        } else {
+            self.origins.retain(|key, _| !in_subtree(key));
+        }
+    }
+
+    /// Returns the origin of the path, or of its nearest recorded ancestor. The paths set by the
+    /// overrides have no origins.
+    fn lookup(&self, path: &str) -> Option<&Origin> {
+        let mut path = path;
+        loop {
+            if self.overridden.contains(path) {
+                return None;
+            }
+            if let Some(origin) = self.origins.get(path) {
+                return Some(origin);
+            }
+            path = path.rsplit_once('.')?.0;
+        }
+    }
+}
+
+/// Returns the dotted path of an assignment target, e.g. `app.replicas`. The items of the lists,
+/// e.g. `apps[0]`, are not tracked.
+fn target_path(target: &ast::Target) -> Option<String> {
+    let mut path = target.name.node.clone();
+    for member in &target.paths {
+        match member {
+            ast::MemberOrIndex::Member(name) => path = format!("{}.{}", path, name.node),
+            ast::MemberOrIndex::Index(_) => return None,
+        }
+    }
+    Some(path)
+}
+
+/// Returns the dotted key of a config entry, e.g. `labels.env` or `"labels"`.
+fn config_key(key: &ast::NodeRef<ast::Expr>) -> Option<String> {
+    match &key.node {
+        ast::Expr::Identifier(identifier) => Some(identifier.get_name()),
+        ast::Expr::StringLit(string_lit) => Some(string_lit.value.clone()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use lsp_types::Position;
+    use proc_macro_crate::bench_test;
+
+    use super::render_with_provenance;
+    use crate::lsp_ext::RenderFormat;
+
+    #[test]
+    #[bench_test]
+    fn render_with_provenance_test() {
+        let dir = std::fs::canonicalize("src/test_data/render_with_provenance").unwrap();
+        let files: Vec<String> = ["base.k", "prod.k"]
+            .iter()
+            .map(|file| dir.join(file).to_str().unwrap().to_string())
+            .collect();
+        let codes: Vec<String> = files
+            .iter()
+            .map(|file| std::fs::read_to_string(file).unwrap())
+            .collect();
+        let result = render_with_provenance(
+            &files,
+            &codes,
+            &HashMap::new(),
+            &["app.name=\"web\"".to_string()],
+            RenderFormat::Json,
+        );
+        assert!(result.diagnostics.is_empty(), "{:?}", result.diagnostics);
+        let value: serde_json::Value = serde_json::from_str(&result.output.unwrap()).unwrap();
+        assert_eq!(value["app"]["replicas"], 3);
+        let location = |path: &str| {
+            let location = &result.provenance[path];
+            (
+                location.uri.to_file_path().unwrap(),
+                location.range.start,
+                location.range.end,
+            )
+        };
+
+        // The value overridden by the later file, and the value computed by an expression.
+        assert_eq!(
+            location("app.replicas"),
+            (
+                dir.join("prod.k"),
+                Position::new(1, 15),
+                Position::new(1, 16)
+            )
+        );
+        assert_eq!(
+            location("app.port"),
+            (
+                dir.join("prod.k"),
+                Position::new(2, 11),
+                Position::new(2, 20)
+            )
+        );
+        assert_eq!(location("app.labels.env").0, dir.join("base.k"));
+        assert_eq!(location("app.labels.env").1, Position::new(9, 17));
+        // The value produced by a branch of a conditional entry.
+        assert_eq!(value["app"]["labels"]["tier"], "web");
+        assert!(!result.provenance.contains_key("app.labels.tier"));
+        assert_eq!(result.ambiguous, vec!["app.labels.tier".to_string()]);
+        // The values set by the overrides have no locations.
+        assert!(!result.provenance.contains_key("app.name"));
+        assert_eq!(location("app").0, dir.join("prod.k"));
+    }
+}
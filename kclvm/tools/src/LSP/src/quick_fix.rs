@@ -1,11 +1,146 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use kclvm_error::{DiagnosticId, ErrorKind, WarningKind};
 use lsp_types::{
     CodeAction, CodeActionKind, CodeActionOrCommand, Diagnostic, NumberOrString, TextEdit, Url,
 };
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+/// The safety level of an auto-fix, from the least to the most risky.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FixSafety {
+    /// The fix never changes the program semantics, e.g. removing an unused import.
+    #[default]
+    Safe,
+    /// The fix is likely correct but should be reviewed, e.g. renaming to a similar name.
+    NeedsReview,
+    /// The fix may change the program semantics.
+    Unsafe,
+}
+
+/// Returns the safety level of the fix of the diagnostic, or `None` if the diagnostic has no fix.
+pub(crate) fn fix_safety(id: &DiagnosticId, has_replacement: bool) -> Option<FixSafety> {
+    match id {
+        DiagnosticId::Warning(WarningKind::UnusedImportWarning)
+        | DiagnosticId::Warning(WarningKind::ReimportWarning) => Some(FixSafety::Safe),
+        DiagnosticId::Error(ErrorKind::CompileError) if has_replacement => {
+            Some(FixSafety::NeedsReview)
+        }
+        DiagnosticId::Error(ErrorKind::InvalidSyntax) if has_replacement => Some(FixSafety::Unsafe),
+        // The explicit conversion may fail at runtime, e.g. `int("a")`.
+        DiagnosticId::Warning(WarningKind::ImplicitCoercionWarning) if has_replacement => {
+            Some(FixSafety::Unsafe)
+        }
+        // Removing the declaration also removes the errors its value may raise when evaluated.
+        DiagnosticId::Warning(WarningKind::UnusedPrivateSymbolWarning) => {
+            Some(FixSafety::NeedsReview)
+        }
+        DiagnosticId::Warning(WarningKind::MissingCheckMessageWarning) if has_replacement => {
+            Some(FixSafety::Safe)
+        }
+        // The replacement may differ from the deprecated builtin in the edge cases.
+        DiagnosticId::Warning(WarningKind::DeprecatedBuiltinWarning) if has_replacement => {
+            Some(FixSafety::NeedsReview)
+        }
+        _ => None,
+    }
+}
+
+/// Builds the `source.fixAll` code action, which applies the quick fixes of all the diagnostics
+/// whose safety level is not higher than `max_safety`. Diagnostics with several alternative
+/// fixes are left to the user.
+pub fn fix_all(
+    uri: &Url,
+    diags: &[Diagnostic],
+    max_safety: FixSafety,
+) -> Option<lsp_types::CodeActionOrCommand> {
+    let mut edits: Vec<TextEdit> = vec![];
+    let mut fixed_diags = vec![];
+    for diag in diags {
+        match extract_fix_safety(&diag.data) {
+            Some(safety) if safety <= max_safety => {}
+            _ => continue,
+        }
+        let actions = quick_fix(uri, &[diag.clone()]);
+        if actions.len() != 1 {
+            continue;
+        }
+        let changes = match &actions[0] {
+            CodeActionOrCommand::CodeAction(action) => {
+                action.edit.as_ref().and_then(|edit| edit.changes.as_ref())
+            }
+            CodeActionOrCommand::Command(_) => None,
+        };
+        if let Some(changes) = changes {
+            for edit in changes.get(uri).into_iter().flatten() {
+                let overlapped = edits.iter().any(|e| {
+                    (e.range.start < edit.range.end && edit.range.start < e.range.end)
+                        || e.range == edit.range
+                });
+                if !overlapped {
+                    edits.push(edit.clone());
+                }
+            }
+            fixed_diags.push(diag.clone());
+        }
+    }
+    if edits.is_empty() {
+        return None;
+    }
+    let mut changes = HashMap::new();
+    changes.insert(uri.clone(), edits);
+    Some(CodeActionOrCommand::CodeAction(CodeAction {
+        title: "Fix all auto-fixable problems".to_string(),
+        kind: Some(CodeActionKind::SOURCE_FIX_ALL),
+        diagnostics: Some(fixed_diags),
+        edit: Some(lsp_types::WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }))
+}
+
+/// Orders the code actions by their kinds, the quick fixes first, then the refactors, the source
+/// actions and the others. The actions of the same kind keep the order they are produced in, so
+/// the list is stable for the same range. The actions with the same title and kind are removed
+/// except the first one.
+pub(crate) fn normalize_code_actions(
+    actions: Vec<CodeActionOrCommand>,
+) -> Vec<CodeActionOrCommand> {
+    let mut seen = HashSet::new();
+    let mut actions: Vec<CodeActionOrCommand> = actions
+        .into_iter()
+        .filter(|action| {
+            let key = match action {
+                CodeActionOrCommand::CodeAction(action) => (
+                    action.title.clone(),
+                    action.kind.as_ref().map(|kind| kind.as_str().to_string()),
+                ),
+                CodeActionOrCommand::Command(command) => (command.title.clone(), None),
+            };
+            seen.insert(key)
+        })
+        .collect();
+    actions.sort_by_key(code_action_kind_order);
+    actions
+}
+
+fn code_action_kind_order(action: &CodeActionOrCommand) -> usize {
+    let kind = match action {
+        CodeActionOrCommand::CodeAction(action) => action.kind.as_ref(),
+        CodeActionOrCommand::Command(_) => None,
+    };
+    match kind.map(|kind| kind.as_str()) {
+        Some(kind) if kind.starts_with(CodeActionKind::QUICKFIX.as_str()) => 0,
+        Some(kind) if kind.starts_with(CodeActionKind::REFACTOR.as_str()) => 1,
+        Some(kind) if kind.starts_with(CodeActionKind::SOURCE.as_str()) => 2,
+        _ => 3,
+    }
+}
+
 pub fn quick_fix(uri: &Url, diags: &[Diagnostic]) -> Vec<lsp_types::CodeActionOrCommand> {
     let mut code_actions: Vec<lsp_types::CodeActionOrCommand> = vec![];
     for diag in diags {
@@ -113,6 +248,96 @@ pub fn quick_fix(uri: &Url, diags: &[Diagnostic]) -> Vec<lsp_types::CodeActionOr
                                 ..Default::default()
                             }))
                         }
+                        WarningKind::ImplicitCoercionWarning => {
+                            for replacement_text in extract_suggested_replacements(&diag.data) {
+                                let mut changes = HashMap::new();
+                                changes.insert(
+                                    uri.clone(),
+                                    vec![TextEdit {
+                                        range: diag.range,
+                                        new_text: replacement_text.clone(),
+                                    }],
+                                );
+                                code_actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                                    title: format!("Convert explicitly: `{}`", replacement_text),
+                                    kind: Some(CodeActionKind::QUICKFIX),
+                                    diagnostics: Some(vec![diag.clone()]),
+                                    edit: Some(lsp_types::WorkspaceEdit {
+                                        changes: Some(changes),
+                                        ..Default::default()
+                                    }),
+                                    ..Default::default()
+                                }))
+                            }
+                        }
+                        WarningKind::UnusedPrivateSymbolWarning => {
+                            let mut changes = HashMap::new();
+                            changes.insert(
+                                uri.clone(),
+                                vec![TextEdit {
+                                    range: diag.range,
+                                    new_text: "".to_string(),
+                                }],
+                            );
+                            code_actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                                title: "Remove unused private symbol".to_string(),
+                                kind: Some(CodeActionKind::QUICKFIX),
+                                diagnostics: Some(vec![diag.clone()]),
+                                edit: Some(lsp_types::WorkspaceEdit {
+                                    changes: Some(changes),
+                                    ..Default::default()
+                                }),
+                                ..Default::default()
+                            }))
+                        }
+                        WarningKind::MissingCheckMessageWarning => {
+                            // The message is inserted after the check instead of replacing it.
+                            for replacement_text in extract_suggested_replacements(&diag.data) {
+                                let mut changes = HashMap::new();
+                                changes.insert(
+                                    uri.clone(),
+                                    vec![TextEdit {
+                                        range: lsp_types::Range::new(
+                                            diag.range.end,
+                                            diag.range.end,
+                                        ),
+                                        new_text: replacement_text,
+                                    }],
+                                );
+                                code_actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                                    title: "Add a check message".to_string(),
+                                    kind: Some(CodeActionKind::QUICKFIX),
+                                    diagnostics: Some(vec![diag.clone()]),
+                                    edit: Some(lsp_types::WorkspaceEdit {
+                                        changes: Some(changes),
+                                        ..Default::default()
+                                    }),
+                                    ..Default::default()
+                                }))
+                            }
+                        }
+                        WarningKind::DeprecatedBuiltinWarning => {
+                            for replacement_text in extract_suggested_replacements(&diag.data) {
+                                let mut changes = HashMap::new();
+                                changes.insert(
+                                    uri.clone(),
+                                    vec![TextEdit {
+                                        range: diag.range,
+                                        new_text: replacement_text.clone(),
+                                    }],
+                                );
+                                code_actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                                    title: format!("Replace with `{}`", replacement_text),
+                                    kind: Some(CodeActionKind::QUICKFIX),
+                                    diagnostics: Some(vec![diag.clone()]),
+                                    edit: Some(lsp_types::WorkspaceEdit {
+                                        changes: Some(changes),
+                                        ..Default::default()
+                                    }),
+                                    ..Default::default()
+                                }))
+                            }
+                        }
                         _ => continue,
                     },
                     DiagnosticId::Suggestions => continue,
@@ -123,7 +348,7 @@ pub fn quick_fix(uri: &Url, diags: &[Diagnostic]) -> Vec<lsp_types::CodeActionOr
     code_actions
 }
 
-fn extract_suggested_replacements(data: &Option<Value>) -> Vec<String> {
+pub(crate) fn extract_suggested_replacements(data: &Option<Value>) -> Vec<String> {
     data.as_ref()
         .and_then(|data| match data {
             Value::Object(obj) => obj.get("suggested_replacement").map(|val| match val {
@@ -139,6 +364,12 @@ fn extract_suggested_replacements(data: &Option<Value>) -> Vec<String> {
         .unwrap_or_default()
 }
 
+fn extract_fix_safety(data: &Option<Value>) -> Option<FixSafety> {
+    data.as_ref()
+        .and_then(|data| data.get("fix_safety"))
+        .and_then(|val| serde_json::from_value(val.clone()).ok())
+}
+
 pub(crate) fn convert_code_to_kcl_diag_id(code: &NumberOrString) -> Option<DiagnosticId> {
     match code {
         NumberOrString::Number(_) => None,
@@ -151,6 +382,49 @@ pub(crate) fn convert_code_to_kcl_diag_id(code: &NumberOrString) -> Option<Diagn
             "ImportPositionWarning" => {
                 Some(DiagnosticId::Warning(WarningKind::ImportPositionWarning))
             }
+            "DuplicateAssignmentWarning" => Some(DiagnosticId::Warning(
+                WarningKind::DuplicateAssignmentWarning,
+            )),
+            "PrecisionLossWarning" => {
+                Some(DiagnosticId::Warning(WarningKind::PrecisionLossWarning))
+            }
+            "UnusedSchemaWarning" => Some(DiagnosticId::Warning(WarningKind::UnusedSchemaWarning)),
+            "ConstantAssertWarning" => {
+                Some(DiagnosticId::Warning(WarningKind::ConstantAssertWarning))
+            }
+            "ImplicitCoercionWarning" => {
+                Some(DiagnosticId::Warning(WarningKind::ImplicitCoercionWarning))
+            }
+            "UnusedPrivateSymbolWarning" => Some(DiagnosticId::Warning(
+                WarningKind::UnusedPrivateSymbolWarning,
+            )),
+            "LineEndingWarning" => Some(DiagnosticId::Warning(WarningKind::LineEndingWarning)),
+            "DefaultViolatesCheckWarning" => Some(DiagnosticId::Warning(
+                WarningKind::DefaultViolatesCheckWarning,
+            )),
+            "TodoCommentWarning" => Some(DiagnosticId::Warning(WarningKind::TodoCommentWarning)),
+            "IncompatibleComparisonWarning" => Some(DiagnosticId::Warning(
+                WarningKind::IncompatibleComparisonWarning,
+            )),
+            "UnsatisfiableCheckWarning" => Some(DiagnosticId::Warning(
+                WarningKind::UnsatisfiableCheckWarning,
+            )),
+            "MissingTypeAnnotationWarning" => Some(DiagnosticId::Warning(
+                WarningKind::MissingTypeAnnotationWarning,
+            )),
+            "MissingCheckMessageWarning" => Some(DiagnosticId::Warning(
+                WarningKind::MissingCheckMessageWarning,
+            )),
+            "InconsistentIndentationWarning" => Some(DiagnosticId::Warning(
+                WarningKind::InconsistentIndentationWarning,
+            )),
+            "BuiltinShadowingWarning" => {
+                Some(DiagnosticId::Warning(WarningKind::BuiltinShadowingWarning))
+            }
+            "DeprecatedBuiltinWarning" => {
+                Some(DiagnosticId::Warning(WarningKind::DeprecatedBuiltinWarning))
+            }
+            "MagicNumberWarning" => Some(DiagnosticId::Warning(WarningKind::MagicNumberWarning)),
             _ => None,
         },
     }
@@ -165,7 +439,7 @@ mod tests {
     use proc_macro_crate::bench_test;
     use std::path::PathBuf;
 
-    use super::quick_fix;
+    use super::{fix_all, quick_fix, FixSafety};
     use crate::{
         compile::{compile_with_params, Params},
         state::KCLVfs,
@@ -191,12 +465,17 @@ mod tests {
             scope_cache: None,
             vfs: Some(KCLVfs::default()),
             gs_cache: None,
+            max_schema_depth: None,
+            minimal_diagnostics: false,
+            implicit_coercion_check: false,
+            explicit_type_check: false,
+            ambiguous_reference_check: false,
         })
         .0;
 
         let diagnostics = diags
             .iter()
-            .flat_map(|diag| kcl_diag_to_lsp_diags_by_file(diag, file))
+            .flat_map(|diag| kcl_diag_to_lsp_diags_by_file(diag, file, None))
             .collect::<Vec<Diagnostic>>();
 
         let uri = Url::from_file_path(file).unwrap();
@@ -270,4 +549,75 @@ mod tests {
         assert_eq!(expected[0], code_actions[0]);
         assert_eq!(expected[1], code_actions[1]);
     }
+
+    #[test]
+    fn fix_all_safety_test() {
+        let uri = Url::from_file_path(std::env::current_dir().unwrap().join("main.k")).unwrap();
+        let range = |line| Range {
+            start: Position { line, character: 0 },
+            end: Position {
+                line,
+                character: 10,
+            },
+        };
+        let unused_import = Diagnostic {
+            range: range(0),
+            code: Some(lsp_types::NumberOrString::String(
+                "UnusedImportWarning".to_string(),
+            )),
+            message: "Module 'abc' imported but unused".to_string(),
+            data: Some(serde_json::json!({ "fix_safety": "safe" })),
+            ..Default::default()
+        };
+        let rename = Diagnostic {
+            range: range(1),
+            code: Some(lsp_types::NumberOrString::String(
+                "CompileError".to_string(),
+            )),
+            message: "name 'nu' is not defined, did you mean '[\"number\"]'?".to_string(),
+            data: Some(serde_json::json!({
+                "suggested_replacement": ["number"],
+                "fix_safety": "needs-review"
+            })),
+            ..Default::default()
+        };
+        let diagnostics = vec![unused_import.clone(), rename.clone()];
+
+        // The code action menu still offers the needs-review fix individually.
+        assert_eq!(quick_fix(&uri, &diagnostics).len(), 2);
+
+        let edits = |action: Option<CodeActionOrCommand>| match action {
+            Some(CodeActionOrCommand::CodeAction(action)) => (
+                action.edit.unwrap().changes.unwrap()[&uri].clone(),
+                action.diagnostics.unwrap(),
+            ),
+            _ => panic!("expected a fix all code action"),
+        };
+
+        let (safe_edits, fixed) = edits(fix_all(&uri, &diagnostics, FixSafety::Safe));
+        assert_eq!(
+            safe_edits,
+            vec![TextEdit {
+                range: range(0),
+                new_text: "".to_string(),
+            }]
+        );
+        assert_eq!(fixed, vec![unused_import.clone()]);
+
+        let (review_edits, fixed) = edits(fix_all(&uri, &diagnostics, FixSafety::NeedsReview));
+        assert_eq!(
+            review_edits,
+            vec![
+                TextEdit {
+                    range: range(0),
+                    new_text: "".to_string(),
+                },
+                TextEdit {
+                    range: range(1),
+                    new_text: "number".to_string(),
+                }
+            ]
+        );
+        assert_eq!(fixed, vec![unused_import, rename]);
+    }
 }
@@ -0,0 +1,113 @@
+//! Renders the config of an entry file, which is the YAML or the JSON output of the evaluation
+//! with the options, e.g. `-D env="prod"`, and the overrides, e.g. `-O app.replicas=3`, the same
+//! as the output of `kcl run`.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use kclvm_ast::ast;
+use kclvm_parser::ParseSessionRef;
+use kclvm_runner::{exec_program, ExecProgramArgs};
+
+use crate::lsp_ext::{RenderConfigResult, RenderFormat};
+
+/// Evaluates the entry `file` whose content is `src` with the options and the overrides.
+pub(crate) fn render_config(
+    file: &str,
+    src: &str,
+    options: &HashMap<String, String>,
+    overrides: &[String],
+    format: RenderFormat,
+) -> RenderConfigResult {
+    let exec_args = ExecProgramArgs {
+        disable_yaml_result: format == RenderFormat::Json,
+        ..exec_program_args(&[file.to_string()], &[src.to_string()], options, overrides)
+    };
+    let (output, error) = match exec_program(ParseSessionRef::default(), &exec_args) {
+        Ok(result) if result.err_message.is_empty() => match format {
+            RenderFormat::Yaml => (Some(result.yaml_result), None),
+            RenderFormat::Json => (Some(result.json_result), None),
+        },
+        Ok(result) => (None, Some(result.err_message)),
+        Err(err) => (None, Some(err.to_string())),
+    };
+    RenderConfigResult {
+        output,
+        diagnostics: error.into_iter().collect(),
+    }
+}
+
+/// Returns the arguments evaluating the entry `files` whose contents are `codes`, in the directory
+/// of the first file. The options are passed in the order of the names, so the same options always
+/// render the same output.
+pub(crate) fn exec_program_args(
+    files: &[String],
+    codes: &[String],
+    options: &HashMap<String, String>,
+    overrides: &[String],
+) -> ExecProgramArgs {
+    let mut args: Vec<ast::Argument> = options
+        .iter()
+        .map(|(name, value)| ast::Argument {
+            name: name.clone(),
+            value: value.clone(),
+        })
+        .collect();
+    args.sort_by(|a, b| a.name.cmp(&b.name));
+    ExecProgramArgs {
+        work_dir: files
+            .first()
+            .and_then(|file| Path::new(file).parent())
+            .map(|dir| dir.to_string_lossy().to_string()),
+        k_filename_list: files.to_vec(),
+        k_code_list: codes.to_vec(),
+        args,
+        overrides: overrides.to_vec(),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use proc_macro_crate::bench_test;
+
+    use super::render_config;
+    use crate::lsp_ext::RenderFormat;
+
+    #[test]
+    #[bench_test]
+    fn render_config_test() {
+        let path = std::fs::canonicalize("src/test_data/render_config/main.k").unwrap();
+        let file = path.to_str().unwrap();
+        let src = std::fs::read_to_string(file).unwrap();
+        let render = |options: &[(&str, &str)]| {
+            let options: HashMap<String, String> = options
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect();
+            let result = render_config(file, &src, &options, &[], RenderFormat::Yaml);
+            assert!(result.diagnostics.is_empty(), "{:?}", result.diagnostics);
+            result.output.unwrap()
+        };
+
+        let dev = render(&[]);
+        assert!(dev.contains("name: nginx-dev"));
+        assert!(dev.contains("replicas: 1"));
+        let prod = render(&[("env", "\"prod\""), ("replicas", "3")]);
+        assert!(prod.contains("name: nginx-prod"));
+        assert!(prod.contains("replicas: 3"));
+        assert_ne!(dev, prod);
+
+        let result = render_config(
+            file,
+            &src,
+            &HashMap::new(),
+            &["app.replicas=5".to_string()],
+            RenderFormat::Json,
+        );
+        let value: serde_json::Value = serde_json::from_str(&result.output.unwrap()).unwrap();
+        assert_eq!(value["app"]["replicas"], 5);
+    }
+}
@@ -1,9 +1,13 @@
 use anyhow::anyhow;
 use crossbeam_channel::Sender;
 
+use kclvm_config::modfile::{get_pkg_root, KCL_MOD_FILE};
 use kclvm_driver::WorkSpaceKind;
+use kclvm_error::Position as KCLPos;
 use kclvm_sema::info::is_valid_kcl_name;
-use lsp_types::{Location, SemanticTokensResult, TextEdit};
+use lsp_types::{
+    CodeActionKind, Location, SemanticTokensFullDeltaResult, SemanticTokensResult, TextEdit, Url,
+};
 use ra_ap_vfs::VfsPath;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -11,20 +15,49 @@ use std::time::Instant;
 
 use crate::{
     analysis::{AnalysisDatabase, DBState},
+    attribute_type::attribute_type,
+    call_hierarchy::{incoming_calls, outgoing_calls, prepare_call_hierarchy},
+    canonical_imports::canonicalize_imports_code_action,
+    codemod::apply_codemod,
+    collapse_variable::collapse_single_use_variable_code_action,
+    commands::{self, extract_config, goto_enclosing_schema_def, sort_schema_attrs},
     completion::completion,
+    conforming_schemas::conforming_schemas,
     dispatcher::RequestDispatcher,
-    document_symbol::document_symbol,
+    document_symbol::{document_symbol, outline_with_types},
+    entry_closure::entry_closure,
     error::LSPError,
+    eval_expr::eval_expression,
+    explain::explain_code,
     find_refs::find_refs,
-    formatting::format,
+    folding_range::folding_ranges,
+    formatting::{
+        fix_all_and_format_code_action, format, reindent_code_action, SOURCE_FIX_ALL_FORMAT,
+    },
     from_lsp::{self, file_path_from_url, kcl_pos},
-    goto_def::goto_def,
+    goto_def::goto_definition,
     hover,
+    indentation::normalize_indentation_code_action,
+    inheritance_graph::inheritance_graph,
     inlay_hints::inlay_hints,
+    line_endings::normalize_line_endings_code_action,
+    locale::localize_hover,
+    lsp_ext,
+    magic_numbers::extract_magic_number_code_actions,
+    options::{list_options, required_options},
+    provenance::render_with_provenance,
     quick_fix,
-    semantic_token::semantic_tokens_full,
+    render_config::render_config,
+    schema_coverage::schema_coverage,
+    schema_diff::{find_schema, schema_diff},
+    schema_instantiations::schema_instantiations,
+    semantic_token::{cache_semantic_tokens, semantic_tokens_full, semantic_tokens_full_delta},
     signature_help::signature_help,
     state::{log_message, LanguageServerSnapshot, LanguageServerState, Task},
+    string_literals::string_literals,
+    to_lsp::url_from_path,
+    util::load_files_code_from_vfs,
+    validate_mod::validate_mod,
 };
 
 impl LanguageServerState {
@@ -35,6 +68,10 @@ impl LanguageServerState {
         request_received: Instant,
     ) -> anyhow::Result<()> {
         self.register_request(&request, request_received);
+        self.log_trace(
+            format!("Received request '{} - ({})'", request.method, request.id),
+            || serde_json::to_string(&request.params).ok(),
+        );
 
         // If a shutdown was requested earlier, immediately respond with an error
         if self.shutdown_requested {
@@ -56,13 +93,38 @@ impl LanguageServerState {
             .on::<lsp_types::request::References>(handle_reference)?
             .on::<lsp_types::request::HoverRequest>(handle_hover)?
             .on::<lsp_types::request::DocumentSymbolRequest>(handle_document_symbol)?
+            .on::<lsp_types::request::FoldingRangeRequest>(handle_folding_range)?
             .on::<lsp_types::request::CodeActionRequest>(handle_code_action)?
             .on::<lsp_types::request::Formatting>(handle_formatting)?
             .on::<lsp_types::request::RangeFormatting>(handle_range_formatting)?
             .on::<lsp_types::request::Rename>(handle_rename)?
             .on::<lsp_types::request::SemanticTokensFullRequest>(handle_semantic_tokens_full)?
+            .on::<lsp_types::request::SemanticTokensFullDeltaRequest>(
+                handle_semantic_tokens_full_delta,
+            )?
             .on::<lsp_types::request::InlayHintRequest>(handle_inlay_hint)?
             .on::<lsp_types::request::SignatureHelpRequest>(handle_signature_help)?
+            .on::<lsp_types::request::CallHierarchyPrepare>(handle_prepare_call_hierarchy)?
+            .on::<lsp_types::request::CallHierarchyIncomingCalls>(handle_incoming_calls)?
+            .on::<lsp_types::request::CallHierarchyOutgoingCalls>(handle_outgoing_calls)?
+            .on::<lsp_ext::EvalExpression>(handle_eval_expression)?
+            .on::<lsp_ext::ExplainCode>(handle_explain_code)?
+            .on::<lsp_ext::AttributeType>(handle_attribute_type)?
+            .on::<lsp_ext::SchemaCoverageRequest>(handle_schema_coverage)?
+            .on::<lsp_ext::ConformingSchemas>(handle_conforming_schemas)?
+            .on::<lsp_ext::SchemaInstantiations>(handle_schema_instantiations)?
+            .on::<lsp_ext::OutlineWithTypes>(handle_outline_with_types)?
+            .on::<lsp_ext::ListOptions>(handle_list_options)?
+            .on::<lsp_ext::RequiredOptions>(handle_required_options)?
+            .on::<lsp_ext::EntryClosure>(handle_entry_closure)?
+            .on::<lsp_ext::StringLiterals>(handle_string_literals)?
+            .on::<lsp_ext::DefinitionsBatch>(handle_definitions_batch)?
+            .on::<lsp_ext::InheritanceGraph>(handle_inheritance_graph)?
+            .on::<lsp_ext::ValidateMod>(handle_validate_mod)?
+            .on::<lsp_ext::RenderConfig>(handle_render_config)?
+            .on::<lsp_ext::RenderWithProvenance>(handle_render_with_provenance)?
+            .on::<lsp_ext::SchemaDiff>(handle_schema_diff)?
+            .on::<lsp_types::request::ExecuteCommand>(handle_execute_command)?
             .on_maybe_retry::<lsp_types::request::Completion>(handle_completion)?
             .finish();
 
@@ -87,6 +149,39 @@ impl LanguageServerSnapshot {
         valid
     }
 
+    /// Resolves the definitions of the positions against the analyzed workspaces, the results are
+    /// in the order of the positions. The database of a file is looked up once for all its
+    /// positions, and the positions of the files not analyzed yet or without definitions resolve
+    /// to empty vectors.
+    pub(crate) fn definitions_batch(&self, requests: &[(Url, KCLPos)]) -> Vec<Vec<Location>> {
+        let mut dbs: HashMap<&Url, Option<Arc<AnalysisDatabase>>> = HashMap::new();
+        requests
+            .iter()
+            .map(|(uri, kcl_pos)| {
+                let db = dbs.entry(uri).or_insert_with(|| {
+                    let path = from_lsp::abs_path(uri).ok()?;
+                    match self.try_get_db_state(&path.into()) {
+                        Ok(Some((_, db_state))) => db_state.latest_db(),
+                        _ => None,
+                    }
+                });
+                let db = match db {
+                    Some(db) => db,
+                    None => return vec![],
+                };
+                match goto_definition(&db.prog, kcl_pos, &db.gs, self.definition_link) {
+                    Some(lsp_types::GotoDefinitionResponse::Scalar(location)) => vec![location],
+                    Some(lsp_types::GotoDefinitionResponse::Array(locations)) => locations,
+                    Some(lsp_types::GotoDefinitionResponse::Link(links)) => links
+                        .into_iter()
+                        .map(|link| Location::new(link.target_uri, link.target_selection_range))
+                        .collect(),
+                    None => vec![],
+                }
+            })
+            .collect()
+    }
+
     /// Attempts to get db in cache, this function does not block.
     /// return Ok(Some(db)) -> Compile completed
     /// return Ok(None) -> In compiling or rw lock, retry to wait compile completed
@@ -121,6 +216,31 @@ impl LanguageServerSnapshot {
         }
     }
 
+    /// Attempts to get the db of the last compilation, which may be outdated by the edits of the
+    /// workspace being compiled. Unlike `try_get_db`, the requests are not retried until the
+    /// compilation completes, so the read-only requests, e.g. the hovers and the completions, run
+    /// concurrently with the compilation. The requests editing the files keep `try_get_db`.
+    /// return Ok(Some(db)) -> Compile completed or in compiling
+    /// return Ok(None) -> First compile or rw lock, retry to wait compile completed
+    /// return Err(_) ->  Compile failed
+    pub(crate) fn try_get_latest_db(
+        &self,
+        path: &VfsPath,
+        sender: &Sender<Task>,
+    ) -> anyhow::Result<Option<Arc<AnalysisDatabase>>> {
+        match self.try_get_db_state(path)? {
+            Some((_, DBState::Failed(e))) => {
+                log_message(
+                    format!("Try get {:?} db state: Failed: {:?}", path, e),
+                    sender,
+                )?;
+                Err(anyhow::anyhow!(e))
+            }
+            Some((_, db_state)) => Ok(db_state.latest_db()),
+            None => Ok(None),
+        }
+    }
+
     /// Attempts to get db in cache, this function does not block.
     /// return Ok(Some(db)) -> Compile completed
     /// return Ok(None) -> RWlock, retry to unlock
@@ -183,18 +303,46 @@ pub(crate) fn handle_semantic_tokens_full(
 ) -> anyhow::Result<Option<SemanticTokensResult>> {
     let file = file_path_from_url(&params.text_document.uri)?;
     let path: VfsPath = from_lsp::abs_path(&params.text_document.uri)?.into();
-    let db = match snapshot.try_get_db(&path, &sender) {
+    let db = match snapshot.try_get_latest_db(&path, &sender) {
         Ok(option_db) => match option_db {
             Some(db) => db,
             None => return Err(anyhow!(LSPError::Retry)),
         },
         Err(_) => return Ok(None),
     };
-    let res = semantic_tokens_full(&file, &db.gs);
+    let res = semantic_tokens_full(&file, &db.gs).map(|res| match res {
+        SemanticTokensResult::Tokens(tokens) => SemanticTokensResult::Tokens(
+            cache_semantic_tokens(&snapshot.semantic_tokens_cache, &file, tokens),
+        ),
+        SemanticTokensResult::Partial(partial) => SemanticTokensResult::Partial(partial),
+    });
 
     Ok(res)
 }
 
+/// Called when a `textDocument/semanticTokens/full/delta` request was received.
+pub(crate) fn handle_semantic_tokens_full_delta(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_types::SemanticTokensDeltaParams,
+    sender: Sender<Task>,
+) -> anyhow::Result<Option<SemanticTokensFullDeltaResult>> {
+    let file = file_path_from_url(&params.text_document.uri)?;
+    let path: VfsPath = from_lsp::abs_path(&params.text_document.uri)?.into();
+    let db = match snapshot.try_get_latest_db(&path, &sender) {
+        Ok(option_db) => match option_db {
+            Some(db) => db,
+            None => return Err(anyhow!(LSPError::Retry)),
+        },
+        Err(_) => return Ok(None),
+    };
+    Ok(semantic_tokens_full_delta(
+        &file,
+        &db.gs,
+        &params.previous_result_id,
+        &snapshot.semantic_tokens_cache,
+    ))
+}
+
 pub(crate) fn handle_formatting(
     snapshot: LanguageServerSnapshot,
     params: lsp_types::DocumentFormattingParams,
@@ -238,16 +386,142 @@ pub(crate) fn handle_range_formatting(
 
 /// Called when a `textDocument/codeAction` request was received.
 pub(crate) fn handle_code_action(
-    _snapshot: LanguageServerSnapshot,
+    snapshot: LanguageServerSnapshot,
     params: lsp_types::CodeActionParams,
-    _sender: Sender<Task>,
+    sender: Sender<Task>,
 ) -> anyhow::Result<Option<lsp_types::CodeActionResponse>> {
     let mut code_actions: Vec<lsp_types::CodeActionOrCommand> = vec![];
+    let refactor_requested = params.context.only.as_ref().map_or(true, |only| {
+        only.iter().any(|kind| {
+            *kind == CodeActionKind::REFACTOR || *kind == CodeActionKind::REFACTOR_REWRITE
+        })
+    });
+    if refactor_requested && params.range.start != params.range.end {
+        let path = from_lsp::abs_path(&params.text_document.uri)?;
+        let vfs = snapshot.vfs.read();
+        if let Some(file_id) = vfs.file_id(&path.into()) {
+            let src = String::from_utf8(vfs.file_contents(file_id).to_vec())?;
+            code_actions.extend(reindent_code_action(
+                &params.text_document.uri,
+                &src,
+                params.range,
+            ));
+        }
+    }
+    let inline_requested = params.context.only.as_ref().map_or(true, |only| {
+        only.iter().any(|kind| {
+            *kind == CodeActionKind::REFACTOR || *kind == CodeActionKind::REFACTOR_INLINE
+        })
+    });
+    if inline_requested {
+        let path = from_lsp::abs_path(&params.text_document.uri)?;
+        let file = file_path_from_url(&params.text_document.uri)?;
+        // The uses of the variable are looked up in the compiled program.
+        if let Ok(Some(db)) = snapshot.try_get_db(&path.clone().into(), &sender) {
+            let vfs = snapshot.vfs.read();
+            if let Some(file_id) = vfs.file_id(&path.into()) {
+                let src = String::from_utf8(vfs.file_contents(file_id).to_vec())?;
+                code_actions.extend(collapse_single_use_variable_code_action(
+                    &params.text_document.uri,
+                    &file,
+                    &src,
+                    &kcl_pos(&file, params.range.start),
+                    &db.prog,
+                    &db.gs,
+                ));
+            }
+        }
+    }
     code_actions.extend(quick_fix::quick_fix(
         &params.text_document.uri,
         &params.context.diagnostics,
     ));
-    Ok(Some(code_actions))
+    if !params.context.diagnostics.is_empty() {
+        let path = from_lsp::abs_path(&params.text_document.uri)?;
+        let vfs = snapshot.vfs.read();
+        if let Some(file_id) = vfs.file_id(&path.into()) {
+            let src = String::from_utf8(vfs.file_contents(file_id).to_vec())?;
+            code_actions.extend(normalize_line_endings_code_action(
+                &params.text_document.uri,
+                &src,
+                &params.context.diagnostics,
+            ));
+            code_actions.extend(normalize_indentation_code_action(
+                &params.text_document.uri,
+                &file_path_from_url(&params.text_document.uri)?,
+                &src,
+                &params.context.diagnostics,
+                snapshot.config.indent_width,
+            ));
+            code_actions.extend(extract_magic_number_code_actions(
+                &params.text_document.uri,
+                &file_path_from_url(&params.text_document.uri)?,
+                &src,
+                &params.context.diagnostics,
+            ));
+        }
+    }
+    let fix_all_requested = params.context.only.as_ref().map_or(true, |only| {
+        only.iter()
+            .any(|kind| *kind == CodeActionKind::SOURCE || *kind == CodeActionKind::SOURCE_FIX_ALL)
+    });
+    // The combined action is offered when asked by its own kind or when no kind is asked, but not
+    // for the `source.fixAll` kind, because the clients applying all the `source.fixAll` actions
+    // on save would apply the same fixes twice.
+    let fix_all_format_kind = CodeActionKind::new(SOURCE_FIX_ALL_FORMAT);
+    let fix_all_format_requested = params
+        .context
+        .only
+        .as_ref()
+        .map_or(true, |only| only.contains(&fix_all_format_kind));
+    if fix_all_requested || fix_all_format_requested {
+        let fix_all = quick_fix::fix_all(
+            &params.text_document.uri,
+            &params.context.diagnostics,
+            snapshot.config.fix_all_safety,
+        );
+        if fix_all_format_requested {
+            let path = from_lsp::abs_path(&params.text_document.uri)?;
+            let file = file_path_from_url(&params.text_document.uri)?;
+            let vfs = snapshot.vfs.read();
+            if let Some(file_id) = vfs.file_id(&path.into()) {
+                let src = String::from_utf8(vfs.file_contents(file_id).to_vec())?;
+                code_actions.extend(fix_all_and_format_code_action(
+                    &params.text_document.uri,
+                    &file,
+                    &src,
+                    fix_all.as_ref(),
+                ));
+            }
+        }
+        if fix_all_requested {
+            code_actions.extend(fix_all);
+        }
+    }
+    let organize_imports_requested = params.context.only.as_ref().map_or(true, |only| {
+        only.iter().any(|kind| {
+            *kind == CodeActionKind::SOURCE || *kind == CodeActionKind::SOURCE_ORGANIZE_IMPORTS
+        })
+    });
+    if organize_imports_requested {
+        let path = from_lsp::abs_path(&params.text_document.uri)?;
+        let file = file_path_from_url(&params.text_document.uri)?;
+        // The import paths are resolved against the root of the compiled program.
+        if let Ok(Some(db)) = snapshot.try_get_db(&path.clone().into(), &sender) {
+            let vfs = snapshot.vfs.read();
+            if let Some(file_id) = vfs.file_id(&path.into()) {
+                let src = String::from_utf8(vfs.file_contents(file_id).to_vec())?;
+                code_actions.extend(canonicalize_imports_code_action(
+                    &params.text_document.uri,
+                    &file,
+                    &src,
+                    &db.prog.root,
+                    snapshot.config.relative_import_base,
+                ));
+            }
+        }
+    }
+    Ok(Some(quick_fix::normalize_code_actions(code_actions)))
 }
 
 /// Called when a `textDocument/definition` request was received.
@@ -261,7 +535,7 @@ pub(crate) fn handle_goto_definition(
     if !snapshot.verify_request_path(&path.clone().into(), &sender) {
         return Ok(None);
     };
-    let db = match snapshot.try_get_db(&path.clone().into(), &sender) {
+    let db = match snapshot.try_get_latest_db(&path.clone().into(), &sender) {
         Ok(option_db) => match option_db {
             Some(db) => db,
             None => return Err(anyhow!(LSPError::Retry)),
@@ -269,13 +543,33 @@ pub(crate) fn handle_goto_definition(
         Err(_) => return Ok(None),
     };
     let kcl_pos = kcl_pos(&file, params.text_document_position_params.position);
-    let res = goto_def(&kcl_pos, &db.gs);
+    let res = goto_definition(&db.prog, &kcl_pos, &db.gs, snapshot.definition_link);
     if res.is_none() {
         log_message("Definition item not found".to_string(), &sender)?;
     }
     Ok(res)
 }
 
+/// Called when a `kcl/definitionsBatch` request was received. The request is retried until all
+/// the files of the positions are analyzed.
+pub(crate) fn handle_definitions_batch(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_ext::DefinitionsBatchParams,
+    sender: Sender<Task>,
+) -> anyhow::Result<Vec<Vec<Location>>> {
+    let mut requests = vec![];
+    for position in &params.positions {
+        let uri = &position.text_document.uri;
+        let path = from_lsp::abs_path(uri)?;
+        if let Ok(None) = snapshot.try_get_latest_db(&path.into(), &sender) {
+            return Err(anyhow!(LSPError::Retry));
+        }
+        let file = file_path_from_url(uri)?;
+        requests.push((uri.clone(), kcl_pos(&file, position.position)));
+    }
+    Ok(snapshot.definitions_batch(&requests))
+}
+
 /// Called when a `textDocument/references` request was received
 pub(crate) fn handle_reference(
     snapshot: LanguageServerSnapshot,
@@ -289,7 +583,7 @@ pub(crate) fn handle_reference(
     if !snapshot.verify_request_path(&path.clone().into(), &sender) {
         return Ok(None);
     }
-    let db = match snapshot.try_get_db(&path.clone().into(), &sender) {
+    let db = match snapshot.try_get_latest_db(&path.clone().into(), &sender) {
         Ok(option_db) => match option_db {
             Some(db) => db,
             None => return Err(anyhow!(LSPError::Retry)),
@@ -327,6 +621,8 @@ pub(crate) fn handle_completion(
         .and_then(|ctx| ctx.trigger_character)
         .and_then(|s| s.chars().next());
 
+    // The completions of a new line depend on the edit that inserted it, so they wait for the
+    // compilation, the others are served from the latest db like the other read-only requests.
     if matches!(completion_trigger_character, Some('\n')) {
         match db_state {
             DBState::Compiling(_) | DBState::Init => return Err(anyhow!(LSPError::Retry)),
@@ -336,10 +632,11 @@ pub(crate) fn handle_completion(
     }
 
     let db = match db_state {
-        DBState::Ready(db) => db,
-        DBState::Compiling(db) => db,
-        DBState::Init => return Err(anyhow!(LSPError::Retry)),
         DBState::Failed(_) => return Ok(None),
+        db_state => match db_state.latest_db() {
+            Some(db) => db,
+            None => return Err(anyhow!(LSPError::Retry)),
+        },
     };
 
     let kcl_pos = kcl_pos(&file, params.text_document_position.position);
@@ -377,7 +674,7 @@ pub(crate) fn handle_hover(
     if !snapshot.verify_request_path(&path.clone().into(), &sender) {
         return Ok(None);
     }
-    let db = match snapshot.try_get_db(&path.clone().into(), &sender) {
+    let db = match snapshot.try_get_latest_db(&path.clone().into(), &sender) {
         Ok(option_db) => match option_db {
             Some(db) => db,
             None => return Err(anyhow!(LSPError::Retry)),
@@ -385,7 +682,13 @@ pub(crate) fn handle_hover(
         Err(_) => return Ok(None),
     };
     let kcl_pos = kcl_pos(&file, params.text_document_position_params.position);
-    let res = hover::hover(&kcl_pos, &db.gs);
+    let mut res = hover::hover(&kcl_pos, &db.prog, &db.gs);
+    if let Some(locale) = snapshot.config.locale.as_deref() {
+        res = res.map(|hover| localize_hover(hover, locale));
+    }
+    if !snapshot.hover_markdown {
+        res = res.map(hover::hover_to_plain_text);
+    }
     if res.is_none() {
         log_message("Hover definition not found".to_string(), &sender)?;
     }
@@ -400,7 +703,7 @@ pub(crate) fn handle_document_symbol(
 ) -> anyhow::Result<Option<lsp_types::DocumentSymbolResponse>> {
     let file = file_path_from_url(&params.text_document.uri)?;
     let path = from_lsp::abs_path(&params.text_document.uri)?;
-    let db = match snapshot.try_get_db(&path.clone().into(), &sender) {
+    let db = match snapshot.try_get_latest_db(&path.clone().into(), &sender) {
         Ok(option_db) => match option_db {
             Some(db) => db,
             None => return Err(anyhow!(LSPError::Retry)),
@@ -411,6 +714,42 @@ pub(crate) fn handle_document_symbol(
     Ok(res)
 }
 
+/// Called when a `textDocument/foldingRange` request was received. The ranges of an opened
+/// document are cached by its version, the edits of the document invalidate them.
+pub(crate) fn handle_folding_range(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_types::FoldingRangeParams,
+    _sender: Sender<Task>,
+) -> anyhow::Result<Option<Vec<lsp_types::FoldingRange>>> {
+    let file = file_path_from_url(&params.text_document.uri)?;
+    let path = from_lsp::abs_path(&params.text_document.uri)?;
+    let (src, version) = {
+        let vfs = snapshot.vfs.read();
+        let file_id = match vfs.file_id(&path.into()) {
+            Some(file_id) => file_id,
+            None => return Ok(None),
+        };
+        let version = snapshot
+            .opened_files
+            .read()
+            .get(&file_id)
+            .map(|info| info.version);
+        (
+            String::from_utf8(vfs.file_contents(file_id).to_vec())?,
+            version,
+        )
+    };
+    let compute = || folding_ranges(&file, &src);
+    Ok(Some(match version {
+        Some(version) => {
+            snapshot
+                .folding_range_cache
+                .get_or_compute(&params.text_document.uri, version, compute)
+        }
+        None => compute(),
+    }))
+}
+
 /// Called when a `textDocument/rename` request was received.
 pub(crate) fn handle_rename(
     snapshot: LanguageServerSnapshot,
@@ -467,7 +806,7 @@ pub(crate) fn handle_inlay_hint(
 ) -> anyhow::Result<Option<Vec<lsp_types::InlayHint>>> {
     let file = file_path_from_url(&params.text_document.uri)?;
     let path = from_lsp::abs_path(&params.text_document.uri)?;
-    let db = match snapshot.try_get_db(&path.clone().into(), &sender) {
+    let db = match snapshot.try_get_latest_db(&path.clone().into(), &sender) {
         Ok(option_db) => match option_db {
             Some(db) => db,
             None => return Err(anyhow!(LSPError::Retry)),
@@ -487,7 +826,7 @@ pub(crate) fn handle_signature_help(
     let pos = kcl_pos(&file, params.text_document_position_params.position);
     let trigger_character = params.context.and_then(|ctx| ctx.trigger_character);
     let path = from_lsp::abs_path(&params.text_document_position_params.text_document.uri)?;
-    let db = match snapshot.try_get_db(&path.clone().into(), &sender) {
+    let db = match snapshot.try_get_latest_db(&path.clone().into(), &sender) {
         Ok(option_db) => match option_db {
             Some(db) => db,
             None => return Err(anyhow!(LSPError::Retry)),
@@ -498,3 +837,511 @@ pub(crate) fn handle_signature_help(
 
     Ok(res)
 }
+
+/// Called when a `textDocument/prepareCallHierarchy` request was received.
+pub(crate) fn handle_prepare_call_hierarchy(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_types::CallHierarchyPrepareParams,
+    sender: Sender<Task>,
+) -> anyhow::Result<Option<Vec<lsp_types::CallHierarchyItem>>> {
+    let file = file_path_from_url(&params.text_document_position_params.text_document.uri)?;
+    let path = from_lsp::abs_path(&params.text_document_position_params.text_document.uri)?;
+    if !snapshot.verify_request_path(&path.clone().into(), &sender) {
+        return Ok(None);
+    }
+    let db = match snapshot.try_get_latest_db(&path.clone().into(), &sender) {
+        Ok(option_db) => match option_db {
+            Some(db) => db,
+            None => return Err(anyhow!(LSPError::Retry)),
+        },
+        Err(_) => return Ok(None),
+    };
+    let kcl_pos = kcl_pos(&file, params.text_document_position_params.position);
+    Ok(prepare_call_hierarchy(&kcl_pos, &db.gs))
+}
+
+/// Called when a `callHierarchy/incomingCalls` request was received.
+pub(crate) fn handle_incoming_calls(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_types::CallHierarchyIncomingCallsParams,
+    sender: Sender<Task>,
+) -> anyhow::Result<Option<Vec<lsp_types::CallHierarchyIncomingCall>>> {
+    let file = file_path_from_url(&params.item.uri)?;
+    let path = from_lsp::abs_path(&params.item.uri)?;
+    let db = match snapshot.try_get_latest_db(&path.clone().into(), &sender) {
+        Ok(option_db) => match option_db {
+            Some(db) => db,
+            None => return Err(anyhow!(LSPError::Retry)),
+        },
+        Err(_) => return Ok(None),
+    };
+    let kcl_pos = kcl_pos(&file, params.item.selection_range.start);
+    Ok(incoming_calls(&kcl_pos, &db.prog, &db.gs))
+}
+
+/// Called when a `callHierarchy/outgoingCalls` request was received.
+pub(crate) fn handle_outgoing_calls(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_types::CallHierarchyOutgoingCallsParams,
+    sender: Sender<Task>,
+) -> anyhow::Result<Option<Vec<lsp_types::CallHierarchyOutgoingCall>>> {
+    let file = file_path_from_url(&params.item.uri)?;
+    let path = from_lsp::abs_path(&params.item.uri)?;
+    let db = match snapshot.try_get_latest_db(&path.clone().into(), &sender) {
+        Ok(option_db) => match option_db {
+            Some(db) => db,
+            None => return Err(anyhow!(LSPError::Retry)),
+        },
+        Err(_) => return Ok(None),
+    };
+    let kcl_pos = kcl_pos(&file, params.item.selection_range.start);
+    Ok(outgoing_calls(&kcl_pos, &db.prog, &db.gs))
+}
+
+/// Called when a `kcl/evalExpression` request was received.
+pub(crate) fn handle_eval_expression(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_ext::EvalExpressionParams,
+    sender: Sender<Task>,
+) -> anyhow::Result<lsp_ext::EvalExpressionResult> {
+    let file = file_path_from_url(&params.text_document.uri)?;
+    let path = from_lsp::abs_path(&params.text_document.uri)?;
+    if !snapshot.verify_request_path(&path.clone().into(), &sender) {
+        return Err(anyhow!("Can not evaluate the expression in file: {file}"));
+    }
+    let src = {
+        let vfs = snapshot.vfs.read();
+        let file_id = vfs
+            .file_id(&path.into())
+            .ok_or(anyhow::anyhow!("Already checked that the file_id exists!"))?;
+
+        String::from_utf8(vfs.file_contents(file_id).to_vec())?
+    };
+    let value = eval_expression(&file, &src, params.range)?;
+    Ok(lsp_ext::EvalExpressionResult { value })
+}
+
+/// Called when a `kcl/explainCode` request was received.
+pub(crate) fn handle_explain_code(
+    _snapshot: LanguageServerSnapshot,
+    params: lsp_ext::ExplainCodeParams,
+    _sender: Sender<Task>,
+) -> anyhow::Result<Option<lsp_ext::ExplainCodeResult>> {
+    Ok(explain_code(&params.code))
+}
+
+/// Called when a `kcl/attributeType` request was received.
+pub(crate) fn handle_attribute_type(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_ext::AttributeTypeParams,
+    sender: Sender<Task>,
+) -> anyhow::Result<Option<lsp_ext::AttributeTypeResult>> {
+    let path = from_lsp::abs_path(&params.text_document.uri)?;
+    let db = match snapshot.try_get_latest_db(&path.clone().into(), &sender) {
+        Ok(option_db) => match option_db {
+            Some(db) => db,
+            None => return Err(anyhow!(LSPError::Retry)),
+        },
+        Err(_) => return Ok(None),
+    };
+    Ok(attribute_type(
+        &db.schema_map,
+        &params.schema_name,
+        &params.attribute_name,
+    ))
+}
+
+/// Called when a `kcl/schemaCoverage` request was received.
+pub(crate) fn handle_schema_coverage(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_ext::SchemaCoverageParams,
+    sender: Sender<Task>,
+) -> anyhow::Result<Option<Vec<lsp_ext::SchemaCoverage>>> {
+    let path = from_lsp::abs_path(&params.text_document.uri)?;
+    let db = match snapshot.try_get_latest_db(&path.clone().into(), &sender) {
+        Ok(option_db) => match option_db {
+            Some(db) => db,
+            None => return Err(anyhow!(LSPError::Retry)),
+        },
+        Err(_) => return Ok(None),
+    };
+    Ok(Some(schema_coverage(&db.prog, &db.gs)))
+}
+
+/// Called when a `kcl/conformingSchemas` request was received.
+pub(crate) fn handle_conforming_schemas(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_ext::ConformingSchemasParams,
+    sender: Sender<Task>,
+) -> anyhow::Result<Option<Vec<lsp_ext::ConformingSchema>>> {
+    let file = file_path_from_url(&params.text_document.uri)?;
+    let path = from_lsp::abs_path(&params.text_document.uri)?;
+    let db = match snapshot.try_get_latest_db(&path.clone().into(), &sender) {
+        Ok(option_db) => match option_db {
+            Some(db) => db,
+            None => return Err(anyhow!(LSPError::Retry)),
+        },
+        Err(_) => return Ok(None),
+    };
+    let kcl_pos = kcl_pos(&file, params.position);
+    Ok(Some(conforming_schemas(&db.prog, &db.schema_map, &kcl_pos)))
+}
+
+/// Called when a `kcl/schemaInstantiations` request was received.
+pub(crate) fn handle_schema_instantiations(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_types::TextDocumentPositionParams,
+    sender: Sender<Task>,
+) -> anyhow::Result<Option<Vec<Location>>> {
+    let file = file_path_from_url(&params.text_document.uri)?;
+    let path = from_lsp::abs_path(&params.text_document.uri)?;
+    let db = match snapshot.try_get_latest_db(&path.clone().into(), &sender) {
+        Ok(option_db) => match option_db {
+            Some(db) => db,
+            None => return Err(anyhow!(LSPError::Retry)),
+        },
+        Err(_) => return Ok(None),
+    };
+    let kcl_pos = kcl_pos(&file, params.position);
+    Ok(schema_instantiations(&kcl_pos, &db.prog, &db.gs))
+}
+
+/// Called when a `kcl/outlineWithTypes` request was received.
+pub(crate) fn handle_outline_with_types(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_ext::OutlineWithTypesParams,
+    sender: Sender<Task>,
+) -> anyhow::Result<Option<Vec<lsp_ext::OutlineNode>>> {
+    let file = file_path_from_url(&params.text_document.uri)?;
+    let path = from_lsp::abs_path(&params.text_document.uri)?;
+    let db = match snapshot.try_get_latest_db(&path.clone().into(), &sender) {
+        Ok(option_db) => match option_db {
+            Some(db) => db,
+            None => return Err(anyhow!(LSPError::Retry)),
+        },
+        Err(_) => return Ok(None),
+    };
+    Ok(Some(outline_with_types(&file, &db.gs)))
+}
+
+/// Called when a `kcl/listOptions` request was received.
+pub(crate) fn handle_list_options(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_ext::ListOptionsParams,
+    sender: Sender<Task>,
+) -> anyhow::Result<Option<Vec<lsp_ext::OptionDeclaration>>> {
+    let path = from_lsp::abs_path(&params.text_document.uri)?;
+    let db = match snapshot.try_get_latest_db(&path.clone().into(), &sender) {
+        Ok(option_db) => match option_db {
+            Some(db) => db,
+            None => return Err(anyhow!(LSPError::Retry)),
+        },
+        Err(_) => return Ok(None),
+    };
+    Ok(Some(list_options(&db.prog, &db.gs)))
+}
+
+/// Called when a `kcl/requiredOptions` request was received.
+pub(crate) fn handle_required_options(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_ext::RequiredOptionsParams,
+    sender: Sender<Task>,
+) -> anyhow::Result<Option<Vec<lsp_ext::OptionDeclaration>>> {
+    let file = file_path_from_url(&params.text_document.uri)?;
+    let path = from_lsp::abs_path(&params.text_document.uri)?;
+    let db = match snapshot.try_get_latest_db(&path.clone().into(), &sender) {
+        Ok(option_db) => match option_db {
+            Some(db) => db,
+            None => return Err(anyhow!(LSPError::Retry)),
+        },
+        Err(_) => return Ok(None),
+    };
+    Ok(Some(required_options(&file, &db.prog, &db.gs)))
+}
+
+/// Called when a `kcl/entryClosure` request was received.
+pub(crate) fn handle_entry_closure(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_ext::EntryClosureParams,
+    sender: Sender<Task>,
+) -> anyhow::Result<Option<lsp_ext::EntryClosureResult>> {
+    let file = file_path_from_url(&params.text_document.uri)?;
+    let path = from_lsp::abs_path(&params.text_document.uri)?;
+    let db = match snapshot.try_get_latest_db(&path.clone().into(), &sender) {
+        Ok(option_db) => match option_db {
+            Some(db) => db,
+            None => return Err(anyhow!(LSPError::Retry)),
+        },
+        Err(_) => return Ok(None),
+    };
+    Ok(Some(entry_closure(&file, &db.prog, &db.gs)))
+}
+
+/// Called when a `kcl/schemaDiff` request was received.
+pub(crate) fn handle_schema_diff(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_ext::SchemaDiffParams,
+    sender: Sender<Task>,
+) -> anyhow::Result<Option<Vec<lsp_ext::AttributeChange>>> {
+    let mut dbs = vec![];
+    for schema_ref in [&params.before, &params.after] {
+        let path = from_lsp::abs_path(&schema_ref.text_document.uri)?;
+        match snapshot.try_get_latest_db(&path.clone().into(), &sender) {
+            Ok(option_db) => match option_db {
+                Some(db) => dbs.push(db),
+                None => return Err(anyhow!(LSPError::Retry)),
+            },
+            Err(_) => return Ok(None),
+        }
+    }
+    let before_file = file_path_from_url(&params.before.text_document.uri)?;
+    let after_file = file_path_from_url(&params.after.text_document.uri)?;
+    let before = find_schema(&dbs[0].schema_map, &before_file, &params.before.name);
+    let after = find_schema(&dbs[1].schema_map, &after_file, &params.after.name);
+    Ok(match (before, after) {
+        (Some(before), Some(after)) => Some(schema_diff(before, after)),
+        _ => None,
+    })
+}
+
+/// Called when a `kcl/stringLiterals` request was received.
+pub(crate) fn handle_string_literals(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_ext::StringLiteralsParams,
+    sender: Sender<Task>,
+) -> anyhow::Result<Option<Vec<lsp_ext::StringLiteral>>> {
+    let path = from_lsp::abs_path(&params.text_document.uri)?;
+    let db = match snapshot.try_get_latest_db(&path.clone().into(), &sender) {
+        Ok(option_db) => match option_db {
+            Some(db) => db,
+            None => return Err(anyhow!(LSPError::Retry)),
+        },
+        Err(_) => return Ok(None),
+    };
+    Ok(Some(string_literals(&db.prog)))
+}
+
+/// Called when a `kcl/inheritanceGraph` request was received.
+pub(crate) fn handle_inheritance_graph(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_ext::InheritanceGraphParams,
+    sender: Sender<Task>,
+) -> anyhow::Result<Option<lsp_ext::InheritanceGraphResult>> {
+    let file = file_path_from_url(&params.text_document.uri)?;
+    let path = from_lsp::abs_path(&params.text_document.uri)?;
+    let db = match snapshot.try_get_latest_db(&path.clone().into(), &sender) {
+        Ok(option_db) => match option_db {
+            Some(db) => db,
+            None => return Err(anyhow!(LSPError::Retry)),
+        },
+        Err(_) => return Ok(None),
+    };
+    Ok(inheritance_graph(&db.prog, &db.gs, &file))
+}
+
+/// Called when a `kcl/validateMod` request was received. The `kcl.mod` file is the document
+/// itself or the one of the project of the document, the unsaved content is validated if the
+/// file is opened.
+pub(crate) fn handle_validate_mod(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_ext::ValidateModParams,
+    _sender: Sender<Task>,
+) -> anyhow::Result<Option<lsp_ext::ValidateModResult>> {
+    let file = file_path_from_url(&params.text_document.uri)?;
+    let mod_file = if std::path::Path::new(&file)
+        .file_name()
+        .map_or(false, |name| name == KCL_MOD_FILE)
+    {
+        std::path::PathBuf::from(&file)
+    } else {
+        match get_pkg_root(&file) {
+            Some(root) => std::path::Path::new(&root).join(KCL_MOD_FILE),
+            None => return Ok(None),
+        }
+    };
+    let uri = url_from_path(&mod_file)?;
+    let path = from_lsp::abs_path(&uri)?;
+    let content = {
+        let vfs = snapshot.vfs.read();
+        match vfs.file_id(&path.into()) {
+            Some(file_id) => String::from_utf8(vfs.file_contents(file_id).to_vec())?,
+            None => match std::fs::read_to_string(&mod_file) {
+                Ok(content) => content,
+                Err(_) => return Ok(None),
+            },
+        }
+    };
+    Ok(Some(validate_mod(uri, &mod_file, &content)))
+}
+
+/// Called when a `kcl/renderConfig` request was received. The unsaved content of the entry file
+/// is rendered if the file is opened.
+pub(crate) fn handle_render_config(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_ext::RenderConfigParams,
+    _sender: Sender<Task>,
+) -> anyhow::Result<lsp_ext::RenderConfigResult> {
+    let file = file_path_from_url(&params.text_document.uri)?;
+    let path = from_lsp::abs_path(&params.text_document.uri)?;
+    let src = {
+        let vfs = snapshot.vfs.read();
+        match vfs.file_id(&path.into()) {
+            Some(file_id) => String::from_utf8(vfs.file_contents(file_id).to_vec())?,
+            None => std::fs::read_to_string(&file)?,
+        }
+    };
+    Ok(render_config(
+        &file,
+        &src,
+        &params.options,
+        &params.overrides,
+        params.format,
+    ))
+}
+
+/// Called when a `kcl/renderWithProvenance` request was received.
+pub(crate) fn handle_render_with_provenance(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_ext::RenderWithProvenanceParams,
+    _sender: Sender<Task>,
+) -> anyhow::Result<lsp_ext::RenderWithProvenanceResult> {
+    let mut files = vec![file_path_from_url(&params.text_document.uri)?];
+    for uri in &params.files {
+        files.push(file_path_from_url(uri)?);
+    }
+    let file_refs: Vec<&str> = files.iter().map(|file| file.as_str()).collect();
+    let codes = load_files_code_from_vfs(&file_refs, &snapshot.vfs)?;
+    Ok(render_with_provenance(
+        &files,
+        &codes,
+        &params.options,
+        &params.overrides,
+        params.format,
+    ))
+}
+
+/// Called when a `workspace/executeCommand` request was received.
+pub(crate) fn handle_execute_command(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_types::ExecuteCommandParams,
+    sender: Sender<Task>,
+) -> anyhow::Result<Option<serde_json::Value>> {
+    match params.command.as_str() {
+        commands::GOTO_ENCLOSING_SCHEMA_DEF => {
+            let args: lsp_types::TextDocumentPositionParams = match params.arguments.first() {
+                Some(arg) => serde_json::from_value(arg.clone())?,
+                None => {
+                    return Err(anyhow!(
+                        "Missing the text document position argument of the command: {}",
+                        params.command
+                    ))
+                }
+            };
+            let file = file_path_from_url(&args.text_document.uri)?;
+            let path = from_lsp::abs_path(&args.text_document.uri)?;
+            let db = match snapshot.try_get_db(&path.clone().into(), &sender) {
+                Ok(option_db) => match option_db {
+                    Some(db) => db,
+                    None => return Err(anyhow!(LSPError::Retry)),
+                },
+                Err(_) => return Ok(None),
+            };
+            let kcl_pos = kcl_pos(&file, args.position);
+            match goto_enclosing_schema_def(&kcl_pos, &db.gs) {
+                Some(location) => Ok(Some(serde_json::to_value(location)?)),
+                None => Ok(None),
+            }
+        }
+        commands::SORT_SCHEMA_ATTRIBUTES => {
+            let args: lsp_types::TextDocumentPositionParams = match params.arguments.first() {
+                Some(arg) => serde_json::from_value(arg.clone())?,
+                None => {
+                    return Err(anyhow!(
+                        "Missing the text document position argument of the command: {}",
+                        params.command
+                    ))
+                }
+            };
+            let file = file_path_from_url(&args.text_document.uri)?;
+            let path = from_lsp::abs_path(&args.text_document.uri)?;
+            if !snapshot.verify_request_path(&path.clone().into(), &sender) {
+                return Ok(None);
+            }
+            let src = {
+                let vfs = snapshot.vfs.read();
+                let file_id = vfs
+                    .file_id(&path.into())
+                    .ok_or(anyhow::anyhow!("Already checked that the file_id exists!"))?;
+
+                String::from_utf8(vfs.file_contents(file_id).to_vec())?
+            };
+            let kcl_pos = kcl_pos(&file, args.position);
+            match sort_schema_attrs(&file, &src, &kcl_pos) {
+                Some(edit) => {
+                    let mut changes = HashMap::new();
+                    changes.insert(args.text_document.uri, vec![edit]);
+                    Ok(Some(serde_json::to_value(lsp_types::WorkspaceEdit {
+                        changes: Some(changes),
+                        ..Default::default()
+                    })?))
+                }
+                None => Ok(None),
+            }
+        }
+        commands::REBUILD_CACHES => Ok(Some(serde_json::to_value(commands::rebuild_caches(
+            &snapshot, &sender,
+        )?)?)),
+        commands::EXTRACT_CONFIG => {
+            let args: lsp_ext::ExtractConfigParams = match params.arguments.first() {
+                Some(arg) => serde_json::from_value(arg.clone())?,
+                None => {
+                    return Err(anyhow!(
+                        "Missing the extract config argument of the command: {}",
+                        params.command
+                    ))
+                }
+            };
+            let file = file_path_from_url(&args.text_document.uri)?;
+            let path = from_lsp::abs_path(&args.text_document.uri)?;
+            if !snapshot.verify_request_path(&path.clone().into(), &sender) {
+                return Ok(None);
+            }
+            let src = {
+                let vfs = snapshot.vfs.read();
+                let file_id = vfs
+                    .file_id(&path.into())
+                    .ok_or(anyhow::anyhow!("Already checked that the file_id exists!"))?;
+
+                String::from_utf8(vfs.file_contents(file_id).to_vec())?
+            };
+            match extract_config(&file, &src, args.range) {
+                Some(edit) => Ok(Some(serde_json::to_value(edit)?)),
+                None => Ok(None),
+            }
+        }
+        commands::APPLY_CODEMOD => {
+            let args: lsp_ext::ApplyCodemodParams = match params.arguments.first() {
+                Some(arg) => serde_json::from_value(arg.clone())?,
+                None => {
+                    return Err(anyhow!(
+                        "Missing the codemod argument of the command: {}",
+                        params.command
+                    ))
+                }
+            };
+            let vfs = snapshot.vfs.read();
+            // The opened documents are transformed with their unsaved content.
+            let load_src = |file: &str| {
+                let path = from_lsp::abs_path(&lsp_types::Url::from_file_path(file).ok()?).ok()?;
+                match vfs.file_id(&path.into()) {
+                    Some(file_id) => String::from_utf8(vfs.file_contents(file_id).to_vec()).ok(),
+                    None => std::fs::read_to_string(file).ok(),
+                }
+            };
+            Ok(Some(serde_json::to_value(apply_codemod(
+                &args, &load_src,
+            )?)?))
+        }
+        _ => Err(anyhow!("Unknown command: {}", params.command)),
+    }
+}
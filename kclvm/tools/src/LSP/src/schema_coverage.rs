@@ -0,0 +1,199 @@
+//! The schema coverage of a program, which reports how many times each schema is instantiated and
+//! how many times each of its attributes is set, e.g.
+//!
+//! ```kcl
+//! schema Server:
+//!     name: str
+//!     port?: int
+//!
+//! server = Server {name = "nginx"}
+//! ```
+//!
+//! `Server` is instantiated once, `name` is set once and `port` is never set. The attributes set in
+//! an instance of a sub-schema are counted for the schema declaring them.
+
+use indexmap::IndexMap;
+use kclvm_ast::ast::{self, Program};
+use kclvm_ast::pos::GetPos;
+use kclvm_ast::walker::MutSelfWalker;
+use kclvm_sema::core::global_state::GlobalState;
+use kclvm_sema::core::symbol::SymbolRef;
+
+use crate::goto_def::find_def;
+use crate::lsp_ext::{AttributeCoverage, SchemaCoverage};
+
+/// A schema defined in the program and its usages.
+struct SchemaUsage {
+    name: String,
+    parent: Option<SymbolRef>,
+    instances: usize,
+    /// The attributes declared in the schema and the times they are set.
+    attrs: IndexMap<String, usize>,
+}
+
+/// Returns the coverage of all the schemas defined in the program, sorted by the schema names.
+pub(crate) fn schema_coverage(program: &Program, gs: &GlobalState) -> Vec<SchemaCoverage> {
+    let mut schemas: IndexMap<SymbolRef, SchemaUsage> = IndexMap::new();
+    for module in program.modules.values() {
+        if let Ok(module) = module.read() {
+            for stmt in &module.body {
+                if let ast::Stmt::Schema(schema_stmt) = &stmt.node {
+                    if let Some(def) = find_def(&schema_stmt.name.get_pos(), gs, true) {
+                        schemas.insert(def, schema_usage(schema_stmt, gs));
+                    }
+                }
+            }
+        }
+    }
+    let mut counter = InstanceCounter {
+        gs,
+        schemas: &mut schemas,
+    };
+    for module in program.modules.values() {
+        if let Ok(module) = module.read() {
+            for stmt in &module.body {
+                counter.walk_stmt(&stmt.node);
+            }
+        }
+    }
+    let mut coverage: Vec<SchemaCoverage> = schemas
+        .into_values()
+        .map(|schema| SchemaCoverage {
+            name: schema.name,
+            instances: schema.instances,
+            attributes: schema
+                .attrs
+                .into_iter()
+                .map(|(name, set_count)| AttributeCoverage { name, set_count })
+                .collect(),
+        })
+        .collect();
+    coverage.sort_by(|a, b| a.name.cmp(&b.name));
+    coverage
+}
+
+fn schema_usage(schema_stmt: &ast::SchemaStmt, gs: &GlobalState) -> SchemaUsage {
+    let parent = schema_stmt.parent_name.as_ref().and_then(|parent_name| {
+        let name = parent_name.node.names.last()?;
+        find_def(&name.get_pos(), gs, true)
+    });
+    let attrs = schema_stmt
+        .body
+        .iter()
+        .filter_map(|stmt| match &stmt.node {
+            ast::Stmt::SchemaAttr(attr) => Some((attr.name.node.clone(), 0)),
+            _ => None,
+        })
+        .collect();
+    SchemaUsage {
+        name: schema_stmt.name.node.clone(),
+        parent,
+        instances: 0,
+        attrs,
+    }
+}
+
+struct InstanceCounter<'a> {
+    gs: &'a GlobalState,
+    schemas: &'a mut IndexMap<SymbolRef, SchemaUsage>,
+}
+
+impl<'a> MutSelfWalker for InstanceCounter<'a> {
+    fn walk_schema_expr(&mut self, schema_expr: &ast::SchemaExpr) {
+        let def = schema_expr
+            .name
+            .node
+            .names
+            .last()
+            .and_then(|name| find_def(&name.get_pos(), self.gs, true));
+        if let Some(def) = def {
+            if let Some(schema) = self.schemas.get_mut(&def) {
+                schema.instances += 1;
+                if let ast::Expr::Config(config_expr) = &schema_expr.config.node {
+                    for entry in &config_expr.items {
+                        if let Some(key) = entry.node.key.as_ref().and_then(config_key) {
+                            self.count_attr(def, &key);
+                        }
+                    }
+                }
+            }
+        }
+        for arg in &schema_expr.args {
+            self.walk_expr(&arg.node);
+        }
+        for keyword in &schema_expr.kwargs {
+            self.walk_keyword(&keyword.node);
+        }
+        self.walk_expr(&schema_expr.config.node);
+    }
+}
+
+impl<'a> InstanceCounter<'a> {
+    /// Counts the attribute for the schema declaring it, which is the schema itself or one of its
+    /// base schemas.
+    fn count_attr(&mut self, schema: SymbolRef, attr: &str) {
+        let mut current = Some(schema);
+        // The inheritance chain is bounded by the number of schemas in case of a cycle.
+        for _ in 0..self.schemas.len() {
+            let schema = match current.and_then(|current| self.schemas.get_mut(&current)) {
+                Some(schema) => schema,
+                None => return,
+            };
+            if let Some(count) = schema.attrs.get_mut(attr) {
+                *count += 1;
+                return;
+            }
+            current = schema.parent;
+        }
+    }
+}
+
+/// Returns the attribute name of a config entry key, e.g. `a` of `a.b = 1` and `"a" = 1`.
+pub(crate) fn config_key(key: &ast::NodeRef<ast::Expr>) -> Option<String> {
+    match &key.node {
+        ast::Expr::Identifier(identifier) => identifier.names.first().map(|name| name.node.clone()),
+        ast::Expr::StringLit(string_lit) => Some(string_lit.value.clone()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proc_macro_crate::bench_test;
+
+    use super::schema_coverage;
+    use crate::tests::compile_test_file;
+
+    #[test]
+    #[bench_test]
+    fn schema_coverage_test() {
+        let (_, program, _, gs, _) = compile_test_file("src/test_data/schema_coverage/main.k");
+        let coverage = schema_coverage(&program, &gs);
+        let got: Vec<(String, usize, Vec<(String, usize)>)> = coverage
+            .into_iter()
+            .map(|schema| {
+                (
+                    schema.name,
+                    schema.instances,
+                    schema
+                        .attributes
+                        .into_iter()
+                        .map(|attr| (attr.name, attr.set_count))
+                        .collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            got,
+            vec![
+                ("Base".to_string(), 0, vec![("labels".to_string(), 1)]),
+                ("Unused".to_string(), 0, vec![("name".to_string(), 0)]),
+                (
+                    "Used".to_string(),
+                    2,
+                    vec![("name".to_string(), 2), ("port".to_string(), 1)]
+                ),
+            ]
+        );
+    }
+}
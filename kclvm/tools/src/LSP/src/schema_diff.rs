@@ -0,0 +1,222 @@
+//! The attribute-level diff between two versions of a schema, e.g. between
+//!
+//! ```kcl
+//! schema Server:
+//!     name: str
+//!     port: int
+//! ```
+//!
+//! and
+//!
+//! ```kcl
+//! schema Server:
+//!     name: str
+//!     port: str
+//!     region: str
+//! ```
+//!
+//! the type of `port` is changed and the required attribute `region` is added, both break the
+//! configs of the schema before. A type change is breaking unless the type is widened, e.g. from
+//! `int` to `int | str`. The attributes are compared by the resolved types and the
+//! inherited attributes are compared too, so moving an attribute to a base schema is no change.
+
+use indexmap::IndexMap;
+use kclvm_sema::ty::{subsume, SchemaAttr, SchemaType};
+
+use crate::lsp_ext::{AttributeChange, AttributeChangeKind};
+
+/// Returns the schema named `name` defined in the file, or `None` if there is no such schema.
+pub(crate) fn find_schema<'a>(
+    schema_map: &'a IndexMap<String, Vec<SchemaType>>,
+    file: &str,
+    name: &str,
+) -> Option<&'a SchemaType> {
+    schema_map
+        .values()
+        .flatten()
+        .find(|schema| !schema.is_instance && schema.name == name && schema.filename == file)
+}
+
+/// Returns the changes from the schema `before` to the schema `after`. The changes of the
+/// attributes of `after` are in the order of the attributes, followed by the removed attributes
+/// in the order of the attributes of `before`.
+pub(crate) fn schema_diff(before: &SchemaType, after: &SchemaType) -> Vec<AttributeChange> {
+    let mut before_attrs = IndexMap::new();
+    collect_attrs(before, &mut before_attrs);
+    let mut after_attrs = IndexMap::new();
+    collect_attrs(after, &mut after_attrs);
+    let mut changes = vec![];
+    for (name, after_attr) in &after_attrs {
+        let before_attr = match before_attrs.get(name) {
+            Some(before_attr) => before_attr,
+            None => {
+                changes.push(AttributeChange {
+                    name: name.clone(),
+                    kind: AttributeChangeKind::Added,
+                    before: None,
+                    after: Some(after_attr.ty.ty_str()),
+                    breaking: !after_attr.is_optional && !after_attr.has_default,
+                });
+                continue;
+            }
+        };
+        let (before_ty, after_ty) = (before_attr.ty.ty_str(), after_attr.ty.ty_str());
+        if before_ty != after_ty {
+            changes.push(AttributeChange {
+                name: name.clone(),
+                kind: AttributeChangeKind::TypeChanged,
+                before: Some(before_ty),
+                after: Some(after_ty),
+                // Widening the type, e.g. to a union of the type or to `any`, accepts the values
+                // before.
+                breaking: !subsume(before_attr.ty.clone(), after_attr.ty.clone(), false),
+            });
+        }
+        if before_attr.is_optional != after_attr.is_optional {
+            let (kind, breaking) = if after_attr.is_optional {
+                (AttributeChangeKind::RequiredToOptional, false)
+            } else {
+                (
+                    AttributeChangeKind::OptionalToRequired,
+                    !after_attr.has_default,
+                )
+            };
+            changes.push(AttributeChange {
+                name: name.clone(),
+                kind,
+                before: None,
+                after: None,
+                breaking,
+            });
+        }
+        if before_attr.default != after_attr.default {
+            changes.push(AttributeChange {
+                name: name.clone(),
+                kind: AttributeChangeKind::DefaultChanged,
+                before: before_attr.default.clone(),
+                after: after_attr.default.clone(),
+                breaking: false,
+            });
+        }
+    }
+    for (name, before_attr) in &before_attrs {
+        if !after_attrs.contains_key(name) {
+            changes.push(AttributeChange {
+                name: name.clone(),
+                kind: AttributeChangeKind::Removed,
+                before: Some(before_attr.ty.ty_str()),
+                after: None,
+                breaking: true,
+            });
+        }
+    }
+    changes
+}
+
+/// Collects the attributes of the schema, its base schemas and its mixins. The internal
+/// attributes, e.g. `__settings__`, are skipped.
+fn collect_attrs<'a>(schema: &'a SchemaType, attrs: &mut IndexMap<String, &'a SchemaAttr>) {
+    if let Some(base) = &schema.base {
+        collect_attrs(base, attrs);
+    }
+    for mixin in &schema.mixins {
+        collect_attrs(mixin, attrs);
+    }
+    for (name, attr) in &schema.attrs {
+        if !name.starts_with("__") {
+            attrs.insert(name.clone(), attr);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proc_macro_crate::bench_test;
+
+    use super::{find_schema, schema_diff};
+    use crate::lsp_ext::AttributeChangeKind;
+    use crate::tests::compile_test_file;
+
+    #[test]
+    #[bench_test]
+    fn schema_diff_test() {
+        let (before_file, _, _, _, before_map) =
+            compile_test_file("src/test_data/schema_diff/before/main.k");
+        let (after_file, _, _, _, after_map) =
+            compile_test_file("src/test_data/schema_diff/after/main.k");
+        let before = find_schema(&before_map, &before_file, "Server").unwrap();
+        let after = find_schema(&after_map, &after_file, "Server").unwrap();
+        let diff = schema_diff(before, after);
+        let changes: Vec<(&str, AttributeChangeKind, Option<&str>, Option<&str>, bool)> = diff
+            .iter()
+            .map(|change| {
+                (
+                    change.name.as_str(),
+                    change.kind,
+                    change.before.as_deref(),
+                    change.after.as_deref(),
+                    change.breaking,
+                )
+            })
+            .collect();
+        // The inherited `labels` and the unchanged `name` are not reported.
+        assert_eq!(
+            changes,
+            vec![
+                (
+                    "port",
+                    AttributeChangeKind::TypeChanged,
+                    Some("int"),
+                    Some("str"),
+                    true
+                ),
+                (
+                    "replicas",
+                    AttributeChangeKind::DefaultChanged,
+                    Some("1"),
+                    Some("3"),
+                    false
+                ),
+                (
+                    "image",
+                    AttributeChangeKind::RequiredToOptional,
+                    None,
+                    None,
+                    false
+                ),
+                (
+                    "region",
+                    AttributeChangeKind::Added,
+                    None,
+                    Some("str"),
+                    true
+                ),
+                (
+                    "timeout",
+                    AttributeChangeKind::TypeChanged,
+                    Some("int"),
+                    Some("int | str"),
+                    false
+                ),
+                (
+                    "extra",
+                    AttributeChangeKind::TypeChanged,
+                    Some("str"),
+                    Some("any"),
+                    false
+                ),
+                (
+                    "debug",
+                    AttributeChangeKind::Removed,
+                    Some("bool"),
+                    None,
+                    true
+                ),
+            ]
+        );
+
+        assert!(find_schema(&before_map, &before_file, "Base").is_some());
+        assert!(find_schema(&before_map, &before_file, "Client").is_none());
+        assert!(find_schema(&before_map, &after_file, "Server").is_none());
+    }
+}
@@ -0,0 +1,112 @@
+//! The instantiation sites of a schema, which are the schema expressions constructing the schema,
+//! e.g.
+//!
+//! ```kcl
+//! schema Server:
+//!     name: str
+//!
+//! servers: [Server] = [Server {name = "a"}, Server {name = "b"}]
+//! ```
+//!
+//! The two `Server {...}` are the instantiation sites of `Server`, while the `[Server]` type
+//! annotation is only a reference to the name, which is the difference from the references.
+
+use kclvm_ast::ast::{self, Program};
+use kclvm_ast::pos::GetPos;
+use kclvm_ast::walker::MutSelfWalker;
+use kclvm_error::Position as KCLPos;
+use kclvm_sema::core::global_state::GlobalState;
+use kclvm_sema::core::symbol::{SymbolKind, SymbolRef};
+use lsp_types::Location;
+
+use crate::goto_def::find_def;
+use crate::to_lsp::lsp_location;
+
+/// Returns the locations of the schema expressions constructing the schema defined at or
+/// referenced by the position, sorted by the files and the positions, or `None` if there is no
+/// schema at the position. A location covers the schema name and the config, e.g. `Server {...}`.
+pub(crate) fn schema_instantiations(
+    kcl_pos: &KCLPos,
+    program: &Program,
+    gs: &GlobalState,
+) -> Option<Vec<Location>> {
+    let schema = find_def(kcl_pos, gs, true)?;
+    if !matches!(schema.get_kind(), SymbolKind::Schema) {
+        return None;
+    }
+    let mut collector = InstantiationCollector {
+        gs,
+        schema,
+        locations: vec![],
+    };
+    for module in program.modules.values() {
+        if let Ok(module) = module.read() {
+            for stmt in &module.body {
+                collector.walk_stmt(&stmt.node);
+            }
+        }
+    }
+    let mut locations = collector.locations;
+    locations.sort_by(|a, b| (a.uri.as_str(), a.range.start).cmp(&(b.uri.as_str(), b.range.start)));
+    Some(locations)
+}
+
+struct InstantiationCollector<'a> {
+    gs: &'a GlobalState,
+    schema: SymbolRef,
+    locations: Vec<Location>,
+}
+
+impl<'a> MutSelfWalker for InstantiationCollector<'a> {
+    fn walk_schema_expr(&mut self, schema_expr: &ast::SchemaExpr) {
+        // The last name of `pkg.Server {...}` is the schema.
+        if let Some(name) = schema_expr.name.node.names.last() {
+            if find_def(&name.get_pos(), self.gs, true) == Some(self.schema) {
+                let start = schema_expr.name.get_pos();
+                let end = schema_expr.config.get_end_pos();
+                if let Some(location) = lsp_location(start.filename.clone(), &start, &end) {
+                    self.locations.push(location);
+                }
+            }
+        }
+        for arg in &schema_expr.args {
+            self.walk_expr(&arg.node);
+        }
+        for keyword in &schema_expr.kwargs {
+            self.walk_keyword(&keyword.node);
+        }
+        self.walk_expr(&schema_expr.config.node);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{Position, Range};
+    use proc_macro_crate::bench_test;
+
+    use super::schema_instantiations;
+    use crate::from_lsp::kcl_pos;
+    use crate::tests::compile_test_file;
+
+    #[test]
+    #[bench_test]
+    fn schema_instantiations_test() {
+        let (file, program, _, gs, _) =
+            compile_test_file("src/test_data/schema_instantiations/main.k");
+
+        // The position of `Server` in the type annotation.
+        let pos = kcl_pos(&file, Position::new(3, 10));
+        let locations = schema_instantiations(&pos, &program, &gs).unwrap();
+        let ranges: Vec<Range> = locations.iter().map(|location| location.range).collect();
+        assert_eq!(
+            ranges,
+            vec![
+                Range::new(Position::new(3, 21), Position::new(3, 40)),
+                Range::new(Position::new(5, 8), Position::new(5, 27)),
+            ]
+        );
+        assert!(locations
+            .iter()
+            .all(|location| location.uri.path().ends_with("main.k")));
+    }
+}
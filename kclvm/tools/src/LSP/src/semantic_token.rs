@@ -1,3 +1,4 @@
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::vec;
 
 use kclvm_error::Position;
@@ -6,7 +7,12 @@ use kclvm_sema::core::{
     symbol::{KCLSymbol, SymbolKind, SymbolRef},
 };
 use kclvm_sema::ty::TypeKind;
-use lsp_types::{SemanticToken, SemanticTokenType, SemanticTokens, SemanticTokensResult};
+use lsp_types::{
+    SemanticToken, SemanticTokenType, SemanticTokens, SemanticTokensDelta, SemanticTokensEdit,
+    SemanticTokensFullDeltaResult, SemanticTokensResult,
+};
+
+use crate::state::KCLSemanticTokensCache;
 
 pub const LEGEND_TYPE: &[SemanticTokenType] = &[
     SemanticTokenType::VARIABLE,
@@ -58,6 +64,72 @@ pub fn semantic_tokens_full(file: &str, gs: &GlobalState) -> Option<SemanticToke
     }))
 }
 
+static NEXT_RESULT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Caches the semantic tokens as the last result of the file with a new result id, which is
+/// returned to the client and sent back in the next `semanticTokens/full/delta` request.
+pub(crate) fn cache_semantic_tokens(
+    cache: &KCLSemanticTokensCache,
+    file: &str,
+    mut tokens: SemanticTokens,
+) -> SemanticTokens {
+    tokens.result_id = Some(NEXT_RESULT_ID.fetch_add(1, Ordering::Relaxed).to_string());
+    cache.write().insert(file.to_string(), tokens.clone());
+    tokens
+}
+
+/// Returns the edits of the semantic tokens against the previous result `previous_result_id` of
+/// the file, or the full tokens if the previous result is not the last cached one.
+pub(crate) fn semantic_tokens_full_delta(
+    file: &str,
+    gs: &GlobalState,
+    previous_result_id: &str,
+    cache: &KCLSemanticTokensCache,
+) -> Option<SemanticTokensFullDeltaResult> {
+    let tokens = match semantic_tokens_full(file, gs)? {
+        SemanticTokensResult::Tokens(tokens) => tokens,
+        SemanticTokensResult::Partial(_) => return None,
+    };
+    let previous = cache
+        .read()
+        .get(file)
+        .filter(|previous| previous.result_id.as_deref() == Some(previous_result_id))
+        .map(|previous| previous.data.clone());
+    let tokens = cache_semantic_tokens(cache, file, tokens);
+    Some(match previous {
+        Some(previous) => SemanticTokensFullDeltaResult::TokensDelta(SemanticTokensDelta {
+            edits: semantic_tokens_edits(&previous, &tokens.data),
+            result_id: tokens.result_id,
+        }),
+        None => SemanticTokensFullDeltaResult::Tokens(tokens),
+    })
+}
+
+/// Returns the edit which turns the `old` tokens into the `new` tokens. The tokens are encoded
+/// relative to the previous ones, so an edit of the file only changes the tokens around it and
+/// the common prefix and suffix of the tokens are not sent. The offsets of the edit count the
+/// integers of the encoded tokens, which are 5 integers per token.
+pub(crate) fn semantic_tokens_edits(
+    old: &[SemanticToken],
+    new: &[SemanticToken],
+) -> Vec<SemanticTokensEdit> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    if prefix == old.len() && prefix == new.len() {
+        return vec![];
+    }
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    vec![SemanticTokensEdit {
+        start: (prefix * 5) as u32,
+        delete_count: ((old.len() - prefix - suffix) * 5) as u32,
+        data: Some(new[prefix..new.len() - suffix].to_vec()),
+    }]
+}
+
 pub(crate) fn get_kind(symbol_ref: SymbolRef, symbol: &KCLSymbol, gs: &GlobalState) -> Option<u32> {
     match symbol_ref.get_kind() {
         SymbolKind::Schema => Some(type_index(SemanticTokenType::STRUCT)),
@@ -162,7 +234,12 @@ mod tests {
     use crate::tests::compile_test_file;
     use proc_macro_crate::bench_test;
 
-    use super::semantic_tokens_full;
+    use super::{
+        cache_semantic_tokens, semantic_tokens_edits, semantic_tokens_full,
+        semantic_tokens_full_delta,
+    };
+    use crate::state::KCLSemanticTokensCache;
+    use lsp_types::{SemanticTokens, SemanticTokensFullDeltaResult, SemanticTokensResult};
 
     #[test]
     #[bench_test]
@@ -220,4 +297,52 @@ mod tests {
             }
         }
     }
+
+    fn tokens_of(file: &str, gs: &kclvm_sema::core::global_state::GlobalState) -> SemanticTokens {
+        match semantic_tokens_full(file, gs).unwrap() {
+            SemanticTokensResult::Tokens(tokens) => tokens,
+            SemanticTokensResult::Partial(_) => unreachable!(),
+        }
+    }
+
+    #[test]
+    #[bench_test]
+    fn semantic_tokens_delta_test() {
+        let (before, _, _, before_gs, _) =
+            compile_test_file("src/test_data/sema_token/delta/before.k");
+        let (after, _, _, after_gs, _) =
+            compile_test_file("src/test_data/sema_token/delta/after.k");
+        let before_tokens = tokens_of(&before, &before_gs);
+        let after_tokens = tokens_of(&after, &after_gs);
+
+        // `b = a` is changed to `b = a + a`, only the inserted `a` is sent and the tokens of the
+        // later lines are not shifted.
+        let edits = semantic_tokens_edits(&before_tokens.data, &after_tokens.data);
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].start, 3 * 5);
+        assert_eq!(edits[0].delete_count, 0);
+        let data = edits[0].data.as_ref().unwrap();
+        assert_eq!(data.len(), 1);
+        assert_eq!((data[0].delta_line, data[0].delta_start), (0, 4));
+        assert!(semantic_tokens_edits(&after_tokens.data, &after_tokens.data).is_empty());
+
+        // The delta is computed against the cached result of the file.
+        let cache = KCLSemanticTokensCache::default();
+        let previous = cache_semantic_tokens(&cache, &after, before_tokens);
+        let previous_result_id = previous.result_id.unwrap();
+        match semantic_tokens_full_delta(&after, &after_gs, &previous_result_id, &cache).unwrap() {
+            SemanticTokensFullDeltaResult::TokensDelta(delta) => {
+                assert_eq!(delta.edits, edits);
+                assert_ne!(delta.result_id, Some(previous_result_id.clone()));
+            }
+            _ => panic!("test failed"),
+        }
+        // The unknown result id falls back to the full tokens.
+        match semantic_tokens_full_delta(&after, &after_gs, &previous_result_id, &cache).unwrap() {
+            SemanticTokensFullDeltaResult::Tokens(tokens) => {
+                assert_eq!(tokens.data, after_tokens.data)
+            }
+            _ => panic!("test failed"),
+        }
+    }
 }
@@ -0,0 +1,187 @@
+//! The source maps of the KCL files generated from other sources, e.g. a DSL transpiled to KCL.
+//! The diagnostics of a generated file are remapped to the original source, which is the file
+//! the users edit. The source map of `main.k` is the JSON file `main.k.map` next to it, e.g.
+//!
+//! ```json
+//! {
+//!     "mappings": [
+//!         {
+//!             "generatedLine": 3,
+//!             "generatedColumn": 4,
+//!             "originalFile": "service.dsl",
+//!             "originalLine": 1,
+//!             "originalColumn": 8
+//!         }
+//!     ]
+//! }
+//! ```
+//!
+//! The lines are 1-based and the columns are 0-based as the KCL positions, and the original files
+//! are relative to the directory of the source map.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use indexmap::IndexSet;
+use kclvm_error::{Diagnostic, Position};
+use serde::Deserialize;
+
+/// A generated position and the original position it is generated from. A mapping covers the
+/// columns of the generated line from `generated_column` to the next mapping of the line.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Mapping {
+    pub generated_line: u64,
+    pub generated_column: u64,
+    pub original_file: String,
+    pub original_line: u64,
+    pub original_column: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub(crate) struct SourceMap {
+    pub mappings: Vec<Mapping>,
+}
+
+impl SourceMap {
+    /// Loads the source map of the generated file, or `None` if the file has no valid source map.
+    /// The original files are resolved against the directory of the source map.
+    pub(crate) fn load(file: &str) -> Option<Self> {
+        let content = std::fs::read_to_string(format!("{}.map", file)).ok()?;
+        let mut source_map: SourceMap = serde_json::from_str(&content).ok()?;
+        if let Some(dir) = Path::new(file).parent() {
+            for mapping in source_map.mappings.iter_mut() {
+                mapping.original_file = dir
+                    .join(&mapping.original_file)
+                    .to_string_lossy()
+                    .to_string();
+            }
+        }
+        Some(source_map)
+    }
+
+    /// Returns the original position of the generated position, or `None` if the position is
+    /// not mapped. A position without a column is mapped to the line of the first mapping of its
+    /// line.
+    pub(crate) fn original_pos(&self, pos: &Position) -> Option<Position> {
+        let mappings = self
+            .mappings
+            .iter()
+            .filter(|mapping| mapping.generated_line == pos.line);
+        match pos.column {
+            Some(column) => mappings
+                .filter(|mapping| mapping.generated_column <= column)
+                .max_by_key(|mapping| mapping.generated_column)
+                .map(|mapping| Position {
+                    filename: mapping.original_file.clone(),
+                    line: mapping.original_line,
+                    column: Some(mapping.original_column + column - mapping.generated_column),
+                }),
+            None => mappings
+                .min_by_key(|mapping| mapping.generated_column)
+                .map(|mapping| Position {
+                    filename: mapping.original_file.clone(),
+                    line: mapping.original_line,
+                    column: None,
+                }),
+        }
+    }
+}
+
+/// Remaps the ranges of all the messages of the diagnostics to the original sources.
+/// `load_source_map` returns the source map of a generated file, it is called once for each file.
+/// The ranges whose starts are not mapped are kept in the generated files, and a range whose end
+/// is mapped elsewhere is shrunk to its start.
+pub(crate) fn remap_diags(
+    diags: IndexSet<Diagnostic>,
+    load_source_map: &dyn Fn(&str) -> Option<SourceMap>,
+) -> IndexSet<Diagnostic> {
+    let mut source_maps: HashMap<String, Option<SourceMap>> = HashMap::new();
+    diags
+        .into_iter()
+        .map(|mut diag| {
+            for message in diag.messages.iter_mut() {
+                let (start, end) = &message.range;
+                let source_map = source_maps
+                    .entry(start.filename.clone())
+                    .or_insert_with(|| load_source_map(&start.filename));
+                let source_map = match source_map {
+                    Some(source_map) => source_map,
+                    None => continue,
+                };
+                if let Some(original_start) = source_map.original_pos(start) {
+                    let original_end = match source_map.original_pos(end) {
+                        Some(original_end)
+                            if end.filename == start.filename
+                                && original_end.filename == original_start.filename =>
+                        {
+                            original_end
+                        }
+                        _ => original_start.clone(),
+                    };
+                    message.range = (original_start, original_end);
+                }
+            }
+            diag
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexSet;
+    use kclvm_error::{Diagnostic, Level, Position};
+    use proc_macro_crate::bench_test;
+
+    use super::{remap_diags, SourceMap};
+
+    #[test]
+    #[bench_test]
+    fn remap_diags_test() {
+        let source_map: SourceMap = serde_json::from_str(
+            r#"{
+                "mappings": [
+                    {"generatedLine": 2, "generatedColumn": 0, "originalFile": "service.dsl", "originalLine": 5, "originalColumn": 2},
+                    {"generatedLine": 2, "generatedColumn": 10, "originalFile": "service.dsl", "originalLine": 6, "originalColumn": 4}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let pos = |filename: &str, line: u64, column: u64| Position {
+            filename: filename.to_string(),
+            line,
+            column: Some(column),
+        };
+        let diags = IndexSet::from([
+            Diagnostic::new(
+                Level::Error,
+                "expected int, got str",
+                (pos("main.k", 2, 12), pos("main.k", 2, 15)),
+            ),
+            // The position is not mapped and kept in the generated file.
+            Diagnostic::new(
+                Level::Error,
+                "name 'b' is not defined",
+                (pos("main.k", 3, 4), pos("main.k", 3, 5)),
+            ),
+        ]);
+        let load_source_map = |file: &str| {
+            if file == "main.k" {
+                Some(source_map.clone())
+            } else {
+                None
+            }
+        };
+        let got: Vec<_> = remap_diags(diags, &load_source_map)
+            .into_iter()
+            .map(|diag| diag.messages[0].range.clone())
+            .collect();
+        assert_eq!(
+            got,
+            vec![
+                (pos("service.dsl", 6, 6), pos("service.dsl", 6, 9)),
+                (pos("main.k", 3, 4), pos("main.k", 3, 5)),
+            ]
+        );
+    }
+}
@@ -1,22 +1,39 @@
 use crate::analysis::{Analysis, AnalysisDatabase, DBState, OpenFileInfo};
+use crate::builtin_shadowing::builtin_shadowing_diags;
+use crate::capabilities::{definition_link_supported, hover_markdown_supported};
+use crate::check_messages::missing_check_message_diags;
 use crate::compile::{compile, Params};
+use crate::config::Config;
+use crate::diagnostic_summary::prepend_summary;
+use crate::folding_range::FoldingRangeCache;
 use crate::from_lsp::file_path_from_url;
+use crate::indentation::inconsistent_indentation_diag;
+use crate::line_endings::line_ending_diag;
+use crate::magic_numbers::{magic_number_diags, DEFAULT_MAGIC_NUMBER_ALLOWLIST};
+use crate::source_map::{remap_diags, SourceMap};
 use crate::to_lsp::{kcl_diag_to_lsp_diags, url_from_path};
-use crate::util::{filter_kcl_config_file, get_file_name, to_json};
+use crate::todo_comments::{todo_comment_diags, DEFAULT_TODO_KEYWORDS};
+use crate::unused_private::unused_private_symbol_diags;
+use crate::unused_schema::unused_schema_diags;
+use crate::util::{filter_kcl_config_file, get_file_name, load_files_code_from_vfs, to_json};
+use crate::watch::DiagnosticWatchers;
 use crossbeam_channel::{select, unbounded, Receiver, Sender};
 use indexmap::IndexSet;
 use kclvm_driver::toolchain::{self, Toolchain};
 use kclvm_driver::{
     lookup_compile_workspace, lookup_compile_workspaces, CompileUnitOptions, WorkSpaceKind,
 };
-use kclvm_parser::KCLModuleCache;
+use kclvm_error::Handler;
+use kclvm_parser::{KCLModuleCache, LoadProgramOptions};
 use kclvm_sema::core::global_state::GlobalState;
 use kclvm_sema::resolver::scope::KCLScopeCache;
+use kclvm_tools::lint::apply_lint_files;
 use lsp_server::RequestId;
 use lsp_server::{ReqQueue, Request, Response};
 use lsp_types::{
-    notification::{Notification, PublishDiagnostics},
-    InitializeParams, PublishDiagnosticsParams, WorkspaceFolder,
+    notification::{LogTrace, Notification, PublishDiagnostics},
+    InitializeParams, LogTraceParams, PublishDiagnosticsParams, SemanticTokens, TraceValue,
+    WorkspaceFolder,
 };
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use parking_lot::RwLock;
@@ -40,6 +57,8 @@ pub(crate) enum Task {
     Retry(Request),
     ChangedFile(FileId, ChangeKind),
     ReOpenFile(FileId, ChangeKind),
+    /// Compiles all the workspaces again after the caches are cleared.
+    RebuildWorkspaces,
 }
 
 #[derive(Debug, Clone)]
@@ -66,6 +85,9 @@ pub(crate) type KCLVfs = Arc<RwLock<Vfs>>;
 
 pub(crate) type KCLWorkSpaceConfigCache = Arc<RwLock<HashMap<WorkSpaceKind, CompileUnitOptions>>>;
 
+/// The last semantic tokens sent to the client of each file.
+pub(crate) type KCLSemanticTokensCache = Arc<RwLock<HashMap<String, SemanticTokens>>>;
+
 pub(crate) type KCLToolChain = Arc<RwLock<dyn Toolchain>>;
 pub(crate) type KCLGlobalStateCache = Arc<Mutex<GlobalState>>;
 
@@ -106,6 +128,20 @@ pub(crate) struct LanguageServerState {
     /// Process files that are not in any defined workspace and delete the workspace when closing the file
     pub temporary_workspace: Arc<RwLock<HashMap<FileId, Option<WorkSpaceKind>>>>,
     pub workspace_folders: Option<Vec<WorkspaceFolder>>,
+    /// User configurations from the client
+    pub config: Arc<Config>,
+    /// The semantic tokens cache for the `semanticTokens/full/delta` requests
+    pub semantic_tokens_cache: KCLSemanticTokensCache,
+    /// The folding ranges cache of the opened documents
+    pub folding_range_cache: FoldingRangeCache,
+    /// The trace level set by the client, see `$/setTrace`
+    pub trace: TraceValue,
+    /// Whether the client renders the Markdown hover contents
+    pub hover_markdown: bool,
+    /// Whether the client accepts the location links of the definitions
+    pub definition_link: bool,
+    /// The subscriptions of the diagnostics of the embedders
+    pub diagnostic_watchers: DiagnosticWatchers,
     /// Actively monitor file system changes. These changes will not be notified through lsp,
     /// e.g., execute `kcl mod add xxx`, `kcl fmt xxx`
     pub fs_event_watcher: Option<
@@ -137,6 +173,20 @@ pub(crate) struct LanguageServerSnapshot {
     pub temporary_workspace: Arc<RwLock<HashMap<FileId, Option<WorkSpaceKind>>>>,
     /// Compile config cache
     pub workspace_config_cache: KCLWorkSpaceConfigCache,
+    /// User configurations from the client
+    pub config: Arc<Config>,
+    /// The semantic tokens cache for the `semanticTokens/full/delta` requests
+    pub semantic_tokens_cache: KCLSemanticTokensCache,
+    /// The folding ranges cache of the opened documents
+    pub folding_range_cache: FoldingRangeCache,
+    /// KCL globalstate cache
+    pub gs_cache: KCLGlobalStateCache,
+    /// Whether the client renders the Markdown hover contents
+    pub hover_markdown: bool,
+    /// Whether the client accepts the location links of the definitions
+    pub definition_link: bool,
+    /// The subscriptions of the diagnostics of the embedders
+    pub diagnostic_watchers: DiagnosticWatchers,
 }
 
 #[allow(unused)]
@@ -172,6 +222,12 @@ impl LanguageServerState {
             }
         };
 
+        let (config, invalid_options) =
+            Config::from_initialization_options(initialize_params.initialization_options.as_ref());
+        for message in invalid_options {
+            let _ = log_message(message, &task_sender);
+        }
+
         let mut state = LanguageServerState {
             sender,
             request_queue: ReqQueue::default(),
@@ -191,7 +247,14 @@ impl LanguageServerState {
             workspace_config_cache: KCLWorkSpaceConfigCache::default(),
             temporary_workspace: Arc::new(RwLock::new(HashMap::new())),
             workspace_folders: initialize_params.workspace_folders.clone(),
+            config: Arc::new(config),
             fs_event_watcher,
+            semantic_tokens_cache: KCLSemanticTokensCache::default(),
+            folding_range_cache: FoldingRangeCache::default(),
+            trace: initialize_params.trace.unwrap_or(TraceValue::Off),
+            hover_markdown: hover_markdown_supported(&initialize_params.capabilities),
+            definition_link: definition_link_supported(&initialize_params.capabilities),
+            diagnostic_watchers: DiagnosticWatchers::default(),
         };
 
         state.init_workspaces();
@@ -484,7 +547,11 @@ impl LanguageServerState {
 
     /// Handles a task sent by another async task
     #[allow(clippy::unnecessary_wraps)]
-    fn handle_task(&mut self, task: Task, request_received: Instant) -> anyhow::Result<()> {
+    pub(crate) fn handle_task(
+        &mut self,
+        task: Task,
+        request_received: Instant,
+    ) -> anyhow::Result<()> {
         match task {
             Task::Notify(notification) => {
                 self.send(notification.into());
@@ -506,6 +573,7 @@ impl LanguageServerState {
                 file_id,
                 change_kind,
             }),
+            Task::RebuildWorkspaces => self.rebuild_workspaces(),
         }
         Ok(())
     }
@@ -526,6 +594,18 @@ impl LanguageServerState {
     pub(super) fn respond(&mut self, response: lsp_server::Response) -> anyhow::Result<()> {
         if let Some((method, start)) = self.request_queue.incoming.complete(&response.id) {
             let duration = start.elapsed();
+            self.log_trace(
+                format!(
+                    "Sending response '{} - ({})'. Processing request took {}ms",
+                    method,
+                    response.id,
+                    duration.as_millis()
+                ),
+                || match &response.error {
+                    Some(error) => serde_json::to_string(error).ok(),
+                    None => serde_json::to_string(&response.result).ok(),
+                },
+            );
             self.send(response.into())?;
             self.log_message(format!(
                 "Finished request {:?} in {:?} micros",
@@ -567,6 +647,13 @@ impl LanguageServerState {
             workspaces: self.analysis.workspaces.clone(),
             temporary_workspace: self.temporary_workspace.clone(),
             workspace_config_cache: self.workspace_config_cache.clone(),
+            config: self.config.clone(),
+            semantic_tokens_cache: self.semantic_tokens_cache.clone(),
+            folding_range_cache: self.folding_range_cache.clone(),
+            gs_cache: self.gs_cache.clone(),
+            hover_markdown: self.hover_markdown,
+            definition_link: self.definition_link,
+            diagnostic_watchers: self.diagnostic_watchers.clone(),
         }
     }
 
@@ -579,6 +666,21 @@ impl LanguageServerState {
         self.send(not.into());
     }
 
+    /// Sends a `$/logTrace` notification if the trace is not off, `verbose` is only called in the
+    /// verbose trace level to compute the details of the message.
+    pub(crate) fn log_trace(&self, message: String, verbose: impl FnOnce() -> Option<String>) {
+        let verbose = match self.trace {
+            TraceValue::Off => return,
+            TraceValue::Messages => None,
+            TraceValue::Verbose => verbose(),
+        };
+        let not = lsp_server::Notification::new(
+            LogTrace::METHOD.to_string(),
+            LogTraceParams { message, verbose },
+        );
+        let _ = self.send(not.into());
+    }
+
     pub(crate) fn is_completed(&self, request: &lsp_server::Request) -> bool {
         self.request_queue.incoming.is_completed(&request.id)
     }
@@ -637,31 +739,25 @@ impl LanguageServerState {
             let tool = Arc::clone(&self.tool);
             let gs_cache = Arc::clone(&self.gs_cache);
 
+            let vfs = Arc::clone(&self.vfs);
+
             let mut files = opts.0.clone();
             move || {
+                // The requests keep reading the previous db until the compiled one replaces it, the
+                // diagnostics are read in the same critical section to diff against that db.
                 let old_diags = {
-                    match snapshot.workspaces.read().get(&workspace) {
-                        Some(option_db) => match option_db {
-                            DBState::Ready(db) => db.diags.clone(),
-                            DBState::Compiling(db) => db.diags.clone(),
-                            DBState::Init | DBState::Failed(_) => IndexSet::new(),
-                        },
-                        None => IndexSet::new(),
-                    }
-                };
-
-                {
                     let mut workspaces = snapshot.workspaces.write();
-                    let state = match workspaces.get_mut(&workspace) {
-                        Some(state) => match state {
-                            DBState::Ready(db) => DBState::Compiling(db.clone()),
-                            DBState::Compiling(db) => DBState::Compiling(db.clone()),
-                            DBState::Init | DBState::Failed(_) => DBState::Init,
-                        },
-                        None => DBState::Init,
+                    let (old_diags, state) = match workspaces.get(&workspace) {
+                        Some(DBState::Ready(db)) | Some(DBState::Compiling(db)) => {
+                            (db.diags.clone(), DBState::Compiling(db.clone()))
+                        }
+                        Some(DBState::Init) | Some(DBState::Failed(_)) | None => {
+                            (IndexSet::new(), DBState::Init)
+                        }
                     };
                     workspaces.insert(workspace.clone(), state);
-                }
+                    old_diags
+                };
                 let start = Instant::now();
 
                 let (diags, compile_res) = compile(
@@ -669,12 +765,110 @@ impl LanguageServerState {
                         file: filename.clone(),
                         module_cache: Some(module_cache),
                         scope_cache: Some(scope_cache),
-                        vfs: Some(snapshot.vfs),
+                        vfs: Some(vfs.clone()),
                         gs_cache: Some(gs_cache),
+                        max_schema_depth: snapshot.config.max_schema_depth,
+                        minimal_diagnostics: snapshot.config.minimal_diagnostics,
+                        implicit_coercion_check: snapshot.config.implicit_coercions,
+                        explicit_type_check: snapshot.config.explicit_types,
+                        ambiguous_reference_check: snapshot.config.ambiguous_references,
                     },
                     &mut files,
-                    opts.1.clone(),
+                    Some(LoadProgramOptions {
+                        relative_import_base: snapshot.config.relative_import_base,
+                        ..opts.1.clone().unwrap_or_default()
+                    }),
                 );
+                let mut diags = diags;
+                if snapshot.config.unused_schemas {
+                    if let Ok((prog, _, gs)) = &compile_res {
+                        diags.extend(unused_schema_diags(
+                            prog,
+                            gs,
+                            snapshot.config.unused_schema_entry_files.as_deref(),
+                        ));
+                    }
+                }
+                if snapshot.config.unused_private_symbols {
+                    if let Ok((prog, _, gs)) = &compile_res {
+                        diags.extend(unused_private_symbol_diags(prog, gs));
+                    }
+                }
+                if snapshot.config.missing_check_messages {
+                    if let Ok((prog, _, _)) = &compile_res {
+                        diags.extend(missing_check_message_diags(prog));
+                    }
+                }
+                if snapshot.config.builtin_shadowing {
+                    if let Ok((prog, _, _)) = &compile_res {
+                        diags.extend(builtin_shadowing_diags(prog));
+                    }
+                }
+                if snapshot.config.magic_numbers {
+                    if let Ok((prog, _, _)) = &compile_res {
+                        diags.extend(magic_number_diags(
+                            prog,
+                            snapshot
+                                .config
+                                .magic_number_allowlist
+                                .as_deref()
+                                .unwrap_or(DEFAULT_MAGIC_NUMBER_ALLOWLIST),
+                            &snapshot.config.magic_number_ignored_contexts,
+                        ));
+                    }
+                }
+                if snapshot.config.line_endings {
+                    if let Ok((prog, _, _)) = &compile_res {
+                        for file in prog.get_main_files() {
+                            if let Ok(src) = load_files_code_from_vfs(&[file.as_str()], &vfs) {
+                                diags.extend(
+                                    src.first().and_then(|src| line_ending_diag(&file, src)),
+                                );
+                            }
+                        }
+                    }
+                }
+                if snapshot.config.inconsistent_indentation {
+                    if let Ok((prog, _, _)) = &compile_res {
+                        for file in prog.get_main_files() {
+                            if let Ok(src) = load_files_code_from_vfs(&[file.as_str()], &vfs) {
+                                diags.extend(src.first().and_then(|src| {
+                                    inconsistent_indentation_diag(
+                                        &file,
+                                        src,
+                                        snapshot.config.indent_width,
+                                    )
+                                }));
+                            }
+                        }
+                    }
+                }
+                if snapshot.config.todo_comments {
+                    if let Ok((prog, _, _)) = &compile_res {
+                        let keywords = snapshot.config.todo_keywords.clone().unwrap_or_else(|| {
+                            DEFAULT_TODO_KEYWORDS
+                                .iter()
+                                .map(|keyword| keyword.to_string())
+                                .collect()
+                        });
+                        diags.extend(todo_comment_diags(
+                            prog,
+                            &keywords,
+                            snapshot.config.todo_severity.level(),
+                        ));
+                    }
+                }
+                let mut handler = Handler { diagnostics: diags };
+                apply_lint_files(&mut handler);
+                if snapshot.config.warnings_as_errors {
+                    handler.promote_warnings_to_errors();
+                }
+                let diags = handler.diagnostics;
+                let diags = if snapshot.config.source_maps {
+                    remap_diags(diags, &SourceMap::load)
+                } else {
+                    diags
+                };
 
                 log_message(
                     format!(
@@ -689,9 +883,10 @@ impl LanguageServerState {
                     &sender,
                 );
 
+                let locale = snapshot.config.locale.as_deref();
                 let mut old_diags_maps = HashMap::new();
                 for diag in &old_diags {
-                    let lsp_diag = kcl_diag_to_lsp_diags(diag);
+                    let lsp_diag = kcl_diag_to_lsp_diags(diag, &|_| None, locale);
                     for (key, value) in lsp_diag {
                         old_diags_maps.entry(key).or_insert(vec![]).extend(value);
                     }
@@ -700,13 +895,25 @@ impl LanguageServerState {
                 // publish diags
                 let mut new_diags_maps = HashMap::new();
 
+                let read_src = |file: &str| {
+                    if snapshot.config.diagnostic_anchors {
+                        load_files_code_from_vfs(&[file], &vfs)
+                            .ok()
+                            .and_then(|mut codes| codes.pop())
+                    } else {
+                        None
+                    }
+                };
                 for diag in &diags {
-                    let lsp_diag = kcl_diag_to_lsp_diags(diag);
+                    let lsp_diag = kcl_diag_to_lsp_diags(diag, &read_src, locale);
                     for (key, value) in lsp_diag {
                         new_diags_maps.entry(key).or_insert(vec![]).extend(value);
                     }
                 }
 
+                snapshot
+                    .diagnostic_watchers
+                    .notify(&old_diags_maps, &new_diags_maps);
                 for (file, diags) in old_diags_maps {
                     if !new_diags_maps.contains_key(&file) {
                         if let Ok(uri) = url_from_path(file) {
@@ -723,7 +930,10 @@ impl LanguageServerState {
                     }
                 }
 
-                for (filename, diagnostics) in new_diags_maps {
+                for (filename, mut diagnostics) in new_diags_maps {
+                    if let Some(threshold) = snapshot.config.diagnostic_summary_threshold {
+                        prepend_summary(&mut diagnostics, threshold);
+                    }
                     if let Ok(uri) = url_from_path(filename) {
                         sender.send(Task::Notify(lsp_server::Notification {
                             method: PublishDiagnostics::METHOD.to_owned(),
@@ -791,13 +1001,29 @@ impl LanguageServerState {
         })
     }
 
+    /// Compiles all the workspaces again with their cached compile configs, which re-indexes the
+    /// opened files and republishes the diagnostics.
+    fn rebuild_workspaces(&self) {
+        let workspaces: Vec<WorkSpaceKind> =
+            self.analysis.workspaces.read().keys().cloned().collect();
+        for workspace in workspaces {
+            let opts = self.workspace_config_cache.read().get(&workspace).cloned();
+            if let Some(opts) = opts {
+                self.async_compile(workspace, opts, None, false);
+            }
+        }
+    }
+
     // Configuration file modifications that do not occur on the IDE client side, e.g., `kcl mod add xxx``
     pub(crate) fn handle_changed_confg_file(&self, paths: &[PathBuf]) {
         for path in paths {
             self.log_message(format!("Changed config file {:?}", path));
             // In workspaces
-            let mut workspaces = self.analysis.workspaces.write();
-            for workspace in workspaces.keys() {
+            // The compile options are looked up without locking the workspaces, which the requests
+            // read meanwhile.
+            let workspaces: Vec<WorkSpaceKind> =
+                self.analysis.workspaces.read().keys().cloned().collect();
+            for workspace in &workspaces {
                 if let Some(p) = match workspace {
                     WorkSpaceKind::ModFile(path_buf) => Some(path_buf.clone()),
                     WorkSpaceKind::SettingFile(path_buf) => Some(path_buf.clone()),
@@ -808,7 +1034,6 @@ impl LanguageServerState {
                     self.async_compile(workspace.clone(), opts, None, false);
                 }
             }
-            drop(workspaces);
 
             // In temp workspaces
             let mut temp_workspace = self.temporary_workspace.write();
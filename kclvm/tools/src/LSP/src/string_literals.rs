@@ -0,0 +1,174 @@
+//! The string literals of a program, e.g. to catalog the user-facing strings for the translation
+//! or to scan them for secrets. A literal is reported once with the static segments and the
+//! interpolations of an interpolated string, and the literals nested in the interpolations, e.g.
+//! `"${name or 'anonymous'}"`, are reported as literals of their own.
+
+use kclvm_ast::ast::{self, Program};
+use kclvm_ast::pos::GetPos;
+use kclvm_ast_pretty::{print_ast_node, ASTNode};
+use lsp_types::{Location, Range, Url};
+
+use crate::lsp_ext::{StringLiteral, StringSegment};
+use crate::positioned_walker::{walk_expr, walk_stmts, PositionedVisitor};
+use crate::to_lsp::lsp_pos;
+
+/// Returns the string literals of all the modules of the program, sorted by the files and the
+/// positions.
+pub(crate) fn string_literals(program: &Program) -> Vec<StringLiteral> {
+    let mut literals = vec![];
+    for module in program.modules.values() {
+        let module = match module.read() {
+            Ok(module) => module,
+            Err(_) => continue,
+        };
+        let uri = match Url::from_file_path(&module.filename) {
+            Ok(uri) => uri,
+            Err(_) => continue,
+        };
+        let mut collector = StringLiteralCollector {
+            uri,
+            literals: vec![],
+        };
+        walk_stmts(&mut collector, &module.body);
+        literals.extend(collector.literals);
+    }
+    literals.sort_by(|a, b| {
+        (a.location.uri.as_str(), a.location.range.start)
+            .cmp(&(b.location.uri.as_str(), b.location.range.start))
+    });
+    literals
+}
+
+fn node_range<T>(node: &ast::Node<T>) -> Range {
+    Range::new(lsp_pos(&node.get_pos()), lsp_pos(&node.get_end_pos()))
+}
+
+/// Whether the source of the string starts with the raw prefix, e.g. `r"\d+"`.
+fn is_raw(raw_value: &str) -> bool {
+    raw_value.starts_with(['r', 'R'])
+}
+
+struct StringLiteralCollector {
+    uri: Url,
+    literals: Vec<StringLiteral>,
+}
+
+impl PositionedVisitor for StringLiteralCollector {
+    fn visit_expr(&mut self, expr: &ast::NodeRef<ast::Expr>) -> bool {
+        match &expr.node {
+            ast::Expr::StringLit(string_lit) => {
+                self.literals.push(StringLiteral {
+                    location: Location::new(self.uri.clone(), node_range(expr)),
+                    text: string_lit.value.clone(),
+                    interpolated: false,
+                    raw: is_raw(&string_lit.raw_value),
+                    segments: vec![],
+                });
+                true
+            }
+            // The segments are not literals of their own.
+            ast::Expr::JoinedString(joined_string) => {
+                self.joined_string(expr, joined_string);
+                false
+            }
+            _ => true,
+        }
+    }
+}
+
+impl StringLiteralCollector {
+    /// Records the interpolated string with its segments, then the literals nested in the
+    /// interpolations.
+    fn joined_string(&mut self, expr: &ast::NodeRef<ast::Expr>, joined_string: &ast::JoinedString) {
+        let mut text = String::new();
+        let mut segments = vec![];
+        let mut interpolations = vec![];
+        for value in &joined_string.values {
+            match &value.node {
+                ast::Expr::StringLit(string_lit) => {
+                    text.push_str(&string_lit.value);
+                    segments.push(StringSegment {
+                        range: node_range(value),
+                        text: string_lit.value.clone(),
+                        interpolated: false,
+                    });
+                }
+                ast::Expr::FormattedValue(formatted_value) => {
+                    let interpolation = print_ast_node(ASTNode::Expr(&formatted_value.value));
+                    match &formatted_value.format_spec {
+                        Some(format_spec) => {
+                            text.push_str(&format!("${{{}: {}}}", interpolation, format_spec))
+                        }
+                        None => text.push_str(&format!("${{{}}}", interpolation)),
+                    }
+                    segments.push(StringSegment {
+                        range: node_range(value),
+                        text: interpolation,
+                        interpolated: true,
+                    });
+                    interpolations.push(&formatted_value.value);
+                }
+                _ => {}
+            }
+        }
+        self.literals.push(StringLiteral {
+            location: Location::new(self.uri.clone(), node_range(expr)),
+            text,
+            interpolated: true,
+            raw: is_raw(&joined_string.raw_value),
+            segments,
+        });
+        for interpolation in interpolations {
+            walk_expr(self, interpolation);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{Position, Range};
+    use proc_macro_crate::bench_test;
+
+    use super::string_literals;
+    use crate::tests::compile_test_file;
+
+    #[test]
+    #[bench_test]
+    fn string_literals_test() {
+        let (_, program, _, _, _) = compile_test_file("src/test_data/string_literals/main.k");
+        let literals = string_literals(&program);
+        let summary: Vec<(u32, &str, bool, bool)> = literals
+            .iter()
+            .map(|literal| {
+                (
+                    literal.location.range.start.line,
+                    literal.text.as_str(),
+                    literal.interpolated,
+                    literal.raw,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (0, "Alice", false, false),
+                (1, "Hello, ${name}!", true, false),
+                (2, "\\d+\\.", false, true),
+            ]
+        );
+
+        let segments: Vec<(&str, bool)> = literals[1]
+            .segments
+            .iter()
+            .map(|segment| (segment.text.as_str(), segment.interpolated))
+            .collect();
+        assert_eq!(
+            segments,
+            vec![("Hello, ", false), ("name", true), ("!", false)]
+        );
+        assert_eq!(
+            literals[0].location.range,
+            Range::new(Position::new(0, 7), Position::new(0, 14))
+        );
+    }
+}
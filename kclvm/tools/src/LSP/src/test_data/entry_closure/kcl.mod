@@ -0,0 +1,4 @@
+[package]
+name = "entry_closure"
+edition = "0.0.1"
+version = "0.0.1"
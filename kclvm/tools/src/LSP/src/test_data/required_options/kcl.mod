@@ -0,0 +1,4 @@
+[package]
+name = "required_options"
+edition = "0.0.1"
+version = "0.0.1"
@@ -0,0 +1,8 @@
+[package]
+name = "validate_mod"
+edition = "v0.9.0"
+version = "0.0.1"
+
+[dependencies]
+k8s = "1.28.x"
+helper = { path = "./helper" }
@@ -63,6 +63,7 @@ use std::time::Duration;
 use kclvm_ast::ast::Program;
 use kclvm_error::Diagnostic as KCLDiagnostic;
 use kclvm_error::Position as KCLPos;
+use kclvm_error::{DiagnosticId, WarningKind};
 use kclvm_parser::KCLModuleCache;
 
 use lsp_types::Diagnostic;
@@ -80,15 +81,22 @@ use crate::compile::compile_with_params;
 use crate::completion::completion;
 use crate::from_lsp::file_path_from_url;
 
+use crate::analysis::{AnalysisDatabase, DBState, OpenFileInfo};
 use crate::app::main_loop;
 use crate::compile::Params;
 use crate::goto_def::goto_def;
 use crate::hover::hover;
+use crate::quick_fix::quick_fix;
+use crate::request::handle_hover;
 use crate::state::KCLGlobalStateCache;
 use crate::state::KCLVfs;
+use crate::state::LanguageServerState;
 use crate::to_lsp::kcl_diag_to_lsp_diags_by_file;
 use crate::util::apply_document_changes;
 use crate::util::to_json;
+use crate::watch::main_loop_with_watchers;
+use crate::watch::DiagnosticWatchers;
+use crate::watch::DiagnosticsSession;
 
 macro_rules! wait_async {
     () => {
@@ -155,6 +163,11 @@ pub(crate) fn compile_test_file(
         scope_cache: Some(KCLScopeCache::default()),
         vfs: Some(KCLVfs::default()),
         gs_cache: Some(KCLGlobalStateCache::default()),
+        max_schema_depth: None,
+        minimal_diagnostics: false,
+        implicit_coercion_check: false,
+        explicit_type_check: false,
+        ambiguous_reference_check: false,
     });
     let (program, schema_map, gs) = compile_res.unwrap();
     (file, program, diags, gs, schema_map)
@@ -187,6 +200,11 @@ pub(crate) fn compile_test_file_and_metadata(
         scope_cache: Some(KCLScopeCache::default()),
         vfs: Some(KCLVfs::default()),
         gs_cache: Some(KCLGlobalStateCache::default()),
+        max_schema_depth: None,
+        minimal_diagnostics: false,
+        implicit_coercion_check: false,
+        explicit_type_check: false,
+        ambiguous_reference_check: false,
     });
     let (program, schema_map, gs) = compile_res.unwrap();
 
@@ -263,7 +281,7 @@ fn build_expect_diags() -> Vec<Diagnostic> {
             Some(DiagnosticSeverity::ERROR),
             vec![],
             Some(NumberOrString::String("InvalidSyntax".to_string())),
-            None,
+            Some(serde_json::json!({ "category": "correctness" })),
         ),
         build_lsp_diag(
             (0, 0, 0, 10),
@@ -271,7 +289,7 @@ fn build_expect_diags() -> Vec<Diagnostic> {
             Some(DiagnosticSeverity::ERROR),
             vec![],
             Some(NumberOrString::String("CannotFindModule".to_string())),
-            None,
+            Some(serde_json::json!({ "category": "correctness" })),
         ),
         build_lsp_diag(
             (0, 0, 0, 10),
@@ -282,7 +300,7 @@ fn build_expect_diags() -> Vec<Diagnostic> {
             Some(DiagnosticSeverity::ERROR),
             vec![],
             Some(NumberOrString::String("CannotFindModule".to_string())),
-            None,
+            Some(serde_json::json!({ "category": "correctness" })),
         ),
         build_lsp_diag(
             (8, 0, 8, 1),
@@ -294,7 +312,7 @@ fn build_expect_diags() -> Vec<Diagnostic> {
                 "The variable 'd' is declared here".to_string(),
             )],
             Some(NumberOrString::String("ImmutableError".to_string())),
-            None,
+            Some(serde_json::json!({ "category": "correctness" })),
         ),
         build_lsp_diag(
             (7, 0, 7, 1),
@@ -306,7 +324,7 @@ fn build_expect_diags() -> Vec<Diagnostic> {
                 "Can not change the value of 'd', because it was declared immutable".to_string(),
             )],
             Some(NumberOrString::String("ImmutableError".to_string())),
-            None,
+            Some(serde_json::json!({ "category": "correctness" })),
         ),
         build_lsp_diag(
             (2, 0, 2, 1),
@@ -314,7 +332,7 @@ fn build_expect_diags() -> Vec<Diagnostic> {
             Some(DiagnosticSeverity::ERROR),
             vec![],
             Some(NumberOrString::String("TypeError".to_string())),
-            None,
+            Some(serde_json::json!({ "category": "correctness" })),
         ),
         build_lsp_diag(
             (10, 8, 10, 10),
@@ -322,7 +340,11 @@ fn build_expect_diags() -> Vec<Diagnostic> {
             Some(DiagnosticSeverity::ERROR),
             vec![],
             Some(NumberOrString::String("CompileError".to_string())),
-            Some(serde_json::json!({ "suggested_replacement": ["number"] })),
+            Some(serde_json::json!({
+                "suggested_replacement": ["number"],
+                "fix_safety": "needs-review",
+                "category": "correctness"
+            })),
         ),
         build_lsp_diag(
             (0, 0, 0, 10),
@@ -330,7 +352,7 @@ fn build_expect_diags() -> Vec<Diagnostic> {
             Some(DiagnosticSeverity::WARNING),
             vec![],
             Some(NumberOrString::String("UnusedImportWarning".to_string())),
-            None,
+            Some(serde_json::json!({ "fix_safety": "safe", "category": "style" })),
         ),
     ];
     expected_diags
@@ -350,12 +372,17 @@ fn diagnostics_test() {
         scope_cache: None,
         vfs: Some(KCLVfs::default()),
         gs_cache: Some(KCLGlobalStateCache::default()),
+        max_schema_depth: None,
+        minimal_diagnostics: false,
+        implicit_coercion_check: false,
+        explicit_type_check: false,
+        ambiguous_reference_check: false,
     })
     .0;
 
     let diagnostics = diags
         .iter()
-        .flat_map(|diag| kcl_diag_to_lsp_diags_by_file(diag, file))
+        .flat_map(|diag| kcl_diag_to_lsp_diags_by_file(diag, file, None))
         .collect::<Vec<Diagnostic>>();
 
     let expected_diags: Vec<Diagnostic> = build_expect_diags();
@@ -365,6 +392,87 @@ fn diagnostics_test() {
     }
 }
 
+#[test]
+#[bench_test]
+fn diagnostic_anchor_test() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let mut test_file = path.clone();
+    test_file.push("src/test_data/diagnostics/diagnostics.k");
+    let file = test_file.to_str().unwrap();
+    let src = std::fs::read_to_string(file).unwrap();
+
+    let diags = compile_with_params(Params {
+        file: Some(file.to_string()),
+        module_cache: None,
+        scope_cache: None,
+        vfs: Some(KCLVfs::default()),
+        gs_cache: Some(KCLGlobalStateCache::default()),
+        max_schema_depth: None,
+        minimal_diagnostics: false,
+        implicit_coercion_check: false,
+        explicit_type_check: false,
+        ambiguous_reference_check: false,
+    })
+    .0;
+
+    let diagnostics = diags
+        .iter()
+        .flat_map(|diag| kcl_diag_to_lsp_diags_by_file(diag, file, Some(&src)))
+        .collect::<Vec<Diagnostic>>();
+    assert!(!diagnostics.is_empty());
+    for diag in &diagnostics {
+        let anchor = &diag.data.as_ref().unwrap()["anchor"];
+        let line = src.lines().nth(diag.range.start.line as usize).unwrap();
+        assert_eq!(anchor["text"], line.trim());
+    }
+
+    // The unused import `import abc` is anchored by the hash of the line.
+    let unused_import = diagnostics
+        .iter()
+        .find(|diag| diag.message == "Module 'abc' imported but unused")
+        .unwrap();
+    assert_eq!(
+        unused_import.data.as_ref().unwrap()["anchor"],
+        serde_json::json!({ "text": "import abc", "hash": "ea93805d0188bd34" })
+    );
+}
+
+#[test]
+fn diagnostic_category_test() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let mut test_file = path.clone();
+    test_file.push("src/test_data/diagnostics/diagnostics.k");
+    let file = test_file.to_str().unwrap();
+
+    let diags = compile_with_params(Params {
+        file: Some(file.to_string()),
+        module_cache: None,
+        scope_cache: None,
+        vfs: Some(KCLVfs::default()),
+        gs_cache: Some(KCLGlobalStateCache::default()),
+        max_schema_depth: None,
+        minimal_diagnostics: false,
+        implicit_coercion_check: false,
+        explicit_type_check: false,
+        ambiguous_reference_check: false,
+    })
+    .0;
+
+    let diagnostics = diags
+        .iter()
+        .flat_map(|diag| kcl_diag_to_lsp_diags_by_file(diag, file, None))
+        .collect::<Vec<Diagnostic>>();
+    let category = |code: &str| {
+        let diag = diagnostics
+            .iter()
+            .find(|diag| diag.code == Some(NumberOrString::String(code.to_string())))
+            .unwrap();
+        diag.data.as_ref().unwrap()["category"].clone()
+    };
+    assert_eq!(category("UnusedImportWarning"), "style");
+    assert_eq!(category("TypeError"), "correctness");
+}
+
 #[test]
 #[bench_test]
 fn test_apply_document_changes() {
@@ -541,6 +649,11 @@ fn complete_import_external_file_test() {
         scope_cache: None,
         vfs: Some(KCLVfs::default()),
         gs_cache: Some(KCLGlobalStateCache::default()),
+        max_schema_depth: None,
+        minimal_diagnostics: false,
+        implicit_coercion_check: false,
+        explicit_type_check: false,
+        ambiguous_reference_check: false,
     })
     .1
     .unwrap();
@@ -601,6 +714,11 @@ fn goto_import_external_file_test() {
         scope_cache: None,
         vfs: Some(KCLVfs::default()),
         gs_cache: Some(KCLGlobalStateCache::default()),
+        max_schema_depth: None,
+        minimal_diagnostics: false,
+        implicit_coercion_check: false,
+        explicit_type_check: false,
+        ambiguous_reference_check: false,
     });
     let gs = compile_res.unwrap().2;
 
@@ -655,6 +773,25 @@ impl Server {
         }
     }
 
+    /// Constructs and initializes a new `Server` whose diagnostics are also sent to `watchers`
+    pub fn new_with_diagnostic_watchers(
+        initialize_params: InitializeParams,
+        watchers: DiagnosticWatchers,
+    ) -> Self {
+        let (connection, client) = Connection::memory();
+
+        let worker = std::thread::spawn(move || {
+            main_loop_with_watchers(connection, initialize_params, watchers).unwrap();
+        });
+
+        Self {
+            next_request_id: Cell::new(1),
+            worker: Some(worker),
+            client,
+            messages: RefCell::new(Vec::new()),
+        }
+    }
+
     /// Sends a request to the language server, returning the response
     pub fn send_request<R: lsp_types::request::Request>(&self, params: R::Params) {
         let id = self.next_request_id.get();
@@ -1013,6 +1150,60 @@ fn goto_def_test() {
     );
 }
 
+#[test]
+fn definitions_batch_test() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("test_data")
+        .join("goto_def_test")
+        .join("goto_def.k");
+    let path = path.to_str().unwrap();
+    let uri = Url::from_file_path(path).unwrap();
+    let src = std::fs::read_to_string(path).unwrap();
+    let server = Project {}.server(InitializeParams::default());
+
+    server.notification::<lsp_types::notification::DidOpenTextDocument>(
+        lsp_types::DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "KCL".to_string(),
+                version: 0,
+                text: src,
+            },
+        },
+    );
+
+    let id = server.next_request_id.get();
+    server.next_request_id.set(id.wrapping_add(1));
+    let position = |line, character| TextDocumentPositionParams {
+        text_document: TextDocumentIdentifier { uri: uri.clone() },
+        position: Position::new(line, character),
+    };
+    // The schema names `Name` and `Person`, and an empty line between them.
+    let r: Request = Request::new(
+        id.into(),
+        "kcl/definitionsBatch".to_string(),
+        crate::lsp_ext::DefinitionsBatchParams {
+            positions: vec![position(24, 8), position(16, 0), position(23, 9)],
+        },
+    );
+    let res = server.send_and_receive(r);
+
+    let location = |start: Position, end: Position| Location {
+        uri: uri.clone(),
+        range: Range { start, end },
+    };
+    assert_eq!(
+        res.result.unwrap(),
+        to_json(vec![
+            vec![location(Position::new(17, 7), Position::new(17, 11))],
+            vec![],
+            vec![location(Position::new(20, 7), Position::new(20, 13))],
+        ])
+        .unwrap()
+    );
+}
+
 #[test]
 fn complete_test() {
     let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -1239,6 +1430,161 @@ fn hover_test() {
     )
 }
 
+#[test]
+fn hover_plain_text_test() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let path = root
+        .join("src")
+        .join("test_data")
+        .join("hover_test")
+        .join("hover.k");
+    let path = path.to_str().unwrap();
+
+    let src = std::fs::read_to_string(path).unwrap();
+    // The client only renders the plain text hover contents.
+    let initialize_params = InitializeParams {
+        capabilities: lsp_types::ClientCapabilities {
+            text_document: Some(lsp_types::TextDocumentClientCapabilities {
+                hover: Some(lsp_types::HoverClientCapabilities {
+                    dynamic_registration: None,
+                    content_format: Some(vec![lsp_types::MarkupKind::PlainText]),
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let server = Project {}.server(initialize_params);
+
+    server.notification::<lsp_types::notification::DidOpenTextDocument>(
+        lsp_types::DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: Url::from_file_path(path).unwrap(),
+                language_id: "KCL".to_string(),
+                version: 0,
+                text: src,
+            },
+        },
+    );
+
+    let id = server.next_request_id.get();
+    server.next_request_id.set(id.wrapping_add(1));
+
+    let r: Request = Request::new(
+        id.into(),
+        "textDocument/hover".to_string(),
+        HoverParams {
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier {
+                    uri: Url::from_file_path(path).unwrap(),
+                },
+                position: Position::new(15, 7),
+            },
+            work_done_progress_params: Default::default(),
+        },
+    );
+
+    let res = server.send_and_receive(r);
+    let hover: Hover = serde_json::from_value(res.result.unwrap()).unwrap();
+    match hover.contents {
+        HoverContents::Markup(markup) => {
+            assert_eq!(markup.kind, lsp_types::MarkupKind::PlainText);
+            assert_eq!(
+                markup.value,
+                "__main__\n\nschema Person:\n    name: str\n    age?: int\n\nhover doc test"
+            );
+            assert!(!markup.value.contains('`'));
+            assert!(!markup.value.contains("**"));
+        }
+        _ => unreachable!("test error"),
+    }
+}
+
+#[test]
+fn concurrent_hover_during_recompile_test() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("test_data")
+        .join("hover_test")
+        .join("hover.k");
+    let uri = Url::from_file_path(&path).unwrap();
+    let (_, prog, diags, gs, schema_map) = compile_test_file("src/test_data/hover_test/hover.k");
+    let db = Arc::new(AnalysisDatabase {
+        prog,
+        gs,
+        diags,
+        schema_map,
+    });
+    let (sender, _receiver) = crossbeam_channel::unbounded();
+    let state = LanguageServerState::new(sender, InitializeParams::default());
+    let workspace = WorkSpaceKind::File(path.clone());
+    let vfs_path: ra_ap_vfs::VfsPath = crate::from_lsp::abs_path(&uri).unwrap().into();
+    let file_id = {
+        let mut vfs = state.vfs.write();
+        vfs.set_file_contents(vfs_path.clone(), Some(std::fs::read(&path).unwrap()));
+        vfs.file_id(&vfs_path).unwrap()
+    };
+    state.opened_files.write().insert(
+        file_id,
+        OpenFileInfo {
+            version: 0,
+            workspaces: HashSet::from([workspace.clone()]),
+        },
+    );
+    // The workspace is being recompiled after an edit of the file.
+    state
+        .analysis
+        .workspaces
+        .write()
+        .insert(workspace.clone(), DBState::Compiling(db.clone()));
+
+    let (task_sender, _task_receiver) = crossbeam_channel::unbounded();
+    let params = HoverParams {
+        text_document_position_params: TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier { uri },
+            position: Position::new(15, 7),
+        },
+        work_done_progress_params: Default::default(),
+    };
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let snapshots: Vec<_> = (0..10).map(|_| state.snapshot()).collect();
+            let params = params.clone();
+            let sender = task_sender.clone();
+            thread::spawn(move || {
+                snapshots
+                    .into_iter()
+                    .map(|snapshot| handle_hover(snapshot, params.clone(), sender.clone()))
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect();
+    // The compiled db replaces the previous one while the hovers run.
+    state
+        .analysis
+        .workspaces
+        .write()
+        .insert(workspace, DBState::Ready(db));
+
+    // The workspaces are read without blocking, so a hover meeting the swap of the states is
+    // retried by the main loop rather than waiting for it.
+    let hovers: Vec<Option<Hover>> = handles
+        .into_iter()
+        .flat_map(|handle| handle.join().unwrap())
+        .filter_map(|hover| match hover {
+            Ok(hover) => Some(hover),
+            Err(err) => {
+                assert_eq!(err.to_string(), crate::error::RETRY_REQUEST);
+                None
+            }
+        })
+        .collect();
+    assert!(!hovers.is_empty());
+    assert!(hovers[0].is_some());
+    assert!(hovers.iter().all(|hover| hover == &hovers[0]));
+}
+
 #[test]
 fn hover_assign_in_lambda_test() {
     let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -1648,6 +1994,11 @@ fn konfig_goto_def_test_base() {
         scope_cache: None,
         vfs: Some(KCLVfs::default()),
         gs_cache: Some(KCLGlobalStateCache::default()),
+        max_schema_depth: None,
+        minimal_diagnostics: false,
+        implicit_coercion_check: false,
+        explicit_type_check: false,
+        ambiguous_reference_check: false,
     })
     .1
     .unwrap();
@@ -1774,12 +2125,17 @@ fn konfig_goto_def_test_main() {
         .join("dev")
         .join("main.k");
     let main_path_str = main_path.to_str().unwrap().to_string();
-    let (_program, _, gs) = compile_with_params(Params {
+    let (program, _, gs) = compile_with_params(Params {
         file: Some(main_path_str.clone()),
         module_cache: None,
         scope_cache: None,
         vfs: Some(KCLVfs::default()),
         gs_cache: Some(KCLGlobalStateCache::default()),
+        max_schema_depth: None,
+        minimal_diagnostics: false,
+        implicit_coercion_check: false,
+        explicit_type_check: false,
+        ambiguous_reference_check: false,
     })
     .1
     .unwrap();
@@ -1867,6 +2223,11 @@ fn konfig_completion_test_main() {
         scope_cache: None,
         vfs: Some(KCLVfs::default()),
         gs_cache: Some(KCLGlobalStateCache::default()),
+        max_schema_depth: None,
+        minimal_diagnostics: false,
+        implicit_coercion_check: false,
+        explicit_type_check: false,
+        ambiguous_reference_check: false,
     })
     .1
     .unwrap();
@@ -1980,12 +2341,17 @@ fn konfig_hover_test_main() {
         .join("main.k");
 
     let main_path_str = main_path.to_str().unwrap().to_string();
-    let (_program, _, gs) = compile_with_params(Params {
+    let (program, _, gs) = compile_with_params(Params {
         file: Some(main_path_str.clone()),
         module_cache: None,
         scope_cache: None,
         vfs: Some(KCLVfs::default()),
         gs_cache: Some(KCLGlobalStateCache::default()),
+        max_schema_depth: None,
+        minimal_diagnostics: false,
+        implicit_coercion_check: false,
+        explicit_type_check: false,
+        ambiguous_reference_check: false,
     })
     .1
     .unwrap();
@@ -1996,7 +2362,7 @@ fn konfig_hover_test_main() {
         line: 6,
         column: Some(32),
     };
-    let got = hover(&pos, &gs).unwrap();
+    let got = hover(&pos, &program, &gs).unwrap();
     match got.contents {
         HoverContents::Array(arr) => {
             let expect: Vec<MarkedString> = vec![
@@ -2022,7 +2388,7 @@ fn konfig_hover_test_main() {
         line: 7,
         column: Some(15),
     };
-    let got = hover(&pos, &gs).unwrap();
+    let got = hover(&pos, &program, &gs).unwrap();
     match got.contents {
         HoverContents::Array(arr) => {
             let expect: Vec<MarkedString> = vec![
@@ -2047,7 +2413,7 @@ fn konfig_hover_test_main() {
         line: 6,
         column: Some(3),
     };
-    let got = hover(&pos, &gs).unwrap();
+    let got = hover(&pos, &program, &gs).unwrap();
     match got.contents {
         HoverContents::Scalar(s) => {
             assert_eq!(
@@ -2412,6 +2778,81 @@ fn rename_test() {
     assert_eq!(res.result.unwrap(), to_json(expect).unwrap());
 }
 
+#[test]
+fn rename_import_alias_test() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("test_data")
+        .join("import_alias")
+        .join("main.k");
+    let path = path.to_str().unwrap();
+    let src = std::fs::read_to_string(path).unwrap();
+    let server = Project {}.server(InitializeParams::default());
+    let url = Url::from_file_path(path).unwrap();
+
+    // Mock open file
+    server.notification::<lsp_types::notification::DidOpenTextDocument>(
+        lsp_types::DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: url.clone(),
+                language_id: "KCL".to_string(),
+                version: 0,
+                text: src,
+            },
+        },
+    );
+
+    let id = server.next_request_id.get();
+    server.next_request_id.set(id.wrapping_add(1));
+
+    // Rename the alias `c` at `n = c.name`, the package `pkg.sub` is unchanged.
+    let new_name = String::from("sub");
+    let r: Request = Request::new(
+        id.into(),
+        "textDocument/rename".to_string(),
+        RenameParams {
+            text_document_position: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri: url.clone() },
+                position: Position::new(2, 4),
+            },
+            new_name: new_name.clone(),
+            work_done_progress_params: Default::default(),
+        },
+    );
+
+    let res = server.send_and_receive(r);
+    let expect = WorkspaceEdit {
+        changes: Some(HashMap::from_iter(vec![(
+            url.clone(),
+            vec![
+                TextEdit {
+                    range: Range {
+                        start: Position::new(0, 19),
+                        end: Position::new(0, 20),
+                    },
+                    new_text: new_name.clone(),
+                },
+                TextEdit {
+                    range: Range {
+                        start: Position::new(2, 4),
+                        end: Position::new(2, 5),
+                    },
+                    new_text: new_name.clone(),
+                },
+                TextEdit {
+                    range: Range {
+                        start: Position::new(3, 4),
+                        end: Position::new(3, 5),
+                    },
+                    new_text: new_name.clone(),
+                },
+            ],
+        )])),
+        ..Default::default()
+    };
+    assert_eq!(res.result.unwrap(), to_json(expect).unwrap());
+}
+
 #[test]
 fn kcl_workspace_init_kclwork_test() {
     let tool: crate::state::KCLToolChain = Arc::new(RwLock::new(toolchain::default()));
@@ -2690,3 +3131,580 @@ fn aug_assign_without_define() {
         compile_test_file("src/test_data/error_code/aug_assign/aug_assign.k");
     assert_eq!(diags.len(), 1);
 }
+
+#[test]
+fn set_trace_verbose_test() {
+    let server = Project {}.server(InitializeParams::default());
+
+    server.notification::<lsp_types::notification::SetTrace>(lsp_types::SetTraceParams {
+        value: lsp_types::TraceValue::Verbose,
+    });
+
+    let id = server.next_request_id.get();
+    server.next_request_id.set(id.wrapping_add(1));
+    let r: Request = Request::new(
+        id.into(),
+        "kcl/explainCode".to_string(),
+        crate::lsp_ext::ExplainCodeParams {
+            code: "TypeError".to_string(),
+        },
+    );
+    server.send_and_receive(r);
+
+    let traces: Vec<lsp_types::LogTraceParams> = server
+        .messages
+        .borrow()
+        .iter()
+        .filter_map(|msg| match msg {
+            Message::Notification(not) if not.method == "$/logTrace" => {
+                serde_json::from_value(not.params.clone()).ok()
+            }
+            _ => None,
+        })
+        .collect();
+    assert!(!traces.is_empty());
+    assert!(traces[0].message.contains("kcl/explainCode"));
+    assert!(traces[0].verbose.as_ref().unwrap().contains("TypeError"));
+}
+
+/// Property test of the position conversions with the edge values and pseudo random values,
+/// see the fuzz target `fuzz_lsp_pos` for the fuzzing version.
+#[test]
+fn position_conversion_property_test() {
+    use crate::from_lsp::kcl_pos;
+    use crate::to_lsp::{kcl_msg_to_lsp_diags, lsp_location, lsp_pos};
+    use kclvm_error::{Message, Position as KCLPos, Style};
+
+    let file = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("test_data")
+        .join("diagnostics")
+        .join("diagnostics.k");
+    let file = file.to_str().unwrap().to_string();
+
+    let mut values: Vec<u64> = vec![
+        0,
+        1,
+        2,
+        u32::MAX as u64 - 1,
+        u32::MAX as u64,
+        u32::MAX as u64 + 1,
+        u64::MAX - 1,
+        u64::MAX,
+    ];
+    // A xorshift generator keeps the test deterministic without extra dependencies.
+    let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+    for _ in 0..64 {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        values.push(seed);
+        values.push(seed % 1024);
+    }
+    let columns: Vec<Option<u64>> = std::iter::once(None)
+        .chain(values.iter().map(|v| Some(*v)))
+        .collect();
+
+    for line in &values {
+        for column in &columns {
+            let pos = KCLPos {
+                filename: file.clone(),
+                line: *line,
+                column: *column,
+            };
+            let lsp = lsp_pos(&pos);
+            assert_eq!(lsp.line as u64, line.saturating_sub(1).min(u32::MAX as u64));
+            assert_eq!(
+                lsp.character as u64,
+                column.unwrap_or(0).min(u32::MAX as u64)
+            );
+
+            // Round trip of the lsp position.
+            let back = kcl_pos(&file, lsp);
+            assert_eq!(back.line, lsp.line as u64 + 1);
+            assert_eq!(back.column, Some(lsp.character as u64));
+        }
+    }
+
+    for start_line in &values {
+        for end_line in &values {
+            let start = KCLPos {
+                filename: file.clone(),
+                line: *start_line,
+                column: Some(end_line % 7),
+            };
+            let end = KCLPos {
+                filename: file.clone(),
+                line: *end_line,
+                column: None,
+            };
+            let location = lsp_location(file.clone(), &start, &end).unwrap();
+            assert!(
+                location.range.start <= location.range.end,
+                "{:?}",
+                location.range
+            );
+
+            let msg = Message {
+                range: (start.clone(), end.clone()),
+                style: Style::LineAndColumn,
+                message: "message".to_string(),
+                note: None,
+                suggested_replacement: None,
+            };
+            let diag = kcl_msg_to_lsp_diags(
+                &msg,
+                DiagnosticSeverity::ERROR,
+                vec![msg.clone()],
+                None,
+                None,
+                None,
+            );
+            assert!(diag.range.start <= diag.range.end, "{:?}", diag.range);
+            let related = &diag.related_information.unwrap()[0].location.range;
+            assert!(related.start <= related.end, "{:?}", related);
+        }
+    }
+}
+
+#[test]
+fn code_action_preview_test() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("test_data")
+        .join("code_action")
+        .join("preview")
+        .join("main.k");
+    let path = path.to_str().unwrap();
+    let src = std::fs::read_to_string(path).unwrap();
+    let uri = Url::from_file_path(path).unwrap();
+    let server = Project {}.server(InitializeParams::default());
+    server.notification::<lsp_types::notification::DidOpenTextDocument>(
+        lsp_types::DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "KCL".to_string(),
+                version: 0,
+                text: src,
+            },
+        },
+    );
+
+    // The unused import `import math`, which is sent twice by the client.
+    let diag = lsp_types::Diagnostic {
+        range: Range::new(Position::new(0, 0), Position::new(0, 11)),
+        severity: Some(lsp_types::DiagnosticSeverity::WARNING),
+        code: Some(lsp_types::NumberOrString::String(
+            "UnusedImportWarning".to_string(),
+        )),
+        message: "Module 'math' imported but unused".to_string(),
+        data: Some(serde_json::json!({ "fix_safety": "safe" })),
+        ..Default::default()
+    };
+    let id = server.next_request_id.get();
+    server.next_request_id.set(id.wrapping_add(1));
+    let r: Request = Request::new(
+        id.into(),
+        "textDocument/codeAction".to_string(),
+        lsp_types::CodeActionParams {
+            text_document: TextDocumentIdentifier { uri },
+            // The under-indented `name = "kcl"`.
+            range: Range::new(Position::new(3, 0), Position::new(4, 0)),
+            context: lsp_types::CodeActionContext {
+                diagnostics: vec![diag.clone(), diag],
+                only: None,
+                trigger_kind: None,
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        },
+    );
+    let res = server.send_and_receive(r);
+    let actions: Vec<lsp_types::CodeActionOrCommand> =
+        serde_json::from_value(res.result.unwrap()).unwrap();
+    let got: Vec<(String, Option<lsp_types::CodeActionKind>, bool)> = actions
+        .into_iter()
+        .map(|action| match action {
+            lsp_types::CodeActionOrCommand::CodeAction(action) => {
+                (action.title, action.kind, action.edit.is_some())
+            }
+            lsp_types::CodeActionOrCommand::Command(command) => (command.title, None, false),
+        })
+        .collect();
+    assert_eq!(
+        got,
+        vec![
+            (
+                "UnusedImportWarning".to_string(),
+                Some(lsp_types::CodeActionKind::QUICKFIX),
+                true
+            ),
+            (
+                "Reindent pasted block".to_string(),
+                Some(lsp_types::CodeActionKind::REFACTOR_REWRITE),
+                true
+            ),
+            (
+                "Fix all auto-fixable problems".to_string(),
+                Some(lsp_types::CodeActionKind::SOURCE_FIX_ALL),
+                true
+            ),
+        ]
+    );
+}
+
+#[test]
+fn rebuild_caches_test() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("test_data")
+        .join("sema_token")
+        .join("delta")
+        .join("before.k");
+    let path = path.to_str().unwrap();
+    let src = std::fs::read_to_string(path).unwrap();
+    let uri = Url::from_file_path(path).unwrap();
+    let server = Project {}.server(InitializeParams::default());
+    server.notification::<lsp_types::notification::DidOpenTextDocument>(
+        lsp_types::DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "KCL".to_string(),
+                version: 0,
+                text: src,
+            },
+        },
+    );
+
+    let id = server.next_request_id.get();
+    server.next_request_id.set(id.wrapping_add(1));
+    let res = server.send_and_receive(Request::new(
+        id.into(),
+        "textDocument/semanticTokens/full".to_string(),
+        SemanticTokensParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        },
+    ));
+    let result_id = res.result.unwrap()["resultId"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let delta_params = lsp_types::SemanticTokensDeltaParams {
+        text_document: TextDocumentIdentifier { uri },
+        previous_result_id: result_id,
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+
+    // The cached tokens are served as a delta.
+    let id = server.next_request_id.get();
+    server.next_request_id.set(id.wrapping_add(1));
+    let res = server.send_and_receive(Request::new(
+        id.into(),
+        "textDocument/semanticTokens/full/delta".to_string(),
+        delta_params.clone(),
+    ));
+    assert!(res.result.unwrap().get("edits").is_some());
+
+    let id = server.next_request_id.get();
+    server.next_request_id.set(id.wrapping_add(1));
+    let res = server.send_and_receive(Request::new(
+        id.into(),
+        "workspace/executeCommand".to_string(),
+        lsp_types::ExecuteCommandParams {
+            command: "kcl.rebuildCaches".to_string(),
+            arguments: vec![],
+            work_done_progress_params: Default::default(),
+        },
+    ));
+    let result: crate::lsp_ext::RebuildCachesResult =
+        serde_json::from_value(res.result.unwrap()).unwrap();
+    assert_eq!(result.documents, 1);
+    assert_eq!(result.workspaces, 1);
+
+    // The tokens are computed again after the caches are cleared.
+    let id = server.next_request_id.get();
+    server.next_request_id.set(id.wrapping_add(1));
+    let res = server.send_and_receive(Request::new(
+        id.into(),
+        "textDocument/semanticTokens/full/delta".to_string(),
+        delta_params,
+    ));
+    let result = res.result.unwrap();
+    assert!(result.get("edits").is_none());
+    assert!(result.get("data").is_some());
+}
+
+#[test]
+fn watch_diagnostics_test() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("test_data")
+        .join("watch_diagnostics")
+        .join("main.k");
+    let uri = Url::from_file_path(&path).unwrap();
+    let watchers = DiagnosticWatchers::default();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let handle = watchers.watch_diagnostics(vec![uri.clone()], move |uri, diagnostics| {
+        let _ = tx.send((uri, diagnostics));
+    });
+    let server = Server::new_with_diagnostic_watchers(InitializeParams::default(), watchers);
+    let change = |version: i32, text: &str| {
+        server.notification::<lsp_types::notification::DidChangeTextDocument>(
+            lsp_types::DidChangeTextDocumentParams {
+                text_document: lsp_types::VersionedTextDocumentIdentifier {
+                    uri: uri.clone(),
+                    version,
+                },
+                content_changes: vec![TextDocumentContentChangeEvent {
+                    range: None,
+                    range_length: None,
+                    text: text.to_string(),
+                }],
+            },
+        );
+    };
+
+    server.notification::<lsp_types::notification::DidOpenTextDocument>(
+        lsp_types::DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "KCL".to_string(),
+                version: 0,
+                text: "a = b\n".to_string(),
+            },
+        },
+    );
+    let (got_uri, diagnostics) = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(got_uri, uri);
+    assert!(!diagnostics.is_empty());
+
+    // Fixing the file clears the diagnostics.
+    change(1, "a = 1\n");
+    let (got_uri, diagnostics) = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(got_uri, uri);
+    assert!(diagnostics.is_empty());
+
+    // No callback after the subscription is stopped.
+    handle.unwatch();
+    change(2, "a = b\n");
+    assert!(rx.recv_timeout(Duration::from_secs(2)).is_err());
+}
+
+#[test]
+fn unwatch_in_callback_test() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("test_data")
+        .join("watch_diagnostics")
+        .join("main.k");
+    let uri = Url::from_file_path(&path).unwrap();
+    let watchers = DiagnosticWatchers::default();
+    let second: Arc<parking_lot::Mutex<Option<crate::watch::WatchHandle>>> = Default::default();
+    let calls = Arc::new(parking_lot::Mutex::new(vec![]));
+    let _first = {
+        let second = second.clone();
+        let calls = calls.clone();
+        watchers.watch_diagnostics(vec![uri.clone()], move |_, _| {
+            calls.lock().push("first");
+            // Unwatches the other subscription of the same change.
+            second.lock().take();
+        })
+    };
+    *second.lock() = Some({
+        let calls = calls.clone();
+        watchers.watch_diagnostics(vec![uri.clone()], move |_, _| {
+            calls.lock().push("second");
+        })
+    });
+    let new = HashMap::from([(
+        path.to_str().unwrap().to_string(),
+        vec![Diagnostic {
+            message: "name 'b' is not defined".to_string(),
+            ..Default::default()
+        }],
+    )]);
+    watchers.notify(&HashMap::new(), &new);
+    // The subscription unwatched before its turn is not called.
+    assert_eq!(*calls.lock(), vec!["first"]);
+}
+
+#[test]
+fn diagnostics_session_test() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("test_data")
+        .join("watch_diagnostics")
+        .join("main.k");
+    let uri = Url::from_file_path(&path).unwrap();
+    let watchers = DiagnosticWatchers::default();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let _handle = watchers.watch_diagnostics(vec![uri.clone()], move |uri, diagnostics| {
+        let _ = tx.send((uri, diagnostics));
+    });
+    let mut session = DiagnosticsSession::new(InitializeParams::default(), watchers);
+
+    session.open(uri.clone(), "a = b\n".to_string()).unwrap();
+    let (got_uri, diagnostics) = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(got_uri, uri);
+    assert!(!diagnostics.is_empty());
+
+    // A change during the compilation of the opened file is retried by the pending tasks.
+    session.change(uri.clone(), "a = 1\n".to_string()).unwrap();
+    let (got_uri, diagnostics) = (0..50)
+        .find_map(|_| {
+            session.process_pending().unwrap();
+            rx.recv_timeout(Duration::from_millis(100)).ok()
+        })
+        .unwrap();
+    assert_eq!(got_uri, uri);
+    assert!(diagnostics.is_empty());
+}
+
+#[test]
+fn lint_file_diagnostics_test() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("test_data")
+        .join("lint_file")
+        .join("main.k");
+    let uri = Url::from_file_path(&path).unwrap();
+    let watchers = DiagnosticWatchers::default();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let _handle = watchers.watch_diagnostics(vec![uri.clone()], move |uri, diagnostics| {
+        let _ = tx.send((uri, diagnostics));
+    });
+    let mut session = DiagnosticsSession::new(InitializeParams::default(), watchers);
+    session
+        .open(uri, std::fs::read_to_string(&path).unwrap())
+        .unwrap();
+
+    // The unused import is reported as an error by the lint file.
+    let (_, diagnostics) = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    let unused_import = diagnostics
+        .iter()
+        .find(|diag| diag.message == "Module 'math' imported but unused")
+        .unwrap();
+    assert_eq!(unused_import.severity, Some(DiagnosticSeverity::ERROR));
+}
+
+#[test]
+fn warnings_as_errors_diagnostics_test() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("test_data")
+        .join("warnings_as_errors")
+        .join("main.k");
+    let uri = Url::from_file_path(&path).unwrap();
+    for (warnings_as_errors, severity) in [
+        (false, DiagnosticSeverity::WARNING),
+        (true, DiagnosticSeverity::ERROR),
+    ] {
+        let watchers = DiagnosticWatchers::default();
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let _handle = watchers.watch_diagnostics(vec![uri.clone()], move |uri, diagnostics| {
+            let _ = tx.send((uri, diagnostics));
+        });
+        let params = InitializeParams {
+            initialization_options: Some(
+                serde_json::json!({ "warningsAsErrors": warnings_as_errors }),
+            ),
+            ..Default::default()
+        };
+        let mut session = DiagnosticsSession::new(params, watchers);
+        session
+            .open(uri.clone(), std::fs::read_to_string(&path).unwrap())
+            .unwrap();
+
+        let (_, diagnostics) = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        let unused_import = diagnostics
+            .iter()
+            .find(|diag| diag.message == "Module 'math' imported but unused")
+            .unwrap();
+        assert_eq!(unused_import.severity, Some(severity));
+    }
+}
+
+#[test]
+#[bench_test]
+fn deprecated_builtins_diagnostics_test() {
+    let (file, _, diags, _, _) = compile_test_file("src/test_data/deprecated_builtins/main.k");
+    let deprecated: Vec<&KCLDiagnostic> = diags
+        .iter()
+        .filter(|diag| {
+            diag.code == Some(DiagnosticId::Warning(WarningKind::DeprecatedBuiltinWarning))
+        })
+        .collect();
+    // `datetime.now`, `len` and `typeof` are not deprecated, the aliased module is reported.
+    let got: Vec<(u64, &str, Option<&str>)> = deprecated
+        .iter()
+        .map(|diag| {
+            let msg = &diag.messages[0];
+            (msg.range.0.line, msg.message.as_str(), msg.note.as_deref())
+        })
+        .collect();
+    let note = Some(r#"Use 'datetime.now("%Y-%m-%d %H:%M:%S")' instead"#);
+    assert_eq!(
+        got,
+        vec![
+            (4, "'datetime.date' is deprecated since 0.11.2", note),
+            (8, "'datetime.date' is deprecated since 0.11.2", note),
+        ]
+    );
+
+    let uri = Url::from_file_path(&file).unwrap();
+    let lsp_diags = kcl_diag_to_lsp_diags_by_file(deprecated[0], &file, None);
+    assert_eq!(
+        lsp_diags[0].range,
+        Range::new(Position::new(3, 4), Position::new(3, 17))
+    );
+    assert_eq!(
+        lsp_diags[0].tags,
+        Some(vec![lsp_types::DiagnosticTag::DEPRECATED])
+    );
+    // `datetime.now` takes the format, so there is no replacement with the same arguments.
+    assert!(quick_fix(&uri, &lsp_diags).is_empty());
+}
+
+#[test]
+fn explicit_types_diagnostics_test() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("test_data")
+        .join("explicit_types")
+        .join("main.k");
+    let uri = Url::from_file_path(&path).unwrap();
+    for explicit_types in [false, true] {
+        let watchers = DiagnosticWatchers::default();
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let _handle = watchers.watch_diagnostics(vec![uri.clone()], move |uri, diagnostics| {
+            let _ = tx.send((uri, diagnostics));
+        });
+        let params = InitializeParams {
+            initialization_options: Some(serde_json::json!({ "explicitTypes": explicit_types })),
+            ..Default::default()
+        };
+        let mut session = DiagnosticsSession::new(params, watchers);
+        session
+            .open(uri.clone(), std::fs::read_to_string(&path).unwrap())
+            .unwrap();
+
+        let (_, diagnostics) = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        let missing_type = diagnostics
+            .iter()
+            .find(|diag| diag.message == "'port' has no type annotation");
+        if explicit_types {
+            let missing_type = missing_type.unwrap();
+            assert_eq!(missing_type.severity, Some(DiagnosticSeverity::WARNING));
+            // The replacement of the quick fix adding the type.
+            assert_eq!(
+                missing_type.data.as_ref().unwrap()["suggested_replacement"],
+                serde_json::json!(["port: int"])
+            );
+        } else {
+            assert!(missing_type.is_none(), "{:?}", diagnostics);
+        }
+    }
+}
@@ -4,10 +4,14 @@ use kclvm_error::DiagnosticId;
 use kclvm_error::Level;
 use kclvm_error::Message;
 use kclvm_error::Position as KCLPos;
+use kclvm_error::WarningKind;
 use kclvm_utils::path::PathPrefix;
 use lsp_types::*;
 use serde_json::json;
 
+use crate::locale::localize_message;
+use crate::quick_fix::{convert_code_to_kcl_diag_id, fix_safety};
+
 use std::{
     path::{Component, Path, Prefix},
     str::FromStr,
@@ -15,53 +19,86 @@ use std::{
 
 /// Convert pos format to lsp position.
 /// The position in lsp protocol is different with position in ast node whose line number is 1 based.
+/// Lines and columns which do not fit in the lsp position are clamped to `u32::MAX`.
 pub fn lsp_pos(pos: &KCLPos) -> Position {
     Position {
-        line: pos.line.checked_sub(1).unwrap_or(0) as u32,
-        character: pos.column.unwrap_or(0) as u32,
+        line: clamp_to_u32(pos.line.saturating_sub(1)),
+        character: clamp_to_u32(pos.column.unwrap_or(0)),
     }
 }
 
+#[inline]
+fn clamp_to_u32(value: u64) -> u32 {
+    u32::try_from(value).unwrap_or(u32::MAX)
+}
+
+/// Convert the start and end positions to the lsp range. An end position before the start
+/// position is moved to the start position.
+pub fn lsp_range(start: &KCLPos, end: &KCLPos) -> Range {
+    let start = lsp_pos(start);
+    Range::new(start, lsp_pos(end).max(start))
+}
+
 /// Convert start and pos format to lsp location.
 /// The position of the location in lsp protocol is different with position in ast node whose line number is 1 based.
+/// An end position before the start position is moved to the start position.
 pub fn lsp_location(file_path: String, start: &KCLPos, end: &KCLPos) -> Option<Location> {
     let uri = Url::from_file_path(file_path).ok()?;
+    let Range { start, end } = lsp_range(start, end);
     Some(Location {
         uri,
-        range: Range {
-            start: lsp_pos(start),
-            end: lsp_pos(end),
-        },
+        range: Range { start, end },
     })
 }
 
-/// Convert KCL message to the LSP diagnostic.
+/// Convert KCL message to the LSP diagnostic. When the source `src` of the file is provided, the
+/// content-based anchor of the message line is added to the diagnostic data. When the `locale` is
+/// provided, the message is localized by its code with the
+/// message catalog of the `locale` module.
 pub fn kcl_msg_to_lsp_diags(
     msg: &Message,
     severity: DiagnosticSeverity,
     related_msg: Vec<Message>,
     code: Option<NumberOrString>,
+    src: Option<&str>,
+    locale: Option<&str>,
 ) -> Diagnostic {
+    let localize = |message: &str| match (locale, &code) {
+        (Some(locale), Some(NumberOrString::String(code))) => {
+            localize_message(code, message, locale)
+        }
+        _ => message.to_string(),
+    };
     let range = msg.range.clone();
-    let start_position = lsp_pos(&range.0);
-    let end_position = lsp_pos(&range.1);
 
-    let data = msg
-        .suggested_replacement
+    let mut data = serde_json::Map::new();
+    if let Some(s_vec) = &msg.suggested_replacement {
+        let s = s_vec
+            .iter()
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<&String>>();
+        if s.is_empty() {
+            data.insert("suggested_replacement".to_string(), json!(""));
+        } else {
+            data.insert("suggested_replacement".to_string(), json!(s));
+        }
+    }
+    // Tag the diagnostic with the safety level of its fix.
+    if let Some(safety) = code
         .as_ref()
-        .map(|s_vec| {
-            s_vec
-                .iter()
-                .filter(|s| !s.is_empty())
-                .collect::<Vec<&String>>()
-        })
-        .map(|s| {
-            if s.is_empty() {
-                json!({ "suggested_replacement": "" })
-            } else {
-                json!({ "suggested_replacement": s })
-            }
-        });
+        .and_then(convert_code_to_kcl_diag_id)
+        .and_then(|id| fix_safety(&id, msg.suggested_replacement.is_some()))
+    {
+        data.insert("fix_safety".to_string(), json!(safety));
+    }
+    if let Some(anchor) = src.and_then(|src| diagnostic_anchor(src, range.0.line)) {
+        data.insert("anchor".to_string(), anchor);
+    }
+    let data = if data.is_empty() {
+        None
+    } else {
+        Some(serde_json::Value::Object(data))
+    };
 
     let related_information = if related_msg.is_empty() {
         None
@@ -73,12 +110,9 @@ pub fn kcl_msg_to_lsp_diags(
                     Ok(uri) => Some(DiagnosticRelatedInformation {
                         location: Location {
                             uri,
-                            range: Range {
-                                start: lsp_pos(&m.range.0),
-                                end: lsp_pos(&m.range.1),
-                            },
+                            range: lsp_range(&m.range.0, &m.range.1),
                         },
-                        message: m.message.clone(),
+                        message: localize(&m.message),
                     }),
                     Err(_) => None,
                 })
@@ -86,19 +120,38 @@ pub fn kcl_msg_to_lsp_diags(
         )
     };
 
+    let message = localize(&msg.message);
     Diagnostic {
-        range: Range::new(start_position, end_position),
+        range: lsp_range(&range.0, &range.1),
         severity: Some(severity),
         code,
         code_description: None,
         source: None,
-        message: msg.message.clone(),
+        message,
         related_information,
         tags: None,
         data,
     }
 }
 
+/// Returns the content-based anchor of the 1-based `line` in the source, which is the trimmed
+/// text of the line and its hash. Tooling can relocate a diagnostic by the anchor after the lines
+/// are shifted, e.g. by reformatting.
+pub(crate) fn diagnostic_anchor(src: &str, line: u64) -> Option<serde_json::Value> {
+    let text = src.lines().nth(line.checked_sub(1)? as usize)?.trim();
+    Some(json!({
+        "text": text,
+        "hash": format!("{:016x}", fnv1a_hash(text)),
+    }))
+}
+
+/// The 64-bit FNV-1a hash, which is stable across platforms and Rust versions.
+fn fnv1a_hash(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
 /// Convert KCL error level to the LSP diagnostic severity.
 pub fn kcl_err_level_to_severity(level: Level) -> DiagnosticSeverity {
     match level {
@@ -109,8 +162,13 @@ pub fn kcl_err_level_to_severity(level: Level) -> DiagnosticSeverity {
     }
 }
 
-/// Convert KCL Diagnostic to LSP Diagnostics.
-pub fn kcl_diag_to_lsp_diags(diag: &KCLDiagnostic) -> HashMap<String, Vec<Diagnostic>> {
+/// Convert KCL Diagnostic to LSP Diagnostics, `src` returns the source of a file to compute the
+/// diagnostic anchors, or `None` to omit them. The messages are localized to the `locale`.
+pub fn kcl_diag_to_lsp_diags(
+    diag: &KCLDiagnostic,
+    src: &dyn Fn(&str) -> Option<String>,
+    locale: Option<&str>,
+) -> HashMap<String, Vec<Diagnostic>> {
     let mut diags_map: HashMap<String, Vec<Diagnostic>> = HashMap::new();
 
     for (idx, msg) in diag.messages.iter().enumerate() {
@@ -124,12 +182,17 @@ pub fn kcl_diag_to_lsp_diags(diag: &KCLDiagnostic) -> HashMap<String, Vec<Diagno
             None
         };
 
-        let lsp_diag = kcl_msg_to_lsp_diags(
+        let file_src = src(&filename);
+        let mut lsp_diag = kcl_msg_to_lsp_diags(
             msg,
             kcl_err_level_to_severity(diag.level),
             related_msg,
             code,
+            file_src.as_deref(),
+            locale,
         );
+        lsp_diag.tags = kcl_diag_tags(diag, idx);
+        add_diag_category(&mut lsp_diag, diag);
 
         diags_map.entry(filename).or_insert(vec![]).push(lsp_diag);
     }
@@ -141,6 +204,7 @@ pub fn kcl_diag_to_lsp_diags(diag: &KCLDiagnostic) -> HashMap<String, Vec<Diagno
 pub(crate) fn kcl_diag_to_lsp_diags_by_file(
     diag: &KCLDiagnostic,
     file_name: &str,
+    src: Option<&str>,
 ) -> Vec<Diagnostic> {
     let mut diags = vec![];
     for (idx, msg) in diag.messages.iter().enumerate() {
@@ -153,12 +217,16 @@ pub(crate) fn kcl_diag_to_lsp_diags_by_file(
                 None
             };
 
-            let lsp_diag = kcl_msg_to_lsp_diags(
+            let mut lsp_diag = kcl_msg_to_lsp_diags(
                 msg,
                 kcl_err_level_to_severity(diag.level),
                 related_msg,
                 code,
+                src,
+                None,
             );
+            lsp_diag.tags = kcl_diag_tags(diag, idx);
+            add_diag_category(&mut lsp_diag, diag);
 
             diags.push(lsp_diag);
         }
@@ -166,6 +234,43 @@ pub(crate) fn kcl_diag_to_lsp_diags_by_file(
     diags
 }
 
+/// Returns the tags of the LSP diagnostic converted from the `msg_idx`-th message of the KCL
+/// diagnostic, e.g. the earlier assignment overridden by a duplicate assignment is unnecessary.
+fn kcl_diag_tags(diag: &KCLDiagnostic, msg_idx: usize) -> Option<Vec<DiagnosticTag>> {
+    match &diag.code {
+        Some(DiagnosticId::Warning(WarningKind::DuplicateAssignmentWarning)) if msg_idx > 0 => {
+            Some(vec![DiagnosticTag::UNNECESSARY])
+        }
+        Some(DiagnosticId::Warning(WarningKind::UnusedSchemaWarning))
+        | Some(DiagnosticId::Warning(WarningKind::UnusedPrivateSymbolWarning)) => {
+            Some(vec![DiagnosticTag::UNNECESSARY])
+        }
+        // The always true assertions are reported as notes and the always false ones as warnings.
+        Some(DiagnosticId::Warning(WarningKind::ConstantAssertWarning))
+            if diag.level == Level::Note =>
+        {
+            Some(vec![DiagnosticTag::UNNECESSARY])
+        }
+        Some(DiagnosticId::Warning(WarningKind::DeprecatedBuiltinWarning)) => {
+            Some(vec![DiagnosticTag::DEPRECATED])
+        }
+        _ => None,
+    }
+}
+
+/// Adds the category of the KCL diagnostic, e.g. `style` for the unused imports, to the data of
+/// the LSP diagnostic, so that the clients can group the diagnostics.
+fn add_diag_category(lsp_diag: &mut Diagnostic, diag: &KCLDiagnostic) {
+    if let Some(id) = &diag.code {
+        let mut data = match lsp_diag.data.take() {
+            Some(serde_json::Value::Object(data)) => data,
+            _ => serde_json::Map::new(),
+        };
+        data.insert("category".to_string(), json!(id.category().name()));
+        lsp_diag.data = Some(serde_json::Value::Object(data));
+    }
+}
+
 /// Convert KCL Diagnostic ID to LSP Diagnostics code.
 /// Todo: use unique id/code instead of name()
 pub(crate) fn kcl_diag_id_to_lsp_diag_code(id: DiagnosticId) -> NumberOrString {
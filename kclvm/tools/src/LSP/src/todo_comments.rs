@@ -0,0 +1,129 @@
+//! The opt-in lint surfacing the task markers of the comments, e.g. `# TODO: fix`, as the low
+//! severity diagnostics. Only the comments of the parsed modules are scanned, so the markers in
+//! the string literals never match.
+
+use indexmap::IndexSet;
+use kclvm_ast::ast::Program;
+use kclvm_ast::pos::GetPos;
+use kclvm_error::{Diagnostic, DiagnosticId, Level, WarningKind};
+use serde::Deserialize;
+
+/// The marker keywords scanned by default.
+pub(crate) const DEFAULT_TODO_KEYWORDS: &[&str] = &["TODO", "FIXME", "XXX"];
+
+/// The severity of the task marker diagnostics.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum TodoSeverity {
+    #[default]
+    Hint,
+    Warning,
+    Error,
+}
+
+impl TodoSeverity {
+    /// Returns the KCL level converted to the LSP severity, the hints are the notes of KCL.
+    pub(crate) fn level(self) -> Level {
+        match self {
+            TodoSeverity::Hint => Level::Note,
+            TodoSeverity::Warning => Level::Warning,
+            TodoSeverity::Error => Level::Error,
+        }
+    }
+}
+
+/// Returns a diagnostic over each comment containing one of the `keywords` as a whole word. The
+/// message is the comment text from the first marker, e.g. `TODO: fix` of `# TODO: fix`.
+pub(crate) fn todo_comment_diags(
+    program: &Program,
+    keywords: &[String],
+    level: Level,
+) -> IndexSet<Diagnostic> {
+    let mut diags = IndexSet::new();
+    for module in program.modules.values() {
+        let module = match module.read() {
+            Ok(module) => module,
+            Err(_) => continue,
+        };
+        for comment in &module.comments {
+            let text = &comment.node.text;
+            let marker = keywords
+                .iter()
+                .filter_map(|keyword| find_word(text, keyword))
+                .min();
+            if let Some(idx) = marker {
+                diags.insert(Diagnostic::new_with_code(
+                    level,
+                    text[idx..].trim_end(),
+                    None,
+                    (comment.get_pos(), comment.get_end_pos()),
+                    Some(DiagnosticId::Warning(WarningKind::TodoCommentWarning)),
+                    None,
+                ));
+            }
+        }
+    }
+    diags
+}
+
+/// Returns the byte index of the first occurrence of `word` which is not a part of a longer
+/// word, e.g. `TODO` is not found in `TODOS`.
+fn find_word(text: &str, word: &str) -> Option<usize> {
+    if word.is_empty() {
+        return None;
+    }
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    text.match_indices(word).map(|(idx, _)| idx).find(|&idx| {
+        !text[..idx].chars().next_back().map_or(false, is_word_char)
+            && !text[idx + word.len()..]
+                .chars()
+                .next()
+                .map_or(false, is_word_char)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use kclvm_error::Level;
+    use lsp_types::DiagnosticSeverity;
+    use proc_macro_crate::bench_test;
+
+    use super::{todo_comment_diags, DEFAULT_TODO_KEYWORDS};
+    use crate::tests::compile_test_file;
+    use crate::to_lsp::kcl_diag_to_lsp_diags_by_file;
+
+    #[test]
+    #[bench_test]
+    fn todo_comment_diags_test() {
+        let (file, program, _, _, _) = compile_test_file("src/test_data/todo_comments/main.k");
+        let keywords: Vec<String> = DEFAULT_TODO_KEYWORDS
+            .iter()
+            .map(|keyword| keyword.to_string())
+            .collect();
+
+        // The `TODO` in the string literal and the `TODOS` in the comment are not reported.
+        let diags = todo_comment_diags(&program, &keywords, Level::Note);
+        let got: Vec<(&str, u64)> = diags
+            .iter()
+            .map(|diag| {
+                (
+                    diag.messages[0].message.as_str(),
+                    diag.messages[0].range.0.line,
+                )
+            })
+            .collect();
+        assert_eq!(
+            got,
+            vec![
+                ("TODO: fix the default port", 1),
+                ("FIXME handle the empty name", 4),
+            ]
+        );
+        let lsp_diags = kcl_diag_to_lsp_diags_by_file(&diags[0], &file, None);
+        assert_eq!(lsp_diags[0].severity, Some(DiagnosticSeverity::HINT));
+
+        let diags = todo_comment_diags(&program, &["FIXME".to_string()], Level::Warning);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].level, Level::Warning);
+    }
+}
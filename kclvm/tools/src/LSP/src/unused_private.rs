@@ -0,0 +1,108 @@
+//! The opt-in lint of the private symbols which are never used in the files declaring them, e.g.
+//!
+//! ```kcl
+//! _double = lambda x { x * 2 }
+//! _unused = lambda x { x + 1 }
+//!
+//! replicas = _double(2)
+//! ```
+//!
+//! `_unused` is reported. Only the references in the same file are looked up, so the lint is
+//! cheap to run on every change.
+
+use indexmap::IndexSet;
+use kclvm_ast::ast::{self, Program};
+use kclvm_ast::pos::{ContainsPos, GetPos};
+use kclvm_error::{Diagnostic, DiagnosticId, Level, WarningKind};
+use kclvm_sema::core::global_state::GlobalState;
+
+use crate::goto_def::find_def;
+
+/// Returns the warnings of the top level private variables and schemas which are not referenced
+/// in their files out of their own declarations. The range of a warning is the declaration
+/// statement, which is removed by the quick fix.
+pub(crate) fn unused_private_symbol_diags(
+    program: &Program,
+    gs: &GlobalState,
+) -> IndexSet<Diagnostic> {
+    let mut diags = IndexSet::new();
+    for module in program.modules.values() {
+        let module = match module.read() {
+            Ok(module) => module,
+            Err(_) => continue,
+        };
+        for stmt in &module.body {
+            let names: Vec<&ast::Node<String>> = match &stmt.node {
+                ast::Stmt::Assign(assign_stmt) => assign_stmt
+                    .targets
+                    .iter()
+                    .filter(|target| target.node.paths.is_empty())
+                    .map(|target| &target.node.name)
+                    .collect(),
+                ast::Stmt::Schema(schema_stmt) => vec![&schema_stmt.name],
+                _ => continue,
+            };
+            for name in names {
+                if !name.node.starts_with('_') {
+                    continue;
+                }
+                let symbol = match find_def(&name.get_pos(), gs, true)
+                    .and_then(|def| gs.get_symbols().get_symbol(def))
+                {
+                    Some(symbol) => symbol,
+                    None => continue,
+                };
+                let used = symbol.get_references().iter().any(|reference| {
+                    match gs.get_symbols().get_symbol(*reference) {
+                        Some(reference) => {
+                            let (start, _) = reference.get_range();
+                            start.filename == module.filename && !stmt.contains_pos(&start)
+                        }
+                        None => false,
+                    }
+                });
+                if !used {
+                    diags.insert(Diagnostic::new_with_code(
+                        Level::Warning,
+                        &format!("Private symbol '{}' is never used in the file", name.node),
+                        None,
+                        (stmt.get_pos(), stmt.get_end_pos()),
+                        Some(DiagnosticId::Warning(
+                            WarningKind::UnusedPrivateSymbolWarning,
+                        )),
+                        None,
+                    ));
+                }
+            }
+        }
+    }
+    diags
+}
+
+#[cfg(test)]
+mod tests {
+    use proc_macro_crate::bench_test;
+
+    use super::unused_private_symbol_diags;
+    use crate::tests::compile_test_file;
+
+    #[test]
+    #[bench_test]
+    fn unused_private_symbol_diags_test() {
+        let (_, program, _, gs, _) = compile_test_file("src/test_data/unused_private/main.k");
+
+        // `_double` is used by `replicas` and `_Base` by `Server`.
+        let diags = unused_private_symbol_diags(&program, &gs);
+        let got: Vec<(&str, u64, u64)> = diags
+            .iter()
+            .map(|diag| {
+                let (start, end) = &diag.messages[0].range;
+                (diag.messages[0].message.as_str(), start.line, end.line)
+            })
+            .collect();
+        assert_eq!(
+            got,
+            vec![("Private symbol '_unused' is never used in the file", 4, 6)]
+        );
+    }
+}
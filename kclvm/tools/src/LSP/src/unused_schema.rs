@@ -0,0 +1,118 @@
+//! The opt-in lint of the top level schemas which are never referenced in the workspace, e.g.
+//!
+//! ```kcl
+//! schema Server:
+//!     name: str
+//!
+//! schema Orphan:
+//!     name: str
+//!
+//! server = Server {name = "nginx"}
+//! ```
+//!
+//! `Orphan` is reported. The lint is off by default, because the schemas of a library may be used
+//! by the programs outside the workspace.
+
+use indexmap::IndexSet;
+use kclvm_ast::ast::{self, Program};
+use kclvm_ast::pos::{ContainsPos, GetPos};
+use kclvm_error::{Diagnostic, DiagnosticId, Level, WarningKind};
+use kclvm_sema::core::global_state::GlobalState;
+
+use crate::goto_def::find_def;
+
+/// Returns the warnings of the unused schemas. The schemas in the entry files are exempt, which
+/// are the files ending with one of `entry_files`, or the main package files by default.
+pub(crate) fn unused_schema_diags(
+    program: &Program,
+    gs: &GlobalState,
+    entry_files: Option<&[String]>,
+) -> IndexSet<Diagnostic> {
+    let main_files = program.get_main_files();
+    let is_entry = |filename: &str| match entry_files {
+        Some(entry_files) => entry_files.iter().any(|file| filename.ends_with(file)),
+        None => main_files.iter().any(|file| file == filename),
+    };
+    let mut diags = IndexSet::new();
+    for module in program.modules.values() {
+        let module = match module.read() {
+            Ok(module) => module,
+            Err(_) => continue,
+        };
+        if is_entry(&module.filename) {
+            continue;
+        }
+        for stmt in &module.body {
+            if let ast::Stmt::Schema(schema_stmt) = &stmt.node {
+                let symbol = match find_def(&schema_stmt.name.get_pos(), gs, true)
+                    .and_then(|def| gs.get_symbols().get_symbol(def))
+                {
+                    Some(symbol) => symbol,
+                    None => continue,
+                };
+                // The references in the schema itself, e.g. a recursive attribute type, do not
+                // make the schema used.
+                let used = symbol.get_references().iter().any(|reference| {
+                    match gs.get_symbols().get_symbol(*reference) {
+                        Some(reference) => !stmt.contains_pos(&reference.get_range().0),
+                        None => false,
+                    }
+                });
+                if !used {
+                    diags.insert(Diagnostic::new_with_code(
+                        Level::Warning,
+                        &format!("Schema '{}' is never used", schema_stmt.name.node),
+                        None,
+                        (schema_stmt.name.get_pos(), schema_stmt.name.get_end_pos()),
+                        Some(DiagnosticId::Warning(WarningKind::UnusedSchemaWarning)),
+                        None,
+                    ));
+                }
+            }
+        }
+    }
+    diags
+}
+
+#[cfg(test)]
+mod tests {
+    use proc_macro_crate::bench_test;
+
+    use super::unused_schema_diags;
+    use crate::tests::compile_test_file;
+
+    #[test]
+    #[bench_test]
+    fn unused_schema_diags_test() {
+        let (_, program, _, gs, _) = compile_test_file("src/test_data/unused_schema/main.k");
+
+        // `Server` is used in main.k and the unused `Entry` is exempt in the entry file.
+        let diags = unused_schema_diags(&program, &gs, None);
+        let messages: Vec<&str> = diags
+            .iter()
+            .map(|diag| diag.messages[0].message.as_str())
+            .collect();
+        assert_eq!(messages, vec!["Schema 'Orphan' is never used"]);
+        let (start, end) = &diags[0].messages[0].range;
+        assert!(start.filename.ends_with("lib.k"));
+        assert_eq!(
+            (start.line, start.column, end.column),
+            (4, Some(7), Some(13))
+        );
+
+        // No file is exempt.
+        let diags = unused_schema_diags(&program, &gs, Some(&[]));
+        let mut messages: Vec<&str> = diags
+            .iter()
+            .map(|diag| diag.messages[0].message.as_str())
+            .collect();
+        messages.sort();
+        assert_eq!(
+            messages,
+            vec![
+                "Schema 'Entry' is never used",
+                "Schema 'Orphan' is never used"
+            ]
+        );
+    }
+}
@@ -0,0 +1,413 @@
+//! Validates the `kcl.mod` file of a project, e.g.
+//!
+//! ```toml
+//! [package]
+//! name = "app"
+//! version = "0.0.1"
+//!
+//! [dependencies]
+//! k8s = "1.28"
+//! helper = { path = "../helper" }
+//! ```
+//!
+//! The versions must be in the `major[.minor[.patch]]` form with the optional pre-release and
+//! build metadata, the local paths must exist relative to the file, and the Git and OCI sources
+//! must be valid urls. The errors are reported at the declarations of the dependencies. The
+//! normalized content puts the sections and their fields in the canonical order and sorts the
+//! dependencies by the names. It is only offered when nothing would be lost, so the files with
+//! the comments or the keys unknown to `kcl.mod` are not normalized.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use kclvm_config::modfile::{has_only_mod_file_fields, parse_mod_file, Dependency, ModFile};
+use lsp_types::{Diagnostic, DiagnosticSeverity, Position, Range, TextEdit, Url};
+
+use crate::lsp_ext::ValidateModResult;
+
+/// Returns the diagnostics of the dependency declarations of the `kcl.mod` file and the edit
+/// normalizing it. `mod_file` is the path of the file and `content` is its text.
+pub(crate) fn validate_mod(uri: Url, mod_file: &Path, content: &str) -> ValidateModResult {
+    let modfile = match parse_mod_file(content) {
+        Ok(modfile) => modfile,
+        Err(err) => {
+            let pos = err
+                .line_col()
+                .map(|(line, col)| Position::new(line as u32, utf16_column(content, line, col)))
+                .unwrap_or_default();
+            return ValidateModResult {
+                uri,
+                diagnostics: vec![error(Range::new(pos, pos), err.to_string())],
+                normalized: None,
+            };
+        }
+    };
+    let root = mod_file.parent().unwrap_or_else(|| Path::new(""));
+    let ranges = dependency_ranges(content);
+    let mut diagnostics = vec![];
+    if let Some(dependencies) = &modfile.dependencies {
+        let mut names: Vec<&String> = dependencies.keys().collect();
+        names.sort();
+        for name in names {
+            if let Some(message) = check_dependency(name, &dependencies[name], root) {
+                let range = ranges.get(name).cloned().unwrap_or_default();
+                diagnostics.push(error(range, message));
+            }
+        }
+    }
+    let new_text = normalize(&modfile);
+    let normalized =
+        if new_text == content || has_comments(content) || !has_only_mod_file_fields(content) {
+            None
+        } else {
+            Some(TextEdit {
+                range: Range::new(Position::new(0, 0), end_position(content)),
+                new_text,
+            })
+        };
+    ValidateModResult {
+        uri,
+        diagnostics,
+        normalized,
+    }
+}
+
+fn error(range: Range, message: String) -> Diagnostic {
+    Diagnostic {
+        range,
+        severity: Some(DiagnosticSeverity::ERROR),
+        message,
+        ..Default::default()
+    }
+}
+
+/// Returns the error message of the dependency, or `None` if the dependency is valid.
+fn check_dependency(name: &str, dependency: &Dependency, root: &Path) -> Option<String> {
+    let invalid_version = |version: &str| {
+        format!(
+            "invalid version '{}' of the dependency '{}', expected a version like '1.0.0'",
+            version, name
+        )
+    };
+    match dependency {
+        Dependency::Version(version) => {
+            (!is_valid_version(version)).then(|| invalid_version(version))
+        }
+        Dependency::Git(source) => {
+            if !is_valid_git_url(&source.git) {
+                return Some(format!(
+                    "invalid Git url '{}' of the dependency '{}'",
+                    source.git, name
+                ));
+            }
+            let refs = [&source.branch, &source.commit, &source.tag];
+            if refs.iter().filter(|r| r.is_some()).count() > 1 {
+                return Some(format!(
+                    "the dependency '{}' can only specify one of the branch, the commit and the tag",
+                    name
+                ));
+            }
+            match &source.version {
+                Some(version) if !is_valid_version(version) => Some(invalid_version(version)),
+                _ => None,
+            }
+        }
+        Dependency::Oci(source) => match Url::parse(&source.oci) {
+            Ok(url) if url.scheme() == "oci" && url.host_str().is_some() => None,
+            _ => Some(format!(
+                "invalid OCI url '{}' of the dependency '{}', expected 'oci://<registry>/<repository>'",
+                source.oci, name
+            )),
+        },
+        Dependency::Local(source) => (!root.join(&source.path).exists()).then(|| {
+            format!(
+                "the path '{}' of the dependency '{}' does not exist",
+                source.path, name
+            )
+        }),
+    }
+}
+
+/// Returns whether the version is `major[.minor[.patch]]` with the optional `v` prefix, the
+/// pre-release, e.g. `-alpha.1`, and the build metadata, e.g. `+build.5`.
+fn is_valid_version(version: &str) -> bool {
+    let version = version.strip_prefix('v').unwrap_or(version);
+    let (version, build) = match version.split_once('+') {
+        Some((version, build)) => (version, Some(build)),
+        None => (version, None),
+    };
+    let (core, pre) = match version.split_once('-') {
+        Some((core, pre)) => (core, Some(pre)),
+        None => (version, None),
+    };
+    let is_identifiers = |s: &str| {
+        s.split('.')
+            .all(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'))
+    };
+    let parts: Vec<&str> = core.split('.').collect();
+    parts.len() <= 3
+        && parts
+            .iter()
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+        && pre.map_or(true, is_identifiers)
+        && build.map_or(true, is_identifiers)
+}
+
+/// Returns whether the url is a valid url or the scp-like form `git@github.com:kcl-lang/kcl`.
+fn is_valid_git_url(url: &str) -> bool {
+    if let Ok(url) = Url::parse(url) {
+        return url.scheme() == "file" || url.host_str().is_some();
+    }
+    match url.split_once(':') {
+        Some((host, path)) => {
+            !host.is_empty() && !host.contains('/') && !path.is_empty() && !path.contains(' ')
+        }
+        None => false,
+    }
+}
+
+/// Returns the ranges of the dependency declarations keyed by the dependency names, which are
+/// the `name = ...` lines of the `[dependencies]` section and the `[dependencies.name]` headers.
+fn dependency_ranges(content: &str) -> HashMap<String, Range> {
+    let mut ranges = HashMap::new();
+    let mut in_dependencies = false;
+    for (line, text) in content.lines().enumerate() {
+        let trimmed = text.trim();
+        let start = (text.len() - text.trim_start().len()) as u32;
+        let range = Range::new(
+            Position::new(line as u32, start),
+            Position::new(line as u32, start + trimmed.encode_utf16().count() as u32),
+        );
+        if trimmed.starts_with('[') {
+            let header = trimmed.trim_start_matches('[');
+            let header = header.split(']').next().unwrap_or_default().trim();
+            in_dependencies = header == "dependencies";
+            if let Some(name) = header.strip_prefix("dependencies.") {
+                ranges.insert(unquote(name), range);
+            }
+        } else if in_dependencies && !trimmed.starts_with('#') {
+            if let Some((name, _)) = trimmed.split_once('=') {
+                ranges.insert(unquote(name), range);
+            }
+        }
+    }
+    ranges
+}
+
+fn unquote(key: &str) -> String {
+    key.trim().trim_matches('"').trim_matches('\'').to_string()
+}
+
+/// Returns the UTF-16 column of the byte column `col` of the line, which the toml errors count in.
+fn utf16_column(content: &str, line: usize, col: usize) -> u32 {
+    let text = content.lines().nth(line).unwrap_or_default();
+    let mut end = col.min(text.len());
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text[..end].encode_utf16().count() as u32
+}
+
+/// Returns whether the content has a comment, which is a `#` out of the strings.
+fn has_comments(content: &str) -> bool {
+    content.lines().any(|line| {
+        let mut quote = None;
+        let mut escaped = false;
+        for c in line.chars() {
+            match quote {
+                // The escapes are only in the basic strings.
+                Some('"') if escaped => escaped = false,
+                Some('"') if c == '\\' => escaped = true,
+                Some(q) if c == q => quote = None,
+                Some(_) => {}
+                None if c == '"' || c == '\'' => quote = Some(c),
+                None if c == '#' => return true,
+                None => {}
+            }
+        }
+        false
+    })
+}
+
+/// Returns the position after the last character of the content.
+fn end_position(content: &str) -> Position {
+    let line = content.matches('\n').count();
+    let last = content.rsplit('\n').next().unwrap_or_default();
+    Position::new(line as u32, last.encode_utf16().count() as u32)
+}
+
+/// Returns the canonical content of the mod file.
+fn normalize(modfile: &ModFile) -> String {
+    let mut sections = vec![];
+    if let Some(package) = &modfile.package {
+        let mut fields = vec![];
+        push_str(&mut fields, "name", &package.name);
+        push_str(&mut fields, "edition", &package.edition);
+        push_str(&mut fields, "version", &package.version);
+        push_str(&mut fields, "description", &package.description);
+        push_list(&mut fields, "include", &package.include);
+        push_list(&mut fields, "exclude", &package.exclude);
+        sections.push(section("package", fields));
+    }
+    if let Some(profile) = &modfile.profile {
+        let mut fields = vec![];
+        push_list(&mut fields, "entries", &profile.entries);
+        push_bool(&mut fields, "disable_none", &profile.disable_none);
+        push_bool(&mut fields, "sort_keys", &profile.sort_keys);
+        push_list(&mut fields, "selectors", &profile.selectors);
+        push_list(&mut fields, "overrides", &profile.overrides);
+        push_list(&mut fields, "options", &profile.options);
+        sections.push(section("profile", fields));
+    }
+    if let Some(dependencies) = &modfile.dependencies {
+        let mut names: Vec<&String> = dependencies.keys().collect();
+        names.sort();
+        let fields = names
+            .into_iter()
+            .map(|name| format!("{} = {}", key(name), dependency_value(&dependencies[name])))
+            .collect();
+        sections.push(section("dependencies", fields));
+    }
+    sections.join("\n")
+}
+
+fn section(name: &str, fields: Vec<String>) -> String {
+    let mut text = format!("[{}]\n", name);
+    for field in fields {
+        text.push_str(&field);
+        text.push('\n');
+    }
+    text
+}
+
+fn dependency_value(dependency: &Dependency) -> String {
+    let mut fields = vec![];
+    match dependency {
+        Dependency::Version(version) => return quote(version),
+        Dependency::Git(source) => {
+            push_str(&mut fields, "git", &Some(source.git.clone()));
+            push_str(&mut fields, "branch", &source.branch);
+            push_str(&mut fields, "commit", &source.commit);
+            push_str(&mut fields, "tag", &source.tag);
+            push_str(&mut fields, "version", &source.version);
+        }
+        Dependency::Oci(source) => {
+            push_str(&mut fields, "oci", &Some(source.oci.clone()));
+            push_str(&mut fields, "tag", &source.tag);
+        }
+        Dependency::Local(source) => {
+            push_str(&mut fields, "path", &Some(source.path.clone()));
+        }
+    }
+    format!("{{ {} }}", fields.join(", "))
+}
+
+fn push_str(fields: &mut Vec<String>, key: &str, value: &Option<String>) {
+    if let Some(value) = value {
+        fields.push(format!("{} = {}", key, quote(value)));
+    }
+}
+
+fn push_bool(fields: &mut Vec<String>, key: &str, value: &Option<bool>) {
+    if let Some(value) = value {
+        fields.push(format!("{} = {}", key, value));
+    }
+}
+
+fn push_list(fields: &mut Vec<String>, key: &str, value: &Option<Vec<String>>) {
+    if let Some(value) = value {
+        let items: Vec<String> = value.iter().map(|item| quote(item)).collect();
+        fields.push(format!("{} = [{}]", key, items.join(", ")));
+    }
+}
+
+/// Returns the TOML basic string, whose escapes are the JSON ones.
+fn quote(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| format!("\"{}\"", value))
+}
+
+/// Returns the bare key, or the quoted key if the name has the characters out of the bare keys.
+fn key(name: &str) -> String {
+    if !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        name.to_string()
+    } else {
+        quote(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use lsp_types::{DiagnosticSeverity, Position, Range};
+    use proc_macro_crate::bench_test;
+
+    use super::{utf16_column, validate_mod};
+    use crate::to_lsp::url_from_path;
+
+    #[test]
+    #[bench_test]
+    fn validate_mod_test() {
+        let mod_file = Path::new("src/test_data/validate_mod/kcl.mod")
+            .canonicalize()
+            .unwrap();
+        let content = std::fs::read_to_string(&mod_file).unwrap();
+        let uri = url_from_path(&mod_file).unwrap();
+
+        let result = validate_mod(uri.clone(), &mod_file, &content);
+        assert_eq!(result.uri, uri);
+        // The local dependency `helper` is valid.
+        assert_eq!(result.diagnostics.len(), 1);
+        let diag = &result.diagnostics[0];
+        assert_eq!(
+            diag.range,
+            Range::new(Position::new(6, 0), Position::new(6, 14))
+        );
+        assert_eq!(diag.severity, Some(DiagnosticSeverity::ERROR));
+        assert_eq!(
+            diag.message,
+            "invalid version '1.28.x' of the dependency 'k8s', expected a version like '1.0.0'"
+        );
+
+        let normalized = result.normalized.unwrap();
+        assert_eq!(
+            normalized.range,
+            Range::new(Position::new(0, 0), Position::new(8, 0))
+        );
+        assert_eq!(
+            normalized.new_text,
+            "[package]\nname = \"validate_mod\"\nedition = \"v0.9.0\"\nversion = \"0.0.1\"\n\n[dependencies]\nhelper = { path = \"./helper\" }\nk8s = \"1.28.x\"\n"
+        );
+
+        // The normalized file is not changed.
+        let result = validate_mod(uri.clone(), &mod_file, &normalized.new_text);
+        assert!(result.normalized.is_none());
+
+        // The comments and the unknown keys would be lost.
+        let commented = format!("# The app.\n{}", content);
+        assert!(validate_mod(uri.clone(), &mod_file, &commented)
+            .normalized
+            .is_none());
+        let unknown = content.replace("[dependencies]", "authors = [\"kcl\"]\n\n[dependencies]");
+        assert!(validate_mod(uri.clone(), &mod_file, &unknown)
+            .normalized
+            .is_none());
+        // The `#` in the strings is not a comment.
+        let hash = content.replace("0.0.1", "0.0.1#1");
+        assert!(validate_mod(uri, &mod_file, &hash).normalized.is_some());
+    }
+
+    #[test]
+    #[bench_test]
+    fn utf16_column_test() {
+        let content = "[package]\nname = \"中文\" x\n";
+        // The byte column 16 is the `x` after the two 3-byte characters.
+        assert_eq!(utf16_column(content, 1, 16), 12);
+        assert_eq!(utf16_column(content, 1, 9), 8);
+        assert_eq!(utf16_column(content, 5, 3), 0);
+    }
+}
@@ -0,0 +1,254 @@
+//! The subscriptions of the diagnostics for the embedders which render the diagnostics in their
+//! own UI instead of the LSP client, e.g.
+//!
+//! ```ignore
+//! let watchers = DiagnosticWatchers::default();
+//! let handle = watchers.watch_diagnostics(vec![uri.clone()], |uri, diagnostics| {
+//!     println!("{}: {:?}", uri, diagnostics);
+//! });
+//! // The watchers are notified by the compilations of the session, no LSP client is needed.
+//! let mut session = DiagnosticsSession::new(initialize_params, watchers);
+//! session.open(uri.clone(), "a = b\n".to_string())?;
+//! session.change(uri, "a = 1\n".to_string())?;
+//! ```
+//!
+//! The language server can also notify the watchers next to its LSP client, see
+//! [main_loop_with_watchers]. The callbacks are stopped when the handles are dropped or unwatched.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Instant;
+
+use crossbeam_channel::Receiver;
+use indexmap::IndexMap;
+use lsp_server::Connection;
+use lsp_types::{
+    Diagnostic, DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    InitializeParams, TextDocumentContentChangeEvent, TextDocumentIdentifier, TextDocumentItem,
+    Url, VersionedTextDocumentIdentifier,
+};
+use parking_lot::RwLock;
+
+use crate::state::LanguageServerState;
+use crate::to_lsp::url_from_path;
+
+type DiagnosticsCallback = Arc<dyn Fn(Url, Vec<Diagnostic>) + Send + Sync>;
+
+struct Watcher {
+    files: HashSet<Url>,
+    callback: DiagnosticsCallback,
+}
+
+#[derive(Default)]
+struct Watchers {
+    next_id: usize,
+    watchers: IndexMap<usize, Watcher>,
+}
+
+/// The registry of the diagnostic subscriptions, which is shared by the main loop and the
+/// embedder.
+#[derive(Clone, Default)]
+pub struct DiagnosticWatchers {
+    inner: Arc<RwLock<Watchers>>,
+}
+
+/// The subscription returned by [DiagnosticWatchers::watch_diagnostics], the callback is not
+/// invoked any more once the handle is dropped. A callback which is already started on another
+/// thread when the handle is dropped still runs to the end, and one starting at the same moment
+/// may still be invoked once, so the callbacks should tolerate a call racing with the drop.
+pub struct WatchHandle {
+    id: usize,
+    watchers: DiagnosticWatchers,
+}
+
+impl WatchHandle {
+    /// Stops the callback of the subscription, which is the same as dropping the handle.
+    pub fn unwatch(self) {
+        drop(self)
+    }
+}
+
+impl Drop for WatchHandle {
+    fn drop(&mut self) {
+        self.watchers.inner.write().watchers.shift_remove(&self.id);
+    }
+}
+
+impl DiagnosticWatchers {
+    /// Subscribes to the diagnostics of the files. The callback is invoked with all the
+    /// diagnostics of a file whenever they change after a compilation, an empty list means the
+    /// diagnostics of the file are cleared. The callback is called on the compilation threads.
+    pub fn watch_diagnostics(
+        &self,
+        files: Vec<Url>,
+        callback: impl Fn(Url, Vec<Diagnostic>) + Send + Sync + 'static,
+    ) -> WatchHandle {
+        let mut inner = self.inner.write();
+        let id = inner.next_id;
+        inner.next_id += 1;
+        inner.watchers.insert(
+            id,
+            Watcher {
+                files: files.into_iter().collect(),
+                callback: Arc::new(callback),
+            },
+        );
+        WatchHandle {
+            id,
+            watchers: self.clone(),
+        }
+    }
+
+    /// Invokes the callbacks of the watched files whose diagnostics are changed from `old` to
+    /// `new`, both keyed by the file names. The diagnostic data, e.g. the anchors, is not compared.
+    pub(crate) fn notify(
+        &self,
+        old: &HashMap<String, Vec<Diagnostic>>,
+        new: &HashMap<String, Vec<Diagnostic>>,
+    ) {
+        let without_data = |diags: Option<&Vec<Diagnostic>>| -> Vec<Diagnostic> {
+            diags
+                .into_iter()
+                .flatten()
+                .map(|diag| Diagnostic {
+                    data: None,
+                    ..diag.clone()
+                })
+                .collect()
+        };
+        let mut calls: Vec<(usize, DiagnosticsCallback, Url, Vec<Diagnostic>)> = vec![];
+        {
+            let inner = self.inner.read();
+            if inner.watchers.is_empty() {
+                return;
+            }
+            let files: HashSet<&String> = old.keys().chain(new.keys()).collect();
+            for file in files {
+                if without_data(old.get(file)) == without_data(new.get(file)) {
+                    continue;
+                }
+                let uri = match url_from_path(file) {
+                    Ok(uri) => uri,
+                    Err(_) => continue,
+                };
+                for (id, watcher) in inner.watchers.iter() {
+                    if watcher.files.contains(&uri) {
+                        calls.push((
+                            *id,
+                            watcher.callback.clone(),
+                            uri.clone(),
+                            new.get(file).cloned().unwrap_or_default(),
+                        ));
+                    }
+                }
+            }
+        }
+        // The callbacks are invoked without the lock, so that they can unwatch, and the
+        // subscriptions unwatched by the earlier callbacks or the other threads are skipped.
+        for (id, callback, uri, diagnostics) in calls {
+            if self.inner.read().watchers.contains_key(&id) {
+                callback(uri, diagnostics);
+            }
+        }
+    }
+}
+
+/// Runs the main loop of the language server like [crate::app::main_loop], the diagnostics of
+/// every compilation are also sent to the subscriptions of `watchers`.
+pub fn main_loop_with_watchers(
+    connection: Connection,
+    initialize_params: InitializeParams,
+    watchers: DiagnosticWatchers,
+) -> anyhow::Result<()> {
+    let mut state = LanguageServerState::new(connection.sender, initialize_params);
+    state.diagnostic_watchers = watchers;
+    state.run(connection.receiver)
+}
+
+/// The incremental compilations of the language server driven without the LSP protocol. The
+/// documents are opened, changed and closed by the embedder and the diagnostics of the
+/// compilations are only sent to the subscriptions of the watchers.
+pub struct DiagnosticsSession {
+    state: LanguageServerState,
+    /// The messages the language server would send to the LSP client, which are discarded.
+    messages: Receiver<lsp_server::Message>,
+    version: i32,
+}
+
+impl DiagnosticsSession {
+    /// Creates the session of the workspaces in `initialize_params`, the diagnostics of its
+    /// compilations are sent to the subscriptions of `watchers`.
+    pub fn new(initialize_params: InitializeParams, watchers: DiagnosticWatchers) -> Self {
+        let (sender, messages) = crossbeam_channel::unbounded();
+        let mut state = LanguageServerState::new(sender, initialize_params);
+        state.diagnostic_watchers = watchers;
+        Self {
+            state,
+            messages,
+            version: 0,
+        }
+    }
+
+    /// Opens the document with its content, which compiles the workspace of the document.
+    pub fn open(&mut self, uri: Url, text: String) -> anyhow::Result<()> {
+        let version = self.next_version();
+        self.state
+            .on_did_open_text_document(DidOpenTextDocumentParams {
+                text_document: TextDocumentItem {
+                    uri,
+                    language_id: "KCL".to_string(),
+                    version,
+                    text,
+                },
+            })?;
+        self.process_changes()
+    }
+
+    /// Replaces the content of the opened document, which compiles its workspace again.
+    pub fn change(&mut self, uri: Url, text: String) -> anyhow::Result<()> {
+        let version = self.next_version();
+        self.state
+            .on_did_change_text_document(DidChangeTextDocumentParams {
+                text_document: VersionedTextDocumentIdentifier { uri, version },
+                content_changes: vec![TextDocumentContentChangeEvent {
+                    range: None,
+                    range_length: None,
+                    text,
+                }],
+            })?;
+        self.process_changes()
+    }
+
+    /// Closes the opened document, its content is read from the file system again.
+    pub fn close(&mut self, uri: Url) -> anyhow::Result<()> {
+        self.state
+            .on_did_close_text_document(DidCloseTextDocumentParams {
+                text_document: TextDocumentIdentifier { uri },
+            })?;
+        self.process_changes()
+    }
+
+    /// Handles the tasks queued by the compilations without blocking, e.g. the compilation of a
+    /// document changed while its workspace was being compiled is retried by a task. The tasks
+    /// queued again are left to the next call, so the embedder calls it periodically, e.g. on
+    /// its own event loop, besides the calls of [Self::open], [Self::change] and [Self::close].
+    pub fn process_pending(&mut self) -> anyhow::Result<()> {
+        let tasks: Vec<_> = self.state.task_receiver.try_iter().collect();
+        for task in tasks {
+            self.state.handle_task(task, Instant::now())?;
+            self.state.process_vfs_changes();
+        }
+        self.messages.try_iter().for_each(drop);
+        Ok(())
+    }
+
+    fn process_changes(&mut self) -> anyhow::Result<()> {
+        self.state.process_vfs_changes();
+        self.process_pending()
+    }
+
+    fn next_version(&mut self) -> i32 {
+        self.version += 1;
+        self.version
+    }
+}
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use indexmap::IndexSet;
+use kclvm_ast::ast::Program;
+use kclvm_error::{Diagnostic, Handler};
+use kclvm_parser::{load_program, LoadProgramOptions, ParseSession};
+use kclvm_runtime::PanicInfo;
+use kclvm_sema::resolver::{
+    resolve_program_with_opts,
+    scope::{KCLScopeCache, ProgramScope},
+    Options,
+};
+#[cfg(test)]
+mod tests;
+
+/// KCL check tools API, parses and type checks a set of files, and returns the parse and
+/// resolve diagnostics. Unlike running the program, the codegen and evaluation phases are
+/// skipped, so the runtime errors, e.g. the failed schema checks, are not reported.
+///
+/// # Examples
+///
+/// ```no_run
+/// use kclvm_tools::check::check;
+/// let diags = check(&["test.k"], None);
+/// ```
+pub fn check(files: &[&str], opts: Option<LoadProgramOptions>) -> Vec<Diagnostic> {
+    let sess = Arc::new(ParseSession::default());
+    let mut opts = opts.unwrap_or_default();
+    opts.load_plugins = true;
+    let mut program = match load_program(sess.clone(), files, Some(opts), None) {
+        Ok(r) => r.program,
+        Err(err) => {
+            let mut diags: IndexSet<Diagnostic> = sess.1.read().diagnostics.clone();
+            let mut handler = Handler::default();
+            handler.add_panic_info(&PanicInfo::from(err.to_string()));
+            diags.extend(handler.diagnostics);
+            return diags.into_iter().collect();
+        }
+    };
+    let mut diags: IndexSet<Diagnostic> = sess.1.read().diagnostics.clone();
+    diags.extend(check_program(&mut program, None).handler.diagnostics);
+    diags.into_iter().collect()
+}
+
+/// Type checks the parsed program without merging the modules or erasing the types, so the
+/// returned scope can be used by the later analyses of the program, e.g. the advanced resolver.
+pub fn check_program(program: &mut Program, cached_scope: Option<KCLScopeCache>) -> ProgramScope {
+    check_program_with_opts(program, Options::default(), cached_scope)
+}
+
+/// Type checks the parsed program like [check_program] with the resolve options, e.g. the
+/// maximum config nesting depth. The modules are still not merged and the types are not erased.
+pub fn check_program_with_opts(
+    program: &mut Program,
+    opts: Options,
+    cached_scope: Option<KCLScopeCache>,
+) -> ProgramScope {
+    resolve_program_with_opts(
+        program,
+        Options {
+            merge_program: false,
+            type_erasure: false,
+            ..opts
+        },
+        cached_scope,
+    )
+}
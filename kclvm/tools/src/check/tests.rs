@@ -0,0 +1,48 @@
+use super::check;
+use kclvm_error::Level;
+use kclvm_parser::ParseSessionRef;
+use kclvm_runner::{exec_program, ExecProgramArgs};
+
+fn exec_error(file: &str) -> String {
+    let args = ExecProgramArgs {
+        k_filename_list: vec![file.to_string()],
+        ..Default::default()
+    };
+    exec_program(ParseSessionRef::default(), &args)
+        .unwrap_err()
+        .to_string()
+}
+
+#[test]
+fn test_check_type_errors() {
+    let file = "./src/check/test_data/type_error.k";
+    let errors: Vec<String> = check(&[file], None)
+        .into_iter()
+        .filter(|diag| diag.level == Level::Error)
+        .map(|diag| diag.messages[0].message.clone())
+        .collect();
+    assert!(!errors.is_empty());
+    // The full compilation reports the same type errors.
+    let exec_error = exec_error(file);
+    for error in &errors {
+        assert!(
+            exec_error.contains(error),
+            "{} not in {}",
+            error,
+            exec_error
+        );
+    }
+}
+
+#[test]
+fn test_check_skips_evaluation() {
+    let file = "./src/check/test_data/eval_error.k";
+    let diags = check(&[file], None);
+    assert!(
+        diags.iter().all(|diag| diag.level != Level::Error),
+        "{:?}",
+        diags
+    );
+    // The assertion only fails when the program is evaluated.
+    assert!(exec_error(file).contains("replicas must be positive"));
+}
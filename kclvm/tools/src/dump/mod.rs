@@ -0,0 +1,75 @@
+//! [kclvm_tools::dump] module dumps the AST of KCL files to JSON for the external analyzers,
+//! the main API function is `dump_ast`.
+//!
+//! The JSON is versioned with [AST_SCHEMA_VERSION], which is bumped whenever the JSON of a node
+//! changes incompatibly, e.g. a field is renamed or removed. The JSON is the serialization of the
+//! AST structs, so the dump is checked against the golden file `test_data/main.json`, and any
+//! change of the AST structs changing the JSON fails the test until the golden file is updated
+//! and the version is bumped.
+use anyhow::Result;
+use kclvm_parser::parse_single_file;
+use serde_json::{json, Value};
+
+#[cfg(test)]
+mod tests;
+
+/// The version of the JSON schema of the dumped AST.
+pub const AST_SCHEMA_VERSION: u64 = 1;
+
+/// Parses the KCL file and dumps its AST to JSON, the parse errors are dumped along with the
+/// partial AST instead of failing.
+///
+/// The JSON schema is:
+///
+/// ```no_check
+/// {
+///     "schema_version": 1,
+///     "filename": "main.k",
+///     "module": {
+///         "filename": "main.k",
+///         "doc": null,
+///         "body": [<node>],
+///         "comments": [<node>]
+///     },
+///     "errors": [
+///         {"message": "...", "filename": "main.k", "line": 1, "column": 0}
+///     ]
+/// }
+/// ```
+///
+/// Each `<node>` has the kind in `node.type`, e.g. `Assign` or `Schema` for the statements and
+/// `Identifier` or `Binary` for the expressions, and the range in the fields `filename`, `line`,
+/// `column`, `end_line` and `end_column`. The lines are 1-based and the columns are 0-based,
+/// which are the same with the KCL positions. The children are the nested nodes in the other
+/// fields of `node`, e.g. `targets` and `value` of an `Assign` node.
+///
+/// # Examples
+///
+/// ```no_run
+/// use kclvm_tools::dump::dump_ast;
+///
+/// let ast = dump_ast("main.k").unwrap();
+/// println!("{}", ast["module"]["body"][0]["node"]["type"]);
+/// ```
+pub fn dump_ast(file: &str) -> Result<Value> {
+    let result = parse_single_file(file, None)?;
+    let errors: Vec<Value> = result
+        .errors
+        .iter()
+        .filter_map(|diag| diag.messages.first())
+        .map(|msg| {
+            json!({
+                "message": msg.message,
+                "filename": msg.range.0.filename,
+                "line": msg.range.0.line,
+                "column": msg.range.0.column,
+            })
+        })
+        .collect();
+    Ok(json!({
+        "schema_version": AST_SCHEMA_VERSION,
+        "filename": result.module.filename,
+        "module": serde_json::to_value(&result.module)?,
+        "errors": errors,
+    }))
+}
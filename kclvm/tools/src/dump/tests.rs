@@ -0,0 +1,51 @@
+use super::{dump_ast, AST_SCHEMA_VERSION};
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+
+#[test]
+fn test_dump_ast() {
+    let ast = dump_ast("./src/dump/test_data/main.k").unwrap();
+    assert_eq!(ast["schema_version"], AST_SCHEMA_VERSION);
+    assert_eq!(ast["errors"].as_array().unwrap().len(), 0);
+
+    let body = ast["module"]["body"].as_array().unwrap();
+    let got: Vec<(&str, u64, u64)> = body
+        .iter()
+        .map(|node| {
+            (
+                node["node"]["type"].as_str().unwrap(),
+                node["line"].as_u64().unwrap(),
+                node["column"].as_u64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(got, vec![("Assign", 1, 0), ("Schema", 3, 0)]);
+    assert_eq!(body[0]["end_line"], 1);
+    assert_eq!(body[0]["end_column"], 5);
+    // The schema statement ends after its body.
+    assert!(body[1]["end_line"].as_u64().unwrap() >= 4);
+
+    // The children of `schema Person` are its attributes.
+    let attr = &body[1]["node"]["body"][0];
+    assert_eq!(attr["node"]["type"], "SchemaAttr");
+    assert_eq!(attr["line"], 4);
+}
+
+/// The dump is compared with the golden file, so any change of the JSON of the AST nodes fails
+/// until the golden file is updated along with [AST_SCHEMA_VERSION] if the change is incompatible.
+#[test]
+fn test_dump_ast_golden() {
+    let ast = dump_ast("./src/dump/test_data/main.k").unwrap();
+    let filename = ast["filename"].as_str().unwrap().to_string();
+    // The filenames are relative to the test data in the golden file.
+    let dumped: Value = serde_json::from_str(
+        &serde_json::to_string(&ast)
+            .unwrap()
+            .replace(&format!("\"{}\"", filename), "\"main.k\""),
+    )
+    .unwrap();
+    let golden: Value =
+        serde_json::from_str(&std::fs::read_to_string("./src/dump/test_data/main.json").unwrap())
+            .unwrap();
+    assert_eq!(dumped, golden);
+}
@@ -0,0 +1,79 @@
+//! [kclvm_tools::fingerprint] module computes the fingerprint of the evaluated config, which tells
+//! whether the effective config is changed, the main API function is `config_fingerprint`.
+//!
+//! The fingerprint is the SHA-256 of the canonical JSON of the evaluation result, so the configs
+//! differing only in the comments, the formatting or the order of the keys have the same
+//! fingerprint.
+use std::fmt::Write;
+
+use anyhow::{bail, Result};
+use kclvm_parser::ParseSessionRef;
+use kclvm_runner::{exec_program, ExecProgramArgs};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+#[cfg(test)]
+mod tests;
+
+/// Evaluates the entry file with the options and returns the hex SHA-256 of the canonical JSON of
+/// the result. The files of the options are replaced by the entry file, and the evaluation errors
+/// are returned as errors.
+///
+/// # Examples
+///
+/// ```no_run
+/// use kclvm_tools::fingerprint::config_fingerprint;
+///
+/// let fingerprint = config_fingerprint("main.k", None).unwrap();
+/// println!("{}", fingerprint);
+/// ```
+pub fn config_fingerprint(entry_file: &str, options: Option<ExecProgramArgs>) -> Result<String> {
+    let mut args = options.unwrap_or_default();
+    args.k_filename_list = vec![entry_file.to_string()];
+    args.k_code_list = vec![];
+    args.sort_keys = true;
+    let result = exec_program(ParseSessionRef::default(), &args)?;
+    if !result.err_message.is_empty() {
+        bail!(result.err_message);
+    }
+    let value: Value = serde_json::from_str(&result.json_result)?;
+    let mut canonical = String::new();
+    write_canonical_json(&value, &mut canonical);
+    let mut hex = String::with_capacity(2 * Sha256::output_size());
+    for byte in Sha256::digest(canonical.as_bytes()) {
+        let _ = write!(&mut hex, "{byte:02x}");
+    }
+    Ok(hex)
+}
+
+/// Writes the JSON without the whitespaces and with the keys of the objects sorted, which does
+/// not depend on the key order of the map of `serde_json`.
+fn write_canonical_json(value: &Value, out: &mut String) {
+    match value {
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical_json(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            out.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical_json(value, out);
+            }
+            out.push('}');
+        }
+        _ => out.push_str(&value.to_string()),
+    }
+}
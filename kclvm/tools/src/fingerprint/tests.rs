@@ -0,0 +1,21 @@
+use super::config_fingerprint;
+
+#[test]
+fn test_config_fingerprint() {
+    let fingerprint = config_fingerprint("./src/fingerprint/test_data/main.k", None).unwrap();
+    assert_eq!(fingerprint.len(), 64);
+    assert_eq!(
+        config_fingerprint("./src/fingerprint/test_data/main.k", None).unwrap(),
+        fingerprint
+    );
+    // The comments, the formatting and the order of the statements are not fingerprinted.
+    assert_eq!(
+        config_fingerprint("./src/fingerprint/test_data/reformatted.k", None).unwrap(),
+        fingerprint
+    );
+    // The changed default value of `port` changes the evaluated config.
+    assert_ne!(
+        config_fingerprint("./src/fingerprint/test_data/changed.k", None).unwrap(),
+        fingerprint
+    );
+}
@@ -92,3 +92,79 @@ pub fn format_source(file: &str, src: &str, opts: &FormatOptions) -> Result<(Str
     let is_formatted = src != formatted_src;
     Ok((formatted_src, is_formatted))
 }
+
+/// The number of spaces a tab is expanded to in the indentation.
+const TAB_WIDTH: usize = 4;
+
+/// Reindents a block of KCL code to the base indentation `indent` without formatting it,
+/// e.g. the code pasted into an indented block. All the lines are shifted by the same delta,
+/// so the relative indentation of the lines is kept. Tabs in the indentation are expanded to
+/// spaces, blank lines are emptied and the other parts of the lines are unchanged. The lines
+/// inside multi-line string literals are kept as they are, so the string values are unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use kclvm_tools::format::reindent_source;
+///
+/// assert_eq!(reindent_source("a = {\n    b = 1\n}\n", "    "), "    a = {\n        b = 1\n    }\n");
+/// ```
+pub fn reindent_source(src: &str, indent: &str) -> String {
+    let string_ranges = string_literal_ranges(src);
+    let mut offset = 0;
+    let lines: Vec<(&str, Option<(String, &str)>)> = src
+        .split('\n')
+        .map(|line| {
+            let start = offset;
+            offset += line.len() + 1;
+            if string_ranges
+                .iter()
+                .any(|(lo, hi)| *lo < start && start < *hi)
+            {
+                return (line, None);
+            }
+            let content = line.trim_start_matches([' ', '\t']);
+            let leading = &line[..line.len() - content.len()];
+            (
+                line,
+                Some((leading.replace('\t', &" ".repeat(TAB_WIDTH)), content)),
+            )
+        })
+        .collect();
+    let min_indent = lines
+        .iter()
+        .filter_map(|(_, indented)| indented.as_ref())
+        .filter(|(_, content)| !content.trim().is_empty())
+        .map(|(leading, _)| leading.len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|(line, indented)| match indented {
+            // The line is a part of a string value.
+            None => line.to_string(),
+            Some((_, content)) if content.trim().is_empty() => String::new(),
+            Some((leading, content)) => {
+                format!("{}{}{}", indent, &leading[min_indent..], content)
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Returns the byte ranges of the string literals in the source.
+fn string_literal_ranges(src: &str) -> Vec<(usize, usize)> {
+    let mut ranges = vec![];
+    let mut offset = 0;
+    for token in kclvm_lexer::tokenize(src) {
+        if let kclvm_lexer::TokenKind::Literal {
+            kind: kclvm_lexer::LiteralKind::Str { .. },
+            ..
+        } = token.kind
+        {
+            ranges.push((offset, offset + token.len));
+        }
+        offset += token.len;
+    }
+    ranges
+}
@@ -96,6 +96,29 @@ fn test_format_with_stdout_option() {
     assert_eq!(changed_files.len(), 2);
 }
 
+#[test]
+fn test_reindent_source() {
+    assert_eq!(
+        reindent_source("a = 1\nb = 2\n", "    "),
+        "    a = 1\n    b = 2\n"
+    );
+    // The relative indentation is kept and the blank lines are emptied.
+    assert_eq!(
+        reindent_source("        x = {\n  \n            y = 1\n        }", "    "),
+        "    x = {\n\n        y = 1\n    }"
+    );
+    // Tabs in the indentation are expanded.
+    assert_eq!(
+        reindent_source("\ta = 1\n\t\tb = 2", ""),
+        "a = 1\n    b = 2"
+    );
+    // The lines inside the multi-line strings are unchanged.
+    assert_eq!(
+        reindent_source("    a = \"\"\"\n  x\n\n\"\"\"\n      b = '''\ny'''", ""),
+        "a = \"\"\"\n  x\n\n\"\"\"\n  b = '''\ny'''"
+    );
+}
+
 #[test]
 fn test_format_with_omit_error_option() {
     let opts = FormatOptions {
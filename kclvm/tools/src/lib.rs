@@ -1,6 +1,10 @@
+pub mod check;
+pub mod dump;
+pub mod fingerprint;
 pub mod fix;
 pub mod format;
 pub mod lint;
+pub mod preview;
 pub mod testing;
 pub mod util;
 pub mod vet;
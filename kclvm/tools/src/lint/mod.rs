@@ -1,7 +1,12 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use indexmap::IndexSet;
-use kclvm_error::{Diagnostic, Handler};
+use kclvm_config::lintfile::{
+    lookup_lint_file_with_errors, LintFile, LintFileError, LintFileErrorKind, LintSeverity,
+};
+use kclvm_error::{Diagnostic, DiagnosticId, Handler, Level, Position, WarningKind};
 use kclvm_parser::{load_program, LoadProgramOptions, ParseSession};
 use kclvm_runtime::PanicInfo;
 use kclvm_sema::resolver::resolve_program_with_opts;
@@ -69,6 +74,43 @@ pub fn lint_files(
     files: &[&str],
     opts: Option<LoadProgramOptions>,
 ) -> (IndexSet<Diagnostic>, IndexSet<Diagnostic>) {
+    lint_files_with_opts(files, opts, &LintOptions::default())
+}
+
+/// Lint options besides the compilation options.
+#[derive(Debug, Default, Clone)]
+pub struct LintOptions {
+    /// Whether to promote all the warnings to errors, e.g. for CI checks which fail on warnings.
+    pub warnings_as_errors: bool,
+    /// Whether to warn the implicit coercions, e.g. comparing a string with a number.
+    pub implicit_coercions: bool,
+    /// Whether to warn the top level variables and the schema attributes without the type
+    /// annotations.
+    pub explicit_types: bool,
+    /// Whether to report the references to the names both imported and defined in the package.
+    pub ambiguous_references: bool,
+}
+
+/// Same as [lint_files] with the lint options `lint_opts`. The lint files `.kcllint.toml` are
+/// also applied to the warnings, see [kclvm_config::lintfile::LintFile].
+pub fn lint_files_with_opts(
+    files: &[&str],
+    opts: Option<LoadProgramOptions>,
+    lint_opts: &LintOptions,
+) -> (IndexSet<Diagnostic>, IndexSet<Diagnostic>) {
+    let mut handler = lint_files_handler(files, opts, lint_opts);
+    apply_lint_files(&mut handler);
+    if lint_opts.warnings_as_errors {
+        handler.promote_warnings_to_errors();
+    }
+    handler.classification()
+}
+
+fn lint_files_handler(
+    files: &[&str],
+    opts: Option<LoadProgramOptions>,
+    lint_opts: &LintOptions,
+) -> Handler {
     // Parse AST program.
     let sess = Arc::new(ParseSession::default());
     let mut opts = opts.unwrap_or_default();
@@ -76,9 +118,9 @@ pub fn lint_files(
     let mut program = match load_program(sess.clone(), files, Some(opts), None) {
         Ok(p) => p.program,
         Err(err_str) => {
-            return Handler::default()
-                .add_panic_info(&PanicInfo::from(err_str.to_string()))
-                .classification();
+            let mut handler = Handler::default();
+            handler.add_panic_info(&PanicInfo::from(err_str.to_string()));
+            return handler;
         }
     };
     sess.append_diagnostic(
@@ -86,12 +128,72 @@ pub fn lint_files(
             &mut program,
             kclvm_sema::resolver::Options {
                 merge_program: false,
+                implicit_coercion_check: lint_opts.implicit_coercions,
+                explicit_type_check: lint_opts.explicit_types,
+                ambiguous_reference_check: lint_opts.ambiguous_references,
                 ..Default::default()
             },
             None,
         )
         .handler
         .diagnostics,
-    )
-    .classification()
+    );
+    sess.1.read().clone()
+}
+
+/// Drops the disabled warnings and overrides the severity of the warnings with the nearest lint
+/// files to the files reporting them. The invalid lint files are reported as the errors and the
+/// unknown lints of the lint files as the warnings, both at the lint files.
+pub fn apply_lint_files(handler: &mut Handler) {
+    let known_lints: Vec<String> = WarningKind::ALL.iter().map(|kind| kind.name()).collect();
+    let mut lint_files: HashMap<PathBuf, LintFile> = HashMap::new();
+    let mut errors: IndexSet<LintFileError> = IndexSet::new();
+    handler.diagnostics = std::mem::take(&mut handler.diagnostics)
+        .into_iter()
+        .filter_map(|mut diag| {
+            let lint = match &diag.code {
+                Some(DiagnosticId::Warning(kind)) => kind.to_string(),
+                _ => return Some(diag),
+            };
+            let dir = match diag
+                .messages
+                .first()
+                .and_then(|msg| Path::new(&msg.range.0.filename).parent())
+            {
+                Some(dir) => dir.to_path_buf(),
+                None => return Some(diag),
+            };
+            let lint_file = lint_files.entry(dir.clone()).or_insert_with(|| {
+                let (lint_file, lint_file_errors) =
+                    lookup_lint_file_with_errors(&dir, Some(&known_lints));
+                errors.extend(lint_file_errors);
+                lint_file
+            });
+            if !lint_file.is_enabled(&lint) {
+                return None;
+            }
+            if let Some(severity) = lint_file.get_severity(&lint) {
+                diag.level = match severity {
+                    LintSeverity::Error => Level::Error,
+                    LintSeverity::Warning => Level::Warning,
+                    LintSeverity::Note => Level::Note,
+                };
+            }
+            Some(diag)
+        })
+        .collect();
+    for error in errors {
+        let pos = Position {
+            filename: error.path.to_string_lossy().to_string(),
+            line: error.line_col.map_or(1, |(line, _)| line as u64 + 1),
+            column: error.line_col.map(|(_, col)| col as u64),
+        };
+        let level = match error.kind {
+            LintFileErrorKind::Invalid => Level::Error,
+            LintFileErrorKind::UnknownLint => Level::Warning,
+        };
+        handler
+            .diagnostics
+            .insert(Diagnostic::new(level, &error.message, (pos.clone(), pos)));
+    }
 }
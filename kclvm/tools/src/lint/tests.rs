@@ -1,4 +1,5 @@
-use super::lint_files;
+use super::{lint_files, lint_files_with_opts, LintOptions};
+use kclvm_error::Handler;
 use std::path::PathBuf;
 
 #[test]
@@ -68,3 +69,93 @@ fn test_unused_check_for_each_file() {
         path.to_str().unwrap().to_string()
     );
 }
+
+#[test]
+fn test_lint_warnings_as_errors() {
+    let files = [
+        "./src/lint/test_data/unused_check_for_each_file/a.k",
+        "./src/lint/test_data/unused_check_for_each_file/b.k",
+    ];
+    let (errs, warnings) = lint_files_with_opts(&files, None, &LintOptions::default());
+    assert_eq!(errs.len(), 0);
+    assert_eq!(warnings.len(), 1);
+
+    let (errs, warnings) = lint_files_with_opts(
+        &files,
+        None,
+        &LintOptions {
+            warnings_as_errors: true,
+            ..Default::default()
+        },
+    );
+    assert_eq!(warnings.len(), 0);
+    assert_eq!(errs.len(), 1);
+    assert_eq!(
+        errs[0].messages[0].message,
+        "Module 'math' imported but unused".to_string()
+    );
+    let handler = Handler { diagnostics: errs };
+    assert!(handler.has_errors());
+}
+
+#[test]
+fn test_lint_file() {
+    // The unused import lint is disabled by `lint_file/.kcllint.toml`, and enabled again as an
+    // error by `lint_file/b/.kcllint.toml`.
+    let (errs, warnings) = lint_files(
+        &[
+            "./src/lint/test_data/lint_file/a/main.k",
+            "./src/lint/test_data/lint_file/b/main.k",
+        ],
+        None,
+    );
+    assert_eq!(warnings.len(), 0);
+    assert_eq!(errs.len(), 1);
+    assert_eq!(
+        errs[0].messages[0].message,
+        "Module 'math' imported but unused".to_string()
+    );
+    assert!(errs[0].messages[0]
+        .range
+        .0
+        .filename
+        .ends_with(&format!("b{}main.k", std::path::MAIN_SEPARATOR)));
+}
+
+#[test]
+fn test_lint_file_errors() {
+    // The misspelled lint does not disable the unused imports and is reported at the lint file,
+    // the invalid lint file is skipped and reported as an error.
+    let (errs, warnings) = lint_files(
+        &[
+            "./src/lint/test_data/lint_file_errors/main.k",
+            "./src/lint/test_data/lint_file_errors/invalid/main.k",
+        ],
+        None,
+    );
+    assert_eq!(errs.len(), 1);
+    assert!(errs[0].messages[0]
+        .message
+        .starts_with("invalid lint file: "));
+    assert!(errs[0].messages[0].range.0.filename.ends_with(&format!(
+        "invalid{}.kcllint.toml",
+        std::path::MAIN_SEPARATOR
+    )));
+    let messages: Vec<(&str, u64)> = warnings
+        .iter()
+        .map(|diag| {
+            (
+                diag.messages[0].message.as_str(),
+                diag.messages[0].range.0.line,
+            )
+        })
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            ("Module 'math' imported but unused", 1),
+            ("Module 'math' imported but unused", 1),
+            ("unknown lint 'UnusedImportWarnin'", 2),
+        ]
+    );
+}
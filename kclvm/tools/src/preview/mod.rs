@@ -0,0 +1,213 @@
+//! [kclvm_tools::preview] module type-checks a single schema for a fast preview loop when
+//! iterating on one schema in a huge package, the main API function is `compile_schema`.
+//!
+//! The main package is sliced to the target schema and the top level declarations it depends on,
+//! the other schemas and statements are not resolved at all. Only the diagnostics inside the
+//! target schema are reported, the errors of its dependencies are ignored.
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use indexmap::IndexSet;
+use kclvm_ast::ast::{self, Program};
+use kclvm_ast::walker::MutSelfWalker;
+use kclvm_ast::MAIN_PKG;
+use kclvm_error::Diagnostic;
+use kclvm_parser::{load_program, LoadProgramOptions, ParseSession};
+use kclvm_sema::resolver::resolve_program_with_opts;
+
+#[cfg(test)]
+mod tests;
+
+/// Type-checks the schema `schema_name` of the main package of the files and returns the
+/// diagnostics inside the schema.
+///
+/// The schema is resolved along with the import statements and the top level declarations it
+/// references transitively, e.g. its base schema, the schemas of its attribute types, the type
+/// aliases and the variables used in its default values.
+///
+/// # Examples
+///
+/// ```no_run
+/// use kclvm_tools::preview::compile_schema;
+///
+/// let diags = compile_schema(&["main.k"], "Server", None).unwrap();
+/// for diag in &diags {
+///     println!("{:?}", diag);
+/// }
+/// ```
+pub fn compile_schema(
+    files: &[&str],
+    schema_name: &str,
+    opts: Option<LoadProgramOptions>,
+) -> Result<IndexSet<Diagnostic>> {
+    let sess = Arc::new(ParseSession::default());
+    let mut opts = opts.unwrap_or_default();
+    opts.load_plugins = true;
+    let mut program = load_program(sess.clone(), files, Some(opts), None)?.program;
+    let (filename, start_line, end_line) = slice_program(&program, schema_name)?;
+    sess.append_diagnostic(
+        resolve_program_with_opts(
+            &mut program,
+            kclvm_sema::resolver::Options {
+                merge_program: false,
+                ..Default::default()
+            },
+            None,
+        )
+        .handler
+        .diagnostics,
+    );
+    let diags = sess.1.read().diagnostics.clone();
+    Ok(diags
+        .into_iter()
+        .filter(|diag| match diag.messages.first() {
+            Some(msg) => {
+                let pos = &msg.range.0;
+                pos.filename == filename && start_line <= pos.line && pos.line <= end_line
+            }
+            None => false,
+        })
+        .collect())
+}
+
+/// Removes the statements of the main package which the schema does not depend on and returns the
+/// file and the line range of the schema.
+fn slice_program(program: &Program, schema_name: &str) -> Result<(String, u64, u64)> {
+    let modules = program.get_modules_for_pkg(MAIN_PKG);
+    let mut kept: Vec<Vec<bool>> = vec![];
+    let mut target = None;
+    let mut needed = NameCollector::default();
+    for module in &modules {
+        let module = module
+            .read()
+            .map_err(|_| anyhow!("Failed to acquire module lock"))?;
+        let mut module_kept = vec![];
+        for stmt in &module.body {
+            let is_target = target.is_none()
+                && match &stmt.node {
+                    ast::Stmt::Schema(schema_stmt) => schema_stmt.name.node == schema_name,
+                    _ => false,
+                };
+            if is_target {
+                target = Some((module.filename.clone(), stmt.line, stmt.end_line));
+                needed.walk_stmt(&stmt.node);
+            }
+            module_kept.push(is_target || matches!(&stmt.node, ast::Stmt::Import(_)));
+        }
+        kept.push(module_kept);
+    }
+    let target = target.ok_or(anyhow!(
+        "schema '{}' is not found in the main package",
+        schema_name
+    ))?;
+    // Keep the declarations of the needed names until no more names are needed.
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (module, module_kept) in modules.iter().zip(kept.iter_mut()) {
+            let module = module
+                .read()
+                .map_err(|_| anyhow!("Failed to acquire module lock"))?;
+            for (stmt, kept) in module.body.iter().zip(module_kept.iter_mut()) {
+                if *kept {
+                    continue;
+                }
+                if defined_names(&stmt.node)
+                    .iter()
+                    .any(|name| needed.names.contains(name))
+                {
+                    *kept = true;
+                    changed = true;
+                    needed.walk_stmt(&stmt.node);
+                }
+            }
+        }
+    }
+    for (module, module_kept) in modules.iter().zip(kept) {
+        let mut module = module
+            .write()
+            .map_err(|_| anyhow!("Failed to acquire module lock"))?;
+        let mut module_kept = module_kept.into_iter();
+        module
+            .body
+            .retain(|_| module_kept.next().unwrap_or_default());
+    }
+    Ok(target)
+}
+
+/// Returns the names defined by a top level statement.
+fn defined_names(stmt: &ast::Stmt) -> Vec<String> {
+    match stmt {
+        ast::Stmt::Schema(schema_stmt) => vec![schema_stmt.name.node.clone()],
+        ast::Stmt::Rule(rule_stmt) => vec![rule_stmt.name.node.clone()],
+        ast::Stmt::TypeAlias(type_alias_stmt) => type_alias_stmt
+            .type_name
+            .node
+            .names
+            .first()
+            .map(|name| vec![name.node.clone()])
+            .unwrap_or_default(),
+        ast::Stmt::Assign(assign_stmt) => assign_stmt
+            .targets
+            .iter()
+            .map(|target| target.node.name.node.clone())
+            .collect(),
+        ast::Stmt::AugAssign(aug_assign_stmt) => {
+            vec![aug_assign_stmt.target.node.name.node.clone()]
+        }
+        ast::Stmt::Unification(unification_stmt) => unification_stmt
+            .target
+            .node
+            .names
+            .first()
+            .map(|name| vec![name.node.clone()])
+            .unwrap_or_default(),
+        _ => vec![],
+    }
+}
+
+/// Collects the names referenced by the identifiers and the type annotations.
+#[derive(Default)]
+struct NameCollector {
+    names: IndexSet<String>,
+}
+
+impl NameCollector {
+    /// Collects the names in a type string, e.g. `Port` and `pkg` of `[Port] | pkg.Port`.
+    fn collect_type_names(&mut self, ty: &str) {
+        for name in ty.split(|c: char| !(c.is_alphanumeric() || c == '_')) {
+            if !name.is_empty() {
+                self.names.insert(name.to_string());
+            }
+        }
+    }
+}
+
+impl MutSelfWalker for NameCollector {
+    fn walk_identifier(&mut self, identifier: &ast::Identifier) {
+        if let Some(name) = identifier.names.first() {
+            self.names.insert(name.node.clone());
+        }
+    }
+
+    fn walk_type_alias_stmt(&mut self, type_alias_stmt: &ast::TypeAliasStmt) {
+        self.collect_type_names(&type_alias_stmt.type_value.node);
+    }
+
+    fn walk_assign_stmt(&mut self, assign_stmt: &ast::AssignStmt) {
+        if let Some(ty) = &assign_stmt.ty {
+            self.collect_type_names(&ty.node.to_string());
+        }
+        self.walk_expr(&assign_stmt.value.node);
+    }
+
+    fn walk_schema_attr(&mut self, schema_attr: &ast::SchemaAttr) {
+        self.collect_type_names(&schema_attr.ty.node.to_string());
+        for decorator in &schema_attr.decorators {
+            self.walk_call_expr(&decorator.node);
+        }
+        if let Some(value) = &schema_attr.value {
+            self.walk_expr(&value.node);
+        }
+    }
+}
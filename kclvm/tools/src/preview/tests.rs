@@ -0,0 +1,24 @@
+use super::compile_schema;
+
+#[test]
+fn test_compile_schema() {
+    let file = "./src/preview/test_data/main.k";
+
+    // `Server` reports its own type error, but not the one in `Unrelated`.
+    let diags = compile_schema(&[file], "Server", None).unwrap();
+    assert!(!diags.is_empty());
+    assert!(diags.iter().all(|diag| diag.messages[0].range.0.line == 5));
+    assert!(diags.iter().any(|diag| diag.messages[0]
+        .message
+        .contains("expected str, got int(1)")));
+
+    let diags = compile_schema(&[file], "Unrelated", None).unwrap();
+    assert!(!diags.is_empty());
+    assert!(diags.iter().all(|diag| diag.messages[0].range.0.line == 12));
+
+    // `Port` is used by `Server` but has no errors itself.
+    let diags = compile_schema(&[file], "Port", None).unwrap();
+    assert!(diags.is_empty());
+
+    assert!(compile_schema(&[file], "NotExist", None).is_err());
+}
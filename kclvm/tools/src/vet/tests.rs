@@ -311,15 +311,18 @@ mod test_expr_builder {
 
 mod test_validater {
     use std::{
+        collections::HashMap,
         fs, panic,
         path::{Path, PathBuf},
     };
 
+    use kclvm_error::{Diagnostic, Level, Position};
+
     use crate::{
         util::loader::LoaderKind,
         vet::{
             tests::deal_windows_filepath,
-            validator::{validate, ValidateOption},
+            validator::{validate, validate_many, ValidateOption},
         },
     };
 
@@ -538,6 +541,63 @@ mod test_validater {
         }
     }
 
+    #[test]
+    fn test_validate_many() {
+        let valid_file =
+            construct_full_path(&format!("{}/{}", "validate_cases", "test.k.json")).unwrap();
+        let invalid_file =
+            construct_full_path(&format!("{}/{}", "invalid_validate_cases", "test.k.json"))
+                .unwrap();
+        let kcl_path = construct_full_path(&format!("{}/{}", "validate_cases", "test.k")).unwrap();
+
+        let opt = ValidateOption::new(
+            Some("User".to_string()),
+            "value".to_string(),
+            "".to_string(),
+            LoaderKind::JSON,
+            Some(kcl_path),
+            None,
+            Default::default(),
+        );
+
+        let missing_file = "invalid/file/path".to_string();
+        // The file which fails the check reports the same error as validating it alone.
+        let invalid_err = validate(ValidateOption {
+            validated_file_path: invalid_file.clone(),
+            ..opt.clone()
+        })
+        .unwrap_err()
+        .to_string();
+        let error = |file: &String, message: &str| {
+            let pos = Position {
+                filename: file.clone(),
+                line: 1,
+                column: None,
+            };
+            vec![Diagnostic::new(Level::Error, message, (pos.clone(), pos))]
+        };
+
+        let results = validate_many(
+            &[
+                valid_file.clone(),
+                invalid_file.clone(),
+                missing_file.clone(),
+            ],
+            &opt,
+        );
+        assert_eq!(
+            results,
+            HashMap::from([
+                (valid_file, vec![]),
+                (invalid_file.clone(), error(&invalid_file, &invalid_err)),
+                (
+                    missing_file.clone(),
+                    error(&missing_file, "Failed to Load 'invalid/file/path'")
+                ),
+            ])
+        );
+    }
+
     fn test_validate_with_invalid_kcl_path() {
         let opt = ValidateOption::new(
             None,
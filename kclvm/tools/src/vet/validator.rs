@@ -66,6 +66,8 @@
 //! ```
 
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
 
 use super::expr_builder::ExprBuilder;
 pub use crate::util::loader::LoaderKind;
@@ -74,6 +76,7 @@ use kclvm_ast::{
     ast::{AssignStmt, Expr, Node, NodeRef, Program, SchemaStmt, Stmt, Target},
     node_ref,
 };
+use kclvm_error::{Diagnostic, Level, Position};
 use kclvm_parser::{LoadProgramOptions, ParseSessionRef};
 use kclvm_runner::{execute, ExecProgramArgs, MapErrorResult};
 
@@ -236,6 +239,63 @@ pub fn validate(val_opt: ValidateOption) -> Result<bool> {
     .map(|_| true)
 }
 
+/// Validate many data files against the same schema, the files are validated in parallel.
+///
+/// `val_opt` is shared by all the files except its `validated_file_path`, which is replaced
+/// by each path in `data_files`.
+///
+/// Returns the diagnostics of every file keyed by the file path, a file which passes the
+/// validation has no diagnostic. A file which fails to load, parse or check reports an
+/// error diagnostic instead of aborting the whole batch.
+pub fn validate_many(
+    data_files: &[String],
+    val_opt: &ValidateOption,
+) -> HashMap<String, Vec<Diagnostic>> {
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(data_files.len());
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(HashMap::with_capacity(data_files.len()));
+    std::thread::scope(|s| {
+        for _ in 0..workers {
+            s.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::SeqCst);
+                let file = match data_files.get(i) {
+                    Some(file) => file,
+                    None => break,
+                };
+                let opt = ValidateOption {
+                    validated_file_path: file.clone(),
+                    ..val_opt.clone()
+                };
+                let diags = match validate(opt) {
+                    Ok(_) => vec![],
+                    Err(err) => {
+                        let pos = Position {
+                            filename: file.clone(),
+                            line: 1,
+                            column: None,
+                        };
+                        vec![Diagnostic::new(
+                            Level::Error,
+                            &err.to_string(),
+                            (pos.clone(), pos),
+                        )]
+                    }
+                };
+                results
+                    .lock()
+                    .expect("Failed to acquire results lock")
+                    .insert(file.clone(), diags);
+            });
+        }
+    });
+    results
+        .into_inner()
+        .expect("Failed to acquire results lock")
+}
+
 fn build_assign(attr_name: &str, node: NodeRef<Expr>) -> NodeRef<Stmt> {
     node_ref!(Stmt::Assign(AssignStmt {
         targets: vec![node_ref!(Target {
@@ -267,6 +327,7 @@ fn filter_schema_stmt_from_prog(prog: &Program) -> Vec<SchemaStmt> {
     result
 }
 
+#[derive(Clone)]
 pub struct ValidateOption {
     schema_name: Option<String>,
     attribute_name: String,